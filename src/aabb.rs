@@ -0,0 +1,150 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::render_cache::hash_vec3;
+
+/// Caja envolvente alineada con los ejes, usada por las BVH
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Aabb {
+    /// Crea una caja a partir de dos esquinas cualesquiera
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Aabb {
+            min: a.min(&b),
+            max: a.max(&b),
+        }
+    }
+
+    /// Caja vacía (neutra para `union`)
+    pub fn empty() -> Self {
+        Aabb {
+            min: Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY),
+            max: Point3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    /// Caja mínima que contiene todos los puntos
+    pub fn from_points(points: &[Point3]) -> Self {
+        points.iter().fold(Aabb::empty(), |bounds, point| bounds.grow(point))
+    }
+
+    /// Caja que contiene a esta y al punto dado
+    pub fn grow(&self, point: &Point3) -> Self {
+        Aabb {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    /// Caja que contiene a ambas (unir con una caja vacía no la cambia)
+    pub fn union(&self, other: &Aabb) -> Self {
+        Aabb {
+            min: self.min.min(&other.min),
+            max: self.max.max(&other.max),
+        }
+    }
+
+    /// Caja agrandada `margin` en todas las direcciones
+    pub fn expand(&self, margin: f32) -> Self {
+        let margin = Vec3::new(margin, margin, margin);
+        Aabb {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn extent(&self) -> Vec3 {
+        self.max - self.min
+    }
+
+    /// Eje (0 = x, 1 = y, 2 = z) en el que la caja es más larga
+    pub fn longest_axis(&self) -> usize {
+        let extent = self.extent();
+        if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Área de la superficie de la caja (0.0 si está vacía)
+    pub fn surface_area(&self) -> f32 {
+        let extent = self.extent();
+        if extent.x < 0.0 || extent.y < 0.0 || extent.z < 0.0 {
+            return 0.0;
+        }
+        2.0 * (extent.x * extent.y + extent.y * extent.z + extent.z * extent.x)
+    }
+
+    /// Indica si el punto está dentro de la caja (bordes incluidos)
+    pub fn contains(&self, point: &Point3) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x
+            && point.y >= self.min.y && point.y <= self.max.y
+            && point.z >= self.min.z && point.z <= self.max.z
+    }
+
+    /// Las 8 esquinas de la caja
+    pub fn corners(&self) -> [Point3; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Point3::new(a.x, a.y, a.z),
+            Point3::new(b.x, a.y, a.z),
+            Point3::new(a.x, b.y, a.z),
+            Point3::new(b.x, b.y, a.z),
+            Point3::new(a.x, a.y, b.z),
+            Point3::new(b.x, a.y, b.z),
+            Point3::new(a.x, b.y, b.z),
+            Point3::new(b.x, b.y, b.z),
+        ]
+    }
+
+    /// Test de intersección rayo-caja por el método de los planos (slabs)
+    /// Solo cuenta las intersecciones con t en [0, t_max]
+    pub fn hit(&self, ray: &Ray, t_max: f32) -> bool {
+        let mut t0 = 0.0_f32;
+        let mut t1 = t_max;
+
+        for (origin, direction, min, max) in [
+            (ray.origin.x, ray.direction.x, self.min.x, self.max.x),
+            (ray.origin.y, ray.direction.y, self.min.y, self.max.y),
+            (ray.origin.z, ray.direction.z, self.min.z, self.max.z),
+        ] {
+            let inv = 1.0 / direction;
+            let mut near = (min - origin) * inv;
+            let mut far = (max - origin) * inv;
+            if near > far {
+                std::mem::swap(&mut near, &mut far);
+            }
+            // Los NaN (rayo paralelo sobre el borde) no reducen el intervalo
+            if near > t0 {
+                t0 = near;
+            }
+            if far < t1 {
+                t1 = far;
+            }
+            if t0 > t1 {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_vec3(state, &self.min);
+        hash_vec3(state, &self.max);
+    }
+}
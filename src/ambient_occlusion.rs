@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Vec3;
+use crate::renderer::HitRecord;
+use crate::scene::Scene;
+use crate::sampling::{stratified_square, cosine_hemisphere};
+use crate::sampler::Sampler;
+use crate::stats::{self, Counter};
+
+/// Parámetros de la oclusión ambiental
+/// Se lanzan `samples`×`samples` rayos en el hemisferio de cada punto; los
+/// que chocan con geometría a menos de `radius` oscurecen la luz ambiental
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AmbientOcclusion {
+    pub samples: u32,
+    pub radius: f32,
+}
+
+impl AmbientOcclusion {
+    /// Crea una configuración de oclusión ambiental
+    pub fn new(samples: u32, radius: f32) -> Self {
+        AmbientOcclusion {
+            samples: samples.max(1),
+            radius,
+        }
+    }
+
+    /// Fracción de luz ambiental que llega al punto de `hit` (1.0 = sin
+    /// oclusión). Los rayos heredan el instante y el punto de vista del
+    /// rayo que llegó al punto
+    pub fn visibility(
+        &self,
+        scene: &Scene,
+        hit: &HitRecord,
+        normal: &Vec3,
+        bias: f32,
+        sampler: &mut dyn Sampler,
+    ) -> f32 {
+        let origin = hit.point + *normal * bias;
+        let directions = stratified_square(self.samples, sampler);
+        let total = directions.len() as f32;
+        let mut occluded = 0.0;
+
+        for (u, v) in directions {
+            let ray = hit.spawn_ray(origin, cosine_hemisphere(normal, u, v));
+            stats::count(Counter::ShadowRays);
+            if let Some((t, _)) = scene.find_closest_intersection(&ray) {
+                if t < self.radius {
+                    occluded += 1.0;
+                }
+            }
+        }
+
+        1.0 - occluded / total
+    }
+}
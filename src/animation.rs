@@ -0,0 +1,311 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Vec3, Color, Point3};
+use crate::transform::Transform;
+use crate::camera::Camera;
+use crate::camera_path::{CameraPath, Interpolation};
+use crate::scene::Scene;
+
+/// Margen (en cuadros) para que el redondeo no descarte el último cuadro
+const FRAME_TOLERANCE: f32 = 1e-3;
+
+/// Valores que se pueden interpolar entre keyframes
+pub trait Animatable: Copy {
+    fn lerp(a: Self, b: Self, t: f32) -> Self;
+}
+
+impl Animatable for f32 {
+    fn lerp(a: f32, b: f32, t: f32) -> f32 {
+        a + (b - a) * t
+    }
+}
+
+impl Animatable for Vec3 {
+    fn lerp(a: Vec3, b: Vec3, t: f32) -> Vec3 {
+        a.lerp(&b, t)
+    }
+}
+
+/// Secuencia de valores en el tiempo con interpolación lineal
+/// Antes del primer keyframe y después del último el valor queda fijo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Track<T> {
+    keyframes: Vec<(f32, T)>,
+}
+
+impl<T: Animatable> Track<T> {
+    pub fn new() -> Self {
+        Track { keyframes: Vec::new() }
+    }
+
+    /// Agrega un keyframe; se mantienen ordenados por tiempo
+    /// Un keyframe con tiempo NaN no se puede ordenar y se descarta
+    pub fn add_keyframe(&mut self, time: f32, value: T) {
+        if time.is_nan() {
+            return;
+        }
+        let index = self.keyframes.partition_point(|(t, _)| *t <= time);
+        self.keyframes.insert(index, (time, value));
+    }
+
+    pub fn with_keyframe(mut self, time: f32, value: T) -> Self {
+        self.add_keyframe(time, value);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// Intervalo de tiempo que cubren los keyframes (None si no hay ninguno)
+    pub fn time_range(&self) -> Option<(f32, f32)> {
+        Some((self.keyframes.first()?.0, self.keyframes.last()?.0))
+    }
+
+    /// Valor en el instante `time` (None si la pista está vacía)
+    pub fn evaluate(&self, time: f32) -> Option<T> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+        // Un instante NaN no cae en ningún tramo: se usa el primer keyframe
+        if time.is_nan() || time <= first.0 {
+            return Some(first.1);
+        }
+        if time >= last.0 {
+            return Some(last.1);
+        }
+
+        let i = self.keyframes.partition_point(|(t, _)| *t <= time).saturating_sub(1);
+        let ((t1, v1), (t2, v2)) = (self.keyframes[i], self.keyframes[i + 1]);
+        let span = t2 - t1;
+        let t = if span > 0.0 { (time - t1) / span } else { 0.0 };
+        Some(T::lerp(v1, v2, t))
+    }
+}
+
+/// Movimiento de un objeto: traslación, rotación (grados alrededor de X, Y
+/// y Z, en ese orden) y escala, relativas a la posición original del objeto.
+/// La rotación y la escala se aplican alrededor de `pivot`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectAnimation {
+    pub object_id: usize,
+    pub pivot: Point3,
+    pub translation: Track<Vec3>,
+    pub rotation: Track<Vec3>,
+    pub scale: Track<Vec3>,
+}
+
+impl ObjectAnimation {
+    pub fn new(object_id: usize) -> Self {
+        ObjectAnimation {
+            object_id,
+            pivot: Point3::zero(),
+            translation: Track::new(),
+            rotation: Track::new(),
+            scale: Track::new(),
+        }
+    }
+
+    pub fn with_pivot(mut self, pivot: Point3) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    pub fn with_translation(mut self, time: f32, translation: Vec3) -> Self {
+        self.translation.add_keyframe(time, translation);
+        self
+    }
+
+    pub fn with_rotation(mut self, time: f32, degrees: Vec3) -> Self {
+        self.rotation.add_keyframe(time, degrees);
+        self
+    }
+
+    pub fn with_scale(mut self, time: f32, scale: Vec3) -> Self {
+        self.scale.add_keyframe(time, scale);
+        self
+    }
+
+    /// Transformación del objeto en el instante `time`
+    pub fn transform_at(&self, time: f32) -> Transform {
+        let translation = self.translation.evaluate(time).unwrap_or_else(Vec3::zero);
+        let rotation = self.rotation.evaluate(time).unwrap_or_else(Vec3::zero);
+        let scale = self.scale.evaluate(time).unwrap_or(Vec3::new(1.0, 1.0, 1.0));
+
+        Transform::translation(-self.pivot)
+            .then(&Transform::scaling(scale))
+            .then(&Transform::rotation_x(rotation.x))
+            .then(&Transform::rotation_y(rotation.y))
+            .then(&Transform::rotation_z(rotation.z))
+            .then(&Transform::translation(self.pivot + translation))
+    }
+
+    fn time_range(&self) -> Option<(f32, f32)> {
+        merge_ranges([self.translation.time_range(), self.rotation.time_range(), self.scale.time_range()])
+    }
+}
+
+/// Cambios de una luz en el tiempo; las pistas vacías dejan el valor actual
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightAnimation {
+    pub light_id: usize,
+    pub position: Track<Point3>,
+    pub color: Track<Color>,
+    pub intensity: Track<f32>,
+}
+
+impl LightAnimation {
+    pub fn new(light_id: usize) -> Self {
+        LightAnimation {
+            light_id,
+            position: Track::new(),
+            color: Track::new(),
+            intensity: Track::new(),
+        }
+    }
+
+    pub fn with_position(mut self, time: f32, position: Point3) -> Self {
+        self.position.add_keyframe(time, position);
+        self
+    }
+
+    pub fn with_color(mut self, time: f32, color: Color) -> Self {
+        self.color.add_keyframe(time, color);
+        self
+    }
+
+    pub fn with_intensity(mut self, time: f32, intensity: f32) -> Self {
+        self.intensity.add_keyframe(time, intensity);
+        self
+    }
+
+    fn time_range(&self) -> Option<(f32, f32)> {
+        merge_ranges([self.position.time_range(), self.color.time_range(), self.intensity.time_range()])
+    }
+}
+
+/// Animación de una escena: el recorrido de la cámara y las pistas de los
+/// objetos y las luces, evaluadas cuadro a cuadro con `Scene::set_time`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timeline {
+    pub fps: f32,
+    pub camera: Option<CameraPath>,
+    pub objects: Vec<ObjectAnimation>,
+    pub lights: Vec<LightAnimation>,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self::new(24.0)
+    }
+}
+
+impl Timeline {
+    pub fn new(fps: f32) -> Self {
+        Timeline {
+            fps,
+            camera: None,
+            objects: Vec::new(),
+            lights: Vec::new(),
+        }
+    }
+
+    pub fn with_camera(mut self, path: CameraPath) -> Self {
+        self.camera = Some(path);
+        self
+    }
+
+    pub fn with_object(mut self, animation: ObjectAnimation) -> Self {
+        self.objects.push(animation);
+        self
+    }
+
+    pub fn with_light(mut self, animation: LightAnimation) -> Self {
+        self.lights.push(animation);
+        self
+    }
+
+    /// Instante (en segundos) del cuadro `frame`, contando desde 0
+    pub fn frame_time(&self, frame: u32) -> f32 {
+        frame as f32 / self.fps
+    }
+
+    /// Duración en segundos: hasta el último keyframe de cualquier pista
+    pub fn duration(&self) -> f32 {
+        let ranges = self.objects.iter().map(ObjectAnimation::time_range)
+            .chain(self.lights.iter().map(LightAnimation::time_range))
+            .chain(std::iter::once(self.camera.as_ref().and_then(CameraPath::time_range)));
+        merge_ranges(ranges).map_or(0.0, |(_, end)| end.max(0.0))
+    }
+
+    /// Cuadros necesarios para cubrir toda la animación (al menos uno)
+    pub fn frame_count(&self) -> u32 {
+        // Si el último keyframe cae justo en un cuadro, el redondeo de
+        // `duration * fps` (p. ej. 34.99999) no debe dejarlo afuera
+        (self.duration() * self.fps + FRAME_TOLERANCE).floor() as u32 + 1
+    }
+
+    /// Vuelta completa de la cámara alrededor de `target` en `frames`
+    /// cuadros, como `Camera::turntable` pero empezando donde está ahora
+    /// `camera` y a su misma distancia y altura respecto de `target`
+    pub fn turntable(camera: &Camera, target: Point3, frames: u32, fps: f32) -> Self {
+        let offset = camera.position - target;
+        let radius = offset.length().max(1e-3);
+        let elevation = (offset.y / radius).clamp(-1.0, 1.0).asin().to_degrees();
+        let start = offset.x.atan2(offset.z).to_degrees();
+
+        let frames = frames.max(1);
+        let mut path = CameraPath::new(Interpolation::Linear);
+        for frame in 0..frames {
+            let azimuth = start + 360.0 * frame as f32 / frames as f32;
+            let orbit = camera.orbit(target, radius, azimuth, elevation);
+            path.add_keyframe(frame as f32 / fps, orbit.position, orbit.look_at);
+        }
+        Timeline::new(fps).with_camera(path)
+    }
+
+    /// Deja la escena como está en el instante `time`
+    /// Los objetos animados que no admiten transformaciones se envuelven en
+    /// una instancia la primera vez (ver `Scene::make_transformable`).
+    /// Retorna un error si una pista apunta a un objeto o luz inexistente.
+    pub fn apply(&self, scene: &mut Scene, time: f32) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = &self.camera {
+            scene.camera = path.camera_at(&scene.camera, time);
+        }
+
+        for animation in &self.objects {
+            let transform = animation.transform_at(time);
+            if !scene.set_transform(animation.object_id, transform) {
+                if !scene.make_transformable(animation.object_id) {
+                    return Err(format!("animación de un objeto inexistente: {}", animation.object_id).into());
+                }
+                scene.set_transform(animation.object_id, transform);
+            }
+        }
+
+        for animation in &self.lights {
+            let light = scene
+                .lights
+                .get_mut(animation.light_id)
+                .ok_or_else(|| format!("animación de una luz inexistente: {}", animation.light_id))?;
+            if let Some(position) = animation.position.evaluate(time) {
+                light.position = position;
+            }
+            if let Some(color) = animation.color.evaluate(time) {
+                light.color = color;
+            }
+            if let Some(intensity) = animation.intensity.evaluate(time) {
+                light.intensity = intensity;
+            }
+        }
+        if !self.lights.is_empty() {
+            scene.rebuild_light_sampler();
+        }
+        Ok(())
+    }
+}
+
+fn merge_ranges(ranges: impl IntoIterator<Item = Option<(f32, f32)>>) -> Option<(f32, f32)> {
+    ranges
+        .into_iter()
+        .flatten()
+        .reduce(|(start_a, end_a), (start_b, end_b)| (start_a.min(start_b), end_a.max(end_b)))
+}
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use crate::vector::Color;
+use crate::scene::Scene;
+use crate::renderer::Renderer;
+use crate::framebuffer::Framebuffer;
+use crate::integrator::DirectLightingIntegrator;
+use crate::segmentation::{MaskKind, Segmentation};
+
+/// Pasadas auxiliares (AOV) que se pueden guardar junto a la imagen final
+/// para composición o para un denoiser externo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aov {
+    /// Distancia del primer impacto a la cámara
+    Depth,
+    /// Normal del primer impacto en espacio de mundo
+    Normal,
+    /// Color de la superficie sin iluminar
+    Albedo,
+    /// Luz que llega directamente de las fuentes al primer impacto
+    Direct,
+    /// El resto de la imagen: reflejos, transparencia, luz ambiental y rebotes
+    Indirect,
+    /// Máscara de segmentación por objeto (ver `segmentation`)
+    ObjectId,
+    /// Máscara de segmentación por material (ver `segmentation`)
+    MaterialId,
+}
+
+impl Aov {
+    /// Todas las pasadas, en el orden en que se documentan
+    pub const ALL: [Aov; 7] =
+        [Aov::Depth, Aov::Normal, Aov::Albedo, Aov::Direct, Aov::Indirect, Aov::ObjectId, Aov::MaterialId];
+
+    /// Pasada con el nombre dado (ver `name`)
+    pub fn from_name(name: &str) -> Option<Aov> {
+        Aov::ALL.into_iter().find(|aov| aov.name() == name)
+    }
+
+    /// Nombre usado en los archivos de salida
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::Direct => "direct",
+            Aov::Indirect => "indirect",
+            Aov::ObjectId => "object_id",
+            Aov::MaterialId => "material_id",
+        }
+    }
+
+    /// Tipo de máscara si la pasada es de segmentación
+    pub fn mask_kind(&self) -> Option<MaskKind> {
+        match self {
+            Aov::ObjectId => Some(MaskKind::Object),
+            Aov::MaterialId => Some(MaskKind::Material),
+            _ => None,
+        }
+    }
+
+    /// Ruta de la pasada a partir de la imagen principal:
+    /// "render.png" → "render.depth.exr"
+    pub fn output_path(&self, beauty_path: &str) -> String {
+        self.path_with_extension(beauty_path, "exr")
+    }
+
+    /// Como `output_path` pero con otra extensión, para los archivos que
+    /// acompañan a la pasada: "render.png" → "render.object_id.json"
+    pub fn path_with_extension(&self, beauty_path: &str, extension: &str) -> String {
+        let path = Path::new(beauty_path);
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or("render");
+        let file_name = format!("{}.{}.{}", stem, self.name(), extension);
+        match path.parent() {
+            Some(parent) => parent.join(file_name).to_string_lossy().into_owned(),
+            None => file_name,
+        }
+    }
+}
+
+/// Calcula las pasadas pedidas. `beauty` es la imagen final ya renderizada;
+/// la pasada indirecta es la diferencia entre ella y la directa (la directa
+/// usa las mismas muestras por píxel, así que ambas suman la imagen final)
+pub fn render_aovs(scene: &Scene, beauty: &Framebuffer, aovs: &[Aov]) -> Vec<(Aov, Framebuffer)> {
+    if aovs.is_empty() {
+        return Vec::new();
+    }
+
+    let guides = Renderer::render_guides(scene);
+    let direct = if aovs.contains(&Aov::Direct) || aovs.contains(&Aov::Indirect) {
+        Some(Renderer::render_with(scene, &DirectLightingIntegrator))
+    } else {
+        None
+    };
+
+    aovs.iter()
+        .map(|aov| {
+            let buffer = match aov {
+                Aov::Depth => guides.depth.clone(),
+                Aov::Normal => guides.normals.clone(),
+                Aov::Albedo => guides.albedo.clone(),
+                Aov::Direct => direct.clone().unwrap_or_default(),
+                Aov::Indirect => subtract(beauty, direct.as_ref().unwrap_or(beauty)),
+                Aov::ObjectId => Segmentation::new(scene, MaskKind::Object).render(scene),
+                Aov::MaterialId => Segmentation::new(scene, MaskKind::Material).render(scene),
+            };
+            (*aov, buffer)
+        })
+        .collect()
+}
+
+/// Diferencia píxel a píxel, sin bajar de cero
+fn subtract(a: &Framebuffer, b: &Framebuffer) -> Framebuffer {
+    let pixels = a
+        .pixels()
+        .iter()
+        .zip(b.pixels())
+        .map(|(a, b)| {
+            (*a - *b).max(&Color::zero())
+        })
+        .collect();
+    Framebuffer::from_pixels(a.width(), a.height(), pixels)
+}
@@ -0,0 +1,80 @@
+use std::path::{Path, PathBuf};
+
+/// Dónde se buscan los archivos que usan las escenas (texturas y mallas)
+/// Una ruta relativa se busca primero junto al archivo de la escena
+/// (`base_dir`), después en los directorios de búsqueda en orden y por
+/// último en el directorio de trabajo, así las escenas siguen funcionando
+/// al moverlas de máquina junto con sus archivos.
+#[derive(Debug, Clone, Default)]
+pub struct AssetPaths {
+    pub base_dir: Option<PathBuf>,
+    pub texture_dirs: Vec<PathBuf>,
+    pub mesh_dirs: Vec<PathBuf>,
+}
+
+impl AssetPaths {
+    pub fn new() -> Self {
+        AssetPaths::default()
+    }
+
+    /// Directorio del archivo de la escena
+    pub fn with_base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(dir.into());
+        self
+    }
+
+    pub fn with_texture_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.texture_dirs.push(dir.into());
+        self
+    }
+
+    pub fn with_mesh_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.mesh_dirs.push(dir.into());
+        self
+    }
+
+    /// Ruta de la textura `path`: la primera que exista (ver `AssetPaths`)
+    /// Si no aparece en ningún lado se retorna tal cual, para que el error
+    /// de carga mencione la ruta original
+    pub fn resolve_texture(&self, path: &str) -> String {
+        self.resolve(path, &self.texture_dirs)
+    }
+
+    /// Como `resolve_texture`, con los directorios de mallas
+    pub fn resolve_mesh(&self, path: &str) -> String {
+        self.resolve(path, &self.mesh_dirs)
+    }
+
+    fn resolve(&self, path: &str, dirs: &[PathBuf]) -> String {
+        let original = Path::new(path);
+        if original.is_absolute() {
+            return path.to_string();
+        }
+        self.base_dir
+            .iter()
+            .chain(dirs)
+            .map(|dir| dir.join(original))
+            .chain(std::iter::once(original.to_path_buf()))
+            .find(|candidate| candidate.exists())
+            .map_or_else(|| path.to_string(), |found| found.to_string_lossy().into_owned())
+    }
+}
+
+/// Lee un archivo de texto de la escena (JSON, PBRT, OBJ)
+/// Sin la feature `fs` (p. ej. en WebAssembly) siempre falla: las escenas
+/// se pasan como texto con `scene_file::parse` o `pbrt::parse`
+pub fn read_to_string(path: impl AsRef<Path>) -> std::io::Result<String> {
+    #[cfg(feature = "fs")]
+    return std::fs::read_to_string(path);
+    #[cfg(not(feature = "fs"))]
+    return Err(without_fs(path.as_ref()));
+}
+
+/// Error de las lecturas de archivos cuando no está la feature `fs`
+#[cfg(not(feature = "fs"))]
+pub(crate) fn without_fs(path: &Path) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("no se puede leer {} sin la feature `fs`", path.display()),
+    )
+}
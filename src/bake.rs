@@ -0,0 +1,249 @@
+// Horneado de texturas: un valor calculado sobre la superficie de un objeto
+// (la oclusión ambiental, por ejemplo) se guarda por texel en el espacio UV
+// del objeto para reutilizarlo sin volver a calcularlo.
+//
+// No hace falta invertir la proyección UV de cada forma: se reparten puntos
+// al azar por la superficie (`Intersectable::sample_surface`), se calcula el
+// valor en cada uno y se promedia en el texel de sus UV. Los texeles que no
+// recibe ningún punto (los bordes de las islas UV) se rellenan con sus
+// vecinos para que el filtrado bilineal no traiga negro por las costuras.
+
+use std::error::Error;
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Color, Point3, Vec3};
+use crate::ambient_occlusion::AmbientOcclusion;
+use crate::scene::{Intersectable, Scene};
+use crate::renderer::{HitRecord, Renderer};
+use crate::framebuffer::Framebuffer;
+use crate::texture::Texture;
+use crate::sampling::{hash_u64, Rng};
+
+/// Índice de los lightmaps de un directorio
+#[cfg(feature = "fs")]
+const LIGHTMAP_INDEX: &str = "lightmaps.json";
+
+/// Puntos de la superficie que procesa cada tarea en paralelo
+const CHUNK_SAMPLES: u32 = 4096;
+/// Texeles de relleno alrededor de las islas UV
+const PADDING: u32 = 4;
+/// Separación de los rayos respecto de la superficie
+const BIAS: f32 = 1e-4;
+
+/// Resolución y cantidad de muestras de un horneado
+#[derive(Debug, Clone, Copy)]
+pub struct BakeSettings {
+    /// Ancho y alto de la textura en texeles
+    pub size: u32,
+    /// Puntos de la superficie por texel (en promedio)
+    pub samples_per_texel: u32,
+    pub seed: u64,
+}
+
+impl BakeSettings {
+    pub fn new(size: u32, samples_per_texel: u32) -> Self {
+        BakeSettings {
+            size: size.max(1),
+            samples_per_texel: samples_per_texel.max(1),
+            seed: 0,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// Oclusión ambiental del objeto `object_id` por texel (1.0 = sin
+/// oclusión, igual en los tres canales). Guardada en una imagen sin
+/// corrección gamma se puede cargar con `Scene::add_map` y asignar con
+/// `Material::with_occlusion_map`. Falla si el objeto no existe, no se
+/// puede muestrear o no tiene coordenadas UV
+pub fn bake_ambient_occlusion(
+    scene: &Scene,
+    object_id: usize,
+    occlusion: &AmbientOcclusion,
+    settings: &BakeSettings,
+) -> Result<Framebuffer, Box<dyn Error>> {
+    let object = scene.object(object_id).ok_or_else(|| format!("no existe el objeto {}", object_id))?;
+    bake(object, settings, |point, normal, rng| {
+        let hit = HitRecord { t: 0.0, point, normal, uv: None, object_id, time: 0.0, viewpoint: point };
+        let visibility = occlusion.visibility(scene, &hit, &normal, BIAS, rng);
+        Color::new(visibility, visibility, visibility)
+    })
+}
+
+/// Iluminación directa e indirecta del objeto `object_id` por texel
+/// (lightmap, en HDR): la luz que reflejaría una superficie difusa blanca
+/// (ver `Renderer::diffuse_lighting`) con hasta `bounces` rebotes
+/// indirectos. Para verla en el render se carga con `Scene::add_map` y se
+/// asigna con `Material::with_lightmap`. Falla como `bake_ambient_occlusion`
+pub fn bake_lighting(
+    scene: &Scene,
+    object_id: usize,
+    bounces: u32,
+    settings: &BakeSettings,
+) -> Result<Framebuffer, Box<dyn Error>> {
+    let object = scene.object(object_id).ok_or_else(|| format!("no existe el objeto {}", object_id))?;
+    bake(object, settings, |point, normal, rng| {
+        let hit = HitRecord { t: 0.0, point, normal, uv: None, object_id, time: 0.0, viewpoint: point };
+        Renderer::diffuse_lighting(&hit, scene, bounces, rng)
+    })
+}
+
+/// Promedia `evaluate` (punto, normal y números aleatorios) en los texeles
+/// de la superficie de `object`
+pub fn bake(
+    object: &dyn Intersectable,
+    settings: &BakeSettings,
+    evaluate: impl Fn(Point3, Vec3, &mut Rng) -> Color + Sync,
+) -> Result<Framebuffer, Box<dyn Error>> {
+    if object.surface_area() <= 0.0 || object.sample_surface(0.5, 0.5).is_none() {
+        return Err("el objeto no se puede hornear: no se puede muestrear su superficie".into());
+    }
+
+    let size = settings.size;
+    let texels = (size * size) as usize;
+    let total = (texels as u64 * settings.samples_per_texel as u64).min(u32::MAX as u64) as u32;
+    let chunks = total.div_ceil(CHUNK_SAMPLES);
+
+    let (sums, counts) = (0..chunks)
+        .into_par_iter()
+        .fold(
+            || (vec![Color::zero(); texels], vec![0u32; texels]),
+            |(mut sums, mut counts), chunk| {
+                let mut rng = Rng::new(hash_u64(settings.seed ^ hash_u64(chunk as u64)));
+                let samples = CHUNK_SAMPLES.min(total - chunk * CHUNK_SAMPLES);
+                for _ in 0..samples {
+                    let Some(point) = object.sample_surface(rng.next_f32(), rng.next_f32()) else {
+                        continue;
+                    };
+                    let Some((u, v, _)) = object.get_uv(&point) else {
+                        continue;
+                    };
+                    let x = ((u.clamp(0.0, 1.0) * size as f32) as u32).min(size - 1);
+                    let y = ((v.clamp(0.0, 1.0) * size as f32) as u32).min(size - 1);
+                    let index = (y * size + x) as usize;
+                    sums[index] += evaluate(point, object.normal_at(&point), &mut rng);
+                    counts[index] += 1;
+                }
+                (sums, counts)
+            },
+        )
+        .reduce(
+            || (vec![Color::zero(); texels], vec![0u32; texels]),
+            |(mut sums, mut counts), (other_sums, other_counts)| {
+                for index in 0..texels {
+                    sums[index] += other_sums[index];
+                    counts[index] += other_counts[index];
+                }
+                (sums, counts)
+            },
+        );
+
+    if counts.iter().all(|&count| count == 0) {
+        return Err("el objeto no se puede hornear: no tiene coordenadas UV".into());
+    }
+
+    let mut pixels: Vec<Option<Color>> = sums
+        .into_iter()
+        .zip(counts)
+        .map(|(sum, count)| (count > 0).then(|| sum / count as f32))
+        .collect();
+    for _ in 0..PADDING {
+        pixels = dilate(&pixels, size);
+    }
+    let pixels = pixels.into_iter().map(|pixel| pixel.unwrap_or_else(Color::zero)).collect();
+    Ok(Framebuffer::from_pixels(size, size, pixels))
+}
+
+/// Rellena cada texel vacío con el promedio de sus vecinos llenos
+fn dilate(pixels: &[Option<Color>], size: u32) -> Vec<Option<Color>> {
+    let size = size as i64;
+    (0..size * size)
+        .map(|index| {
+            if let Some(pixel) = pixels[index as usize] {
+                return Some(pixel);
+            }
+            let (x, y) = (index % size, index / size);
+            let neighbors: Vec<Color> = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)]
+                .iter()
+                .map(|(dx, dy)| (x + dx, y + dy))
+                .filter(|&(nx, ny)| (0..size).contains(&nx) && (0..size).contains(&ny))
+                .filter_map(|(nx, ny)| pixels[(ny * size + nx) as usize])
+                .collect();
+            (!neighbors.is_empty()).then(|| {
+                neighbors.iter().fold(Color::zero(), |sum, color| sum + *color) / neighbors.len() as f32
+            })
+        })
+        .collect()
+}
+
+/// Textura con el resultado de un horneado, para agregarla a la escena con
+/// `Scene::add_map` sin pasar por un archivo
+pub fn to_texture(baked: &Framebuffer) -> Texture {
+    let data = baked
+        .pixels()
+        .chunks_exact(baked.width() as usize)
+        .map(|row| row.to_vec())
+        .collect();
+    Texture {
+        width: baked.width(),
+        height: baked.height(),
+        data,
+        path: None,
+        modified: None,
+    }
+}
+
+/// Lightmaps horneados en un directorio: qué archivo corresponde a cada
+/// objeto. Se guarda como `lightmaps.json` junto a las imágenes EXR
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LightmapIndex {
+    pub size: u32,
+    pub bounces: u32,
+    pub lightmaps: Vec<LightmapEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightmapEntry {
+    /// ID del objeto en la escena
+    pub object: usize,
+    /// Imagen, relativa al directorio del índice
+    pub file: String,
+}
+
+#[cfg(feature = "fs")]
+impl LightmapIndex {
+    pub fn save(&self, dir: &str) -> Result<(), Box<dyn Error>> {
+        let path = std::path::Path::new(dir).join(LIGHTMAP_INDEX);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| format!("no se pudo escribir {}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    pub fn load(dir: &str) -> Result<Self, Box<dyn Error>> {
+        let path = std::path::Path::new(dir).join(LIGHTMAP_INDEX);
+        let text = std::fs::read_to_string(&path).map_err(|e| format!("no se pudo leer {}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    /// Carga los lightmaps de `dir` como mapas de la escena y los asigna
+    /// a los materiales de sus objetos. Retorna cuántos se asignaron
+    pub fn apply(&self, scene: &mut Scene, dir: &str) -> Result<usize, Box<dyn Error>> {
+        for entry in &self.lightmaps {
+            let path = std::path::Path::new(dir).join(&entry.file);
+            let texture = Texture::from_image(&path.to_string_lossy())
+                .map_err(|e| format!("no se pudo leer {}: {}", path.display(), e))?;
+            let map = scene.add_map(texture);
+            let material = scene
+                .material_mut(entry.object)
+                .ok_or_else(|| format!("lightmap de un objeto inexistente o sin material propio: {}", entry.object))?;
+            material.lightmap = Some(map);
+        }
+        Ok(self.lightmaps.len())
+    }
+}
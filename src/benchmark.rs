@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use raytracer::cancel::CancelToken;
+use raytracer::progress::ProgressSink;
+use raytracer::renderer::{Renderer, Tile};
+use raytracer::stats;
+use raytracer::vector::Color;
+
+use crate::cli::Args;
+
+/// Mide cuánto tarda cada bloque: el tiempo entre dos bloques terminados
+/// por el mismo hilo (el primero, desde el inicio del render)
+struct TileTimer {
+    start: Instant,
+    last: Mutex<HashMap<ThreadId, Instant>>,
+    durations: Mutex<Vec<Duration>>,
+}
+
+impl TileTimer {
+    fn new() -> Self {
+        TileTimer {
+            start: Instant::now(),
+            last: Mutex::new(HashMap::new()),
+            durations: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl ProgressSink for TileTimer {
+    fn on_progress(&self, _done: usize, _total: usize) {}
+
+    fn on_tile(&self, _tile: &Tile, _pixels: &[Color]) {
+        let now = Instant::now();
+        let previous = self.last.lock().unwrap().insert(thread::current().id(), now).unwrap_or(self.start);
+        self.durations.lock().unwrap().push(now - previous);
+    }
+}
+
+/// Tiempos de una etapa en todas las repeticiones
+#[derive(Default)]
+struct Timings(Vec<Duration>);
+
+impl Timings {
+    fn millis(&self) -> Vec<f64> {
+        self.0.iter().map(|duration| duration.as_secs_f64() * 1000.0).collect()
+    }
+
+    fn min(&self) -> f64 {
+        self.millis().into_iter().fold(f64::INFINITY, f64::min)
+    }
+
+    fn max(&self) -> f64 {
+        self.millis().into_iter().fold(0.0, f64::max)
+    }
+
+    fn mean(&self) -> f64 {
+        self.millis().iter().sum::<f64>() / self.0.len().max(1) as f64
+    }
+
+    /// Percentil `p` (de 0 a 100), por el método del rango más cercano
+    fn percentile(&self, p: f64) -> f64 {
+        let mut millis = self.millis();
+        millis.sort_by(f64::total_cmp);
+        let rank = ((p / 100.0) * millis.len() as f64).ceil() as usize;
+        millis.get(rank.saturating_sub(1)).copied().unwrap_or(0.0)
+    }
+
+    fn row(&self, name: &str) -> String {
+        format!("  {:<20}{:>12.2}{:>12.2}{:>12.2}", name, self.min(), self.mean(), self.max())
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ "min_ms": self.min(), "mean_ms": self.mean(), "max_ms": self.max(), "runs_ms": self.millis() })
+    }
+}
+
+/// `--benchmark N`: carga y renderiza la escena N veces (siempre en CPU y
+/// sin la caché de renders) y muestra cuánto tardó cada etapa
+pub fn run(args: &Args, runs: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let output = args.output();
+    let (mut scene_build, mut bvh_build, mut render, mut save) =
+        (Timings::default(), Timings::default(), Timings::default(), Timings::default());
+    let mut tiles = Timings::default();
+    let mut mrays = Vec::new();
+    let mut resolution = (0, 0, 0);
+
+    for run in 1..=runs.max(1) {
+        println!("Benchmark: render {} de {}...", run, runs.max(1));
+
+        let start = Instant::now();
+        let mut scene = crate::load_scene(args)?;
+        scene_build.0.push(start.elapsed());
+        resolution = (scene.settings.width, scene.settings.height, scene.settings.samples_per_pixel);
+
+        let start = Instant::now();
+        scene.rebuild_tlas();
+        bvh_build.0.push(start.elapsed());
+
+        let timer = TileTimer::new();
+        stats::reset();
+        let start = Instant::now();
+        let framebuffer = Renderer::render_cancellable(&scene, &timer, &CancelToken::new());
+        let elapsed = start.elapsed();
+        render.0.push(elapsed);
+        tiles.0.extend(timer.durations.into_inner().unwrap());
+        mrays.push(stats::snapshot().mrays_per_second(elapsed));
+
+        let start = Instant::now();
+        crate::save_output(args, &scene.development, &framebuffer, output)?;
+        save.0.push(start.elapsed());
+    }
+
+    let (width, height, samples) = resolution;
+    let threads = rayon::current_num_threads();
+    let mean_mrays = mrays.iter().sum::<f64>() / mrays.len().max(1) as f64;
+    println!();
+    println!("Benchmark: {} render(s) de {}x{}, {} muestras por píxel, {} hilos", runs.max(1), width, height, samples, threads);
+    println!("  {:<20}{:>12}{:>12}{:>12}", "Etapa", "mín (ms)", "media (ms)", "máx (ms)");
+    println!("{}", scene_build.row("Construir escena"));
+    println!("{}", bvh_build.row("Construir BVH"));
+    println!("{}", render.row("Render"));
+    println!("{}", tiles.row("Bloque"));
+    println!("{}", save.row("Guardar imagen"));
+    println!(
+        "  Bloques: {} por render, p50 {:.2} ms, p95 {:.2} ms",
+        tiles.0.len() / runs.max(1) as usize,
+        tiles.percentile(50.0),
+        tiles.percentile(95.0)
+    );
+    println!("  {:.2} Mrayos/s de media", mean_mrays);
+
+    if let Some(path) = &args.benchmark_json {
+        let report = json!({
+            "scene": args.scene.as_deref().unwrap_or("ejemplo"),
+            "runs": runs.max(1),
+            "width": width,
+            "height": height,
+            "samples_per_pixel": samples,
+            "threads": threads,
+            "stages": {
+                "scene_build": scene_build.to_json(),
+                "bvh_build": bvh_build.to_json(),
+                "render": render.to_json(),
+                "save": save.to_json(),
+            },
+            "tiles": {
+                "count": tiles.0.len(),
+                "min_ms": tiles.min(),
+                "mean_ms": tiles.mean(),
+                "p50_ms": tiles.percentile(50.0),
+                "p95_ms": tiles.percentile(95.0),
+                "max_ms": tiles.max(),
+            },
+            "mrays_per_second": mrays,
+        });
+        std::fs::write(path, serde_json::to_string_pretty(&report)? + "\n")
+            .map_err(|e| format!("no se pudo escribir {}: {}", path, e))?;
+        println!("✓ Tiempos guardados en: {}", path);
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::f32::consts::PI;
+
+use crate::vector::Vec3;
+use crate::sampling::orthonormal_basis;
+
+// Funciones del modelo de microfacetas GGX (Trowbridge-Reitz) para los
+// reflejos brillantes con rugosidad. `alpha` es la rugosidad al cuadrado,
+// la parametrización habitual que hace la rugosidad perceptualmente lineal.
+
+/// Convierte la rugosidad del material (0 = espejo, 1 = muy mate) en alpha
+pub fn roughness_to_alpha(roughness: f32) -> f32 {
+    (roughness * roughness).max(1e-4)
+}
+
+/// Distribución de normales D(h) de GGX
+pub fn ggx_distribution(n_dot_h: f32, alpha: f32) -> f32 {
+    if n_dot_h <= 0.0 {
+        return 0.0;
+    }
+    let a2 = alpha * alpha;
+    let denom = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    a2 / (PI * denom * denom)
+}
+
+/// Término de sombreado-enmascaramiento de Smith para una dirección
+pub fn smith_g1(n_dot_v: f32, alpha: f32) -> f32 {
+    if n_dot_v <= 0.0 {
+        return 0.0;
+    }
+    let a2 = alpha * alpha;
+    2.0 * n_dot_v / (n_dot_v + (a2 + (1.0 - a2) * n_dot_v * n_dot_v).sqrt())
+}
+
+/// Normal de microfaceta muestreada con densidad D(h)·cosθh
+/// (u, v) en [0, 1)²; el resultado está en el hemisferio de `normal`
+pub fn sample_ggx_half_vector(normal: &Vec3, alpha: f32, u: f32, v: f32) -> Vec3 {
+    let phi = 2.0 * PI * u;
+    let cos_theta = ((1.0 - v) / (1.0 + (alpha * alpha - 1.0) * v)).max(0.0).sqrt();
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    (tangent * (sin_theta * phi.cos()) + bitangent * (sin_theta * phi.sin()) + *normal * cos_theta).normalize()
+}
+
+/// Peso f·cosθ/pdf de una reflexión muestreada con `sample_ggx_half_vector`
+/// `wo` apunta hacia el observador y `wi` hacia la luz reflejada
+/// Con ese muestreo D se cancela y queda G·(wo·h) / ((n·wo)(n·h))
+pub fn ggx_sample_weight(normal: &Vec3, wo: &Vec3, wi: &Vec3, half: &Vec3, alpha: f32) -> f32 {
+    let n_dot_o = normal.dot(wo);
+    let n_dot_i = normal.dot(wi);
+    let n_dot_h = normal.dot(half);
+    if n_dot_o <= 0.0 || n_dot_i <= 0.0 || n_dot_h <= 0.0 {
+        return 0.0;
+    }
+
+    let g = smith_g1(n_dot_o, alpha) * smith_g1(n_dot_i, alpha);
+    g * wo.dot(half).max(0.0) / (n_dot_o * n_dot_h)
+}
@@ -0,0 +1,374 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Point3;
+use crate::ray::Ray;
+use crate::aabb::Aabb;
+use crate::packet::{self, RayPacket, PacketHits, LANES};
+use crate::stats::{self, Counter};
+
+/// Máximo de primitivas en una hoja
+const MAX_LEAF_SIZE: usize = 4;
+
+/// Intervalos por eje en los que se agrupan los centroides al evaluar la SAH
+const SAH_BINS: usize = 12;
+
+/// Costo de atravesar un nodo relativo al de probar una primitiva
+const TRAVERSAL_COST: f32 = 1.0;
+
+/// Criterio para dividir los nodos al construir la jerarquía
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SplitMethod {
+    /// Mediana de los centroides en el eje más largo: rápida de construir,
+    /// pero deja nodos muy solapados cuando los objetos tienen tamaños dispares
+    Median,
+    /// Heurística de área de superficie: elige el corte que minimiza el costo
+    /// esperado de recorrido (área de cada hijo × primitivas que contiene)
+    #[default]
+    Sah,
+}
+
+/// Nodo de la BVH guardado en un arreglo plano
+/// En los nodos internos `first` es el índice del hijo izquierdo (el derecho
+/// le sigue); en las hojas es el inicio de sus primitivas en `items`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct BvhNode {
+    bounds: Aabb,
+    first: usize,
+    count: usize, // 0 = nodo interno
+}
+
+/// Jerarquía de volúmenes envolventes sobre primitivas identificadas por un
+/// índice. La misma estructura se usa como BLAS (triángulos de una malla) y
+/// como TLAS (objetos e instancias de la escena).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    items: Vec<(usize, Aabb)>,
+}
+
+impl Bvh {
+    /// Construye la jerarquía sobre pares (índice de primitiva, caja)
+    /// dividiendo los nodos con la heurística de área de superficie
+    pub fn build(items: Vec<(usize, Aabb)>) -> Self {
+        Self::build_with(items, SplitMethod::Sah)
+    }
+
+    /// Construye la jerarquía con el criterio de división indicado
+    pub fn build_with(items: Vec<(usize, Aabb)>, method: SplitMethod) -> Self {
+        let mut bvh = Bvh {
+            nodes: Vec::new(),
+            items,
+        };
+        if !bvh.items.is_empty() {
+            let count = bvh.items.len();
+            bvh.nodes.push(BvhNode { bounds: Aabb::empty(), first: 0, count });
+            bvh.subdivide(0, 0, count, method);
+        }
+        bvh
+    }
+
+    fn subdivide(&mut self, node: usize, start: usize, end: usize, method: SplitMethod) {
+        let items = &mut self.items[start..end];
+        let bounds = items.iter().fold(Aabb::empty(), |acc, (_, b)| acc.union(b));
+        self.nodes[node].bounds = bounds;
+
+        if items.len() <= MAX_LEAF_SIZE {
+            self.nodes[node].first = start;
+            self.nodes[node].count = items.len();
+            return;
+        }
+
+        let centroids = items.iter().fold(Aabb::empty(), |acc, (_, b)| acc.grow(&b.centroid()));
+        let sah_split = match method {
+            SplitMethod::Sah => sah_partition(items, &bounds, &centroids),
+            SplitMethod::Median => None,
+        };
+        // Si la SAH no encuentra un corte útil (p. ej. centroides iguales) se usa la mediana
+        let mid = sah_split.unwrap_or_else(|| {
+            let axis = centroids.longest_axis();
+            let mid = items.len() / 2;
+            items.select_nth_unstable_by(mid, |(_, a), (_, b)| {
+                a.centroid()[axis].total_cmp(&b.centroid()[axis])
+            });
+            mid
+        });
+
+        let left = self.nodes.len();
+        self.nodes.push(BvhNode { bounds: Aabb::empty(), first: 0, count: 0 });
+        self.nodes.push(BvhNode { bounds: Aabb::empty(), first: 0, count: 0 });
+        self.nodes[node].first = left;
+        self.nodes[node].count = 0;
+
+        self.subdivide(left, start, start + mid, method);
+        self.subdivide(left + 1, start + mid, end, method);
+    }
+
+    /// Actualiza las cajas sin cambiar la estructura, para objetos que se
+    /// movieron un poco (p. ej. entre cuadros de una animación). Es mucho más
+    /// barato que reconstruir, aunque la calidad baja si se mueven mucho.
+    /// `bounds_of` da la caja nueva de cada primitiva; si alguna deja de
+    /// tenerla retorna false y la jerarquía debe reconstruirse.
+    pub fn refit<F>(&mut self, mut bounds_of: F) -> bool
+    where
+        F: FnMut(usize) -> Option<Aabb>,
+    {
+        for (item, bounds) in self.items.iter_mut() {
+            match bounds_of(*item) {
+                Some(new_bounds) => *bounds = new_bounds,
+                None => return false,
+            }
+        }
+
+        // Los hijos siempre están después de su padre en el arreglo, así que
+        // recorriéndolo al revés cada nodo se ajusta después de sus hijos
+        for index in (0..self.nodes.len()).rev() {
+            let node = self.nodes[index];
+            let bounds = if node.count > 0 {
+                self.items[node.first..node.first + node.count]
+                    .iter()
+                    .fold(Aabb::empty(), |acc, (_, b)| acc.union(b))
+            } else {
+                self.nodes[node.first].bounds.union(&self.nodes[node.first + 1].bounds)
+            };
+            self.nodes[index].bounds = bounds;
+        }
+
+        true
+    }
+
+    /// Costo SAH estimado de recorrer la jerarquía (para comparar construcciones)
+    pub fn cost(&self) -> f32 {
+        let root_area = match self.bounds() {
+            Some(bounds) if bounds.surface_area() > 0.0 => bounds.surface_area(),
+            _ => return 0.0,
+        };
+        self.nodes
+            .iter()
+            .map(|node| {
+                let weight = node.bounds.surface_area() / root_area;
+                if node.count > 0 { weight * node.count as f32 } else { weight * TRAVERSAL_COST }
+            })
+            .sum()
+    }
+
+    /// Caja que envuelve todas las primitivas (None si no hay ninguna)
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.nodes.first().map(|node| node.bounds)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Intersección más cercana: `intersect_item` calcula la distancia a la
+    /// primitiva indicada y solo se llama para las hojas que el rayo atraviesa
+    pub fn intersect<F>(&self, ray: &Ray, intersect_item: F) -> Option<(f32, usize)>
+    where
+        F: FnMut(usize, &Ray) -> Option<f32>,
+    {
+        self.intersect_within(ray, f32::INFINITY, intersect_item)
+    }
+
+    /// Como `intersect`, pero descarta las intersecciones más allá de
+    /// `max_distance`; los nodos que empiezan después ni se visitan
+    pub fn intersect_within<F>(&self, ray: &Ray, max_distance: f32, mut intersect_item: F) -> Option<(f32, usize)>
+    where
+        F: FnMut(usize, &Ray) -> Option<f32>,
+    {
+        let mut closest: Option<(f32, usize)> = None;
+        let mut stack = Vec::with_capacity(64);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            stats::count(Counter::BvhNodeVisits);
+            let node = &self.nodes[index];
+            let t_max = closest.map_or(max_distance, |(t, _)| t);
+            if !node.bounds.hit(ray, t_max) {
+                continue;
+            }
+
+            if node.count > 0 {
+                stats::add(Counter::IntersectionTests, node.count as u64);
+                for &(item, _) in &self.items[node.first..node.first + node.count] {
+                    if let Some(t) = intersect_item(item, ray) {
+                        if t < closest.map_or(max_distance, |(best, _)| best) {
+                            closest = Some((t, item));
+                        }
+                    }
+                }
+            } else {
+                stack.push(node.first + 1);
+                stack.push(node.first);
+            }
+        }
+
+        closest
+    }
+
+    /// Versión para paquetes de rayos: un nodo se visita si al menos uno de
+    /// los rayos atraviesa su caja, y cada primitiva se prueba con todo el
+    /// paquete a la vez
+    pub fn intersect_packet<F>(&self, rays: &RayPacket, intersect_item: F) -> [Option<(f32, usize)>; LANES]
+    where
+        F: FnMut(usize, &RayPacket) -> PacketHits,
+    {
+        self.intersect_packet_within(rays, f32::INFINITY, intersect_item)
+    }
+
+    /// Versión para paquetes de `intersect_within`
+    pub fn intersect_packet_within<F>(
+        &self,
+        rays: &RayPacket,
+        max_distance: f32,
+        mut intersect_item: F,
+    ) -> [Option<(f32, usize)>; LANES]
+    where
+        F: FnMut(usize, &RayPacket) -> PacketHits,
+    {
+        let mut closest: [Option<(f32, usize)>; LANES] = [None; LANES];
+        let mut stack = Vec::with_capacity(64);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            stats::count(Counter::BvhNodeVisits);
+            let node = &self.nodes[index];
+            let t_max = closest.map(|hit| hit.map_or(max_distance, |(t, _)| t));
+            if !packet::hit_bounds(rays, &node.bounds, &t_max).contains(&true) {
+                continue;
+            }
+
+            if node.count > 0 {
+                stats::add(Counter::IntersectionTests, node.count as u64);
+                for &(item, _) in &self.items[node.first..node.first + node.count] {
+                    let hits = intersect_item(item, rays);
+                    for lane in 0..LANES {
+                        if let Some(t) = hits[lane] {
+                            if t < closest[lane].map_or(max_distance, |(best, _)| best) {
+                                closest[lane] = Some((t, item));
+                            }
+                        }
+                    }
+                }
+            } else {
+                stack.push(node.first + 1);
+                stack.push(node.first);
+            }
+        }
+
+        closest
+    }
+
+    /// Nodos en el orden del arreglo interno como (caja, first, count) junto
+    /// con los índices de primitiva en el orden en que las referencian las
+    /// hojas; sirve para copiar la jerarquía a otra estructura (p. ej. la GPU)
+    pub fn flatten(&self) -> (Vec<(Aabb, usize, usize)>, Vec<usize>) {
+        let nodes = self.nodes.iter().map(|node| (node.bounds, node.first, node.count)).collect();
+        let order = self.items.iter().map(|(item, _)| *item).collect();
+        (nodes, order)
+    }
+
+    /// Llama a `visit` para cada primitiva cuya caja (agrandada `margin`)
+    /// contiene el punto
+    pub fn query_point<F>(&self, point: &Point3, margin: f32, mut visit: F)
+    where
+        F: FnMut(usize),
+    {
+        let mut stack = Vec::with_capacity(64);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !node.bounds.expand(margin).contains(point) {
+                continue;
+            }
+
+            if node.count > 0 {
+                for (item, bounds) in &self.items[node.first..node.first + node.count] {
+                    if bounds.expand(margin).contains(point) {
+                        visit(*item);
+                    }
+                }
+            } else {
+                stack.push(node.first + 1);
+                stack.push(node.first);
+            }
+        }
+    }
+}
+
+/// Busca el corte de menor costo SAH agrupando los centroides en intervalos
+/// sobre cada eje, y reordena `items` para que los del hijo izquierdo queden
+/// primero. Retorna cuántos van a la izquierda, o None si ningún corte es útil.
+fn sah_partition(items: &mut [(usize, Aabb)], bounds: &Aabb, centroids: &Aabb) -> Option<usize> {
+    let parent_area = bounds.surface_area();
+    if parent_area <= 0.0 {
+        return None;
+    }
+
+    let bin_of = |item: &Aabb, axis: usize| {
+        let min = centroids.min[axis];
+        let extent = centroids.max[axis] - min;
+        let bin = ((item.centroid()[axis] - min) / extent * SAH_BINS as f32) as usize;
+        bin.min(SAH_BINS - 1)
+    };
+
+    // Mejor (costo, eje, primer intervalo del hijo derecho)
+    let mut best: Option<(f32, usize, usize)> = None;
+    for axis in 0..3 {
+        if centroids.max[axis] - centroids.min[axis] <= 0.0 {
+            continue;
+        }
+
+        let mut counts = [0usize; SAH_BINS];
+        let mut bin_bounds = [Aabb::empty(); SAH_BINS];
+        for (_, item) in items.iter() {
+            let bin = bin_of(item, axis);
+            counts[bin] += 1;
+            bin_bounds[bin] = bin_bounds[bin].union(item);
+        }
+
+        // Barrido desde la derecha para conocer área y cantidad de cada sufijo
+        let mut right_cost = [0.0f32; SAH_BINS];
+        let mut right_box = Aabb::empty();
+        let mut right_count = 0;
+        for bin in (1..SAH_BINS).rev() {
+            right_box = right_box.union(&bin_bounds[bin]);
+            right_count += counts[bin];
+            right_cost[bin] = right_box.surface_area() * right_count as f32;
+        }
+
+        let mut left_box = Aabb::empty();
+        let mut left_count = 0;
+        for split in 1..SAH_BINS {
+            left_box = left_box.union(&bin_bounds[split - 1]);
+            left_count += counts[split - 1];
+            if left_count == 0 || left_count == items.len() {
+                continue;
+            }
+            let cost = TRAVERSAL_COST + (left_box.surface_area() * left_count as f32 + right_cost[split]) / parent_area;
+            if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                best = Some((cost, axis, split));
+            }
+        }
+    }
+
+    let (_, axis, split) = best?;
+    let mut left = 0;
+    for index in 0..items.len() {
+        if bin_of(&items[index].1, axis) < split {
+            items.swap(index, left);
+            left += 1;
+        }
+    }
+    (left > 0 && left < items.len()).then_some(left)
+}
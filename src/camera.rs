@@ -1,15 +1,93 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
 use crate::vector::{Point3, Vec3};
 use crate::ray::Ray;
+use crate::sampling::concentric_disk;
+use crate::render_cache::hash_f32;
+
+/// Tiempo de exposición e ISO con los que la escala de exposición vale 1
+const REFERENCE_EXPOSURE_TIME: f32 = 1.0 / 125.0;
+const REFERENCE_ISO: f32 = 100.0;
+
+/// Cómo se convierte un punto de la imagen en una dirección de la cámara
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Projection {
+    /// Proyección en perspectiva sobre un plano (usa `fov` de la cámara)
+    #[default]
+    Perspective,
+    /// Ojo de pez: el ángulo con el eje de la cámara depende de la distancia
+    /// al centro de la imagen. `fov` (en grados, puede superar 180°) es el
+    /// campo que cubre el lado más corto de la imagen, así que el círculo
+    /// de la lente queda inscrito en ella; las esquinas ven más allá.
+    Fisheye { mapping: FisheyeMapping, fov: f32 },
+    /// Panorama de 360°×180°: la coordenada horizontal es la longitud y la
+    /// vertical la latitud alrededor de la cámara (conviene una imagen 2:1).
+    /// Con la cámara nivelada mirando hacia +X sigue la misma convención que
+    /// `Environment::Equirectangular`, así que el resultado sirve como entorno
+    Equirectangular,
+}
+
+/// Relación entre la distancia al centro y el ángulo en un ojo de pez
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum FisheyeMapping {
+    /// El ángulo es proporcional a la distancia (domos, planetarios)
+    #[default]
+    Equidistant,
+    /// Cada zona de la imagen cubre el mismo ángulo sólido (lentes fotográficas)
+    Equisolid,
+}
+
+impl Projection {
+    /// Agrega la proyección al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        match *self {
+            Projection::Perspective => state.write_u8(0),
+            Projection::Fisheye { mapping, fov } => {
+                state.write_u8(1);
+                state.write_u8(mapping as u8);
+                hash_f32(state, fov);
+            }
+            Projection::Equirectangular => state.write_u8(2),
+        }
+    }
+}
 
 /// Estructura de cámara que define la vista y parámetros de renderizado
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Camera {
     pub position: Point3,
     pub look_at: Point3,
     pub up: Vec3,
+    /// Giro alrededor del eje de visión, en grados (positivo = antihorario
+    /// visto desde detrás de la cámara, el horizonte se inclina en sentido horario)
+    pub roll: f32,
     pub fov: f32,
     pub aspect_ratio: f32,
     pub width: u32,
     pub height: u32,
+    pub projection: Projection,
+    /// Desplazamiento del plano de visión (solo en perspectiva), en
+    /// fracciones del ancho y del alto de la imagen: mueve el encuadre sin
+    /// girar la cámara, así que las líneas verticales siguen paralelas
+    pub shift_x: f32,
+    pub shift_y: f32,
+
+    // Profundidad de campo (lente delgada); aperture = 0 es una cámara estenopeica
+    pub aperture: f32,       // Radio de la lente
+    pub focus_distance: f32, // Distancia al plano enfocado
+
+    // Exposición fotográfica: escala la radiancia antes del tone mapping
+    // como lo haría una cámara real (cada paso de EV duplica la luz)
+    pub exposure_compensation: f32, // En pasos (EV)
+    pub shutter_speed: f32,         // Tiempo de exposición en segundos
+    pub iso: f32,
+
+    // Motion blur: intervalo de obturación y desplazamiento de la cámara por unidad de tiempo
+    pub shutter_open: f32,
+    pub shutter_close: f32,
+    pub velocity: Vec3,
 
     // Vectores internos calculados
     forward: Vec3,
@@ -37,10 +115,22 @@ impl Camera {
             position,
             look_at,
             up: up.normalize(),
+            roll: 0.0,
             fov,
             aspect_ratio,
             width,
             height,
+            projection: Projection::Perspective,
+            shift_x: 0.0,
+            shift_y: 0.0,
+            aperture: 0.0,
+            focus_distance: (look_at - position).length().max(1e-3),
+            exposure_compensation: 0.0,
+            shutter_speed: REFERENCE_EXPOSURE_TIME,
+            iso: REFERENCE_ISO,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
+            velocity: Vec3::zero(),
             forward: Vec3::zero(),
             right: Vec3::zero(),
             up_normalized: Vec3::zero(),
@@ -55,12 +145,273 @@ impl Camera {
         camera
     }
 
+    /// Crea una cámara a partir de su matriz de transformación (cámara →
+    /// mundo), como la exportan Blender o glTF: la cámara mira hacia -Z
+    /// local, +Y local es arriba y la última columna es la posición
+    /// La matriz se indexa `[fila][columna]` (p' = M·p); los arreglos de
+    /// glTF vienen por columnas y hay que trasponerlos. La distancia de
+    /// enfoque queda en 1; se cambia con `with_depth_of_field`
+    pub fn from_matrix(
+        camera_to_world: [[f32; 4]; 4],
+        fov: f32,
+        aspect_ratio: f32,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        let m = camera_to_world;
+        let position = Point3::new(m[0][3], m[1][3], m[2][3]);
+        let up = Vec3::new(m[0][1], m[1][1], m[2][1]);
+        let forward = -Vec3::new(m[0][2], m[1][2], m[2][2]).normalize();
+
+        Camera::new(position, position + forward, up, fov, aspect_ratio, width, height)
+    }
+
+    /// Crea una cámara a partir de una matriz de vista (mundo → cámara, como
+    /// la de `lookAt` en OpenGL), que es la inversa de la de `from_matrix`
+    /// Se asume que es una transformación rígida (rotación y traslación)
+    pub fn from_view_matrix(
+        view: [[f32; 4]; 4],
+        fov: f32,
+        aspect_ratio: f32,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        // Inversa de una transformación rígida: rotación traspuesta y -Rᵀ·t
+        let mut camera_to_world = [[0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        for row in 0..3 {
+            for column in 0..3 {
+                camera_to_world[row][column] = view[column][row];
+            }
+            camera_to_world[row][3] = -(0..3).map(|k| view[k][row] * view[k][3]).sum::<f32>();
+        }
+
+        Camera::from_matrix(camera_to_world, fov, aspect_ratio, width, height)
+    }
+
+    /// Matriz cámara → mundo con la convención de `from_matrix`: columnas
+    /// derecha, arriba, atrás (la cámara mira hacia -Z local) y posición.
+    /// Incluye el giro de `roll`
+    pub fn camera_to_world(&self) -> [[f32; 4]; 4] {
+        let columns = [self.right, self.up_normalized, -self.forward, self.position];
+        let mut matrix = [[0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        for (column, vector) in columns.iter().enumerate() {
+            matrix[0][column] = vector.x;
+            matrix[1][column] = vector.y;
+            matrix[2][column] = vector.z;
+        }
+        matrix
+    }
+
+    /// Matriz de vista (mundo → cámara), la inversa de `camera_to_world` y
+    /// la que recibe `from_view_matrix`
+    pub fn view_matrix(&self) -> [[f32; 4]; 4] {
+        let camera_to_world = self.camera_to_world();
+        let mut view = [[0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]];
+        for row in 0..3 {
+            for column in 0..3 {
+                view[row][column] = camera_to_world[column][row];
+            }
+            view[row][3] = -(0..3).map(|k| camera_to_world[k][row] * camera_to_world[k][3]).sum::<f32>();
+        }
+        view
+    }
+
+    /// Parámetros intrínsecos en píxeles (fx, fy, cx, cy) de la proyección
+    /// en perspectiva, con el origen en la esquina superior izquierda y la
+    /// Y hacia abajo como en OpenCV. None con otras proyecciones
+    pub fn intrinsics(&self) -> Option<(f32, f32, f32, f32)> {
+        if self.projection != Projection::Perspective {
+            return None;
+        }
+        let (width, height) = (self.width as f32, self.height as f32);
+        Some((
+            width / self.viewport_width,
+            height / self.viewport_height,
+            width * (0.5 - self.shift_x),
+            height * (0.5 + self.shift_y),
+        ))
+    }
+
+    /// Activa la profundidad de campo con el radio de apertura y la distancia
+    /// de enfoque dados; los objetos fuera de ese plano se ven desenfocados
+    pub fn with_depth_of_field(mut self, aperture: f32, focus_distance: f32) -> Self {
+        self.aperture = aperture.max(0.0);
+        self.focus_distance = focus_distance.max(1e-3);
+        self
+    }
+
+    /// Cambia la proyección (perspectiva, ojo de pez o panorama)
+    pub fn with_projection(mut self, projection: Projection) -> Self {
+        self.projection = projection;
+        self
+    }
+
+    /// Ojo de pez con el mapeo y el campo de visión (en grados) dados
+    pub fn with_fisheye(self, mapping: FisheyeMapping, fov: f32) -> Self {
+        self.with_projection(Projection::Fisheye { mapping, fov: fov.clamp(1.0, 360.0) })
+    }
+
+    /// Descentra la proyección (lente descentrable): con la cámara nivelada
+    /// y `shift_y` > 0 se encuadra la parte alta de un edificio sin que las
+    /// paredes converjan. También sirve para dividir un render grande en
+    /// partes: cada parte es la misma cámara con otro desplazamiento
+    pub fn with_lens_shift(mut self, shift_x: f32, shift_y: f32) -> Self {
+        self.shift_x = shift_x;
+        self.shift_y = shift_y;
+        self.update_vectors();
+        self
+    }
+
+    /// Panorama equirectangular de 360°
+    pub fn with_equirectangular(self) -> Self {
+        self.with_projection(Projection::Equirectangular)
+    }
+
+    /// Cambia la relación de aspecto (ancho / alto) y recalcula el plano de visión
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: f32) {
+        self.aspect_ratio = aspect_ratio;
+        self.update_vectors();
+    }
+
+    /// Cambia la resolución por un factor (p. ej. 0.25 para una vista previa
+    /// rápida). La relación de aspecto no cambia, así que el encuadre es el
+    /// mismo que a resolución completa
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        let scale = scale.max(0.0);
+        self.width = ((self.width as f32 * scale).round() as u32).max(1);
+        self.height = ((self.height as f32 * scale).round() as u32).max(1);
+        self
+    }
+
+    /// Mueve la cámara a `position` mirando a `look_at` y recalcula la base
+    pub fn set_view(&mut self, position: Point3, look_at: Point3) {
+        self.position = position;
+        self.look_at = look_at;
+        self.update_vectors();
+    }
+
+    /// Copia de la cámara en órbita alrededor de `target`, a distancia
+    /// `radius` y mirando hacia él. `azimuth` (en grados) gira alrededor del
+    /// eje Y empezando en +Z hacia +X; `elevation` (en grados) sube desde el
+    /// plano horizontal
+    pub fn orbit(&self, target: Point3, radius: f32, azimuth: f32, elevation: f32) -> Camera {
+        let (sin_azimuth, cos_azimuth) = azimuth.to_radians().sin_cos();
+        let (sin_elevation, cos_elevation) = elevation.clamp(-89.9, 89.9).to_radians().sin_cos();
+        let offset = Vec3::new(cos_elevation * sin_azimuth, sin_elevation, cos_elevation * cos_azimuth);
+
+        let mut camera = self.clone();
+        camera.up = Vec3::new(0.0, 1.0, 0.0);
+        camera.focus_distance = radius.max(1e-3);
+        camera.set_view(target + offset * radius, target);
+        camera
+    }
+
+    /// `frames` cámaras repartidas en una vuelta completa alrededor de
+    /// `target` (plato giratorio); la última queda justo antes de cerrar la
+    /// vuelta, así que la secuencia se puede repetir sin saltos
+    pub fn turntable(&self, target: Point3, radius: f32, elevation: f32, frames: u32) -> Vec<Camera> {
+        (0..frames)
+            .map(|frame| {
+                let azimuth = 360.0 * frame as f32 / frames as f32;
+                self.orbit(target, radius, azimuth, elevation)
+            })
+            .collect()
+    }
+
+    /// Inclina la cámara `degrees` grados alrededor de su eje de visión
+    /// (plano holandés) sin tener que calcular otro vector `up`
+    pub fn with_roll(mut self, degrees: f32) -> Self {
+        self.set_roll(degrees);
+        self
+    }
+
+    /// Cambia el giro alrededor del eje de visión y recalcula la base
+    pub fn set_roll(&mut self, degrees: f32) {
+        self.roll = degrees;
+        self.update_vectors();
+    }
+
+    /// Copia de la cámara desplazada `offset` unidades hacia su derecha
+    /// (negativo = izquierda) sin girarla, para renderizar cada ojo en estéreo
+    pub fn with_eye_offset(&self, offset: f32) -> Camera {
+        let shift = self.right * offset;
+        let mut camera = self.clone();
+        camera.position = self.position + shift;
+        camera.look_at = self.look_at + shift;
+        camera.update_vectors();
+        camera
+    }
+
+    /// Compensación de exposición en pasos (+1 EV = el doble de luz)
+    pub fn with_exposure_compensation(mut self, ev: f32) -> Self {
+        self.exposure_compensation = ev;
+        self
+    }
+
+    /// Tiempo de exposición en segundos; no afecta al motion blur, que usa
+    /// el intervalo de `with_shutter`
+    pub fn with_shutter_speed(mut self, seconds: f32) -> Self {
+        self.shutter_speed = seconds.max(0.0);
+        self
+    }
+
+    pub fn with_iso(mut self, iso: f32) -> Self {
+        self.iso = iso.max(0.0);
+        self
+    }
+
+    /// Factor por el que se multiplica la radiancia que llega a la cámara
+    /// Con 1/125 s, ISO 100 y sin compensación vale 1, así que las escenas
+    /// que no tocan estos parámetros se ven igual que antes
+    pub fn exposure_scale(&self) -> f32 {
+        self.exposure_compensation.exp2()
+            * (self.shutter_speed / REFERENCE_EXPOSURE_TIME)
+            * (self.iso / REFERENCE_ISO)
+    }
+
+    /// Intervalo en el que el obturador está abierto; los objetos que se
+    /// mueven durante ese intervalo aparecen desenfocados por el movimiento
+    pub fn with_shutter(mut self, open: f32, close: f32) -> Self {
+        self.shutter_open = open;
+        self.shutter_close = close.max(open);
+        self
+    }
+
+    /// Desplazamiento de la cámara por unidad de tiempo durante la exposición
+    pub fn with_motion(mut self, velocity: Vec3) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Indica si el obturador permanece abierto un intervalo no nulo
+    pub fn has_motion_blur(&self) -> bool {
+        self.shutter_close > self.shutter_open
+    }
+
+    /// Convierte una muestra en [0, 1) en un instante del intervalo de obturación
+    pub fn shutter_time(&self, sample: f32) -> f32 {
+        self.shutter_open + (self.shutter_close - self.shutter_open) * sample
+    }
+
+    /// Traslada un rayo de cámara al instante `time`, moviendo su origen con la cámara
+    pub fn at_time(&self, ray: Ray, time: f32) -> Ray {
+        Ray::new(ray.origin + self.velocity * time, ray.direction).with_time(time)
+    }
+
     fn update_vectors(&mut self) {
         // Calcular vectores de la cámara
         self.forward = (self.look_at - self.position).normalize();
         self.right = self.forward.cross(&self.up).normalize();
         self.up_normalized = self.right.cross(&self.forward).normalize();
 
+        // Girar la base alrededor del eje de visión
+        if self.roll != 0.0 {
+            let (sin, cos) = self.roll.to_radians().sin_cos();
+            let right = self.right * cos + self.up_normalized * sin;
+            self.up_normalized = self.up_normalized * cos - self.right * sin;
+            self.right = right;
+        }
+
         // Calcular dimensiones del viewport
         let theta = self.fov.to_radians();
         let h = (theta / 2.0).tan();
@@ -76,18 +427,75 @@ impl Camera {
             self.position +
             self.forward -
             self.horizontal / 2.0 -
-            self.vertical / 2.0;
+            self.vertical / 2.0 +
+            self.horizontal * self.shift_x +
+            self.vertical * self.shift_y;
+    }
+
+    /// Plano de visión: esquina inferior izquierda y vectores que lo recorren
+    /// horizontal y verticalmente (el rayo (u, v) pasa por esquina + horizontal·u + vertical·v)
+    pub fn viewport(&self) -> (Point3, Vec3, Vec3) {
+        (self.lower_left_corner, self.horizontal, self.vertical)
     }
 
     /// Genera un rayo desde la cámara hacia coordenadas (u, v) del framebuffer
     /// u y v están en el rango [0, 1]
     pub fn get_ray(&self, u: f32, v: f32) -> Ray {
-        let direction =
-            self.lower_left_corner +
-            self.horizontal * u +
-            self.vertical * v -
-            self.position;
+        Ray::new(self.position, self.direction(u, v).normalize())
+    }
+
+    /// Dirección (sin normalizar) del rayo que pasa por (u, v)
+    /// En perspectiva llega hasta el plano de visión, a distancia 1 de la
+    /// cámara; en las demás proyecciones es unitaria
+    fn direction(&self, u: f32, v: f32) -> Vec3 {
+        match self.projection {
+            Projection::Perspective => {
+                self.lower_left_corner +
+                self.horizontal * u +
+                self.vertical * v -
+                self.position
+            }
+            Projection::Fisheye { mapping, fov } => {
+                // Coordenadas centradas, con el lado más corto en [-1, 1]
+                let shorter = self.aspect_ratio.min(1.0);
+                let x = (2.0 * u - 1.0) * self.aspect_ratio / shorter;
+                let y = (2.0 * v - 1.0) / shorter;
+                let r = (x * x + y * y).sqrt();
+
+                let half_fov = fov.to_radians() / 2.0;
+                let theta = match mapping {
+                    FisheyeMapping::Equidistant => r * half_fov,
+                    FisheyeMapping::Equisolid => 2.0 * (r * (half_fov / 2.0).sin()).min(1.0).asin(),
+                };
+                let phi = y.atan2(x);
+
+                self.forward * theta.cos()
+                    + (self.right * phi.cos() + self.up_normalized * phi.sin()) * theta.sin()
+            }
+            Projection::Equirectangular => {
+                let longitude = (u - 0.5) * 2.0 * std::f32::consts::PI;
+                let latitude = (v - 0.5) * std::f32::consts::PI;
+
+                (self.forward * longitude.cos() + self.right * longitude.sin()) * latitude.cos()
+                    + self.up_normalized * latitude.sin()
+            }
+        }
+    }
+
+    /// Genera un rayo a través de la lente para la profundidad de campo
+    /// (lens_u, lens_v) en [0, 1)² eligen el punto de la lente; todos los
+    /// rayos de un mismo píxel convergen en el plano de enfoque
+    pub fn get_ray_lens(&self, u: f32, v: f32, lens_u: f32, lens_v: f32) -> Ray {
+        if self.aperture <= 0.0 {
+            return self.get_ray(u, v);
+        }
+
+        // Punto del plano de enfoque al que apunta el píxel
+        let focus_point = self.position + self.direction(u, v) * self.focus_distance;
+
+        let (dx, dy) = concentric_disk(lens_u, lens_v);
+        let origin = self.position + (self.right * dx + self.up_normalized * dy) * self.aperture;
 
-        Ray::new(self.position, direction.normalize())
+        Ray::new(origin, (focus_point - origin).normalize())
     }
 }
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Point3;
+use crate::camera::Camera;
+
+/// Cómo se interpola entre dos keyframes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Interpolation {
+    /// Tramos rectos a velocidad constante (cambios bruscos en cada keyframe)
+    Linear,
+    /// Curva Catmull-Rom: pasa por todos los keyframes con tangentes
+    /// continuas, así que el movimiento no da tirones
+    #[default]
+    CatmullRom,
+}
+
+/// Posición y punto de mira de la cámara en un instante
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CameraKeyframe {
+    pub time: f32,
+    pub position: Point3,
+    pub look_at: Point3,
+}
+
+/// Recorrido de la cámara definido por keyframes, base para animaciones
+/// Antes del primer keyframe y después del último la cámara queda quieta.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CameraPath {
+    keyframes: Vec<CameraKeyframe>,
+    pub interpolation: Interpolation,
+}
+
+impl CameraPath {
+    pub fn new(interpolation: Interpolation) -> Self {
+        CameraPath {
+            keyframes: Vec::new(),
+            interpolation,
+        }
+    }
+
+    /// Agrega un keyframe; se mantienen ordenados por tiempo
+    /// Un keyframe con tiempo NaN no se puede ordenar y se descarta
+    pub fn add_keyframe(&mut self, time: f32, position: Point3, look_at: Point3) {
+        if time.is_nan() {
+            return;
+        }
+        let index = self.keyframes.partition_point(|keyframe| keyframe.time <= time);
+        self.keyframes.insert(index, CameraKeyframe { time, position, look_at });
+    }
+
+    pub fn with_keyframe(mut self, time: f32, position: Point3, look_at: Point3) -> Self {
+        self.add_keyframe(time, position, look_at);
+        self
+    }
+
+    pub fn keyframes(&self) -> &[CameraKeyframe] {
+        &self.keyframes
+    }
+
+    /// Intervalo de tiempo que cubren los keyframes (None si no hay ninguno)
+    pub fn time_range(&self) -> Option<(f32, f32)> {
+        Some((self.keyframes.first()?.time, self.keyframes.last()?.time))
+    }
+
+    /// Posición y punto de mira en el instante `time`
+    /// Retorna None si el recorrido no tiene keyframes
+    pub fn evaluate(&self, time: f32) -> Option<(Point3, Point3)> {
+        let (first, last) = (self.keyframes.first()?, self.keyframes.last()?);
+        // Un instante NaN no cae en ningún tramo: se usa el primer keyframe
+        if time.is_nan() || time <= first.time {
+            return Some((first.position, first.look_at));
+        }
+        if time >= last.time {
+            return Some((last.position, last.look_at));
+        }
+
+        // Tramo [i, i + 1] que contiene a `time`
+        let i = self.keyframes.partition_point(|keyframe| keyframe.time <= time).saturating_sub(1);
+        let (k1, k2) = (&self.keyframes[i], &self.keyframes[i + 1]);
+        let span = k2.time - k1.time;
+        let t = if span > 0.0 { (time - k1.time) / span } else { 0.0 };
+
+        match self.interpolation {
+            Interpolation::Linear => Some((
+                k1.position + (k2.position - k1.position) * t,
+                k1.look_at + (k2.look_at - k1.look_at) * t,
+            )),
+            Interpolation::CatmullRom => {
+                // En los extremos se repite el keyframe del borde
+                let k0 = &self.keyframes[i.saturating_sub(1)];
+                let k3 = &self.keyframes[(i + 2).min(self.keyframes.len() - 1)];
+                Some((
+                    catmull_rom(k0.position, k1.position, k2.position, k3.position, t),
+                    catmull_rom(k0.look_at, k1.look_at, k2.look_at, k3.look_at, t),
+                ))
+            }
+        }
+    }
+
+    /// Copia de `camera` colocada donde indica el recorrido en `time`
+    /// (el resto de parámetros, como el fov o la lente, no cambian)
+    pub fn camera_at(&self, camera: &Camera, time: f32) -> Camera {
+        let mut camera = camera.clone();
+        if let Some((position, look_at)) = self.evaluate(time) {
+            camera.set_view(position, look_at);
+        }
+        camera
+    }
+}
+
+/// Spline Catmull-Rom uniforme entre p1 (t = 0) y p2 (t = 1)
+fn catmull_rom(p0: Point3, p1: Point3, p2: Point3, p3: Point3, t: f32) -> Point3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (p1 * 2.0
+        + (p2 - p0) * t
+        + (p0 * 2.0 - p1 * 5.0 + p2 * 4.0 - p3) * t2
+        + (p1 * 3.0 - p0 - p2 * 3.0 + p3) * t3)
+        * 0.5
+}
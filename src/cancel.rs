@@ -0,0 +1,26 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Señal para detener un render desde otro hilo (cierre de la ventana de
+/// vista previa, Ctrl-C...). Las copias comparten el mismo estado.
+/// El renderer la consulta entre bloques o pasadas, así que el render
+/// termina poco después de cancelarse y retorna la imagen parcial.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pide que el render se detenga
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
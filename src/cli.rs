@@ -0,0 +1,497 @@
+use std::path::Path;
+
+use clap::{Parser, ValueEnum};
+
+use raytracer::settings::RenderSettings;
+use raytracer::sampler::SamplerKind;
+use raytracer::filter::PixelFilter;
+use raytracer::output_format::{self, OutputFormat};
+use raytracer::assets::AssetPaths;
+use raytracer::aov::Aov;
+use raytracer::renderer::Tile;
+use raytracer::stereo::{Stereo, StereoLayout};
+use raytracer::development::Development;
+use raytracer::exposure::Exposure;
+use raytracer::gamma::ColorEncoding;
+use raytracer::tonemap::ToneMapping;
+
+use crate::config::Config;
+
+/// Imagen de salida si no se indica --output ni `output_dir`
+const DEFAULT_OUTPUT: &str = "src/output/phase3_cube_textured.png";
+
+/// Raytracer: renderiza la escena de ejemplo o una escena en JSON
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// Archivo con la escena (JSON, .pbrt para importar una escena de PBRT
+    /// o una instantánea .rtsnap de --save-snapshot), o el nombre de una
+    /// escena incluida:
+    /// cornell, diorama, bodegon o esferas (sin él se usa la escena de ejemplo)
+    #[arg(long)]
+    pub scene: Option<String>,
+
+    /// Imagen de salida (.png, .ppm, .bmp, .jpg o .tga según la extensión);
+    /// la copia HDR se guarda al lado con extensión .exr
+    /// [por defecto: src/output/phase3_cube_textured.png]
+    #[arg(short, long, value_parser = output_path)]
+    pub output: Option<String>,
+
+    /// Guarda la imagen final .png con 16 bits por canal, para que cielos y
+    /// penumbras no se escalonen al retocarla (los cuadros de animación y
+    /// los demás formatos siguen en 8 bits)
+    #[arg(long)]
+    pub png16: bool,
+
+    /// Calidad de 1 (archivo mínimo) a 100 cuando la salida es .jpg; los
+    /// demás formatos no tienen pérdida
+    #[arg(long, value_name = "Q", default_value_t = output_format::DEFAULT_JPEG_QUALITY, value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub jpeg_quality: u8,
+
+    #[command(flatten)]
+    pub development: DevelopmentArgs,
+
+    /// Pasadas auxiliares (AOV) a guardar en EXR junto a la imagen,
+    /// separadas por comas: depth, normal, albedo, direct, indirect,
+    /// object_id y material_id. Las máscaras object_id y material_id se
+    /// guardan además en colores, con su leyenda en JSON
+    #[arg(long, value_name = "PASADAS", value_delimiter = ',', value_parser = aov)]
+    pub aov: Vec<Aov>,
+
+    /// Archivo de preferencias (por defecto, raytracer.toml si existe)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<String>,
+
+    /// Ancho en píxeles (si falta el alto se conserva la proporción)
+    #[arg(long)]
+    pub width: Option<u32>,
+
+    /// Alto en píxeles (si falta el ancho se conserva la proporción)
+    #[arg(long)]
+    pub height: Option<u32>,
+
+    /// Factor de resolución, p. ej. 0.25 para una vista previa rápida
+    #[arg(long, default_value_t = 1.0, value_parser = positive_f32)]
+    pub scale: f32,
+
+    /// Muestras por píxel
+    #[arg(short, long)]
+    pub samples: Option<u32>,
+
+    /// Reflexiones/transmisiones encadenadas como máximo
+    #[arg(long)]
+    pub max_depth: Option<u32>,
+
+    /// Distancia máxima de los rayos: lo que queda más lejos no se ve ni
+    /// proyecta sombras (reemplaza el plano lejano de la escena)
+    #[arg(long, value_name = "DIST", value_parser = positive_f32)]
+    pub far_clip: Option<f32>,
+
+    /// Distancia desde la que los objetos se funden con el fondo hasta
+    /// desaparecer en --far-clip
+    #[arg(long, value_name = "DIST", requires = "far_clip")]
+    pub far_fade: Option<f32>,
+
+    /// Sampler de las muestras de cada píxel (por defecto, el de la escena)
+    #[arg(long, value_enum)]
+    pub sampler: Option<SamplerArg>,
+
+    /// Filtro con el que se combinan las muestras de cada píxel (por
+    /// defecto, el de la escena)
+    #[arg(long, value_enum)]
+    pub pixel_filter: Option<FilterArg>,
+
+    /// Radio en píxeles de --pixel-filter [por defecto: 0.5 para box, 1 para
+    /// tent y 1.5 para gaussian]
+    #[arg(long, value_name = "R", requires = "pixel_filter", value_parser = positive_f32)]
+    pub filter_radius: Option<f32>,
+
+    /// Renderiza solo la zona [x0, x1) × [y0, y1) de la imagen, en píxeles,
+    /// y la pega sobre la imagen de salida existente (si no existe o tiene
+    /// otro tamaño, sobre una imagen negra)
+    #[arg(long, value_name = "X0,Y0,X1,Y1", value_parser = crop)]
+    pub crop: Option<Tile>,
+
+    /// Renderiza un par estereoscópico (una imagen por ojo) y los combina
+    /// en la imagen de salida
+    #[arg(long, value_enum)]
+    pub stereo: Option<StereoArg>,
+
+    /// Separación entre los ojos de --stereo, en unidades de la escena
+    /// [por defecto: 0.065, la de una persona si la escena está en metros]
+    #[arg(long, value_name = "DIST", requires = "stereo", value_parser = positive_f32)]
+    pub interocular: Option<f32>,
+
+    /// Exporta la escena a OBJ o glTF (según la extensión: .obj o .gltf)
+    /// para abrirla en Blender, en lugar de renderizarla
+    #[arg(long, value_name = "FILE")]
+    pub export: Option<String>,
+
+    /// Guarda la escena ya construida (con sus BVH) en una instantánea
+    /// binaria .rtsnap que --scene carga al instante, en lugar de renderizarla
+    #[arg(long, value_name = "FILE")]
+    pub save_snapshot: Option<String>,
+
+    /// Hornea la oclusión ambiental del objeto de --bake-object en esta
+    /// imagen (en su espacio UV, sin corrección gamma) en lugar de renderizar;
+    /// sirve como `occlusion_map` de su material
+    #[arg(long, value_name = "FILE", requires = "bake_object")]
+    pub bake_ao: Option<String>,
+
+    /// Hornea la iluminación directa e indirecta de los objetos en lightmaps
+    /// EXR en este directorio, con un índice `lightmaps.json`, en lugar de
+    /// renderizar. Con --bake-object solo ese objeto; si no, todos los que
+    /// se pueden hornear (con superficie muestreable y coordenadas UV)
+    #[arg(long, value_name = "DIR", conflicts_with = "bake_ao")]
+    pub bake_lightmaps: Option<String>,
+
+    /// Objeto a hornear: su nombre en la escena o su ID
+    #[arg(long, value_name = "OBJECT")]
+    pub bake_object: Option<String>,
+
+    /// Usa los lightmaps horneados con --bake-lightmaps en este directorio:
+    /// los objetos se sombrean con ellos en lugar de con las luces
+    #[arg(long, value_name = "DIR")]
+    pub lightmaps: Option<String>,
+
+    /// Ancho y alto en texeles de las texturas horneadas; las muestras por
+    /// texel son las muestras por píxel de la escena
+    #[arg(long, value_name = "N", default_value_t = 512, value_parser = clap::value_parser!(u32).range(1..))]
+    pub bake_size: u32,
+
+    /// Renderiza la animación de la escena en este directorio, un PNG por
+    /// cuadro (0001.png, 0002.png...)
+    #[arg(long, value_name = "DIR")]
+    pub frames: Option<String>,
+
+    /// Renderiza la animación de la escena como video (.mp4, .webm...)
+    /// usando ffmpeg; con --frames se guardan además los PNG
+    #[arg(long, value_name = "FILE")]
+    pub video: Option<String>,
+
+    /// Renderiza N cuadros con la cámara dando una vuelta completa alrededor
+    /// del centro de la escena, a su distancia y altura actuales, en lugar
+    /// de la animación de la escena. Sin --frames ni --video los PNG quedan
+    /// en `<salida>_turntable/`
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub turntable: Option<u32>,
+
+    /// Genera un dataset sintético en este directorio: variaciones al azar
+    /// de la escena (cámara, luces y colores) con su imagen, distancia,
+    /// normales y parámetros de cámara (ver `raytracer::dataset`)
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["frames", "video", "turntable"])]
+    pub dataset: Option<String>,
+
+    /// Cantidad de muestras de --dataset
+    #[arg(long, value_name = "N", default_value_t = 100, requires = "dataset", value_parser = clap::value_parser!(u32).range(1..))]
+    pub dataset_size: u32,
+
+    /// Vuelve a renderizar cada vez que se guardan el archivo de escena o
+    /// sus texturas
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Muestra el render en una ventana mientras se forma (requiere la
+    /// feature `preview`); cerrarla cancela el render
+    #[arg(long)]
+    pub preview: bool,
+
+    /// Abre un explorador interactivo para mover la cámara (WASD y ratón)
+    /// antes del render final (requiere la feature `preview`)
+    #[arg(long)]
+    pub explore: bool,
+
+    /// Abre el editor de escenas (objetos, materiales, luces y una vista
+    /// que se renderiza mientras se edita) antes del render final (requiere
+    /// la feature `editor`)
+    #[arg(long)]
+    pub editor: bool,
+
+    /// Inicia el servicio de render por HTTP en esta dirección, p. ej.
+    /// 127.0.0.1:8080 (requiere la feature `server`)
+    #[arg(long, value_name = "ADDR")]
+    pub serve: Option<String>,
+
+    /// Transmite el render en curso como MJPEG en esta dirección, p. ej.
+    /// 0.0.0.0:8081, para seguirlo desde un navegador (requiere la feature
+    /// `server`)
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["preview", "progress_format"])]
+    pub stream: Option<String>,
+
+    /// Carga y renderiza la escena N veces y muestra cuánto tarda cada etapa
+    /// (construcción de la escena y de la BVH, render, bloques y guardado)
+    #[arg(long, value_name = "N")]
+    pub benchmark: Option<u32>,
+
+    /// Guarda además los tiempos de --benchmark en este archivo JSON
+    #[arg(long, value_name = "FILE", requires = "benchmark")]
+    pub benchmark_json: Option<String>,
+
+    /// Formato del avance del render: `json` emite un evento JSON por línea
+    /// en la salida de errores (porcentaje, bloques, tiempo restante y
+    /// pasada), para scripts y granjas de render
+    #[arg(long, value_enum, default_value_t = ProgressFormat::Text, conflicts_with = "preview")]
+    pub progress_format: ProgressFormat,
+
+    /// Hilos de render (por defecto, uno por núcleo)
+    #[arg(short = 'j', long)]
+    pub threads: Option<usize>,
+
+    /// Directorios de búsqueda de texturas y mallas del archivo de preferencias
+    #[arg(skip)]
+    pub assets: AssetPaths,
+}
+
+/// Revelado de la imagen final; lo que no se indica queda como en la
+/// escena (ver `Scene::development`)
+#[derive(Debug, Clone, Copy, clap::Args)]
+pub struct DevelopmentArgs {
+    /// Operador de tone mapping
+    #[arg(long, value_enum)]
+    pub tone_mapping: Option<ToneMappingArg>,
+
+    /// Exposición en EV antes del tone mapping, o `auto` para elegirla según
+    /// la imagen
+    #[arg(long, value_name = "EV|auto", value_parser = exposure, allow_negative_numbers = true)]
+    pub exposure: Option<Exposure>,
+
+    /// Codificación de la imagen final: `srgb`, `linear` o un valor de gamma
+    #[arg(long, value_name = "GAMMA|srgb|linear", value_parser = encoding)]
+    pub encoding: Option<ColorEncoding>,
+}
+
+impl DevelopmentArgs {
+    /// Reemplaza en `development` lo que se indicó en la línea de comandos
+    pub fn apply_to(&self, development: &mut Development) {
+        if let Some(tone_mapping) = self.tone_mapping {
+            development.tone_mapping = tone_mapping.into();
+        }
+        if let Some(exposure) = self.exposure {
+            development.exposure = exposure;
+        }
+        if let Some(encoding) = self.encoding {
+            development.encoding = encoding;
+        }
+    }
+}
+
+/// Operadores que se pueden elegir con `--tone-mapping` (ver `ToneMapping`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ToneMappingArg {
+    /// Recorta cada canal a [0, 1]
+    Clamp,
+    /// Reinhard sobre la luminancia
+    Reinhard,
+    /// Curva fílmica ACES
+    Aces,
+}
+
+impl From<ToneMappingArg> for ToneMapping {
+    fn from(tone_mapping: ToneMappingArg) -> Self {
+        match tone_mapping {
+            ToneMappingArg::Clamp => ToneMapping::Clamp,
+            ToneMappingArg::Reinhard => ToneMapping::Reinhard,
+            ToneMappingArg::Aces => ToneMapping::Aces,
+        }
+    }
+}
+
+/// Cómo se informa del avance del render (`--progress-format`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    /// Mensajes para leer en la consola
+    Text,
+    /// Eventos JSON, uno por línea (ver `JsonProgress`)
+    Json,
+}
+
+/// Samplers que se pueden elegir con `--sampler` (ver `SamplerKind`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SamplerArg {
+    /// Números aleatorios independientes
+    Random,
+    /// Estratificado con jitter
+    Stratified,
+    /// Secuencia de Halton
+    Halton,
+    /// Baja discrepancia con ruido azul entre píxeles
+    BlueNoise,
+}
+
+impl From<SamplerArg> for SamplerKind {
+    fn from(sampler: SamplerArg) -> Self {
+        match sampler {
+            SamplerArg::Random => SamplerKind::Random,
+            SamplerArg::Stratified => SamplerKind::Stratified,
+            SamplerArg::Halton => SamplerKind::Halton,
+            SamplerArg::BlueNoise => SamplerKind::BlueNoise,
+        }
+    }
+}
+
+/// Cómo se combinan los ojos de `--stereo` (ver `StereoLayout`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StereoArg {
+    /// Izquierda y derecha una al lado de la otra
+    SideBySide,
+    /// Anaglifo rojo/cian
+    Anaglyph,
+}
+
+/// Filtros que se pueden elegir con `--pixel-filter` (ver `PixelFilter`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FilterArg {
+    /// Promedio simple
+    Box,
+    /// Peso lineal
+    Tent,
+    /// Gaussiana
+    Gaussian,
+}
+
+impl Args {
+    /// Completa las opciones que no se pasaron con las de `config`
+    /// El ancho y el alto se toman juntos: si se indicó alguno en la línea
+    /// de comandos, la resolución del archivo no se usa.
+    pub fn with_config(mut self, config: &Config) -> Self {
+        if !self.overrides_resolution() {
+            self.width = config.width;
+            self.height = config.height;
+        }
+        self.threads = self.threads.or(config.threads);
+        self.assets = config.asset_paths();
+        if let (None, Some(dir)) = (&self.output, &config.output_dir) {
+            let file_name = Path::new(DEFAULT_OUTPUT).file_name().unwrap_or_default();
+            self.output = Some(dir.join(file_name).to_string_lossy().into_owned());
+        }
+        self
+    }
+
+    /// Ruta de la imagen de salida
+    pub fn output(&self) -> &str {
+        self.output.as_deref().unwrap_or(DEFAULT_OUTPUT)
+    }
+
+    /// Ajustes de la escena con las opciones de la línea de comandos encima
+    pub fn apply_to(&self, settings: RenderSettings) -> RenderSettings {
+        let aspect_ratio = settings.aspect_ratio();
+        let (width, height) = match (self.width, self.height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, (width as f32 / aspect_ratio).round() as u32),
+            (None, Some(height)) => ((height as f32 * aspect_ratio).round() as u32, height),
+            (None, None) => (settings.width, settings.height),
+        };
+
+        let mut settings = RenderSettings { width: width.max(1), height: height.max(1), ..settings };
+        if let Some(samples) = self.samples {
+            settings = settings.with_samples_per_pixel(samples);
+        }
+        if let Some(max_depth) = self.max_depth {
+            settings = settings.with_max_depth(max_depth);
+        }
+        settings.with_scale(self.scale)
+    }
+
+    /// Filtro de --pixel-filter con su radio (--filter-radius)
+    pub fn pixel_filter(&self) -> Option<PixelFilter> {
+        let filter = self.pixel_filter?;
+        Some(match filter {
+            FilterArg::Box => PixelFilter::Box { radius: self.filter_radius.unwrap_or(0.5).max(0.5) },
+            FilterArg::Tent => PixelFilter::tent(self.filter_radius.unwrap_or(1.0)),
+            FilterArg::Gaussian => PixelFilter::gaussian(self.filter_radius.unwrap_or(1.5)),
+        })
+    }
+
+    /// Render estereoscópico de --stereo con la separación de --interocular
+    pub fn stereo(&self) -> Option<Stereo> {
+        let layout = match self.stereo? {
+            StereoArg::SideBySide => StereoLayout::SideBySide,
+            StereoArg::Anaglyph => StereoLayout::Anaglyph,
+        };
+        let stereo = Stereo::new(layout);
+        Some(match self.interocular {
+            Some(interocular) => stereo.with_interocular(interocular),
+            None => stereo,
+        })
+    }
+
+    /// Indica si se pidió una resolución distinta a la de la escena (la
+    /// cámara tiene que adoptar la nueva proporción)
+    pub fn overrides_resolution(&self) -> bool {
+        self.width.is_some() || self.height.is_some()
+    }
+
+    /// Directorio de los cuadros de la animación: el de --frames o, con
+    /// --turntable y sin --video, uno junto a la imagen de salida
+    pub fn frames_dir(&self) -> Option<String> {
+        if self.frames.is_some() || self.turntable.is_none() || self.video.is_some() {
+            return self.frames.clone();
+        }
+        let output = Path::new(self.output());
+        let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+        Some(output.with_file_name(format!("{}_turntable", stem)).to_string_lossy().into_owned())
+    }
+
+    /// Copia HDR de la imagen: la misma ruta con extensión .exr
+    pub fn exr_path(&self) -> String {
+        Path::new(self.output()).with_extension("exr").to_string_lossy().into_owned()
+    }
+
+    /// Registro de renders, en el directorio de la imagen
+    pub fn cache_path(&self) -> String {
+        Path::new(self.output()).with_file_name(".render_cache").to_string_lossy().into_owned()
+    }
+}
+
+fn output_path(value: &str) -> Result<String, String> {
+    OutputFormat::from_path(value).map(|_| value.to_string()).map_err(|e| e.to_string())
+}
+
+fn positive_f32(value: &str) -> Result<f32, String> {
+    match value.parse::<f32>() {
+        Ok(value) if value > 0.0 => Ok(value),
+        _ => Err(format!("se esperaba un número mayor que cero: {}", value)),
+    }
+}
+
+fn exposure(value: &str) -> Result<Exposure, String> {
+    match value {
+        "auto" => Ok(Exposure::auto()),
+        _ => value
+            .parse()
+            .ok()
+            .filter(|ev: &f32| ev.is_finite())
+            .map(Exposure::Fixed)
+            .ok_or_else(|| format!("se esperaba un número de EV o 'auto': {}", value)),
+    }
+}
+
+fn encoding(value: &str) -> Result<ColorEncoding, String> {
+    match value {
+        "srgb" => Ok(ColorEncoding::Srgb),
+        "linear" => Ok(ColorEncoding::Linear),
+        _ => positive_f32(value)
+            .map(ColorEncoding::Gamma)
+            .map_err(|_| format!("se esperaba 'srgb', 'linear' o una gamma mayor que cero: {}", value)),
+    }
+}
+
+fn aov(value: &str) -> Result<Aov, String> {
+    Aov::from_name(value).ok_or_else(|| {
+        let names: Vec<&str> = Aov::ALL.iter().map(Aov::name).collect();
+        format!("pasada desconocida: '{}' (se admiten {})", value, names.join(", "))
+    })
+}
+
+fn crop(value: &str) -> Result<Tile, String> {
+    let error = || format!("se esperaba x0,y0,x1,y1 con x0 < x1 e y0 < y1: {}", value);
+    let coordinates = value
+        .split(',')
+        .map(|part| part.trim().parse())
+        .collect::<Result<Vec<u32>, _>>()
+        .map_err(|_| error())?;
+    match coordinates[..] {
+        [x0, y0, x1, y1] if x0 < x1 && y0 < y1 => Ok(Tile { x0, y0, x1, y1 }),
+        _ => Err(error()),
+    }
+}
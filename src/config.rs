@@ -0,0 +1,70 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use raytracer::assets::AssetPaths;
+
+/// Archivo de preferencias que se busca en el directorio de trabajo
+pub const CONFIG_FILE: &str = "raytracer.toml";
+
+/// Preferencias del proyecto en `raytracer.toml`; las opciones de la línea
+/// de comandos tienen prioridad. Ejemplo:
+///
+/// ```toml
+/// output_dir = "renders"
+/// width = 1280
+/// height = 720
+/// threads = 6
+/// texture_paths = ["textures", "../compartido/texturas"]
+/// mesh_paths = ["modelos"]
+/// ```
+///
+/// Las rutas relativas son relativas al directorio del archivo.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Directorio de la imagen cuando no se indica --output
+    pub output_dir: Option<PathBuf>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub threads: Option<usize>,
+    /// Directorios donde buscar las texturas de las escenas
+    #[serde(default)]
+    pub texture_paths: Vec<PathBuf>,
+    /// Directorios donde buscar las mallas .obj de las escenas
+    #[serde(default)]
+    pub mesh_paths: Vec<PathBuf>,
+}
+
+impl Config {
+    /// Lee `path`, o `raytracer.toml` del directorio de trabajo si existe
+    /// Sin archivo se usan los valores por defecto; un archivo inválido es
+    /// un error (mejor que ignorar las preferencias en silencio)
+    pub fn load(path: Option<&str>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = match path {
+            Some(path) => Path::new(path),
+            None if Path::new(CONFIG_FILE).exists() => Path::new(CONFIG_FILE),
+            None => return Ok(Config::default()),
+        };
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("no se pudo leer {}: {}", path.display(), e))?;
+        let config: Config = toml::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(config.relative_to(path.parent().unwrap_or(Path::new(""))))
+    }
+
+    /// Directorios de búsqueda para cargar las escenas
+    pub fn asset_paths(&self) -> AssetPaths {
+        let assets = self
+            .texture_paths
+            .iter()
+            .fold(AssetPaths::new(), |assets, dir| assets.with_texture_dir(dir));
+        self.mesh_paths.iter().fold(assets, |assets, dir| assets.with_mesh_dir(dir))
+    }
+
+    fn relative_to(mut self, base: &Path) -> Self {
+        self.output_dir = self.output_dir.map(|dir| base.join(dir));
+        self.texture_paths = self.texture_paths.iter().map(|dir| base.join(dir)).collect();
+        self.mesh_paths = self.mesh_paths.iter().map(|dir| base.join(dir)).collect();
+        self
+    }
+}
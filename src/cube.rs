@@ -1,10 +1,18 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
 use crate::vector::{Point3, Vec3};
 use crate::ray::Ray;
 use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::packet::{self, RayPacket, PacketHits};
+use crate::gpu::GpuPrimitive;
+use crate::render_cache::{hash_vec3, hash_material};
 
 /// Estructura que representa un cubo alineado con los ejes (AABB)
 /// El cubo se define por sus puntos mínimo y máximo en los ejes
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Cube {
     pub min: Point3,        // Esquina mínima (x, y, z más bajos)
     pub max: Point3,        // Esquina máxima (x, y, z más altos)
@@ -33,30 +41,9 @@ impl Cube {
         let mut t_max = f32::INFINITY;
 
         // Intersectar con los tres pares de planos (x, y, z)
-        for i in 0..3 {
-            let ray_start = match i {
-                0 => ray.origin.x,
-                1 => ray.origin.y,
-                _ => ray.origin.z,
-            };
-
-            let ray_dir = match i {
-                0 => ray.direction.x,
-                1 => ray.direction.y,
-                _ => ray.direction.z,
-            };
-
-            let min_bound = match i {
-                0 => self.min.x,
-                1 => self.min.y,
-                _ => self.min.z,
-            };
-
-            let max_bound = match i {
-                0 => self.max.x,
-                1 => self.max.y,
-                _ => self.max.z,
-            };
+        for axis in 0..3 {
+            let (ray_start, ray_dir) = (ray.origin[axis], ray.direction[axis]);
+            let (min_bound, max_bound) = (self.min[axis], self.max[axis]);
 
             if ray_dir.abs() > 1e-6 {
                 let t0 = (min_bound - ray_start) / ray_dir;
@@ -118,27 +105,15 @@ impl Cube {
         let size_y = self.max.y - self.min.y;
         let size_z = self.max.z - self.min.z;
 
-        if (point.y - self.max.y).abs() < epsilon {
-            let u = (point.x - self.min.x) / size_x;
-            let v = (point.z - self.min.z) / size_z;
-            Some((u, v, 0))
-        } else if (point.y - self.min.y).abs() < epsilon {
+        if (point.y - self.max.y).abs() < epsilon || (point.y - self.min.y).abs() < epsilon {
             let u = (point.x - self.min.x) / size_x;
             let v = (point.z - self.min.z) / size_z;
             Some((u, v, 0))
-        } else if (point.x - self.min.x).abs() < epsilon {
+        } else if (point.x - self.min.x).abs() < epsilon || (point.x - self.max.x).abs() < epsilon {
             let u = (point.z - self.min.z) / size_z;
             let v = (point.y - self.min.y) / size_y;
             Some((u, v, 0))
-        } else if (point.x - self.max.x).abs() < epsilon {
-            let u = (point.z - self.min.z) / size_z;
-            let v = (point.y - self.min.y) / size_y;
-            Some((u, v, 0))
-        } else if (point.z - self.min.z).abs() < epsilon {
-            let u = (point.x - self.min.x) / size_x;
-            let v = (point.y - self.min.y) / size_y;
-            Some((u, v, 0))
-        } else if (point.z - self.max.z).abs() < epsilon {
+        } else if (point.z - self.min.z).abs() < epsilon || (point.z - self.max.z).abs() < epsilon {
             let u = (point.x - self.min.x) / size_x;
             let v = (point.y - self.min.y) / size_y;
             Some((u, v, 0))
@@ -146,4 +121,103 @@ impl Cube {
             None
         }
     }
+
+    /// Intersección con un paquete de rayos (ver `packet`)
+    pub fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        packet::intersect_box(rays, &self.bounds())
+    }
+
+    /// Problemas que impiden renderizar el cubo (ver `Scene::validate`)
+    pub fn validate(&self) -> Vec<String> {
+        if !self.min.is_finite() || !self.max.is_finite() {
+            return vec!["cubo con esquinas no finitas (NaN o infinito)".to_string()];
+        }
+        let axes = [("x", self.min.x, self.max.x), ("y", self.min.y, self.max.y), ("z", self.min.z, self.max.z)];
+        axes.iter()
+            .filter(|(_, min, max)| min > max)
+            .map(|(axis, min, max)| format!("cubo degenerado: min.{} ({}) es mayor que max.{} ({}); ¿están invertidas las esquinas?", axis, min, axis, max))
+            .collect()
+    }
+
+    /// Los 12 triángulos de las caras, con las normales hacia afuera
+    pub fn tessellate(&self) -> (Vec<Point3>, Vec<[usize; 3]>) {
+        // Esquina i: el bit 0 elige x, el 1 elige y y el 2 elige z
+        let vertices = (0..8)
+            .map(|i| Point3::new(
+                if i & 1 == 0 { self.min.x } else { self.max.x },
+                if i & 2 == 0 { self.min.y } else { self.max.y },
+                if i & 4 == 0 { self.min.z } else { self.max.z },
+            ))
+            .collect();
+        let faces = [[0, 4, 6, 2], [1, 3, 7, 5], [0, 1, 5, 4], [2, 6, 7, 3], [0, 2, 3, 1], [4, 5, 7, 6]];
+        let triangles = faces.iter().flat_map(|[a, b, c, d]| [[*a, *b, *c], [*a, *c, *d]]).collect();
+        (vertices, triangles)
+    }
+
+    /// Caja envolvente (el propio cubo)
+    pub fn bounds(&self) -> Aabb {
+        Aabb::new(self.min, self.max)
+    }
+
+    /// Área de la superficie del cubo
+    pub fn surface_area(&self) -> f32 {
+        let size = self.max - self.min;
+        2.0 * (size.x * size.y + size.y * size.z + size.x * size.z)
+    }
+
+    /// Punto de la superficie a partir de (u, v) en [0, 1)², distribuido uniformemente
+    /// `u` elige la cara (proporcionalmente a su área) y luego se reutiliza
+    /// como coordenada dentro de ella
+    pub fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        let size = self.max - self.min;
+        let areas = [size.y * size.z, size.x * size.z, size.x * size.y];
+        let total = areas[0] + areas[1] + areas[2];
+        if total <= 0.0 {
+            return None;
+        }
+
+        // Cada par de caras opuestas ocupa un tramo de u proporcional a su área
+        let mut start = 0.0;
+        for (axis, area) in areas.iter().enumerate() {
+            let fraction = area / total;
+            if u < start + fraction || axis == 2 {
+                let local = ((u - start) / fraction).clamp(0.0, 1.0);
+                let (upper, s) = if local < 0.5 { (false, local * 2.0) } else { (true, local * 2.0 - 1.0) };
+
+                let point = match axis {
+                    0 => Point3::new(
+                        if upper { self.max.x } else { self.min.x },
+                        self.min.y + s * size.y,
+                        self.min.z + v * size.z,
+                    ),
+                    1 => Point3::new(
+                        self.min.x + s * size.x,
+                        if upper { self.max.y } else { self.min.y },
+                        self.min.z + v * size.z,
+                    ),
+                    _ => Point3::new(
+                        self.min.x + s * size.x,
+                        self.min.y + v * size.y,
+                        if upper { self.max.z } else { self.min.z },
+                    ),
+                };
+                return Some(point);
+            }
+            start += fraction;
+        }
+
+        None
+    }
+
+    /// Primitiva para el backend de GPU (UV por cara sobre la textura 0)
+    pub fn gpu_primitive(&self) -> GpuPrimitive {
+        GpuPrimitive::aabb(self.min, self.max).with_texture(0)
+    }
+
+    /// Agrega el estado de el cubo al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_vec3(state, &self.min);
+        hash_vec3(state, &self.max);
+        hash_material(state, &self.material);
+    }
 }
@@ -0,0 +1,242 @@
+// Datos sintéticos para entrenar modelos de visión: variaciones al azar de
+// una escena (encuadre de la cámara, luces y colores de los materiales),
+// cada una guardada con sus pasadas y los parámetros de su cámara:
+//
+//   DIR/dataset.json        resolución, semilla, variaciones y lista de muestras
+//   DIR/rgb/00000.png       imagen final
+//   DIR/depth/00000.exr     distancia a lo largo del rayo hasta el primer
+//                           impacto (no la profundidad Z); infinito si no hay
+//   DIR/normal/00000.exr    normal del primer impacto en espacio de mundo
+//   DIR/camera/00000.json   posición, matrices e intrínsecos de la cámara
+//
+// Cada muestra depende solo de la semilla base y de su número, así que se
+// puede regenerar o ampliar un dataset sin repetir las anteriores.
+
+use std::path::{Path, PathBuf};
+
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::vector::{Color, Point3};
+use crate::camera::Camera;
+use crate::light::Light;
+use crate::material::Material;
+use crate::scene::Scene;
+use crate::renderer::GBuffer;
+use crate::sampling::{hash_u64, Rng};
+
+/// Pasadas que se guardan de cada muestra, con su carpeta y extensión
+const PASSES: [(&str, &str); 4] = [("rgb", "png"), ("depth", "exr"), ("normal", "exr"), ("camera", "json")];
+
+/// Cuánto puede cambiar cada aspecto de la escena entre muestras; cero
+/// deja ese aspecto como en la escena original
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Variations {
+    /// Giro máximo de la cámara alrededor del centro de la escena, en grados
+    /// a cada lado (180 = cualquier dirección)
+    pub camera_azimuth: f32,
+    /// Cambio máximo de la altura de la cámara, en grados hacia arriba o abajo
+    pub camera_elevation: f32,
+    /// Cambio máximo de la distancia de la cámara al centro, como fracción
+    pub camera_distance: f32,
+    /// Cambio máximo de la intensidad de cada luz, como fracción
+    pub light_intensity: f32,
+    /// Cambio máximo de cada canal del color de las luces, como fracción
+    pub light_color: f32,
+    /// Cambio máximo de cada canal del color de los materiales, como fracción
+    pub material_color: f32,
+}
+
+impl Default for Variations {
+    fn default() -> Self {
+        Variations {
+            camera_azimuth: 180.0,
+            camera_elevation: 15.0,
+            camera_distance: 0.2,
+            light_intensity: 0.3,
+            light_color: 0.1,
+            material_color: 0.3,
+        }
+    }
+}
+
+/// Genera variaciones de una escena a partir de sus valores originales
+pub struct SceneVariator {
+    variations: Variations,
+    seed: u64,
+    camera: Camera,
+    target: Point3,
+    lights: Vec<Light>,
+    materials: Vec<(usize, Material)>,
+}
+
+impl SceneVariator {
+    /// Guarda los valores de `scene` que se van a variar. La cámara orbita
+    /// alrededor del centro de la caja de la escena (o de su punto de mira
+    /// si no hay objetos acotados). Los objetos cuyo material no se puede
+    /// cambiar (ver `Intersectable::material_mut`) conservan el suyo
+    pub fn new(scene: &mut Scene, variations: Variations, seed: u64) -> Self {
+        let target = scene.bounds().map_or(scene.camera.look_at, |bounds| bounds.centroid());
+        let ids: Vec<usize> = (0..scene.objects.len()).map(|index| scene.object_id(index)).collect();
+        let materials = ids
+            .into_iter()
+            .filter_map(|id| scene.material_mut(id).map(|material| (id, *material)))
+            .collect();
+
+        SceneVariator {
+            variations,
+            seed,
+            camera: scene.camera.clone(),
+            target,
+            lights: scene.lights.clone(),
+            materials,
+        }
+    }
+
+    pub fn variations(&self) -> &Variations {
+        &self.variations
+    }
+
+    /// Semilla de la muestra `index` (la que se guarda en su JSON)
+    pub fn sample_seed(&self, index: u32) -> u64 {
+        hash_u64(self.seed ^ hash_u64(index as u64))
+    }
+
+    /// Deja en `scene` la variación número `index`
+    pub fn apply(&self, scene: &mut Scene, index: u32) {
+        let mut rng = Rng::new(self.sample_seed(index));
+        let v = &self.variations;
+
+        let offset = self.camera.position - self.target;
+        let radius = offset.length().max(1e-3);
+        let azimuth = offset.x.atan2(offset.z).to_degrees() + spread(&mut rng, v.camera_azimuth);
+        let elevation = (offset.y / radius).clamp(-1.0, 1.0).asin().to_degrees() + spread(&mut rng, v.camera_elevation);
+        let distance = radius * (1.0 + spread(&mut rng, v.camera_distance)).max(0.05);
+        scene.camera = self.camera.orbit(self.target, distance, azimuth, elevation);
+
+        for (light, original) in scene.lights.iter_mut().zip(&self.lights) {
+            light.intensity = original.intensity * (1.0 + spread(&mut rng, v.light_intensity)).max(0.0);
+            light.color = tint(&mut rng, original.color, v.light_color);
+        }
+        scene.rebuild_light_sampler();
+
+        for (id, original) in &self.materials {
+            if let Some(material) = scene.material_mut(*id) {
+                let color = tint(&mut rng, original.color, v.material_color);
+                material.color = color.min(&Color::new(1.0, 1.0, 1.0));
+            }
+        }
+    }
+
+    /// Devuelve a `scene` sus valores originales
+    pub fn restore(&self, scene: &mut Scene) {
+        scene.camera = self.camera.clone();
+        scene.lights.clone_from(&self.lights);
+        scene.rebuild_light_sampler();
+        for (id, original) in &self.materials {
+            if let Some(material) = scene.material_mut(*id) {
+                *material = *original;
+            }
+        }
+    }
+}
+
+/// Número uniforme en [-amount, amount]
+fn spread(rng: &mut Rng, amount: f32) -> f32 {
+    (rng.next_f32() * 2.0 - 1.0) * amount
+}
+
+/// `color` con cada canal escalado hasta `amount` hacia arriba o abajo
+fn tint(rng: &mut Rng, color: Color, amount: f32) -> Color {
+    let mut channel = |value: f32| (value * (1.0 + spread(rng, amount))).max(0.0);
+    Color::new(channel(color.x), channel(color.y), channel(color.z))
+}
+
+/// Parámetros de la cámara para el JSON de cada muestra
+pub fn camera_json(camera: &Camera) -> Value {
+    let intrinsics = camera.intrinsics().map(|(fx, fy, cx, cy)| json!({ "fx": fx, "fy": fy, "cx": cx, "cy": cy }));
+    json!({
+        "width": camera.width,
+        "height": camera.height,
+        "projection": camera.projection,
+        "fov_y_degrees": camera.fov,
+        "position": camera.position,
+        "look_at": camera.look_at,
+        "up": camera.up,
+        "intrinsics": intrinsics,
+        "camera_to_world": camera.camera_to_world(),
+        "world_to_camera": camera.view_matrix(),
+    })
+}
+
+/// Carpeta de un dataset: crea la estructura y escribe las muestras
+pub struct DatasetWriter {
+    dir: PathBuf,
+    samples: Vec<Value>,
+}
+
+impl DatasetWriter {
+    /// Crea `dir` y sus subcarpetas (si ya existen, las muestras con el
+    /// mismo número se sobrescriben)
+    pub fn create(dir: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        for (pass, _) in PASSES {
+            let path = Path::new(dir).join(pass);
+            std::fs::create_dir_all(&path).map_err(|e| format!("no se pudo crear {}: {}", path.display(), e))?;
+        }
+        Ok(DatasetWriter { dir: PathBuf::from(dir), samples: Vec::new() })
+    }
+
+    fn path(&self, pass: &str, extension: &str, index: u32) -> String {
+        self.dir.join(pass).join(format!("{:05}.{}", index, extension)).to_string_lossy().into_owned()
+    }
+
+    /// Guarda la muestra `index`: la imagen final, la distancia y las
+    /// normales de `guides` y la cámara con la que se renderizó
+    pub fn write(
+        &mut self,
+        index: u32,
+        seed: u64,
+        rgb: &RgbImage,
+        guides: &GBuffer,
+        camera: &Camera,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let [rgb_path, depth_path, normal_path, camera_path] = PASSES.map(|(pass, extension)| self.path(pass, extension, index));
+        rgb.save(&rgb_path)?;
+        guides.depth.save_exr(&depth_path)?;
+        guides.normals.save_exr(&normal_path)?;
+
+        let mut parameters = camera_json(camera);
+        parameters["index"] = json!(index);
+        parameters["seed"] = json!(seed);
+        std::fs::write(&camera_path, serde_json::to_string_pretty(&parameters)?)?;
+
+        let relative = |path: &str| Path::new(path).strip_prefix(&self.dir).map_or(path.to_string(), |p| p.to_string_lossy().into_owned());
+        let sample = json!({
+            "index": index,
+            "seed": seed,
+            "rgb": relative(&rgb_path),
+            "depth": relative(&depth_path),
+            "normal": relative(&normal_path),
+            "camera": relative(&camera_path),
+        });
+        self.samples.push(sample);
+        Ok(())
+    }
+
+    /// Escribe `dataset.json` con las muestras guardadas
+    pub fn finish(self, scene: &Scene, variator: &SceneVariator) -> Result<(), Box<dyn std::error::Error>> {
+        let manifest = json!({
+            "count": self.samples.len(),
+            "width": scene.settings.width,
+            "height": scene.settings.height,
+            "samples_per_pixel": scene.settings.samples_per_pixel,
+            "seed": variator.seed,
+            "variations": variator.variations(),
+            "samples": self.samples,
+        });
+        let path = self.dir.join("dataset.json");
+        std::fs::write(&path, serde_json::to_string_pretty(&manifest)?)?;
+        Ok(())
+    }
+}
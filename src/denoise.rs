@@ -0,0 +1,119 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Color;
+use crate::framebuffer::Framebuffer;
+use crate::render_cache::hash_f32;
+
+/// Núcleo B3-spline de 5 muestras usado en cada nivel del filtro
+const KERNEL: [f32; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+/// Filtro À-Trous guiado por aristas (Dammertz et al. 2010)
+/// Cada iteración aplica un núcleo de 5×5 con huecos cada vez mayores
+/// (1, 2, 4, ...) y pondera los vecinos según lo parecidos que sean su
+/// color, su normal y su albedo. Así el ruido de pocas muestras se suaviza
+/// sin borrar los bordes de los objetos ni los detalles de las texturas.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Denoiser {
+    pub iterations: u32,
+    pub sigma_color: f32,
+    pub sigma_normal: f32,
+    pub sigma_albedo: f32,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Denoiser::new(5)
+    }
+}
+
+impl Denoiser {
+    /// Crea un filtro con `iterations` niveles y tolerancias por defecto
+    pub fn new(iterations: u32) -> Self {
+        Denoiser {
+            iterations: iterations.max(1),
+            sigma_color: 0.5,
+            sigma_normal: 0.2,
+            sigma_albedo: 0.1,
+        }
+    }
+
+    /// Ajusta la tolerancia de cada guía (menor = bordes más marcados)
+    pub fn with_sigmas(mut self, color: f32, normal: f32, albedo: f32) -> Self {
+        self.sigma_color = color.max(1e-4);
+        self.sigma_normal = normal.max(1e-4);
+        self.sigma_albedo = albedo.max(1e-4);
+        self
+    }
+
+    /// Filtra la imagen usando los buffers de normales y albedo del primer impacto
+    pub fn apply(&self, color: &Framebuffer, normals: &Framebuffer, albedo: &Framebuffer) -> Framebuffer {
+        let mut current = color.clone();
+
+        for level in 0..self.iterations {
+            let step = 1i32 << level;
+            // El ruido restante disminuye en cada nivel, así que la tolerancia de color también
+            let sigma_color = self.sigma_color / (1u32 << level) as f32;
+            current = self.filter_level(&current, normals, albedo, step, sigma_color);
+        }
+
+        current
+    }
+
+    fn filter_level(
+        &self,
+        input: &Framebuffer,
+        normals: &Framebuffer,
+        albedo: &Framebuffer,
+        step: i32,
+        sigma_color: f32,
+    ) -> Framebuffer {
+        let width = input.width() as i32;
+        let height = input.height() as i32;
+
+        Framebuffer::from_fn(input.width(), input.height(), |x, y| {
+            let center_color = input.get(x, y);
+            let center_normal = normals.get(x, y);
+            let center_albedo = albedo.get(x, y);
+
+            let mut sum = Color::zero();
+            let mut total_weight = 0.0;
+
+            for (j, ky) in KERNEL.iter().enumerate() {
+                for (i, kx) in KERNEL.iter().enumerate() {
+                    let qx = (x as i32 + (i as i32 - 2) * step).clamp(0, width - 1) as u32;
+                    let qy = (y as i32 + (j as i32 - 2) * step).clamp(0, height - 1) as u32;
+
+                    let sample = input.get(qx, qy);
+                    let weight = kx * ky
+                        * edge_weight(center_color - sample, sigma_color)
+                        * edge_weight(center_normal - normals.get(qx, qy), self.sigma_normal)
+                        * edge_weight(center_albedo - albedo.get(qx, qy), self.sigma_albedo);
+
+                    sum += sample * weight;
+                    total_weight += weight;
+                }
+            }
+
+            if total_weight > 0.0 {
+                sum / total_weight
+            } else {
+                center_color
+            }
+        })
+    }
+
+    /// Agrega los parámetros del filtro al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        state.write_u32(self.iterations);
+        hash_f32(state, self.sigma_color);
+        hash_f32(state, self.sigma_normal);
+        hash_f32(state, self.sigma_albedo);
+    }
+}
+
+/// Peso gaussiano según la diferencia entre dos valores de una guía
+fn edge_weight(difference: Color, sigma: f32) -> f32 {
+    (-difference.dot(&difference) / (sigma * sigma)).exp()
+}
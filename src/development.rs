@@ -0,0 +1,63 @@
+use std::hash::Hasher;
+
+use image::RgbImage;
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::{Framebuffer, Rgb16Image};
+use crate::exposure::Exposure;
+use crate::gamma::{self, ColorEncoding};
+use crate::postprocess::{self, PostEffect};
+use crate::tonemap::ToneMapping;
+
+/// Revelado de la imagen HDR terminada: exposición, efectos, tone mapping y
+/// codificación, en ese orden. Va con la escena (ver `Scene::development`)
+/// para que el CLI, el servidor y la versión web produzcan la misma imagen
+/// a partir del mismo archivo de escena.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Development {
+    /// Exposición antes del tone mapping; `Exposure::auto()` la elige según la imagen
+    pub exposure: Exposure,
+    /// Efectos sobre la imagen final (no sobre el EXR), en orden
+    pub effects: Vec<PostEffect>,
+    pub tone_mapping: ToneMapping,
+    pub encoding: ColorEncoding,
+}
+
+impl Default for Development {
+    fn default() -> Self {
+        Development {
+            exposure: Exposure::default(),
+            effects: Vec::new(),
+            tone_mapping: ToneMapping::Aces,
+            encoding: ColorEncoding::Gamma(gamma::DEFAULT_GAMMA),
+        }
+    }
+}
+
+impl Development {
+    /// Imagen HDR con la exposición y los efectos aplicados
+    pub fn expose(&self, framebuffer: &Framebuffer) -> Framebuffer {
+        postprocess::apply_all(&self.exposure.apply(framebuffer), &self.effects)
+    }
+
+    /// Imagen final de 8 bits
+    pub fn to_rgb8(&self, framebuffer: &Framebuffer) -> RgbImage {
+        self.expose(framebuffer).to_rgb8(self.tone_mapping, self.encoding)
+    }
+
+    /// Imagen final de 16 bits por canal
+    pub fn to_rgb16(&self, framebuffer: &Framebuffer) -> Rgb16Image {
+        self.expose(framebuffer).to_rgb16(self.tone_mapping, self.encoding)
+    }
+
+    /// Agrega el revelado al hash del render (cambia la imagen guardada)
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        self.exposure.hash_state(state);
+        state.write_usize(self.effects.len());
+        for effect in &self.effects {
+            effect.hash_state(state);
+        }
+        self.tone_mapping.hash_state(state);
+        self.encoding.hash_state(state);
+    }
+}
@@ -0,0 +1,426 @@
+// Editor de escenas (feature `editor`): una ventana de egui con la lista de
+// objetos y luces, los editores de la transformación y el material del
+// objeto elegido, los controles de las luces y una vista que se renderiza de
+// forma progresiva a baja resolución. Cada cambio cancela el render de la
+// vista y empieza otro.
+//
+// La vista se renderiza en otro hilo con la escena detrás de un RwLock: la
+// interfaz la lee para mostrar los valores y solo la bloquea para escribir
+// después de cancelar el render en curso.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use eframe::egui;
+
+use crate::vector::{Vec3, Point3, Color};
+use crate::camera::Camera;
+use crate::material::Material;
+use crate::light::Light;
+use crate::transform::Transform;
+use crate::scene::{Intersectable, Scene, SceneItem};
+use crate::framebuffer::{Framebuffer, color_to_rgb};
+use crate::renderer::Renderer;
+use crate::cancel::CancelToken;
+use crate::tonemap::ToneMapping;
+use crate::gamma::ColorEncoding;
+
+/// Fracción de la resolución a la que se renderiza la vista del editor
+const EDITOR_SCALE: f32 = 0.5;
+/// Grados de órbita por píxel de arrastre en la vista
+const ORBIT_SENSITIVITY: f32 = 0.3;
+/// Acercamiento por punto de desplazamiento de la rueda del ratón
+const ZOOM_SENSITIVITY: f32 = 0.002;
+
+/// Pedido de render de la vista; `ctx` sirve para pedir que se redibuje la
+/// ventana con cada pasada
+struct Job {
+    ctx: egui::Context,
+    cancel: CancelToken,
+}
+
+/// Cambio pedido desde la interfaz; se aplica al final del cuadro
+enum Edit {
+    Transform(usize, ObjectEdit),
+    Material(usize, Material),
+    Light(usize, Light),
+    Camera(Camera),
+}
+
+/// Traslación, rotación (grados alrededor de X, Y y Z, en ese orden) y
+/// escala de un objeto respecto de cómo estaba al abrir el editor, alrededor
+/// del centro de su caja (como en `ObjectAnimation`)
+#[derive(Clone, Copy)]
+struct ObjectEdit {
+    /// Transformación que tenía el objeto (None si no admitía ninguna)
+    original: Option<Transform>,
+    pivot: Point3,
+    translation: Vec3,
+    rotation: Vec3,
+    scale: Vec3,
+}
+
+impl ObjectEdit {
+    fn new(object: &dyn Intersectable) -> Self {
+        ObjectEdit {
+            original: object.transform(),
+            pivot: object.bounds().map_or_else(Point3::zero, |bounds| bounds.centroid()),
+            translation: Vec3::zero(),
+            rotation: Vec3::zero(),
+            scale: Vec3::new(1.0, 1.0, 1.0),
+        }
+    }
+
+    fn transform(&self) -> Transform {
+        let edit = Transform::translation(-self.pivot)
+            .then(&Transform::scaling(self.scale))
+            .then(&Transform::rotation_x(self.rotation.x))
+            .then(&Transform::rotation_y(self.rotation.y))
+            .then(&Transform::rotation_z(self.rotation.z))
+            .then(&Transform::translation(self.pivot + self.translation));
+        self.original.unwrap_or_else(Transform::identity).then(&edit)
+    }
+}
+
+/// Abre el editor sobre `scene` y espera a que se cierre la ventana; la
+/// escena queda con los cambios y con sus ajustes de render originales.
+/// Falla si no hay entorno gráfico
+pub fn edit(scene: &mut Scene, tone_mapping: ToneMapping, encoding: ColorEncoding) -> Result<(), Box<dyn Error>> {
+    let settings = scene.settings;
+    scene.set_render_settings(settings.with_scale(EDITOR_SCALE));
+
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([1280.0, 800.0]),
+        ..Default::default()
+    };
+    let result = {
+        let lock = RwLock::new(&mut *scene);
+        let (jobs, pending) = mpsc::channel();
+        let (frames, rendered) = mpsc::channel();
+        std::thread::scope(|scope| {
+            let lock = &lock;
+            scope.spawn(move || render_views(lock, pending, frames));
+            // Al cerrarse la ventana se descarta la aplicación y con ella el
+            // canal de pedidos, así que el hilo de render termina
+            eframe::run_native(
+                "Raytracer - editor",
+                options,
+                Box::new(move |_| {
+                    Ok(Box::new(EditorApp {
+                        scene: lock,
+                        jobs,
+                        frames: rendered,
+                        job: None,
+                        texture: None,
+                        samples: (0, settings.samples_per_pixel.max(1)),
+                        selected: None,
+                        objects: HashMap::new(),
+                        message: None,
+                        tone_mapping,
+                        encoding,
+                    }))
+                }),
+            )
+        })
+    };
+
+    scene.set_render_settings(settings);
+    result.map_err(|e| format!("no se pudo abrir el editor: {}", e).into())
+}
+
+/// Atiende los pedidos de render de la vista hasta que se cierre el canal
+fn render_views(scene: &RwLock<&mut Scene>, jobs: Receiver<Job>, frames: Sender<(Framebuffer, u32)>) {
+    while let Ok(job) = jobs.recv() {
+        // Solo interesa el último cambio
+        let job = jobs.try_iter().last().unwrap_or(job);
+        if job.cancel.is_cancelled() {
+            continue;
+        }
+        let scene = scene.read().unwrap();
+        Renderer::render_progressive(&scene, Duration::ZERO, &job.cancel, |image, samples| {
+            let _ = frames.send((image.clone(), samples));
+            job.ctx.request_repaint();
+        });
+    }
+}
+
+struct EditorApp<'a, 's> {
+    scene: &'a RwLock<&'s mut Scene>,
+    jobs: Sender<Job>,
+    frames: Receiver<(Framebuffer, u32)>,
+    /// Cancelación del render de la vista en curso
+    job: Option<CancelToken>,
+    texture: Option<egui::TextureHandle>,
+    /// Muestras de la imagen mostrada y muestras por píxel de la escena
+    samples: (u32, u32),
+    selected: Option<SceneItem>,
+    objects: HashMap<usize, ObjectEdit>,
+    /// Aviso de la última edición que no se pudo aplicar
+    message: Option<String>,
+    tone_mapping: ToneMapping,
+    encoding: ColorEncoding,
+}
+
+impl Drop for EditorApp<'_, '_> {
+    fn drop(&mut self) {
+        if let Some(job) = &self.job {
+            job.cancel();
+        }
+    }
+}
+
+impl eframe::App for EditorApp<'_, '_> {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if self.job.is_none() {
+            self.restart(ctx);
+        }
+        if let Some((image, samples)) = self.frames.try_iter().last() {
+            self.show(ctx, &image);
+            self.samples.0 = samples;
+        }
+
+        let mut edit = None;
+        egui::TopBottomPanel::bottom("ayuda").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Arrastrar en la vista: orbitar · Rueda: acercar · Cerrar la ventana: renderizar la escena");
+                if let Some(message) = &self.message {
+                    ui.separator();
+                    ui.colored_label(egui::Color32::YELLOW, message);
+                }
+            });
+        });
+        egui::SidePanel::left("escena").resizable(true).show(ctx, |ui| self.scene_panel(ui));
+        egui::SidePanel::right("propiedades").min_width(280.0).show(ctx, |ui| edit = self.properties_panel(ui));
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(camera) = self.viewport(ui) {
+                edit = Some(Edit::Camera(camera));
+            }
+        });
+
+        if let Some(edit) = edit {
+            self.apply(ctx, edit);
+        }
+    }
+}
+
+impl EditorApp<'_, '_> {
+    /// Empieza un render nuevo de la vista
+    fn restart(&mut self, ctx: &egui::Context) {
+        let cancel = CancelToken::new();
+        let _ = self.jobs.send(Job { ctx: ctx.clone(), cancel: cancel.clone() });
+        self.job = Some(cancel);
+    }
+
+    /// Cancela el render de la vista, cambia la escena y vuelve a renderizarla
+    fn apply(&mut self, ctx: &egui::Context, edit: Edit) {
+        if let Some(job) = &self.job {
+            job.cancel();
+        }
+        self.message = None;
+        {
+            let mut scene = self.scene.write().unwrap();
+            match edit {
+                Edit::Transform(id, object) => {
+                    if object.original.is_none() && scene.object(id).is_some_and(|object| object.transform().is_none()) {
+                        scene.make_transformable(id);
+                    }
+                    scene.set_transform(id, object.transform());
+                    self.objects.insert(id, object);
+                }
+                Edit::Material(id, material) => match scene.material_mut(id) {
+                    Some(target) => *target = material,
+                    None => self.message = Some("el material es compartido con otras instancias y no se puede editar".to_string()),
+                },
+                Edit::Light(id, light) => {
+                    if let Some(target) = scene.lights.get_mut(id) {
+                        *target = light;
+                        scene.rebuild_light_sampler();
+                    }
+                }
+                Edit::Camera(camera) => scene.camera = camera,
+            }
+        }
+        self.restart(ctx);
+    }
+
+    /// Muestra una imagen de la vista (de cualquier tamaño: se escala al mostrarla)
+    fn show(&mut self, ctx: &egui::Context, image: &Framebuffer) {
+        let pixels: Vec<u8> = image
+            .pixels()
+            .iter()
+            .flat_map(|color| color_to_rgb(*color, self.tone_mapping, self.encoding).0)
+            .collect();
+        let image = egui::ColorImage::from_rgb([image.width() as usize, image.height() as usize], &pixels);
+        match &mut self.texture {
+            Some(texture) => texture.set(image, egui::TextureOptions::LINEAR),
+            None => self.texture = Some(ctx.load_texture("vista", image, egui::TextureOptions::LINEAR)),
+        }
+    }
+
+    /// Lista de objetos y luces para elegir qué editar
+    fn scene_panel(&mut self, ui: &mut egui::Ui) {
+        let lock = self.scene;
+        let scene = lock.read().unwrap();
+        ui.heading("Objetos");
+        egui::ScrollArea::vertical().id_salt("objetos").max_height(ui.available_height() * 0.6).show(ui, |ui| {
+            for index in 0..scene.objects.len() {
+                let item = SceneItem::Object(scene.object_id(index));
+                if ui.selectable_label(self.selected == Some(item), item_label(&scene, item)).clicked() {
+                    self.selected = Some(item);
+                }
+            }
+        });
+        ui.separator();
+        ui.heading("Luces");
+        egui::ScrollArea::vertical().id_salt("luces").show(ui, |ui| {
+            for id in 0..scene.lights.len() {
+                let item = SceneItem::Light(id);
+                if ui.selectable_label(self.selected == Some(item), item_label(&scene, item)).clicked() {
+                    self.selected = Some(item);
+                }
+            }
+        });
+    }
+
+    /// Editores del elemento elegido; retorna el cambio si hubo alguno
+    fn properties_panel(&mut self, ui: &mut egui::Ui) -> Option<Edit> {
+        let lock = self.scene;
+        let scene = lock.read().unwrap();
+        let Some(item) = self.selected else {
+            ui.label("Elegir un objeto o una luz de la lista");
+            return None;
+        };
+        ui.heading(item_label(&scene, item));
+        let mut edit = None;
+
+        match item {
+            SceneItem::Object(id) => {
+                let object = scene.object(id)?;
+                let mut transform = *self.objects.entry(id).or_insert_with(|| ObjectEdit::new(object));
+                egui::CollapsingHeader::new("Transformación").default_open(true).show(ui, |ui| {
+                    let mut changed = vec3_editor(ui, "Posición", &mut transform.translation, 0.05);
+                    changed |= vec3_editor(ui, "Rotación", &mut transform.rotation, 1.0);
+                    changed |= vec3_editor(ui, "Escala", &mut transform.scale, 0.01);
+                    if ui.button("Restablecer").clicked() {
+                        transform = ObjectEdit {
+                            translation: Vec3::zero(),
+                            rotation: Vec3::zero(),
+                            scale: Vec3::new(1.0, 1.0, 1.0),
+                            ..transform
+                        };
+                        changed = true;
+                    }
+                    if changed {
+                        edit = Some(Edit::Transform(id, transform));
+                    }
+                });
+
+                let mut material = *object.get_material();
+                egui::CollapsingHeader::new("Material").default_open(true).show(ui, |ui| {
+                    if material_editor(ui, &mut material) {
+                        edit = Some(Edit::Material(id, material));
+                    }
+                });
+            }
+            SceneItem::Light(id) => {
+                let mut light = *scene.lights.get(id)?;
+                let mut changed = vec3_editor(ui, "Posición", &mut light.position, 0.05);
+                changed |= color_editor(ui, "Color", &mut light.color);
+                changed |= ui.add(egui::Slider::new(&mut light.intensity, 0.0..=10.0).text("Intensidad").clamping(egui::SliderClamping::Never)).changed();
+                changed |= ui.checkbox(&mut light.casts_shadows, "Proyecta sombras").changed();
+                if changed {
+                    edit = Some(Edit::Light(id, light));
+                }
+            }
+            SceneItem::Texture(_) => {}
+        }
+        edit
+    }
+
+    /// Imagen de la vista; arrastrar orbita la cámara alrededor de su punto
+    /// de mira y la rueda la acerca. Retorna la cámara nueva si se movió
+    fn viewport(&mut self, ui: &mut egui::Ui) -> Option<Camera> {
+        ui.label(format!("{}/{} muestras", self.samples.0, self.samples.1));
+        let Some(texture) = &self.texture else {
+            ui.centered_and_justified(|ui| ui.spinner());
+            return None;
+        };
+        let size = texture.size_vec2();
+        let available = ui.available_size();
+        let fit = (available.x / size.x).min(available.y / size.y).max(0.0);
+        let image = egui::Image::new(egui::load::SizedTexture::new(texture.id(), size * fit)).sense(egui::Sense::drag());
+        let response = ui.vertical_centered(|ui| ui.add(image)).inner;
+
+        let drag = if response.dragged() { response.drag_delta() } else { egui::Vec2::ZERO };
+        let scroll = if response.hovered() { ui.input(|input| input.smooth_scroll_delta.y) } else { 0.0 };
+        if drag == egui::Vec2::ZERO && scroll == 0.0 {
+            return None;
+        }
+
+        // Ángulos actuales, con la misma convención que `Camera::orbit`
+        let camera = self.scene.read().unwrap().camera.clone();
+        let offset = camera.position - camera.look_at;
+        let radius = offset.length().max(1e-3);
+        let azimuth = offset.x.atan2(offset.z).to_degrees() - drag.x * ORBIT_SENSITIVITY;
+        let elevation = (offset.y / radius).clamp(-1.0, 1.0).asin().to_degrees() + drag.y * ORBIT_SENSITIVITY;
+        let distance = radius * (-scroll * ZOOM_SENSITIVITY).exp();
+        Some(camera.orbit(camera.look_at, distance, azimuth, elevation))
+    }
+}
+
+/// Nombre del elemento en la escena, o su tipo e ID si no tiene
+fn item_label(scene: &Scene, item: SceneItem) -> String {
+    let label = match item {
+        SceneItem::Object(id) => format!("Objeto {}", id),
+        SceneItem::Light(id) => format!("Luz {}", id),
+        SceneItem::Texture(id) => format!("Textura {}", id),
+    };
+    match scene.name_of(item) {
+        Some(name) => format!("{} ({})", name, label),
+        None => label,
+    }
+}
+
+/// Campos de X, Y y Z; retorna true si cambió alguno
+fn vec3_editor(ui: &mut egui::Ui, label: &str, value: &mut Vec3, speed: f32) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut changed = false;
+        for (axis, component) in ["x", "y", "z"].into_iter().zip([&mut value.x, &mut value.y, &mut value.z]) {
+            changed |= ui.add(egui::DragValue::new(component).speed(speed).prefix(format!("{}: ", axis))).changed();
+        }
+        changed
+    })
+    .inner
+}
+
+/// Selector de un color lineal entre 0 y 1
+fn color_editor(ui: &mut egui::Ui, label: &str, color: &mut Color) -> bool {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        let mut rgb = [color.x, color.y, color.z];
+        let changed = ui.color_edit_button_rgb(&mut rgb).changed();
+        *color = Color::new(rgb[0], rgb[1], rgb[2]);
+        changed
+    })
+    .inner
+}
+
+fn material_editor(ui: &mut egui::Ui, material: &mut Material) -> bool {
+    let mut changed = color_editor(ui, "Color", &mut material.color);
+    for (value, label) in [
+        (&mut material.albedo, "Albedo"),
+        (&mut material.specular, "Especular"),
+        (&mut material.reflectivity, "Reflexión"),
+        (&mut material.roughness, "Rugosidad"),
+        (&mut material.transparency, "Transparencia"),
+    ] {
+        changed |= ui.add(egui::Slider::new(value, 0.0..=1.0).text(label)).changed();
+    }
+    changed |= ui.add(egui::Slider::new(&mut material.shininess, 1.0..=512.0).logarithmic(true).text("Brillo")).changed();
+    changed |= vec3_editor(ui, "Emisión", &mut material.emission, 0.05);
+    changed
+}
@@ -0,0 +1,123 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Vec3, Color};
+use crate::texture::Texture;
+use crate::render_cache::hash_f32;
+use crate::sky::SkyModel;
+
+/// Entorno que rodea la escena
+/// Define lo que ven los rayos que no chocan con ningún objeto y, además,
+/// actúa como fuente de luz ambiental (iluminación basada en imagen)
+#[derive(Serialize, Deserialize)]
+pub enum Environment {
+    /// Mapa equirectangular (latitud-longitud), normalmente una imagen HDR
+    Equirectangular { texture: Texture, intensity: f32 },
+    /// Skybox de seis caras en el orden +X, -X, +Y, -Y, +Z, -Z
+    /// (misma convención de orientación que los cubemaps de OpenGL)
+    Cubemap { faces: Box<[Texture; 6]>, intensity: f32 },
+    /// Cielo analítico calculado a partir de la posición del sol
+    Sky(SkyModel),
+}
+
+impl Environment {
+    /// Crea un entorno a partir de una textura equirectangular
+    pub fn equirectangular(texture: Texture, intensity: f32) -> Self {
+        Environment::Equirectangular { texture, intensity }
+    }
+
+    /// Carga un mapa de entorno equirectangular desde disco (.hdr, .exr, .png...)
+    pub fn from_image(path: &str, intensity: f32) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Environment::equirectangular(Texture::from_image(path)?, intensity))
+    }
+
+    /// Crea un skybox a partir de seis texturas (+X, -X, +Y, -Y, +Z, -Z)
+    pub fn cubemap(faces: [Texture; 6], intensity: f32) -> Self {
+        Environment::Cubemap {
+            faces: Box::new(faces),
+            intensity,
+        }
+    }
+
+    /// Carga un skybox desde seis imágenes (+X, -X, +Y, -Y, +Z, -Z)
+    pub fn cubemap_from_images(paths: [&str; 6], intensity: f32) -> Result<Self, Box<dyn std::error::Error>> {
+        let faces = [
+            Texture::from_image(paths[0])?,
+            Texture::from_image(paths[1])?,
+            Texture::from_image(paths[2])?,
+            Texture::from_image(paths[3])?,
+            Texture::from_image(paths[4])?,
+            Texture::from_image(paths[5])?,
+        ];
+        Ok(Environment::cubemap(faces, intensity))
+    }
+
+    /// Radiancia que llega desde la dirección `direction`
+    pub fn sample(&self, direction: &Vec3) -> Color {
+        match self {
+            Environment::Equirectangular { texture, intensity } => {
+                let dir = direction.normalize();
+
+                // Misma convención que las coordenadas UV de la esfera
+                let u = 0.5 + dir.z.atan2(dir.x) / std::f32::consts::PI * 0.5;
+                let v = 0.5 - dir.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+
+                texture.sample(u, v) * *intensity
+            }
+            Environment::Cubemap { faces, intensity } => {
+                let (face, u, v) = Self::cubemap_face(direction);
+                // El filtrado bilineal con bordes extendidos evita costuras visibles
+                faces[face].sample_bilinear(u, v) * *intensity
+            }
+            Environment::Sky(sky) => sky.radiance(direction),
+        }
+    }
+
+    /// Selecciona la cara del cubemap según el eje dominante de la dirección
+    /// y calcula las coordenadas UV dentro de esa cara
+    fn cubemap_face(direction: &Vec3) -> (usize, f32, f32) {
+        let (x, y, z) = (direction.x, direction.y, direction.z);
+        let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+
+        let (face, sc, tc, ma) = if ax >= ay && ax >= az {
+            if x > 0.0 { (0, -z, -y, ax) } else { (1, z, -y, ax) }
+        } else if ay >= az {
+            if y > 0.0 { (2, x, z, ay) } else { (3, x, -z, ay) }
+        } else if z > 0.0 {
+            (4, x, -y, az)
+        } else {
+            (5, -x, -y, az)
+        };
+
+        if ma <= 0.0 {
+            return (face, 0.5, 0.5);
+        }
+
+        let u = 0.5 * (sc / ma + 1.0);
+        let v = 0.5 * (tc / ma + 1.0);
+        (face, u, v)
+    }
+
+    /// Agrega el estado del entorno al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        match self {
+            Environment::Equirectangular { texture, intensity } => {
+                state.write_u8(0);
+                texture.hash_state(state);
+                hash_f32(state, *intensity);
+            }
+            Environment::Cubemap { faces, intensity } => {
+                state.write_u8(1);
+                for face in faces.iter() {
+                    face.hash_state(state);
+                }
+                hash_f32(state, *intensity);
+            }
+            Environment::Sky(sky) => {
+                state.write_u8(2);
+                sky.hash_state(state);
+            }
+        }
+    }
+}
@@ -0,0 +1,355 @@
+// Exportación de escenas a Wavefront OBJ (con su .mtl) o glTF 2.0 (.gltf con
+// los datos binarios incrustados), para abrirlas en Blender u otro programa.
+// Las primitivas se convierten en triángulos (ver `Intersectable::tessellate`)
+// y los materiales se aproximan: no se exportan texturas ni coordenadas UV,
+// y los planos infinitos quedan como cuadrados grandes.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::vector::{Point3, Vec3};
+use crate::material::Material;
+use crate::light::{Light, LightKind};
+use crate::scene::{Scene, SceneItem};
+use crate::framebuffer::create_parent_dir;
+
+/// Formato de exportación, elegido por la extensión de la ruta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Geometría y materiales; la cámara y las luces van como comentarios
+    Obj,
+    /// Geometría, materiales PBR, cámara y luces (KHR_lights_punctual)
+    Gltf,
+}
+
+impl ExportFormat {
+    /// Formato según la extensión de `path` (obj o gltf)
+    pub fn from_path(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let extension = Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "obj" => Ok(ExportFormat::Obj),
+            "gltf" => Ok(ExportFormat::Gltf),
+            _ => Err(format!("formato de exportación no admitido: '{}' (se admiten obj y gltf)", path).into()),
+        }
+    }
+}
+
+/// Objeto de la escena convertido en triángulos
+struct ExportedMesh {
+    name: String,
+    material: Material,
+    vertices: Vec<Point3>,
+    triangles: Vec<[usize; 3]>,
+}
+
+/// Resultado de una exportación
+#[derive(Debug, Clone, Default)]
+pub struct ExportReport {
+    /// Objetos exportados
+    pub exported: usize,
+    /// Nombres de los objetos omitidos por no saber convertirse en triángulos
+    pub skipped: Vec<String>,
+}
+
+/// Exporta `scene` a `path` en el formato de su extensión
+/// Los objetos que no saben convertirse en triángulos se omiten y quedan
+/// anotados en el resultado
+pub fn export(scene: &Scene, path: &str) -> Result<ExportReport, Box<dyn std::error::Error>> {
+    let format = ExportFormat::from_path(path)?;
+    let (meshes, skipped) = tessellate_scene(scene);
+    create_parent_dir(path).map_err(|e| format!("no se pudo crear el directorio de {}: {}", path, e))?;
+
+    match format {
+        ExportFormat::Obj => write_obj(scene, &meshes, path)?,
+        ExportFormat::Gltf => {
+            let text = serde_json::to_string_pretty(&gltf_document(scene, &meshes))?;
+            std::fs::write(path, text).map_err(|e| format!("no se pudo escribir {}: {}", path, e))?;
+        }
+    }
+    Ok(ExportReport { exported: meshes.len(), skipped })
+}
+
+/// Mallas de los objetos de la escena y nombres de los que no se pueden
+/// convertir en triángulos
+fn tessellate_scene(scene: &Scene) -> (Vec<ExportedMesh>, Vec<String>) {
+    let mut meshes = Vec::new();
+    let mut skipped = Vec::new();
+    for (index, object) in scene.objects.iter().enumerate() {
+        let id = scene.object_id(index);
+        let name = scene
+            .name_of(SceneItem::Object(id))
+            .map_or_else(|| format!("objeto_{}", id), str::to_string);
+        match object.tessellate() {
+            Some((vertices, triangles)) => meshes.push(ExportedMesh {
+                name,
+                material: *object.get_material(),
+                vertices,
+                triangles,
+            }),
+            None => skipped.push(name),
+        }
+    }
+    (meshes, skipped)
+}
+
+fn write_obj(scene: &Scene, meshes: &[ExportedMesh], path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mtl_path = Path::new(path).with_extension("mtl");
+    let mtl_name = mtl_path.file_name().unwrap_or_default().to_string_lossy();
+
+    let mut obj = String::new();
+    let camera = &scene.camera;
+    writeln!(obj, "# Exportado por raytracer")?;
+    writeln!(
+        obj,
+        "# cámara: posición {} mirando a {} (fov vertical {}°)",
+        triple(camera.position),
+        triple(camera.look_at),
+        camera.fov
+    )?;
+    for light in &scene.lights {
+        writeln!(
+            obj,
+            "# luz: {:?} en {} color {} intensidad {}",
+            light.kind,
+            triple(light.position),
+            triple(light.color),
+            light.intensity
+        )?;
+    }
+    writeln!(obj, "mtllib {}", mtl_name)?;
+
+    let mut mtl = String::from("# Materiales aproximados de la escena\n");
+    // Los índices de OBJ son globales y empiezan en 1
+    let mut first_vertex = 1;
+    for mesh in meshes {
+        let name = obj_name(&mesh.name);
+        writeln!(obj, "o {}", name)?;
+        writeln!(obj, "usemtl {}", name)?;
+        for vertex in &mesh.vertices {
+            writeln!(obj, "v {}", triple(*vertex))?;
+        }
+        for [a, b, c] in &mesh.triangles {
+            writeln!(obj, "f {} {} {}", first_vertex + a, first_vertex + b, first_vertex + c)?;
+        }
+        first_vertex += mesh.vertices.len();
+        write_mtl_material(&mut mtl, &name, &mesh.material)?;
+    }
+
+    std::fs::write(path, obj).map_err(|e| format!("no se pudo escribir {}: {}", path, e))?;
+    std::fs::write(&mtl_path, mtl).map_err(|e| format!("no se pudo escribir {}: {}", mtl_path.display(), e))?;
+    Ok(())
+}
+
+fn write_mtl_material(mtl: &mut String, name: &str, material: &Material) -> std::fmt::Result {
+    let diffuse = material.color * material.albedo;
+    writeln!(mtl, "\nnewmtl {}", name)?;
+    writeln!(mtl, "Kd {}", triple(diffuse))?;
+    writeln!(mtl, "Ks {s} {s} {s}", s = material.specular)?;
+    writeln!(mtl, "Ns {}", material.shininess)?;
+    writeln!(mtl, "Ke {}", triple(material.emission))?;
+    writeln!(mtl, "d {}", 1.0 - material.transparency)?;
+    if material.transparency > 0.0 {
+        writeln!(mtl, "Ni 1.5")?;
+    }
+    // Extensión PBR de MTL (la leen Blender y la mayoría de los importadores)
+    writeln!(mtl, "Pr {}", material.roughness)?;
+    writeln!(mtl, "Pm {}", material.reflectivity)
+}
+
+/// Componentes separados por espacios, como los escriben OBJ y MTL
+fn triple(v: Vec3) -> String {
+    format!("{} {} {}", v.x, v.y, v.z)
+}
+
+/// Nombre sin espacios (OBJ y MTL separan los campos por espacios)
+fn obj_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+fn gltf_document(scene: &Scene, meshes: &[ExportedMesh]) -> Value {
+    let mut buffer = Vec::new();
+    let mut buffer_views = Vec::new();
+    let mut accessors = Vec::new();
+    let mut gltf_meshes = Vec::new();
+    let mut materials = Vec::new();
+    let mut nodes = Vec::new();
+
+    for (index, mesh) in meshes.iter().enumerate() {
+        let (min, max) = bounds(&mesh.vertices);
+        let positions: Vec<f32> = mesh.vertices.iter().flat_map(|v| [v.x, v.y, v.z]).collect();
+        let indices: Vec<u32> = mesh.triangles.iter().flatten().map(|&i| i as u32).collect();
+
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": buffer.len(),
+            "byteLength": positions.len() * 4,
+            "target": 34962,
+        }));
+        buffer.extend(positions.iter().flat_map(|value| value.to_le_bytes()));
+        buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": buffer.len(),
+            "byteLength": indices.len() * 4,
+            "target": 34963,
+        }));
+        buffer.extend(indices.iter().flat_map(|value| value.to_le_bytes()));
+
+        accessors.push(json!({
+            "bufferView": 2 * index,
+            "componentType": 5126,
+            "count": mesh.vertices.len(),
+            "type": "VEC3",
+            "min": [min.x, min.y, min.z],
+            "max": [max.x, max.y, max.z],
+        }));
+        accessors.push(json!({
+            "bufferView": 2 * index + 1,
+            "componentType": 5125,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+
+        gltf_meshes.push(json!({
+            "name": mesh.name,
+            "primitives": [{ "attributes": { "POSITION": 2 * index }, "indices": 2 * index + 1, "material": index }],
+        }));
+        materials.push(gltf_material(&mesh.name, &mesh.material));
+        nodes.push(json!({ "name": mesh.name, "mesh": index }));
+    }
+
+    let camera = &scene.camera;
+    nodes.push(json!({
+        "name": "camara",
+        "camera": 0,
+        "matrix": oriented_matrix(camera.position - camera.look_at, camera.up, camera.position),
+    }));
+
+    let mut lights = Vec::new();
+    for light in &scene.lights {
+        let (light_json, node) = gltf_light(light, lights.len());
+        lights.push(light_json);
+        nodes.push(node);
+    }
+
+    json!({
+        "asset": { "version": "2.0", "generator": "raytracer" },
+        "extensionsUsed": ["KHR_lights_punctual"],
+        "extensions": { "KHR_lights_punctual": { "lights": lights } },
+        "scene": 0,
+        "scenes": [{ "nodes": (0..nodes.len()).collect::<Vec<_>>() }],
+        "nodes": nodes,
+        "meshes": gltf_meshes,
+        "materials": materials,
+        "cameras": [{
+            "type": "perspective",
+            "perspective": {
+                "yfov": camera.fov.to_radians(),
+                "aspectRatio": camera.aspect_ratio,
+                "znear": 0.01,
+            },
+        }],
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{
+            "byteLength": buffer.len(),
+            "uri": format!("data:application/octet-stream;base64,{}", base64(&buffer)),
+        }],
+    })
+}
+
+fn gltf_material(name: &str, material: &Material) -> Value {
+    let color = material.color;
+    let mut value = json!({
+        "name": name,
+        "pbrMetallicRoughness": {
+            "baseColorFactor": [color.x, color.y, color.z, 1.0 - material.transparency],
+            "metallicFactor": material.reflectivity,
+            "roughnessFactor": material.roughness,
+        },
+    });
+    if material.is_emissive() {
+        // glTF limita el factor a 1; la intensidad se pierde
+        let emission = material.emission;
+        let peak = emission.x.max(emission.y).max(emission.z).max(1.0);
+        value["emissiveFactor"] = json!([emission.x / peak, emission.y / peak, emission.z / peak]);
+    }
+    if material.transparency > 0.0 {
+        value["alphaMode"] = json!("BLEND");
+    }
+    value
+}
+
+/// Luz de KHR_lights_punctual y el nodo que la ubica
+/// Las luces de área y esféricas se exportan como puntuales en su centro
+fn gltf_light(light: &Light, index: usize) -> (Value, Value) {
+    let color = [light.color.x, light.color.y, light.color.z];
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let (light_json, matrix) = match light.kind {
+        // Las luces de glTF apuntan hacia -Z local
+        LightKind::Spot { direction, inner_angle, outer_angle, .. } => (
+            json!({
+                "type": "spot",
+                "spot": { "innerConeAngle": inner_angle.min(outer_angle), "outerConeAngle": outer_angle },
+            }),
+            oriented_matrix(-direction, up, light.position),
+        ),
+        LightKind::Directional { direction } => (json!({ "type": "directional" }), oriented_matrix(direction, up, Point3::zero())),
+        LightKind::Area { edge_u, edge_v, .. } => {
+            (json!({ "type": "point" }), oriented_matrix(up, up, light.position + (edge_u + edge_v) * 0.5))
+        }
+        LightKind::Point | LightKind::Sphere { .. } => (json!({ "type": "point" }), oriented_matrix(up, up, light.position)),
+    };
+
+    let mut light_json = light_json;
+    light_json["color"] = json!(color);
+    light_json["intensity"] = json!(light.intensity);
+    let node = json!({
+        "name": format!("luz_{}", index),
+        "matrix": matrix,
+        "extensions": { "KHR_lights_punctual": { "light": index } },
+    });
+    (light_json, node)
+}
+
+/// Matriz de glTF (por columnas) de un nodo en `position` cuyo eje Z local
+/// apunta en `z_axis`, con el eje Y lo más cerca posible de `up`
+fn oriented_matrix(z_axis: Vec3, up: Vec3, position: Point3) -> [f32; 16] {
+    let z = z_axis.normalize();
+    let up = if up.cross(&z).length_squared() < 1e-6 { Vec3::new(1.0, 0.0, 0.0) } else { up };
+    let x = up.cross(&z).normalize();
+    let y = z.cross(&x);
+    [
+        x.x, x.y, x.z, 0.0,
+        y.x, y.y, y.z, 0.0,
+        z.x, z.y, z.z, 0.0,
+        position.x, position.y, position.z, 1.0,
+    ]
+}
+
+fn bounds(vertices: &[Point3]) -> (Point3, Point3) {
+    let infinity = Point3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    vertices.iter().fold((infinity, -infinity), |(min, max), v| (min.min(v), max.max(v)))
+}
+
+/// Base64 estándar (con relleno) para incrustar el buffer en el .gltf
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| group | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[(group >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
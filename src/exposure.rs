@@ -0,0 +1,120 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::Framebuffer;
+use crate::render_cache::hash_f32;
+use crate::tonemap::luminance;
+
+/// Rango del histograma de luminancia, en log2 (EV)
+const HISTOGRAM_MIN_EV: f32 = -16.0;
+const HISTOGRAM_MAX_EV: f32 = 16.0;
+const HISTOGRAM_BINS: usize = 128;
+
+/// Fracción de píxeles más oscuros y más brillantes que no cuentan para el
+/// promedio (fondos negros, reflejos del sol...)
+const IGNORE_DARKEST: f32 = 0.4;
+const IGNORE_BRIGHTEST: f32 = 0.05;
+
+/// Exposición aplicada a la imagen HDR antes del tone mapping
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exposure {
+    /// Exposición fija en pasos (EV): la imagen se multiplica por 2^ev
+    Fixed(f32),
+    /// Exposición elegida a partir del histograma de luminancia de la
+    /// imagen: la luminancia media (en escala logarítmica, sin los extremos)
+    /// se lleva al gris `key` (0.18 es el gris medio). El resultado se
+    /// limita a [min_ev, max_ev] para que una escena casi negra no se
+    /// amplifique sin límite.
+    Auto { key: f32, min_ev: f32, max_ev: f32 },
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Exposure::Fixed(0.0)
+    }
+}
+
+impl Exposure {
+    /// Exposición automática con el gris medio y sin límites estrictos
+    pub fn auto() -> Self {
+        Exposure::Auto { key: 0.18, min_ev: -8.0, max_ev: 8.0 }
+    }
+
+    /// Exposición en EV que corresponde a la imagen
+    pub fn ev_for(&self, framebuffer: &Framebuffer) -> f32 {
+        match *self {
+            Exposure::Fixed(ev) => ev,
+            Exposure::Auto { key, min_ev, max_ev } => match average_log_luminance(framebuffer) {
+                Some(average) => (key.max(1e-6).log2() - average).clamp(min_ev, max_ev),
+                None => 0.0,
+            },
+        }
+    }
+
+    /// Multiplica la imagen por 2^ev
+    pub fn apply(&self, framebuffer: &Framebuffer) -> Framebuffer {
+        let scale = self.ev_for(framebuffer).exp2();
+        framebuffer.map(|color| color * scale)
+    }
+
+    /// Agrega la exposición al hash del render (cambia la imagen guardada)
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        match *self {
+            Exposure::Fixed(ev) => {
+                state.write_u8(0);
+                hash_f32(state, ev);
+            }
+            Exposure::Auto { key, min_ev, max_ev } => {
+                state.write_u8(1);
+                hash_f32(state, key);
+                hash_f32(state, min_ev);
+                hash_f32(state, max_ev);
+            }
+        }
+    }
+}
+
+/// Promedio de log2(luminancia) de los píxeles entre los percentiles
+/// `IGNORE_DARKEST` y `1 - IGNORE_BRIGHTEST`, calculado sobre un histograma
+/// Retorna None si la imagen está vacía o es completamente negra
+fn average_log_luminance(framebuffer: &Framebuffer) -> Option<f32> {
+    let bin_width = (HISTOGRAM_MAX_EV - HISTOGRAM_MIN_EV) / HISTOGRAM_BINS as f32;
+    let mut histogram = [0u32; HISTOGRAM_BINS];
+    let mut lit = 0u32;
+
+    for color in framebuffer.pixels() {
+        let l = luminance(*color);
+        if l <= 0.0 || !l.is_finite() {
+            continue;
+        }
+        let bin = ((l.log2() - HISTOGRAM_MIN_EV) / bin_width).clamp(0.0, (HISTOGRAM_BINS - 1) as f32);
+        histogram[bin as usize] += 1;
+        lit += 1;
+    }
+
+    if lit == 0 {
+        return None;
+    }
+
+    // Se recorre el histograma quitando píxeles por abajo y por arriba
+    let low = lit as f32 * IGNORE_DARKEST;
+    let high = lit as f32 * (1.0 - IGNORE_BRIGHTEST);
+    let mut seen = 0.0;
+    let mut sum = 0.0;
+    let mut weight = 0.0;
+
+    for (bin, &count) in histogram.iter().enumerate() {
+        let start = seen;
+        seen += count as f32;
+        let used = seen.min(high) - start.max(low);
+        if used > 0.0 {
+            let ev = HISTOGRAM_MIN_EV + (bin as f32 + 0.5) * bin_width;
+            sum += ev * used;
+            weight += used;
+        }
+    }
+
+    if weight > 0.0 { Some(sum / weight) } else { None }
+}
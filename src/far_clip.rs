@@ -0,0 +1,50 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::render_cache::hash_f32;
+
+/// Distancia máxima de los rayos (plano lejano)
+/// Las intersecciones más allá de `distance` se descartan y el rayo cuenta
+/// como si no hubiera chocado con nada, tanto para los rayos de cámara como
+/// para los de sombra y los rebotes. En escenas enormes evita recorrer la
+/// geometría lejana; con `fade_start` los objetos vistos desde la cámara se
+/// funden de a poco con el fondo en lugar de desaparecer de golpe.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FarClip {
+    pub distance: f32,
+    /// Distancia a la que empieza el fundido hacia el fondo (None = corte seco)
+    pub fade_start: Option<f32>,
+}
+
+impl FarClip {
+    pub fn new(distance: f32) -> Self {
+        FarClip {
+            distance: distance.max(0.0),
+            fade_start: None,
+        }
+    }
+
+    /// Funde las superficies con el fondo entre `start` y el plano lejano
+    pub fn with_fade(mut self, start: f32) -> Self {
+        self.fade_start = Some(start.clamp(0.0, self.distance));
+        self
+    }
+
+    /// Fracción del color de la superficie que se conserva a la distancia `t`
+    /// (1 antes del fundido, 0 en el plano lejano, con una transición suave)
+    pub fn visibility(&self, t: f32) -> f32 {
+        match self.fade_start {
+            Some(start) if t > start => {
+                let x = ((t - start) / (self.distance - start).max(1e-6)).clamp(0.0, 1.0);
+                1.0 - x * x * (3.0 - 2.0 * x)
+            }
+            _ => 1.0,
+        }
+    }
+
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_f32(state, self.distance);
+        hash_f32(state, self.fade_start.unwrap_or(-1.0));
+    }
+}
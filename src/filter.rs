@@ -0,0 +1,84 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::render_cache::hash_f32;
+
+/// Filtro de reconstrucción de píxeles
+/// Con varias muestras por píxel cada muestra cae en un punto al azar dentro
+/// del soporte del filtro (un cuadrado de lado 2·radio centrado en el píxel,
+/// que puede extenderse sobre los vecinos) y el píxel es el promedio de las
+/// muestras ponderado por el filtro. Los bordes quedan más suaves y con
+/// menos escalones que con un promedio simple.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PixelFilter {
+    /// Todas las muestras pesan lo mismo; con radio 0.5 es el promedio
+    /// simple dentro del píxel
+    Box { radius: f32 },
+    /// Peso que decrece linealmente hasta cero en el radio
+    Tent { radius: f32 },
+    /// Gaussiana de parámetro `alpha` (más alto = más estrecha), desplazada
+    /// para llegar a cero justo en el radio
+    Gaussian { radius: f32, alpha: f32 },
+}
+
+impl Default for PixelFilter {
+    fn default() -> Self {
+        PixelFilter::Box { radius: 0.5 }
+    }
+}
+
+impl PixelFilter {
+    pub fn tent(radius: f32) -> Self {
+        PixelFilter::Tent { radius: radius.max(0.5) }
+    }
+
+    pub fn gaussian(radius: f32) -> Self {
+        PixelFilter::Gaussian { radius: radius.max(0.5), alpha: 2.0 }
+    }
+
+    pub fn radius(&self) -> f32 {
+        match *self {
+            PixelFilter::Box { radius } | PixelFilter::Tent { radius } | PixelFilter::Gaussian { radius, .. } => radius,
+        }
+    }
+
+    /// Convierte un par de números en [0, 1) en un desplazamiento desde el
+    /// centro del píxel (en píxeles) y el peso de la muestra
+    pub fn sample(&self, u: f32, v: f32) -> (f32, f32, f32) {
+        let radius = self.radius();
+        let dx = (2.0 * u - 1.0) * radius;
+        let dy = (2.0 * v - 1.0) * radius;
+        (dx, dy, self.evaluate(dx) * self.evaluate(dy))
+    }
+
+    /// Valor del filtro (separable) a una distancia `d` del centro en un eje
+    fn evaluate(&self, d: f32) -> f32 {
+        match *self {
+            PixelFilter::Box { .. } => 1.0,
+            PixelFilter::Tent { radius } => (radius - d.abs()).max(0.0),
+            PixelFilter::Gaussian { radius, alpha } => {
+                ((-alpha * d * d).exp() - (-alpha * radius * radius).exp()).max(0.0)
+            }
+        }
+    }
+
+    /// Agrega el filtro al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        match *self {
+            PixelFilter::Box { radius } => {
+                state.write_u8(0);
+                hash_f32(state, radius);
+            }
+            PixelFilter::Tent { radius } => {
+                state.write_u8(1);
+                hash_f32(state, radius);
+            }
+            PixelFilter::Gaussian { radius, alpha } => {
+                state.write_u8(2);
+                hash_f32(state, radius);
+                hash_f32(state, alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Color;
+use crate::framebuffer::Framebuffer;
+use crate::render_cache::hash_f32;
+use crate::tonemap::luminance;
+
+/// Supresión de "fireflies": píxeles aislados muy brillantes producidos por
+/// muestras improbables (p. ej. un rebote difuso que encuentra una fuente
+/// pequeña e intensa). Ambas opciones introducen un pequeño sesgo a cambio
+/// de imágenes mucho más limpias con pocas muestras.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FireflyFilter {
+    /// Luminancia máxima de una muestra individual
+    pub max_radiance: Option<f32>,
+    /// Un píxel cuya luminancia supera `umbral ×` la de todos sus vecinos
+    /// se reemplaza por la mediana de su vecindario 3×3
+    pub outlier_threshold: Option<f32>,
+}
+
+impl FireflyFilter {
+    /// Limita la luminancia de cada muestra, conservando el tono del color
+    pub fn with_max_radiance(mut self, max_radiance: f32) -> Self {
+        self.max_radiance = Some(max_radiance.max(0.0));
+        self
+    }
+
+    /// Activa el rechazo de píxeles atípicos con el umbral dado
+    pub fn with_outlier_rejection(mut self, threshold: f32) -> Self {
+        self.outlier_threshold = Some(threshold.max(1.0));
+        self
+    }
+
+    /// Aplica el límite de radiancia a una muestra
+    pub fn clamp_sample(&self, color: Color) -> Color {
+        match self.max_radiance {
+            Some(max) => {
+                let l = luminance(color);
+                if l > max {
+                    color * (max / l)
+                } else {
+                    color
+                }
+            }
+            None => color,
+        }
+    }
+
+    /// Reemplaza los píxeles atípicos (si la opción está activa)
+    pub fn reject_outliers(&self, framebuffer: Framebuffer) -> Framebuffer {
+        let threshold = match self.outlier_threshold {
+            Some(threshold) => threshold,
+            None => return framebuffer,
+        };
+
+        let width = framebuffer.width() as i32;
+        let height = framebuffer.height() as i32;
+
+        Framebuffer::from_fn(framebuffer.width(), framebuffer.height(), |x, y| {
+            let center = framebuffer.get(x, y);
+            let mut neighbours = Vec::with_capacity(9);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                    if (dx != 0 || dy != 0) && (0..width).contains(&nx) && (0..height).contains(&ny) {
+                        neighbours.push(framebuffer.get(nx as u32, ny as u32));
+                    }
+                }
+            }
+
+            let brightest = neighbours.iter().map(|c| luminance(*c)).fold(0.0, f32::max);
+            if neighbours.is_empty() || luminance(center) <= brightest * threshold {
+                return center;
+            }
+
+            neighbours.push(center);
+            neighbours.sort_by(|a, b| luminance(*a).total_cmp(&luminance(*b)));
+            neighbours[neighbours.len() / 2]
+        })
+    }
+
+    /// Agrega las opciones al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_f32(state, self.max_radiance.unwrap_or(-1.0));
+        hash_f32(state, self.outlier_threshold.unwrap_or(-1.0));
+    }
+}
@@ -0,0 +1,185 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use image::{ImageBuffer, Rgb, RgbImage};
+use rayon::prelude::*;
+
+use crate::vector::Color;
+use crate::tonemap::ToneMapping;
+use crate::gamma::ColorEncoding;
+
+/// Imagen RGB de 16 bits por canal
+pub type Rgb16Image = ImageBuffer<Rgb<u16>, Vec<u16>>;
+
+/// Imagen renderizada en colores lineales HDR
+/// Los píxeles se guardan en un único vector, fila por fila (el píxel
+/// (x, y) está en `y * width + x`): recorrerla es lineal en memoria y cada
+/// fila es un trozo contiguo que un hilo puede escribir sin bloqueos.
+#[derive(Debug, Clone, Default)]
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+}
+
+impl Framebuffer {
+    /// Imagen negra de `width`×`height`
+    pub fn new(width: u32, height: u32) -> Self {
+        Framebuffer {
+            width,
+            height,
+            pixels: vec![Color::zero(); (width * height) as usize],
+        }
+    }
+
+    /// Envuelve píxeles ya calculados, ordenados por filas
+    pub fn from_pixels(width: u32, height: u32, pixels: Vec<Color>) -> Self {
+        assert_eq!(pixels.len(), (width * height) as usize, "el número de píxeles no coincide con el tamaño");
+        Framebuffer { width, height, pixels }
+    }
+
+    /// Calcula cada píxel con `f(x, y)`, repartiendo las filas entre los hilos
+    pub fn from_fn<F>(width: u32, height: u32, f: F) -> Self
+    where
+        F: Fn(u32, u32) -> Color + Sync,
+    {
+        let mut framebuffer = Self::new(width, height);
+        framebuffer.par_rows_mut().enumerate().for_each(|(y, row)| {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = f(x as u32, y as u32);
+            }
+        });
+        framebuffer
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Color {
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: Color) {
+        let index = self.index(x, y);
+        self.pixels[index] = color;
+    }
+
+    /// Todos los píxeles, fila por fila
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    /// Fila `y` de la imagen
+    pub fn row_mut(&mut self, y: u32) -> &mut [Color] {
+        let start = self.index(0, y);
+        &mut self.pixels[start..start + self.width as usize]
+    }
+
+    /// Filas de la imagen para escribirlas en paralelo
+    pub fn par_rows_mut(&mut self) -> rayon::slice::ChunksExactMut<'_, Color> {
+        let width = self.width.max(1) as usize;
+        self.pixels.par_chunks_exact_mut(width)
+    }
+
+    /// Nueva imagen aplicando `f` a cada píxel
+    pub fn map<F>(&self, f: F) -> Framebuffer
+    where
+        F: Fn(Color) -> Color + Sync,
+    {
+        Framebuffer {
+            width: self.width,
+            height: self.height,
+            pixels: self.pixels.par_iter().map(|color| f(*color)).collect(),
+        }
+    }
+
+    /// Imagen de 8 bits: cada color se comprime con el operador de tone
+    /// mapping y luego se aplica la codificación de salida
+    pub fn to_rgb8(&self, tone_mapping: ToneMapping, encoding: ColorEncoding) -> RgbImage {
+        let mut img = ImageBuffer::new(self.width, self.height);
+        for (pixel, color) in img.pixels_mut().zip(&self.pixels) {
+            *pixel = color_to_rgb(*color, tone_mapping, encoding);
+        }
+        img
+    }
+
+    /// Imagen de 16 bits por canal con la misma conversión que `to_rgb8`:
+    /// los degradados suaves (cielos, penumbras) no se escalonan al retocarla
+    pub fn to_rgb16(&self, tone_mapping: ToneMapping, encoding: ColorEncoding) -> Rgb16Image {
+        let mut img = ImageBuffer::new(self.width, self.height);
+        for (pixel, color) in img.pixels_mut().zip(&self.pixels) {
+            *pixel = color_to_rgb16(*color, tone_mapping, encoding);
+        }
+        img
+    }
+
+    /// Guarda la imagen como PNG (ver `to_rgb8`)
+    #[cfg(feature = "fs")]
+    pub fn save_png(
+        &self,
+        path: &str,
+        tone_mapping: ToneMapping,
+        encoding: ColorEncoding,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let img = self.to_rgb8(tone_mapping, encoding);
+        create_parent_dir(path)?;
+        img.save(path)?;
+        Ok(())
+    }
+
+    /// Guarda la imagen como OpenEXR (RGB de 32 bits en coma flotante)
+    /// Los valores se escriben lineales y sin recortar, tal como salen del renderer
+    #[cfg(feature = "fs")]
+    pub fn save_exr(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut img: ImageBuffer<Rgb<f32>, Vec<f32>> = ImageBuffer::new(self.width, self.height);
+        for (pixel, color) in img.pixels_mut().zip(&self.pixels) {
+            *pixel = Rgb([color.x, color.y, color.z]);
+        }
+
+        create_parent_dir(path)?;
+        img.save(path)?;
+        Ok(())
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        debug_assert!(x < self.width && y < self.height, "píxel ({}, {}) fuera de la imagen", x, y);
+        (y * self.width + x) as usize
+    }
+}
+
+/// Convierte un color lineal HDR a RGB (0-255): primero se comprime con el
+/// operador de tone mapping y luego se aplica la codificación de salida
+pub fn color_to_rgb(color: Color, tone_mapping: ToneMapping, encoding: ColorEncoding) -> Rgb<u8> {
+    let color = encoding.encode_color(tone_mapping.apply(color));
+    let r = (color.x * 255.0).clamp(0.0, 255.0) as u8;
+    let g = (color.y * 255.0).clamp(0.0, 255.0) as u8;
+    let b = (color.z * 255.0).clamp(0.0, 255.0) as u8;
+    Rgb([r, g, b])
+}
+
+/// Como `color_to_rgb`, pero con 16 bits por canal
+pub fn color_to_rgb16(color: Color, tone_mapping: ToneMapping, encoding: ColorEncoding) -> Rgb<u16> {
+    let color = encoding.encode_color(tone_mapping.apply(color));
+    let r = (color.x * 65535.0).clamp(0.0, 65535.0) as u16;
+    let g = (color.y * 65535.0).clamp(0.0, 65535.0) as u16;
+    let b = (color.z * 65535.0).clamp(0.0, 65535.0) as u16;
+    Rgb([r, g, b])
+}
+
+/// Crea el directorio de `path` si no existe
+#[cfg(feature = "fs")]
+pub(crate) fn create_parent_dir(path: &str) -> std::io::Result<()> {
+    match Path::new(path).parent() {
+        Some(parent) => std::fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
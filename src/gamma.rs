@@ -0,0 +1,65 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Color;
+use crate::render_cache::hash_f32;
+
+/// Gamma de salida por defecto (aproximación habitual de sRGB)
+pub const DEFAULT_GAMMA: f32 = 2.2;
+
+/// Codificación aplicada al convertir los colores lineales del framebuffer
+/// a valores de 8 bits. Los monitores esperan valores codificados con
+/// gamma; guardar los valores lineales directamente produce imágenes
+/// demasiado oscuras y contrastadas.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorEncoding {
+    /// Sin corrección (valores lineales)
+    Linear,
+    /// Potencia simple: c^(1/gamma)
+    Gamma(f32),
+    /// Curva sRGB exacta (tramo lineal cerca de cero + potencia 2.4)
+    Srgb,
+}
+
+impl Default for ColorEncoding {
+    fn default() -> Self {
+        ColorEncoding::Gamma(DEFAULT_GAMMA)
+    }
+}
+
+impl ColorEncoding {
+    /// Codifica un canal lineal en [0, 1]
+    pub fn encode(&self, value: f32) -> f32 {
+        let value = value.clamp(0.0, 1.0);
+        match *self {
+            ColorEncoding::Linear => value,
+            ColorEncoding::Gamma(gamma) => value.powf(1.0 / gamma.max(1e-3)),
+            ColorEncoding::Srgb => {
+                if value <= 0.003_130_8 {
+                    value * 12.92
+                } else {
+                    1.055 * value.powf(1.0 / 2.4) - 0.055
+                }
+            }
+        }
+    }
+
+    /// Codifica los tres canales de un color
+    pub fn encode_color(&self, color: Color) -> Color {
+        Color::new(self.encode(color.x), self.encode(color.y), self.encode(color.z))
+    }
+
+    /// Agrega la codificación al hash del render (cambia la imagen guardada)
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        match *self {
+            ColorEncoding::Linear => state.write_u8(0),
+            ColorEncoding::Gamma(gamma) => {
+                state.write_u8(1);
+                hash_f32(state, gamma);
+            }
+            ColorEncoding::Srgb => state.write_u8(2),
+        }
+    }
+}
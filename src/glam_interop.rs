@@ -0,0 +1,63 @@
+// Conversiones con glam (feature `glam`), para pasar posiciones, direcciones
+// y transformaciones desde y hacia código que ya usa sus tipos sin copiar
+// componente por componente:
+//
+//   Vec3 / Point3 / Color  <->  glam::Vec3, glam::Vec3A
+//   Transform               ->  glam::Mat4
+//   glam::Mat4              ->  Transform (TryFrom: debe ser afín e invertible)
+//
+// Transform guarda la matriz por filas y glam por columnas; la traslación
+// queda en la cuarta columna de la Mat4.
+
+use crate::vector::Vec3;
+use crate::transform::Transform;
+
+/// Tolerancia para aceptar como afín la última fila de una Mat4
+const AFFINE_TOLERANCE: f32 = 1e-6;
+
+impl From<glam::Vec3> for Vec3 {
+    fn from(v: glam::Vec3) -> Self {
+        Vec3::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vec3> for glam::Vec3 {
+    fn from(v: Vec3) -> Self {
+        glam::Vec3::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<glam::Vec3A> for Vec3 {
+    fn from(v: glam::Vec3A) -> Self {
+        Vec3::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vec3> for glam::Vec3A {
+    fn from(v: Vec3) -> Self {
+        glam::Vec3A::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Transform> for glam::Mat4 {
+    fn from(transform: Transform) -> Self {
+        let m = transform.matrix;
+        let column = |j: usize| glam::Vec4::new(m[0][j], m[1][j], m[2][j], 0.0);
+        glam::Mat4::from_cols(column(0), column(1), column(2), glam::Vec3::from(transform.translation).extend(1.0))
+    }
+}
+
+impl TryFrom<glam::Mat4> for Transform {
+    type Error = String;
+
+    /// Falla si la matriz es proyectiva (su última fila no es 0, 0, 0, 1)
+    /// o si su parte lineal no es invertible
+    fn try_from(matrix: glam::Mat4) -> Result<Self, Self::Error> {
+        if !matrix.row(3).abs_diff_eq(glam::Vec4::W, AFFINE_TOLERANCE) {
+            return Err(format!("la matriz no es afín: su última fila es {:?}", matrix.row(3).to_array()));
+        }
+        let linear = [0, 1, 2].map(|i| matrix.row(i).truncate().to_array());
+        Transform::new(linear, matrix.w_axis.truncate().into())
+            .ok_or_else(|| "la matriz no es invertible".to_string())
+    }
+}
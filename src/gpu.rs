@@ -0,0 +1,539 @@
+// Backend de GPU: la escena se aplana en buffers con la disposición de
+// memoria que espera el shader de cómputo `shaders/trace.wgsl` (primitivas,
+// nodos de la BVH, materiales, luces y texturas). El shader recorre la BVH y
+// sombrea con el modelo básico del renderer de CPU (ambiente + Phong con
+// sombras duras), que sigue siendo la referencia. `GpuScene::intersect`
+// repite el recorrido del shader en CPU para validar los buffers.
+//
+// El envío a la GPU (`render`) usa wgpu y solo se compila con la feature
+// `gpu`: `cargo run --release --features gpu`.
+
+use std::error::Error;
+
+use bytemuck::{Pod, Zeroable};
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::material::Material;
+use crate::light::LightKind;
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::mesh;
+use crate::transform::Transform;
+use crate::scene::Scene;
+use crate::camera::Projection;
+#[cfg(feature = "gpu")]
+use crate::vector::Color;
+#[cfg(feature = "gpu")]
+use crate::framebuffer::Framebuffer;
+
+/// Código WGSL del shader de trazado
+pub const TRACE_SHADER: &str = include_str!("shaders/trace.wgsl");
+
+/// Tamaño del grupo de trabajo (en píxeles por lado) declarado en el shader
+pub const WORKGROUP_SIZE: u32 = 8;
+
+/// Valor de `texture` cuando la primitiva no tiene textura
+pub const NO_TEXTURE: u32 = u32::MAX;
+
+pub const KIND_SPHERE: u32 = 0;
+pub const KIND_BOX: u32 = 1;
+pub const KIND_TRIANGLE: u32 = 2;
+pub const KIND_PLANE: u32 = 3;
+
+/// Primitiva geométrica tal como la lee el shader
+/// Esfera: `a` = centro y radio; caja: `a` = mínimo, `b` = máximo;
+/// triángulo: `a`, `b`, `c` = vértices; plano: `a` = punto, `b` = normal
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+pub struct GpuPrimitive {
+    pub kind: u32,
+    pub object: u32,  // Índice del objeto de la escena (y de su material)
+    pub texture: u32, // ID de textura de sus UV, o NO_TEXTURE
+    pub _pad: u32,
+    pub a: [f32; 4],
+    pub b: [f32; 4],
+    pub c: [f32; 4],
+}
+
+impl GpuPrimitive {
+    fn new(kind: u32, a: [f32; 4], b: [f32; 4], c: [f32; 4]) -> Self {
+        GpuPrimitive { kind, object: 0, texture: NO_TEXTURE, _pad: 0, a, b, c }
+    }
+
+    pub fn sphere(center: Point3, radius: f32) -> Self {
+        Self::new(KIND_SPHERE, vec4(&center, radius), [0.0; 4], [0.0; 4])
+    }
+
+    pub fn aabb(min: Point3, max: Point3) -> Self {
+        Self::new(KIND_BOX, vec4(&min, 0.0), vec4(&max, 0.0), [0.0; 4])
+    }
+
+    pub fn triangle(v0: Point3, v1: Point3, v2: Point3) -> Self {
+        Self::new(KIND_TRIANGLE, vec4(&v0, 0.0), vec4(&v1, 0.0), vec4(&v2, 0.0))
+    }
+
+    pub fn plane(point: Point3, normal: Vec3) -> Self {
+        Self::new(KIND_PLANE, vec4(&point, 0.0), vec4(&normal, 0.0), [0.0; 4])
+    }
+
+    /// Asigna la textura que se muestrea con las UV de la primitiva
+    pub fn with_texture(mut self, texture_id: usize) -> Self {
+        self.texture = texture_id as u32;
+        self
+    }
+
+    /// Copia transformada de la primitiva
+    /// Las esferas y cajas dejan de serlo con rotaciones o escalas no
+    /// uniformes, así que solo se admiten triángulos y planos
+    pub fn transformed(&self, transform: &Transform) -> Option<Self> {
+        let point = |p: &[f32; 4]| vec4(&transform.transform_point(&Vec3::new(p[0], p[1], p[2])), 0.0);
+        let mut primitive = *self;
+        match self.kind {
+            KIND_TRIANGLE => {
+                primitive.a = point(&self.a);
+                primitive.b = point(&self.b);
+                primitive.c = point(&self.c);
+            }
+            KIND_PLANE => {
+                let normal = transform.transform_normal(&Vec3::new(self.b[0], self.b[1], self.b[2]));
+                primitive.a = point(&self.a);
+                primitive.b = vec4(&normal, 0.0);
+            }
+            _ => return None,
+        }
+        Some(primitive)
+    }
+
+    /// Caja envolvente (None para los planos, que son infinitos)
+    fn bounds(&self) -> Option<Aabb> {
+        let (a, b, c) = (to_vec3(&self.a), to_vec3(&self.b), to_vec3(&self.c));
+        match self.kind {
+            KIND_SPHERE => {
+                let r = Vec3::new(self.a[3], self.a[3], self.a[3]);
+                Some(Aabb::new(a - r, a + r))
+            }
+            KIND_BOX => Some(Aabb::new(a, b)),
+            KIND_TRIANGLE => Some(Aabb::from_points(&[a, b, c])),
+            _ => None,
+        }
+    }
+
+    /// Intersección con un rayo, igual que en el shader
+    fn intersect(&self, ray: &Ray) -> Option<f32> {
+        let (a, b, c) = (to_vec3(&self.a), to_vec3(&self.b), to_vec3(&self.c));
+        let t = match self.kind {
+            KIND_SPHERE => {
+                let oc = ray.origin - a;
+                let half_b = oc.dot(&ray.direction);
+                let discriminant = half_b * half_b - ray.direction.dot(&ray.direction) * (oc.dot(&oc) - self.a[3] * self.a[3]);
+                if discriminant < 0.0 {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                let inv_a = 1.0 / ray.direction.dot(&ray.direction);
+                let near = (-half_b - root) * inv_a;
+                if near > 1e-4 { near } else { (-half_b + root) * inv_a }
+            }
+            KIND_BOX => {
+                let (near, far) = slab_range(ray, &a, &b)?;
+                if near > 1e-4 { near } else { far }
+            }
+            KIND_TRIANGLE => return mesh::intersect_triangle(ray, a, b, c),
+            _ => {
+                let denom = ray.direction.dot(&b);
+                if denom.abs() < 1e-6 {
+                    return None;
+                }
+                (a - ray.origin).dot(&b) / denom
+            }
+        };
+        (t > 1e-4).then_some(t)
+    }
+}
+
+/// Nodo de la BVH: en los internos `first` es el hijo izquierdo (el derecho
+/// le sigue); en las hojas, la primera primitiva y `count` > 0
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+pub struct GpuBvhNode {
+    pub min: [f32; 3],
+    pub first: u32,
+    pub max: [f32; 3],
+    pub count: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+pub struct GpuMaterial {
+    pub color: [f32; 3],
+    pub albedo: f32,
+    pub emission: [f32; 3],
+    pub specular: f32,
+    pub shininess: f32,
+    pub reflectivity: f32,
+    pub _pad: [u32; 2],
+}
+
+impl From<&Material> for GpuMaterial {
+    fn from(material: &Material) -> Self {
+        GpuMaterial {
+            color: array3(&material.color),
+            albedo: material.albedo,
+            emission: array3(&material.emission),
+            specular: material.specular,
+            shininess: material.shininess,
+            reflectivity: material.reflectivity,
+            _pad: [0; 2],
+        }
+    }
+}
+
+/// Luz puntual
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+pub struct GpuLight {
+    pub position: [f32; 3],
+    pub intensity: f32,
+    pub color: [f32; 3],
+    pub casts_shadows: u32,
+}
+
+/// Ubicación de una textura dentro del buffer común de texeles
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+pub struct GpuTexture {
+    pub width: u32,
+    pub height: u32,
+    pub offset: u32,
+    pub _pad: u32,
+}
+
+/// Parámetros globales del render (buffer uniforme)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+pub struct GpuUniforms {
+    pub origin: [f32; 4],
+    pub lower_left_corner: [f32; 4],
+    pub horizontal: [f32; 4],
+    pub vertical: [f32; 4],
+    pub background: [f32; 4],
+    pub ambient: [f32; 4],
+    pub width: u32,
+    pub height: u32,
+    pub primitive_count: u32,
+    pub bounded_count: u32, // Las primitivas [bounded_count, primitive_count) son planos
+    pub light_count: u32,
+    pub node_count: u32,
+    pub max_depth: u32,
+    pub samples: u32,
+}
+
+/// Escena preparada para subirse a la GPU
+pub struct GpuScene {
+    pub uniforms: GpuUniforms,
+    pub primitives: Vec<GpuPrimitive>,
+    pub nodes: Vec<GpuBvhNode>,
+    pub materials: Vec<GpuMaterial>,
+    pub lights: Vec<GpuLight>,
+    pub textures: Vec<GpuTexture>,
+    pub texels: Vec<[f32; 4]>,
+}
+
+impl GpuScene {
+    /// Aplana la escena. Falla si contiene objetos o luces que el shader no
+    /// sabe trazar; en ese caso hay que usar el renderer de CPU. Los efectos
+    /// que el shader no implementa (medios, entorno, enlaces de luz, plano
+    /// lejano...) se ignoran
+    pub fn from_scene(scene: &Scene) -> Result<Self, Box<dyn Error>> {
+        let mut bounded = Vec::new();
+        let mut unbounded = Vec::new();
+        for (id, object) in scene.objects.iter().enumerate() {
+            let primitives = object
+                .gpu_primitives()
+                .ok_or_else(|| format!("el objeto {} no se puede trazar en la GPU", id))?;
+            for mut primitive in primitives {
+                primitive.object = id as u32;
+                match primitive.bounds() {
+                    Some(bounds) => bounded.push((primitive, bounds)),
+                    None => unbounded.push(primitive),
+                }
+            }
+        }
+
+        let bvh = Bvh::build(bounded.iter().enumerate().map(|(index, (_, bounds))| (index, *bounds)).collect());
+        let (flat_nodes, order) = bvh.flatten();
+        let nodes: Vec<GpuBvhNode> = flat_nodes
+            .iter()
+            .map(|(bounds, first, count)| GpuBvhNode {
+                min: array3(&bounds.min),
+                first: *first as u32,
+                max: array3(&bounds.max),
+                count: *count as u32,
+            })
+            .collect();
+        let mut primitives: Vec<GpuPrimitive> = order.iter().map(|&index| bounded[index].0).collect();
+        let bounded_count = primitives.len();
+        primitives.extend(unbounded);
+
+        let lights = scene
+            .lights
+            .iter()
+            .map(|light| match light.kind {
+                LightKind::Point => Ok(GpuLight {
+                    position: array3(&light.position),
+                    intensity: light.intensity,
+                    color: array3(&light.color),
+                    casts_shadows: light.casts_shadows as u32,
+                }),
+                _ => Err("la GPU solo admite luces puntuales"),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut textures = Vec::new();
+        let mut texels = Vec::new();
+        for texture in &scene.textures {
+            textures.push(GpuTexture {
+                width: texture.width,
+                height: texture.height,
+                offset: texels.len() as u32,
+                _pad: 0,
+            });
+            texels.extend(texture.data.iter().flatten().map(|texel| vec4(texel, 1.0)));
+        }
+
+        let camera = &scene.camera;
+        if camera.projection != Projection::Perspective {
+            return Err("la GPU solo admite la proyección en perspectiva".into());
+        }
+        let (lower_left_corner, horizontal, vertical) = camera.viewport();
+        let uniforms = GpuUniforms {
+            origin: vec4(&camera.position, 0.0),
+            lower_left_corner: vec4(&lower_left_corner, 0.0),
+            horizontal: vec4(&horizontal, 0.0),
+            vertical: vec4(&vertical, 0.0),
+            background: vec4(&scene.background_color, 1.0),
+            ambient: vec4(&scene.ambient_light.radiance(), 0.0),
+            width: camera.width,
+            height: camera.height,
+            primitive_count: primitives.len() as u32,
+            bounded_count: bounded_count as u32,
+            light_count: lights.len() as u32,
+            node_count: nodes.len() as u32,
+            max_depth: scene.settings.max_depth,
+            samples: scene.settings.samples_per_pixel.max(1),
+        };
+
+        Ok(GpuScene {
+            uniforms,
+            primitives,
+            nodes,
+            materials: scene.objects.iter().map(|object| object.get_material().into()).collect(),
+            lights,
+            textures,
+            texels,
+        })
+    }
+
+    /// Bytes a subir en total (para informar del uso de memoria)
+    pub fn upload_size(&self) -> usize {
+        std::mem::size_of::<GpuUniforms>()
+            + bytemuck::cast_slice::<_, u8>(&self.primitives).len()
+            + bytemuck::cast_slice::<_, u8>(&self.nodes).len()
+            + bytemuck::cast_slice::<_, u8>(&self.materials).len()
+            + bytemuck::cast_slice::<_, u8>(&self.lights).len()
+            + bytemuck::cast_slice::<_, u8>(&self.textures).len()
+            + bytemuck::cast_slice::<_, u8>(&self.texels).len()
+    }
+
+    /// Intersección más cercana recorriendo los buffers como lo hace el
+    /// shader; retorna la distancia y el índice del objeto
+    pub fn intersect(&self, ray: &Ray) -> Option<(f32, usize)> {
+        let mut closest: Option<(f32, usize)> = None;
+        let test = |primitive: &GpuPrimitive, closest: &mut Option<(f32, usize)>| {
+            if let Some(t) = primitive.intersect(ray) {
+                if t < closest.map_or(f32::INFINITY, |(best, _)| best) {
+                    *closest = Some((t, primitive.object as usize));
+                }
+            }
+        };
+
+        let mut stack = Vec::with_capacity(64);
+        if !self.nodes.is_empty() {
+            stack.push(0);
+        }
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            let t_max = closest.map_or(f32::INFINITY, |(t, _)| t);
+            let bounds = Aabb::new(Vec3::new(node.min[0], node.min[1], node.min[2]), Vec3::new(node.max[0], node.max[1], node.max[2]));
+            if !bounds.hit(ray, t_max) {
+                continue;
+            }
+
+            if node.count > 0 {
+                let first = node.first as usize;
+                for primitive in &self.primitives[first..first + node.count as usize] {
+                    test(primitive, &mut closest);
+                }
+            } else {
+                stack.push(node.first as usize + 1);
+                stack.push(node.first as usize);
+            }
+        }
+
+        for primitive in &self.primitives[self.uniforms.bounded_count as usize..] {
+            test(primitive, &mut closest);
+        }
+
+        closest
+    }
+}
+
+/// Renderiza la escena en la GPU con el shader de cómputo
+/// Retorna un error si no hay adaptador disponible o si la escena tiene
+/// elementos que el shader no admite; el llamador debe recurrir a la CPU
+#[cfg(feature = "gpu")]
+pub fn render(scene: &Scene) -> Result<Framebuffer, Box<dyn Error>> {
+    use wgpu::util::DeviceExt;
+
+    let gpu_scene = GpuScene::from_scene(scene)?;
+    let (width, height) = (gpu_scene.uniforms.width, gpu_scene.uniforms.height);
+
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        ..Default::default()
+    }))?;
+    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+        label: Some("raytracer"),
+        required_limits: adapter.limits(),
+        ..Default::default()
+    }))?;
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("trace.wgsl"),
+        source: wgpu::ShaderSource::Wgsl(TRACE_SHADER.into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("trace"),
+        layout: None,
+        module: &shader,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    // wgpu no admite buffers vacíos: los arreglos sin elementos llevan uno nulo
+    let storage = |label: &str, contents: &[u8]| {
+        let padding = [0u8; 64];
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(label),
+            contents: if contents.is_empty() { &padding } else { contents },
+            usage: wgpu::BufferUsages::STORAGE,
+        })
+    };
+    let uniforms = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("uniforms"),
+        contents: bytemuck::bytes_of(&gpu_scene.uniforms),
+        usage: wgpu::BufferUsages::UNIFORM,
+    });
+    let buffers = [
+        storage("primitives", bytemuck::cast_slice(&gpu_scene.primitives)),
+        storage("nodes", bytemuck::cast_slice(&gpu_scene.nodes)),
+        storage("materials", bytemuck::cast_slice(&gpu_scene.materials)),
+        storage("lights", bytemuck::cast_slice(&gpu_scene.lights)),
+        storage("textures", bytemuck::cast_slice(&gpu_scene.textures)),
+        storage("texels", bytemuck::cast_slice(&gpu_scene.texels)),
+    ];
+
+    let output_size = (width as u64) * (height as u64) * std::mem::size_of::<[f32; 4]>() as u64;
+    let output = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("output"),
+        size: output_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("readback"),
+        size: output_size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+
+    let mut entries = vec![wgpu::BindGroupEntry { binding: 0, resource: uniforms.as_entire_binding() }];
+    for (binding, buffer) in buffers.iter().enumerate() {
+        entries.push(wgpu::BindGroupEntry { binding: binding as u32 + 1, resource: buffer.as_entire_binding() });
+    }
+    entries.push(wgpu::BindGroupEntry { binding: 7, resource: output.as_entire_binding() });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("scene"),
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &entries,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("trace") });
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("trace"),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(width.div_ceil(WORKGROUP_SIZE), height.div_ceil(WORKGROUP_SIZE), 1);
+    }
+    encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, output_size);
+    queue.submit([encoder.finish()]);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    readback.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    device.poll(wgpu::PollType::wait_indefinitely())?;
+    receiver.recv()??;
+
+    let pixels: Vec<[f32; 4]> = bytemuck::cast_slice(&readback.slice(..).get_mapped_range()?).to_vec();
+    readback.unmap();
+
+    let exposure = scene.camera.exposure_scale();
+    Ok(Framebuffer::from_pixels(
+        width,
+        height,
+        pixels.iter().map(|p| Color::new(p[0], p[1], p[2]) * exposure).collect(),
+    ))
+}
+
+/// Intervalo [near, far] del rayo dentro de la caja
+fn slab_range(ray: &Ray, min: &Point3, max: &Point3) -> Option<(f32, f32)> {
+    let mut near = -f32::INFINITY;
+    let mut far = f32::INFINITY;
+    for (origin, direction, min, max) in [
+        (ray.origin.x, ray.direction.x, min.x, max.x),
+        (ray.origin.y, ray.direction.y, min.y, max.y),
+        (ray.origin.z, ray.direction.z, min.z, max.z),
+    ] {
+        if direction.abs() > 1e-6 {
+            let t0 = (min - origin) / direction;
+            let t1 = (max - origin) / direction;
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+            if near > far {
+                return None;
+            }
+        } else if origin < min || origin > max {
+            return None;
+        }
+    }
+    Some((near, far))
+}
+
+fn array3(v: &Vec3) -> [f32; 3] {
+    (*v).into()
+}
+
+fn vec4(v: &Vec3, w: f32) -> [f32; 4] {
+    [v.x, v.y, v.z, w]
+}
+
+fn to_vec3(v: &[f32; 4]) -> Vec3 {
+    Vec3::new(v[0], v[1], v[2])
+}
@@ -0,0 +1,122 @@
+use std::hash::Hasher;
+use std::sync::Arc;
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::transform::Transform;
+use crate::scene::Intersectable;
+use crate::gpu::GpuPrimitive;
+use crate::snapshot::{ObjectSnapshot, SharedObjects};
+
+/// Copia transformada de un objeto compartido
+/// La geometría (y su BVH, si es una malla) se guarda una sola vez; cada
+/// instancia solo guarda su transformación, así que moverla no obliga a
+/// reconstruir la BVH del objeto, solo la de la escena (TLAS).
+pub struct Instance {
+    pub object: Arc<dyn Intersectable>,
+    pub transform: Transform,
+}
+
+impl Instance {
+    pub fn new(object: Arc<dyn Intersectable>, transform: Transform) -> Self {
+        Instance { object, transform }
+    }
+
+    /// Lleva un rayo al espacio local del objeto
+    /// Retorna el rayo local (normalizado) y el factor para convertir sus
+    /// distancias a distancias del rayo original
+    fn local_ray(&self, ray: &Ray) -> (Ray, f32) {
+        let origin = self.transform.inverse_transform_point(&ray.origin);
+        let direction = self.transform.inverse_transform_vector(&ray.direction);
+        let viewpoint = self.transform.inverse_transform_point(&ray.viewpoint);
+        let length = direction.length();
+        (Ray { origin, direction: direction / length, time: ray.time, viewpoint }, 1.0 / length)
+    }
+}
+
+impl Intersectable for Instance {
+    fn intersect(&self, ray: &Ray) -> Option<f32> {
+        let (local_ray, scale) = self.local_ray(ray);
+        self.object.intersect(&local_ray).map(|t| t * scale)
+    }
+
+    fn normal_at(&self, point: &Point3) -> Vec3 {
+        let local = self.transform.inverse_transform_point(point);
+        self.transform.transform_normal(&self.object.normal_at(&local))
+    }
+
+    fn get_material(&self) -> &Material {
+        self.object.get_material()
+    }
+
+    fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)> {
+        self.object.get_uv(&self.transform.inverse_transform_point(point))
+    }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        self.object.hash_state(state);
+        self.transform.hash_state(state);
+    }
+
+    fn normal_at_time(&self, point: &Point3, time: f32) -> Vec3 {
+        let local = self.transform.inverse_transform_point(point);
+        self.transform.transform_normal(&self.object.normal_at_time(&local, time))
+    }
+
+    fn get_uv_at_time(&self, point: &Point3, time: f32) -> Option<(f32, f32, usize)> {
+        self.object.get_uv_at_time(&self.transform.inverse_transform_point(point), time)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        let local = self.object.bounds()?;
+        let corners = local.corners().map(|corner| self.transform.transform_point(&corner));
+        Some(Aabb::from_points(&corners))
+    }
+
+    fn set_transform(&mut self, transform: Transform) -> bool {
+        self.transform = transform;
+        true
+    }
+
+    fn transform(&self) -> Option<Transform> {
+        Some(self.transform)
+    }
+
+    /// Solo si ninguna otra instancia comparte el objeto
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        Arc::get_mut(&mut self.object)?.material_mut()
+    }
+
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        self.object
+            .gpu_primitives()?
+            .iter()
+            .map(|primitive| primitive.transformed(&self.transform))
+            .collect()
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut problems = self.object.validate();
+        if !self.transform.is_finite() {
+            problems.push("instancia con una transformación no finita (NaN o infinito)".to_string());
+        }
+        problems
+    }
+
+    fn texture_id(&self) -> Option<usize> {
+        self.object.texture_id()
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        let (vertices, triangles) = self.object.tessellate()?;
+        let vertices = vertices.iter().map(|vertex| self.transform.transform_point(vertex)).collect();
+        Some((vertices, triangles))
+    }
+
+    fn snapshot(&self, shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        let object = shared.share(&self.object)?;
+        Some(ObjectSnapshot::Instance { object, transform: self.transform })
+    }
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Color;
+use crate::ray::Ray;
+use crate::scene::Scene;
+use crate::sampler::Sampler;
+use crate::renderer::Renderer;
+use crate::ambient_occlusion::AmbientOcclusion;
+
+/// Algoritmo que calcula la luz que llega a lo largo de un rayo
+/// El renderer se encarga de generar los rayos de cámara y promediar las
+/// muestras; el integrador decide cómo se calcula cada una
+pub trait Integrator: Send + Sync {
+    /// Radiancia incidente a lo largo de `ray` (Li en la notación de PBRT)
+    fn li(&self, ray: &Ray, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        self.li_with_hit(ray, scene.find_closest_hit(ray), scene, sampler)
+    }
+
+    /// Igual que `li`, con el impacto más cercano de `ray` ya buscado (el
+    /// renderer busca los de los rayos de cámara en paquetes)
+    fn li_with_hit(&self, ray: &Ray, hit: Option<(f32, usize)>, scene: &Scene, sampler: &mut dyn Sampler) -> Color;
+}
+
+/// Trazado de rayos clásico (Whitted): luces directas, sombras y reflejos
+pub struct WhittedIntegrator {
+    pub max_depth: u32,
+}
+
+impl Integrator for WhittedIntegrator {
+    fn li_with_hit(&self, ray: &Ray, hit: Option<(f32, usize)>, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        Renderer::trace_ray_with_hit(ray, hit, scene, self.max_depth, sampler)
+    }
+}
+
+/// Solo oclusión ambiental: blanco donde el punto ve el cielo, oscuro en
+/// esquinas y grietas. Útil para revisar la geometría sin materiales ni luces
+pub struct AmbientOcclusionIntegrator {
+    pub occlusion: AmbientOcclusion,
+}
+
+impl Integrator for AmbientOcclusionIntegrator {
+    fn li_with_hit(&self, ray: &Ray, hit: Option<(f32, usize)>, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        match hit.map(|(t, id)| Renderer::hit_record(ray, scene, t, id)) {
+            Some((hit, _)) => {
+                // La normal debe mirar hacia el lado por el que llega el rayo
+                let normal = if hit.normal.dot(&ray.direction) > 0.0 { -hit.normal } else { hit.normal };
+                let visibility = self.occlusion.visibility(scene, &hit, &normal, 1e-4, sampler);
+                Color::new(visibility, visibility, visibility)
+            }
+            None => Color::new(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// Solo la luz directa en el primer impacto (sin reflejos, transparencia
+/// ni luz ambiental). Se usa para separar las pasadas directa e indirecta
+pub struct DirectLightingIntegrator;
+
+impl Integrator for DirectLightingIntegrator {
+    fn li_with_hit(&self, ray: &Ray, hit: Option<(f32, usize)>, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        match hit.map(|(t, id)| Renderer::hit_record(ray, scene, t, id)) {
+            Some((hit, object)) => {
+                let view_dir = (scene.camera.position - hit.point).normalize();
+                Renderer::direct_radiance(&hit, object.get_material(), scene, &view_dir, sampler)
+            }
+            None => scene.background(&ray.direction),
+        }
+    }
+}
+
+/// Límite de rebotes del path tracer cuando se usa ruleta rusa
+/// Es solo una protección: casi todos los caminos terminan mucho antes
+pub const MAX_PATH_DEPTH: u32 = 64;
+
+/// Rebote a partir del cual se aplica la ruleta rusa por defecto
+pub const ROULETTE_START_DEPTH: u32 = 3;
+
+/// Path tracing Monte Carlo con rebotes difusos (iluminación global)
+pub struct PathTracingIntegrator {
+    pub max_depth: u32,
+    pub roulette_depth: Option<u32>, // None = cortar siempre en max_depth
+    pub next_event: bool,            // Muestrear las luces en cada rebote difuso
+}
+
+impl PathTracingIntegrator {
+    /// Path tracer con corte fijo en `max_depth` rebotes
+    pub fn new(max_depth: u32) -> Self {
+        PathTracingIntegrator {
+            max_depth,
+            roulette_depth: None,
+            next_event: false,
+        }
+    }
+
+    /// Activa la ruleta rusa a partir del rebote `start_depth`
+    pub fn with_russian_roulette(mut self, start_depth: u32) -> Self {
+        self.roulette_depth = Some(start_depth);
+        self
+    }
+
+    /// Activa el muestreo explícito de luces (next-event estimation)
+    pub fn with_next_event_estimation(mut self) -> Self {
+        self.next_event = true;
+        self
+    }
+}
+
+impl Integrator for PathTracingIntegrator {
+    fn li_with_hit(&self, ray: &Ray, hit: Option<(f32, usize)>, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        Renderer::trace_path_from(ray, hit, scene, self, true, sampler)
+    }
+}
+
+/// Integradores disponibles, para elegir uno desde la escena
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum IntegratorKind {
+    #[default]
+    Whitted,
+    AmbientOcclusion,
+    PathTracing,
+}
+
+impl IntegratorKind {
+    /// Construye el integrador correspondiente
+    /// La oclusión ambiental usa la configuración de la escena si existe; el
+    /// path tracer muestrea las luces directamente y termina los caminos con
+    /// ruleta rusa en lugar de `max_depth`
+    pub fn build(&self, scene: &Scene, max_depth: u32) -> Box<dyn Integrator> {
+        match self {
+            IntegratorKind::Whitted => Box::new(WhittedIntegrator { max_depth }),
+            IntegratorKind::AmbientOcclusion => Box::new(AmbientOcclusionIntegrator {
+                occlusion: scene.ambient_occlusion.unwrap_or(AmbientOcclusion::new(4, 1.0)),
+            }),
+            IntegratorKind::PathTracing => Box::new(
+                PathTracingIntegrator::new(MAX_PATH_DEPTH.max(max_depth))
+                    .with_russian_roulette(ROULETTE_START_DEPTH)
+                    .with_next_event_estimation(),
+            ),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Raytracer en Rust: escenas con esferas, planos, cubos, pirámides y
+//! mallas, materiales con texturas, luces puntuales, focos y de área,
+//! path tracing y un backend opcional en GPU (feature `gpu`).
+//!
+//! Uso mínimo como biblioteca:
+//!
+//! ```no_run
+//! use raytracer::camera::Camera;
+//! use raytracer::material::Material;
+//! use raytracer::light::Light;
+//! use raytracer::renderer::Renderer;
+//! use raytracer::scene::Scene;
+//! use raytracer::sphere::Sphere;
+//! use raytracer::vector::{Color, Point3, Vec3};
+//!
+//! let camera = Camera::new(
+//!     Point3::new(0.0, 1.0, 5.0),
+//!     Point3::zero(),
+//!     Vec3::new(0.0, 1.0, 0.0),
+//!     45.0,
+//!     4.0 / 3.0,
+//!     400,
+//!     300,
+//! );
+//! let mut scene = Scene::new(camera, Color::new(0.2, 0.2, 0.25));
+//! scene.add_sphere(Sphere::new(Point3::zero(), 1.0, Material::diffuse(Color::new(0.8, 0.3, 0.3))));
+//! scene.add_light(Light::white(Point3::new(5.0, 5.0, 5.0), 1.0));
+//!
+//! let image = Renderer::render(&scene);
+//! image.save_exr("esfera.exr").unwrap();
+//! ```
+//!
+//! También se puede cargar una escena en JSON con `Scene::from_file`.
+//!
+//! Sin la feature `fs` (activada por defecto) no se leen ni escriben
+//! archivos y la biblioteca compila para `wasm32-unknown-unknown`; la
+//! feature `wasm` agrega la API para JavaScript de `wasm::WebRenderer`.
+//! Con la feature `glam`, `Vec3` y `Transform` se convierten con `From`
+//! desde y hacia los tipos de glam (ver `glam_interop`).
+
+pub mod vector;
+#[cfg(feature = "glam")]
+pub mod glam_interop;
+pub mod ray;
+pub mod camera;
+pub mod material;
+pub mod light;
+pub mod sphere;
+pub mod plane;
+pub mod cube;
+pub mod pyramid;
+pub mod scene;
+pub mod renderer;
+pub mod integrator;
+pub mod texture;
+pub mod assets;
+pub mod render_cache;
+pub mod sampling;
+pub mod sampler;
+pub mod ambient_occlusion;
+pub mod bake;
+pub mod environment;
+pub mod sky;
+pub mod medium;
+pub mod light_sampler;
+pub mod moving;
+pub mod gamma;
+pub mod tonemap;
+pub mod denoise;
+pub mod firefly;
+pub mod brdf;
+pub mod aabb;
+pub mod bvh;
+pub mod transform;
+pub mod mesh;
+pub mod obj;
+pub mod lod;
+#[cfg(feature = "fs")]
+pub mod export;
+pub mod instance;
+pub mod prefab;
+pub mod aov;
+pub mod segmentation;
+pub mod packet;
+pub mod gpu;
+pub mod stats;
+pub mod progress;
+pub mod cancel;
+pub mod framebuffer;
+pub mod output_format;
+pub mod postprocess;
+pub mod exposure;
+pub mod development;
+pub mod settings;
+pub mod filter;
+pub mod far_clip;
+pub mod stereo;
+pub mod camera_path;
+pub mod animation;
+#[cfg(feature = "fs")]
+pub mod video;
+#[cfg(feature = "fs")]
+pub mod dataset;
+#[cfg(feature = "preview")]
+pub mod preview;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "server")]
+pub mod stream;
+#[cfg(feature = "editor")]
+pub mod editor;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod scene_file;
+pub mod pbrt;
+pub mod snapshot;
+pub mod scene_builder;
+pub mod scenes;
@@ -1,11 +1,59 @@
-use crate::vector::{Point3, Color};
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Point3, Vec3, Color};
+use crate::render_cache::{hash_f32, hash_vec3};
+use crate::sampling::{stratified_square, concentric_disk, orthonormal_basis};
+use crate::sampler::Sampler;
+use crate::transform::Transform;
+
+/// Distancia a la que se coloca una luz direccional para los rayos de sombra
+const DIRECTIONAL_DISTANCE: f32 = 1.0e5;
+
+/// Tipo de fuente de luz
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum LightKind {
+    /// Luz puntual que ilumina en todas direcciones
+    Point,
+    /// Foco que ilumina dentro de un cono alrededor de `direction`
+    /// Los ángulos son medios ángulos del cono, en radianes
+    Spot {
+        direction: Vec3,
+        inner_angle: f32,
+        outer_angle: f32,
+        falloff: f32,
+    },
+    /// Luz de área rectangular: `position` es una esquina y el rectángulo
+    /// se extiende a lo largo de `edge_u` y `edge_v`. Se toman
+    /// `samples`×`samples` muestras para calcular sombras suaves.
+    Area {
+        edge_u: Vec3,
+        edge_v: Vec3,
+        samples: u32,
+    },
+    /// Luz esférica de radio `radius` centrada en `position`
+    /// Se muestrea sobre el disco visible desde el punto iluminado, por lo
+    /// que la penumbra crece con el tamaño de la luz y se reduce con la distancia
+    Sphere {
+        radius: f32,
+        samples: u32,
+    },
+    /// Luz direccional infinitamente lejana (por ejemplo, el sol)
+    /// `direction` apunta desde la escena hacia la luz; `position` no se usa
+    Directional {
+        direction: Vec3,
+    },
+}
 
 /// Estructura que representa una fuente de luz
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Light {
     pub position: Point3,
     pub color: Color,
     pub intensity: f32,
+    pub kind: LightKind,
+    pub casts_shadows: bool, // Si es false no se lanzan rayos de sombra
 }
 
 impl Light {
@@ -15,6 +63,8 @@ impl Light {
             position,
             color,
             intensity,
+            kind: LightKind::Point,
+            casts_shadows: true,
         }
     }
 
@@ -24,6 +74,255 @@ impl Light {
             position,
             color: Color::new(1.0, 1.0, 1.0),
             intensity,
+            kind: LightKind::Point,
+            casts_shadows: true,
+        }
+    }
+
+    /// Crea un foco (spot light) que apunta en `direction`
+    /// Dentro de `inner_angle` la luz es completa y se desvanece hasta
+    /// `outer_angle` (ambos en grados, medidos desde el eje del cono)
+    pub fn spot(
+        position: Point3,
+        direction: Vec3,
+        color: Color,
+        intensity: f32,
+        inner_angle: f32,
+        outer_angle: f32,
+    ) -> Self {
+        let outer_angle = outer_angle.max(inner_angle);
+        Light {
+            position,
+            color,
+            intensity,
+            kind: LightKind::Spot {
+                direction: direction.normalize(),
+                inner_angle: inner_angle.to_radians(),
+                outer_angle: outer_angle.to_radians(),
+                falloff: 1.0,
+            },
+            casts_shadows: true,
+        }
+    }
+
+    /// Crea una luz de área rectangular a partir de una esquina y dos aristas
+    /// `samples` es el número de muestras por lado (samples² rayos de sombra)
+    pub fn area(
+        corner: Point3,
+        edge_u: Vec3,
+        edge_v: Vec3,
+        color: Color,
+        intensity: f32,
+        samples: u32,
+    ) -> Self {
+        Light {
+            position: corner,
+            color,
+            intensity,
+            kind: LightKind::Area {
+                edge_u,
+                edge_v,
+                samples: samples.max(1),
+            },
+            casts_shadows: true,
         }
     }
+
+    /// Crea una luz esférica con el radio dado
+    /// `samples` es el número de muestras por lado (samples² rayos de sombra)
+    pub fn sphere(center: Point3, radius: f32, color: Color, intensity: f32, samples: u32) -> Self {
+        Light {
+            position: center,
+            color,
+            intensity,
+            kind: LightKind::Sphere {
+                radius: radius.max(0.0),
+                samples: samples.max(1),
+            },
+            casts_shadows: true,
+        }
+    }
+
+    /// Crea una luz direccional; `direction` apunta hacia la luz
+    pub fn directional(direction: Vec3, color: Color, intensity: f32) -> Self {
+        Light {
+            position: Point3::zero(),
+            color,
+            intensity,
+            kind: LightKind::Directional {
+                direction: direction.normalize(),
+            },
+            casts_shadows: true,
+        }
+    }
+
+    /// Puntos de la luz vistos desde `hit_point` para evaluar la iluminación
+    /// Las luces puntuales y los focos tienen un único punto; las luces de
+    /// área y esféricas se muestrean con una cuadrícula estratificada
+    pub fn sample_points(&self, hit_point: &Point3, sampler: &mut dyn Sampler) -> Vec<Point3> {
+        match self.kind {
+            LightKind::Point | LightKind::Spot { .. } => vec![self.position],
+            // Un punto muy lejano en la dirección de la luz
+            LightKind::Directional { direction } => vec![*hit_point + direction * DIRECTIONAL_DISTANCE],
+            LightKind::Area { edge_u, edge_v, samples } => stratified_square(samples, sampler)
+                .into_iter()
+                .map(|(u, v)| self.position + edge_u * u + edge_v * v)
+                .collect(),
+            LightKind::Sphere { radius, samples } => {
+                let to_light = self.position - *hit_point;
+                let distance = to_light.length();
+
+                // Dentro de la esfera (o sin radio) se comporta como luz puntual
+                if distance <= radius || radius <= 0.0 {
+                    return vec![self.position];
+                }
+
+                // Disco visible: círculo donde los rayos tangentes tocan la esfera
+                let w = to_light / distance;
+                let disk_center = self.position - w * (radius * radius / distance);
+                let disk_radius = radius * (1.0 - (radius * radius) / (distance * distance)).sqrt();
+
+                let (tangent, bitangent) = orthonormal_basis(&w);
+
+                stratified_square(samples, sampler)
+                    .into_iter()
+                    .map(|(u, v)| {
+                        let (dx, dy) = concentric_disk(u, v);
+                        disk_center + tangent * (dx * disk_radius) + bitangent * (dy * disk_radius)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Activa o desactiva las sombras de esta luz
+    /// Útil para luces de relleno que no deben producir sombras dobles
+    pub fn with_shadows(mut self, casts_shadows: bool) -> Self {
+        self.casts_shadows = casts_shadows;
+        self
+    }
+
+    /// Ajusta el exponente de caída entre el cono interior y el exterior
+    /// (1.0 = transición suave, valores mayores concentran la luz)
+    pub fn with_falloff(mut self, exponent: f32) -> Self {
+        if let LightKind::Spot { ref mut falloff, .. } = self.kind {
+            *falloff = exponent.max(0.0);
+        }
+        self
+    }
+
+    /// Copia de la luz con la posición y las direcciones transformadas
+    /// El radio de las luces esféricas no se escala
+    pub fn transformed(&self, transform: &Transform) -> Light {
+        let kind = match self.kind {
+            LightKind::Spot { direction, inner_angle, outer_angle, falloff } => LightKind::Spot {
+                direction: transform.transform_vector(&direction).normalize(),
+                inner_angle,
+                outer_angle,
+                falloff,
+            },
+            LightKind::Area { edge_u, edge_v, samples } => LightKind::Area {
+                edge_u: transform.transform_vector(&edge_u),
+                edge_v: transform.transform_vector(&edge_v),
+                samples,
+            },
+            LightKind::Directional { direction } => LightKind::Directional {
+                direction: transform.transform_vector(&direction).normalize(),
+            },
+            kind => kind,
+        };
+
+        Light {
+            position: transform.transform_point(&self.position),
+            kind,
+            ..*self
+        }
+    }
+
+    /// Factor de atenuación (0.0 a 1.0) para la dirección `light_dir`,
+    /// que apunta desde la superficie hacia la luz
+    pub fn cone_attenuation(&self, light_dir: &Vec3) -> f32 {
+        match self.kind {
+            LightKind::Point
+            | LightKind::Area { .. }
+            | LightKind::Sphere { .. }
+            | LightKind::Directional { .. } => 1.0,
+            LightKind::Spot { direction, inner_angle, outer_angle, falloff } => {
+                let cos_theta = (-*light_dir).dot(&direction);
+                let cos_inner = inner_angle.cos();
+                let cos_outer = outer_angle.cos();
+
+                if cos_theta >= cos_inner {
+                    1.0
+                } else if cos_theta <= cos_outer {
+                    0.0
+                } else {
+                    // Interpolación suave (smoothstep) entre ambos conos
+                    let t = (cos_theta - cos_outer) / (cos_inner - cos_outer);
+                    (t * t * (3.0 - 2.0 * t)).powf(falloff)
+                }
+            }
+        }
+    }
+
+    /// Agrega el estado de la luz al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_vec3(state, &self.position);
+        hash_vec3(state, &self.color);
+        hash_f32(state, self.intensity);
+        state.write_u8(self.casts_shadows as u8);
+
+        match self.kind {
+            LightKind::Point => state.write_u8(0),
+            LightKind::Spot { direction, inner_angle, outer_angle, falloff } => {
+                state.write_u8(1);
+                hash_vec3(state, &direction);
+                hash_f32(state, inner_angle);
+                hash_f32(state, outer_angle);
+                hash_f32(state, falloff);
+            }
+            LightKind::Area { edge_u, edge_v, samples } => {
+                state.write_u8(2);
+                hash_vec3(state, &edge_u);
+                hash_vec3(state, &edge_v);
+                state.write_u32(samples);
+            }
+            LightKind::Sphere { radius, samples } => {
+                state.write_u8(3);
+                hash_f32(state, radius);
+                state.write_u32(samples);
+            }
+            LightKind::Directional { direction } => {
+                state.write_u8(4);
+                hash_vec3(state, &direction);
+            }
+        }
+    }
+}
+
+/// Luz ambiental uniforme que llega a todas las superficies
+/// Es independiente del color de fondo que ven los rayos que no chocan
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AmbientLight {
+    pub color: Color,
+    pub intensity: f32,
+}
+
+impl AmbientLight {
+    /// Crea una luz ambiental
+    pub fn new(color: Color, intensity: f32) -> Self {
+        AmbientLight { color, intensity }
+    }
+
+    /// Radiancia ambiental resultante (color × intensidad)
+    pub fn radiance(&self) -> Color {
+        self.color * self.intensity
+    }
+}
+
+impl Default for AmbientLight {
+    /// Luz ambiental blanca tenue (equivale al antiguo AMBIENT_STRENGTH = 0.2)
+    fn default() -> Self {
+        AmbientLight::new(Color::new(1.0, 1.0, 1.0), 0.2)
+    }
 }
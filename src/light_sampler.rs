@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::light::Light;
+use crate::sampler::Sampler;
+
+/// Distribución para elegir luces proporcionalmente a su potencia
+/// En escenas con cientos de luces, cada punto evalúa solo unas pocas
+/// luces elegidas al azar (las más potentes con mayor probabilidad) y pondera
+/// su aporte por 1 / probabilidad, lo que mantiene el resultado sin sesgo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightSampler {
+    pub samples_per_hit: u32,
+    cdf: Vec<f32>,
+    total_power: f32,
+}
+
+impl LightSampler {
+    /// Construye la distribución para las luces dadas
+    pub fn new(lights: &[Light], samples_per_hit: u32) -> Self {
+        let mut cdf = Vec::with_capacity(lights.len());
+        let mut total_power = 0.0;
+
+        for light in lights {
+            total_power += Self::power(light);
+            cdf.push(total_power);
+        }
+
+        LightSampler {
+            samples_per_hit: samples_per_hit.max(1),
+            cdf,
+            total_power,
+        }
+    }
+
+    /// Potencia aproximada de una luz (intensidad × brillo del color)
+    fn power(light: &Light) -> f32 {
+        let brightness = (light.color.x + light.color.y + light.color.z) / 3.0;
+        (light.intensity * brightness).max(0.0)
+    }
+
+    /// Probabilidad de elegir la luz `light_id` en una muestra
+    pub fn pdf(&self, light_id: usize) -> f32 {
+        if self.total_power <= 0.0 {
+            return 0.0;
+        }
+        let previous = if light_id == 0 { 0.0 } else { self.cdf[light_id - 1] };
+        (self.cdf[light_id] - previous) / self.total_power
+    }
+
+    /// Elige una luz y retorna su índice
+    pub fn sample(&self, sampler: &mut dyn Sampler) -> Option<usize> {
+        if self.total_power <= 0.0 {
+            return None;
+        }
+
+        let target = sampler.next_1d() * self.total_power;
+        let index = self.cdf.partition_point(|&c| c <= target);
+        Some(index.min(self.cdf.len() - 1))
+    }
+
+    /// Luces a evaluar en un punto junto con el peso de cada una
+    /// Si hay pocas luces se evalúan todas con peso 1
+    pub fn select(&self, sampler: &mut dyn Sampler) -> Vec<(usize, f32)> {
+        let count = self.cdf.len();
+        if count <= self.samples_per_hit as usize {
+            return (0..count).map(|id| (id, 1.0)).collect();
+        }
+
+        let mut selected = Vec::with_capacity(self.samples_per_hit as usize);
+        for _ in 0..self.samples_per_hit {
+            if let Some(id) = self.sample(sampler) {
+                let weight = 1.0 / (self.samples_per_hit as f32 * self.pdf(id));
+                selected.push((id, weight));
+            }
+        }
+        selected
+    }
+}
@@ -0,0 +1,205 @@
+// Niveles de detalle (LOD) de una malla: varias versiones de la misma malla,
+// de la más detallada a la más simple, y cada rayo se prueba contra una sola
+// de ellas según lo lejos (o lo chica) que se vea la malla desde la cámara.
+// Así un modelo pesado que queda lejos en una escena grande cuesta lo que
+// su versión simplificada.
+//
+// El nivel se mide desde el punto de vista del rayo (`Ray::viewpoint`), que
+// los rayos de sombra y de rebote heredan del rayo de cámara: todo el camino
+// ve el mismo nivel y la malla no se sombrea a sí misma con otro.
+
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::mesh::TriangleMesh;
+use crate::scene::Intersectable;
+use crate::gpu::GpuPrimitive;
+use crate::render_cache::hash_f32;
+use crate::snapshot::{ObjectSnapshot, SharedObjects};
+
+/// Cómo se mide la malla desde el punto de vista de cada rayo para elegir
+/// el nivel
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LodMetric {
+    /// Distancia al centro de la malla; un nivel se usa desde su umbral
+    /// en adelante
+    #[default]
+    Distance,
+    /// Tamaño aparente de la malla (el diámetro de su esfera envolvente),
+    /// en grados; un nivel se usa cuando la malla se ve de su umbral o
+    /// menos. Con una cámara de `fov` grados de alto y `height` píxeles,
+    /// un grado son unos `height / fov` píxeles
+    ProjectedSize,
+}
+
+/// Un nivel de detalle y desde qué medida se usa (ver `LodMetric`)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LodLevel {
+    pub mesh: TriangleMesh,
+    pub threshold: f32,
+}
+
+/// Malla con varios niveles de detalle, ordenados del más detallado al
+/// más simple. Todos los niveles se ven con el material del primero
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LodMesh {
+    levels: Vec<LodLevel>,
+    metric: LodMetric,
+    /// Caja que contiene a todos los niveles
+    bounds: Option<Aabb>,
+}
+
+impl LodMesh {
+    /// Malla con un solo nivel, el más detallado, que se usa cuando no
+    /// corresponde ninguno de los que se agreguen después
+    pub fn new(mesh: TriangleMesh, metric: LodMetric) -> Self {
+        let bounds = mesh.bounds();
+        LodMesh {
+            levels: vec![LodLevel { mesh, threshold: 0.0 }],
+            metric,
+            bounds,
+        }
+    }
+
+    /// Agrega un nivel más simple que los anteriores, que se usa desde
+    /// `threshold`: una distancia mayor o un tamaño aparente menor que los
+    /// umbrales de los niveles anteriores
+    pub fn with_level(mut self, mesh: TriangleMesh, threshold: f32) -> Self {
+        self.bounds = match (self.bounds, mesh.bounds()) {
+            (Some(a), Some(b)) => Some(a.union(&b)),
+            (a, b) => a.or(b),
+        };
+        self.levels.push(LodLevel { mesh, threshold });
+        self
+    }
+
+    pub fn levels(&self) -> &[LodLevel] {
+        &self.levels
+    }
+
+    pub fn metric(&self) -> LodMetric {
+        self.metric
+    }
+
+    /// Medida de la malla vista desde `origin`, según `metric`
+    pub fn measure(&self, origin: &Point3) -> f32 {
+        let Some(bounds) = self.bounds else {
+            return 0.0;
+        };
+        let distance = origin.distance(&bounds.centroid());
+        match self.metric {
+            LodMetric::Distance => distance,
+            LodMetric::ProjectedSize => {
+                let radius = bounds.extent().length() / 2.0;
+                if distance <= radius {
+                    180.0
+                } else {
+                    2.0 * (radius / distance).asin().to_degrees()
+                }
+            }
+        }
+    }
+
+    /// Posición en `levels` del nivel que corresponde a un rayo con punto
+    /// de vista en `origin`
+    pub fn select(&self, origin: &Point3) -> usize {
+        let measure = self.measure(origin);
+        self.levels[1..]
+            .iter()
+            .take_while(|level| match self.metric {
+                LodMetric::Distance => measure >= level.threshold,
+                LodMetric::ProjectedSize => measure <= level.threshold,
+            })
+            .count()
+    }
+
+    fn finest(&self) -> &TriangleMesh {
+        &self.levels[0].mesh
+    }
+}
+
+impl Intersectable for LodMesh {
+    fn intersect(&self, ray: &Ray) -> Option<f32> {
+        self.levels[self.select(&ray.viewpoint)].mesh.intersect(ray)
+    }
+
+    /// El punto no dice con qué nivel se encontró, así que se usa la normal
+    /// del triángulo más cercano entre todos los niveles
+    fn normal_at(&self, point: &Point3) -> Vec3 {
+        self.levels
+            .iter()
+            .filter_map(|level| level.mesh.closest_normal(point))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or(Vec3::new(0.0, 1.0, 0.0), |(_, normal)| normal)
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.finest().material
+    }
+
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        Some(&mut self.levels[0].mesh.material)
+    }
+
+    fn get_uv(&self, _point: &Point3) -> Option<(f32, f32, usize)> {
+        None
+    }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        state.write_u8(self.metric as u8);
+        state.write_usize(self.levels.len());
+        for level in &self.levels {
+            level.mesh.hash_state(state);
+            hash_f32(state, level.threshold);
+        }
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.bounds
+    }
+
+    /// El backend de GPU no elige niveles: usa el más detallado
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        Some(self.finest().gpu_primitives())
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut problems: Vec<String> = self.levels.iter().flat_map(|level| level.mesh.validate()).collect();
+        let thresholds: Vec<f32> = self.levels[1..].iter().map(|level| level.threshold).collect();
+        if thresholds.iter().any(|threshold| !threshold.is_finite()) {
+            problems.push("nivel de detalle con umbral no finito (NaN o infinito)".to_string());
+        }
+        let ordered = thresholds.windows(2).all(|pair| match self.metric {
+            LodMetric::Distance => pair[0] <= pair[1],
+            LodMetric::ProjectedSize => pair[0] >= pair[1],
+        });
+        if !ordered {
+            problems.push("niveles de detalle desordenados: los umbrales deben ir del nivel más detallado al más simple".to_string());
+        }
+        problems
+    }
+
+    /// Las luces de área y el horneado usan el nivel más detallado
+    fn surface_area(&self) -> f32 {
+        self.finest().surface_area()
+    }
+
+    fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        self.finest().sample_surface(u, v)
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        let mesh = self.finest();
+        Some((mesh.vertices.clone(), mesh.triangles.clone()))
+    }
+
+    fn snapshot(&self, _shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        Some(ObjectSnapshot::Lod(self.clone()))
+    }
+}
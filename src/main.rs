@@ -1,85 +1,599 @@
-mod vector;
-mod ray;
-mod camera;
-mod material;
-mod light;
-mod sphere;
-mod plane;
-mod cube;
-mod pyramid;
-mod scene;
-mod renderer;
-mod texture;
+mod cli;
+mod config;
+mod benchmark;
 
+use std::hash::Hasher;
 use std::path::Path;
-use image::{ImageBuffer, Rgb};
-
-use vector::{Vec3, Color, Point3};
-use camera::Camera;
-use material::Material;
-use light::Light;
-use sphere::Sphere;
-use plane::Plane;
-use cube::Cube;
-use pyramid::Pyramid;
-use scene::Scene;
-use renderer::Renderer;
-use texture::Texture;
-
-const WIDTH: u32 = 800;
-const HEIGHT: u32 = 600;
-const MAX_DEPTH: u32 = 5;
+use std::time::Duration;
+use clap::Parser;
+use image::{ImageBuffer, Pixel};
+
+use raytracer::{aov, bake, export, pbrt, scene_file, scenes, segmentation, snapshot, stats};
+use raytracer::vector::{Vec3, Color, Point3};
+use raytracer::camera::Camera;
+use raytracer::material::Material;
+use raytracer::light::Light;
+use raytracer::plane::Plane;
+use raytracer::cube::Cube;
+use raytracer::scene::{Scene, SceneItem, Severity};
+use raytracer::ambient_occlusion::AmbientOcclusion;
+use raytracer::bake::{BakeSettings, LightmapEntry, LightmapIndex};
+use raytracer::renderer::{Renderer, Tile};
+use raytracer::texture::Texture;
+use raytracer::assets::AssetPaths;
+use raytracer::render_cache::{RenderCache, StableHasher};
+use raytracer::gamma::ColorEncoding;
+use raytracer::tonemap::ToneMapping;
+use raytracer::aov::Aov;
+use raytracer::segmentation::{MaskKind, Segmentation};
+use raytracer::progress::{ConsoleProgress, JsonProgress, ProgressSink};
+use raytracer::cancel::CancelToken;
+use raytracer::framebuffer::{Framebuffer, color_to_rgb, color_to_rgb16};
+use raytracer::output_format::OutputFormat;
+use raytracer::exposure::Exposure;
+use raytracer::development::Development;
+use raytracer::settings::RenderSettings;
+use raytracer::stereo::Stereo;
+use raytracer::video::VideoEncoder;
+use raytracer::animation::Timeline;
+use raytracer::far_clip::FarClip;
+use raytracer::dataset::{DatasetWriter, SceneVariator, Variations};
+#[cfg(feature = "preview")]
+use raytracer::preview::PreviewWindow;
+#[cfg(feature = "editor")]
+use raytracer::editor;
+#[cfg(feature = "server")]
+use raytracer::server::RenderServer;
+#[cfg(feature = "server")]
+use raytracer::stream::RenderStream;
+use cli::{Args, DevelopmentArgs, ProgressFormat};
+use config::Config;
+
+const PREVIEW_INTERVAL: Duration = Duration::from_secs(5);
+// Cada cuánto se revisan los archivos en modo --watch
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+// Cuadros por segundo de --turntable (para el video y los tiempos de cada cuadro)
+const TURNTABLE_FPS: f32 = 24.0;
+// Oclusión ambiental de --bake-ao si la escena no la configura
+const BAKE_OCCLUSION: AmbientOcclusion = AmbientOcclusion { samples: 4, radius: 1.0 };
+// Cuánto varía la escena entre las muestras de --dataset (cero = sin cambios)
+const DATASET_VARIATIONS: Variations = Variations {
+    camera_azimuth: 180.0,
+    camera_elevation: 15.0,
+    camera_distance: 0.2,
+    light_intensity: 0.3,
+    light_color: 0.1,
+    material_color: 0.3,
+};
 
 fn main() {
+    let args = Args::parse();
     println!("🎨 Raytracer - Fase 3: Cubo con texturas Minecraft");
-    println!("Resolución: {}x{}", WIDTH, HEIGHT);
+
+    let config = match Config::load(args.config.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("❌ No se pudo leer la configuración: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let args = args.with_config(&config);
+
+    if let Some(threads) = args.threads {
+        if let Err(e) = rayon::ThreadPoolBuilder::new().num_threads(threads).build_global() {
+            println!("⚠ No se pudo fijar el número de hilos: {}", e);
+        }
+    }
+
+    if let Some(address) = &args.serve {
+        serve(address, args.development);
+        return;
+    }
+
+    if let Some(runs) = args.benchmark {
+        if let Err(e) = benchmark::run(&args, runs) {
+            println!("❌ Falló el benchmark: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut scene = match load_scene(&args) {
+        Ok(scene) => scene,
+        Err(e) => {
+            println!("❌ No se pudo cargar la escena: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(path) = &args.export {
+        match export::export(&scene, path) {
+            Ok(report) => {
+                for name in &report.skipped {
+                    println!("⚠ No se exporta {}: no se puede convertir en triángulos", name);
+                }
+                println!("✓ Escena exportada a {} ({} objetos)", path, report.exported);
+            }
+            Err(e) => {
+                println!("❌ No se pudo exportar la escena: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(path) = &args.bake_ao {
+        match bake_ambient_occlusion(&scene, &args, path) {
+            Ok(()) => println!("✓ Oclusión ambiental horneada en: {}", path),
+            Err(e) => {
+                println!("❌ No se pudo hornear la oclusión ambiental: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if let Some(dir) = &args.bake_lightmaps {
+        if let Err(e) = bake_lightmaps(&scene, &args, dir) {
+            println!("❌ No se pudieron hornear los lightmaps: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(path) = &args.save_snapshot {
+        match snapshot::save(&scene, path) {
+            Ok(()) => println!("✓ Instantánea de la escena guardada en: {}", path),
+            Err(e) => {
+                println!("❌ No se pudo guardar la instantánea: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let settings = scene.settings;
+    let (width, height) = (settings.width, settings.height);
+    println!("Resolución: {}x{} ({} muestras por píxel)", width, height, settings.samples_per_pixel);
+
+    // Ctrl-C detiene el render y se guarda lo que se haya completado
+    let cancel = CancelToken::new();
+    let handler_token = cancel.clone();
+    if let Err(e) = ctrlc::set_handler(move || handler_token.cancel()) {
+        println!("⚠ No se pudo instalar el manejador de Ctrl-C: {}", e);
+    }
+
+    if args.explore {
+        explore(&mut scene);
+    }
+    if args.editor {
+        edit_scene(&mut scene);
+    }
+
+    #[cfg(feature = "preview")]
+    if args.preview {
+        match PreviewWindow::open("Raytracer", width, height) {
+            Ok(window) => {
+                let mut window = window.with_tone_mapping(scene.development.tone_mapping, scene.development.encoding);
+                window.run(&cancel, |tiles| session(&args, scene, &cancel, Some(tiles)));
+                window.wait_until_closed();
+                return;
+            }
+            Err(e) => println!("⚠ No se pudo abrir la ventana de vista previa: {}", e),
+        }
+    }
+    #[cfg(not(feature = "preview"))]
+    if args.preview {
+        println!("⚠ La ventana de vista previa requiere compilar con --features preview");
+    }
+
+    #[cfg(feature = "server")]
+    if let Some(address) = &args.stream {
+        match RenderStream::bind(address, width, height) {
+            Ok(stream) => {
+                let stream = stream.with_tone_mapping(scene.development.tone_mapping, scene.development.encoding);
+                println!("✓ Render en vivo en http://{}/", stream.address());
+                session(&args, scene, &cancel, Some(&stream));
+                return;
+            }
+            Err(e) => println!("⚠ No se pudo iniciar la transmisión del render: {}", e),
+        }
+    }
+    #[cfg(not(feature = "server"))]
+    if args.stream.is_some() {
+        println!("⚠ La transmisión del render requiere compilar con --features server");
+    }
+
+    let json_progress = JsonProgress::new();
+    let tiles = match args.progress_format {
+        ProgressFormat::Json => Some(&json_progress as &dyn ProgressSink),
+        ProgressFormat::Text => None,
+    };
+    session(&args, scene, &cancel, tiles);
+}
+
+/// Escena de `--scene` (o la de ejemplo) con los ajustes de la línea de comandos
+fn load_scene(args: &Args) -> Result<Scene, Box<dyn std::error::Error>> {
+    let mut scene = match args.scene.as_deref() {
+        Some(path) if Path::new(path).exists() => {
+            let scene = if path.to_lowercase().ends_with(".pbrt") {
+                let import = pbrt::load_with(path, &args.assets)?;
+                for warning in &import.warnings {
+                    println!("⚠ PBRT: {}", warning);
+                }
+                import.scene
+            } else if path.to_lowercase().ends_with(".rtsnap") {
+                snapshot::load(path)?
+            } else {
+                scene_file::load_with(path, &args.assets)?
+            };
+            println!("✓ Escena cargada de {}", path);
+            scene
+        }
+        Some(name) => {
+            let Some(scene) = scenes::by_name(name) else {
+                let names: Vec<&str> = scenes::GALLERY.iter().map(|(name, _)| *name).collect();
+                return Err(format!("no existe el archivo '{}' ni una escena incluida con ese nombre ({})", name, names.join(", ")).into());
+            };
+            println!("✓ Escena incluida: {}", name);
+            scene
+        }
+        None => example_scene(&args.assets),
+    };
+    let settings = args.apply_to(scene.settings);
+    scene.set_render_settings(settings);
+    args.development.apply_to(&mut scene.development);
+    if let Some(sampler) = args.sampler {
+        scene.set_sampler(sampler.into());
+    }
+    if let Some(filter) = args.pixel_filter() {
+        scene.set_pixel_filter(filter);
+    }
+    if let Some(distance) = args.far_clip {
+        let far_clip = FarClip::new(distance);
+        scene.set_far_clip(match args.far_fade {
+            Some(start) => far_clip.with_fade(start),
+            None => far_clip,
+        });
+    }
+    if args.overrides_resolution() {
+        scene.camera.set_aspect_ratio(settings.aspect_ratio());
+    }
+    if let Some(frames) = args.turntable {
+        let bounds = scene.bounds().ok_or("--turntable necesita al menos un objeto acotado en la escena")?;
+        scene.set_animation(Timeline::turntable(&scene.camera, bounds.centroid(), frames, TURNTABLE_FPS));
+    }
+    if let Some(dir) = &args.lightmaps {
+        let count = LightmapIndex::load(dir)?.apply(&mut scene, dir)?;
+        println!("✓ {} lightmap(s) cargados de {}", count, dir);
+    }
+    check_scene(&scene)?;
+    Ok(scene)
+}
+
+/// ID del objeto de `--bake-object`, por nombre o por número
+fn bake_object(scene: &Scene, args: &Args) -> Result<usize, Box<dyn std::error::Error>> {
+    let name = args.bake_object.as_deref().ok_or("falta --bake-object")?;
+    match scene.find(name) {
+        Some(SceneItem::Object(id)) => Ok(id),
+        Some(_) => Err(format!("'{}' no es un objeto", name).into()),
+        None => name
+            .parse()
+            .ok()
+            .filter(|&id| scene.object(id).is_some())
+            .ok_or_else(|| format!("no hay ningún objeto llamado '{}' ni con ese ID", name).into()),
+    }
+}
+
+/// Hornea la oclusión ambiental de `--bake-object` en `path` (`--bake-ao`)
+/// con la configuración de oclusión de la escena (o `BAKE_OCCLUSION`)
+fn bake_ambient_occlusion(scene: &Scene, args: &Args, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let object_id = bake_object(scene, args)?;
+    let occlusion = scene.ambient_occlusion.unwrap_or(BAKE_OCCLUSION);
+    let settings = BakeSettings::new(args.bake_size, scene.settings.samples_per_pixel).with_seed(scene.seed);
+    println!(
+        "Horneando la oclusión ambiental del objeto {} ({}x{} texeles, {} muestras por texel)...",
+        object_id, settings.size, settings.size, settings.samples_per_texel
+    );
+    let start = std::time::Instant::now();
+    let baked = bake::bake_ambient_occlusion(scene, object_id, &occlusion, &settings)?;
+    println!("✓ Horneado en {:.2}s", start.elapsed().as_secs_f32());
+    if let Some(parent) = Path::new(path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    baked.to_rgb8(ToneMapping::Clamp, ColorEncoding::Linear).save(path)?;
+    Ok(())
+}
+
+/// Hornea los lightmaps de `--bake-object` (o de todos los objetos que se
+/// pueden hornear) en `dir`, con tantos rebotes como `max_depth`
+fn bake_lightmaps(scene: &Scene, args: &Args, dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let objects = match args.bake_object {
+        Some(_) => vec![bake_object(scene, args)?],
+        None => (0..scene.objects.len()).map(|index| scene.object_id(index)).collect(),
+    };
+    let settings = BakeSettings::new(args.bake_size, scene.settings.samples_per_pixel).with_seed(scene.seed);
+    let bounces = scene.settings.max_depth;
+    std::fs::create_dir_all(dir).map_err(|e| format!("no se pudo crear {}: {}", dir, e))?;
+    println!(
+        "Horneando lightmaps de {}x{} texeles ({} muestras por texel, {} rebotes)...",
+        settings.size, settings.size, settings.samples_per_texel, bounces
+    );
+
+    let start = std::time::Instant::now();
+    let mut index = LightmapIndex { size: settings.size, bounces, lightmaps: Vec::new() };
+    for object in objects {
+        let baked = match bake::bake_lighting(scene, object, bounces, &settings) {
+            Ok(baked) => baked,
+            Err(e) => {
+                println!("  Objeto {} omitido: {}", object, e);
+                continue;
+            }
+        };
+        let file = format!("{}.exr", object);
+        baked.save_exr(&Path::new(dir).join(&file).to_string_lossy())?;
+        println!("  ✓ Objeto {}: {}", object, file);
+        index.lightmaps.push(LightmapEntry { object, file });
+    }
+    if index.lightmaps.is_empty() {
+        return Err("ningún objeto se pudo hornear".into());
+    }
+    index.save(dir)?;
+    println!("✓ {} lightmap(s) horneados en {:.2}s en: {}", index.lightmaps.len(), start.elapsed().as_secs_f32(), dir);
+    Ok(())
+}
+
+/// Muestra los problemas de la escena (ver `Scene::validate`) y falla si
+/// alguno arruinaría el render
+fn check_scene(scene: &Scene) -> Result<(), Box<dyn std::error::Error>> {
+    let diagnostics = scene.validate();
+    for diagnostic in &diagnostics {
+        println!("⚠ {}", diagnostic);
+    }
+    match diagnostics.iter().filter(|diagnostic| diagnostic.severity == Severity::Error).count() {
+        0 => Ok(()),
+        errors => Err(format!("la escena tiene {} error(es)", errors).into()),
+    }
+}
+
+/// Renderiza y, con `--watch`, sigue renderizando cada vez que cambian el
+/// archivo de escena o sus texturas, hasta que se pulse Ctrl-C
+fn session(args: &Args, mut scene: Scene, cancel: &CancelToken, tiles: Option<&dyn ProgressSink>) {
+    run(args, &mut scene, cancel, tiles);
+    if !args.watch {
+        return;
+    }
+
+    let modified = || args.scene.as_ref().and_then(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok());
+    let mut scene_modified = modified();
+    println!("👀 Esperando cambios (Ctrl-C para salir)...");
+    while !cancel.is_cancelled() {
+        std::thread::sleep(WATCH_INTERVAL);
+
+        if modified() != scene_modified {
+            scene_modified = modified();
+            // Si el editor todavía está escribiendo el archivo puede fallar;
+            // se reintenta con el próximo cambio
+            match load_scene(args) {
+                Ok(reloaded) => scene = reloaded,
+                Err(e) => {
+                    println!("❌ No se pudo cargar la escena: {}", e);
+                    continue;
+                }
+            }
+        } else {
+            let reloaded = scene.reload_textures();
+            if reloaded.is_empty() {
+                continue;
+            }
+            println!("✓ Texturas recargadas: {:?}", reloaded);
+        }
+
+        run(args, &mut scene, cancel, tiles);
+        println!("👀 Esperando cambios (Ctrl-C para salir)...");
+    }
+}
+
+/// Servicio de render por HTTP (`--serve`); las opciones de revelado de la
+/// línea de comandos se aplican a todas las escenas recibidas
+#[cfg(feature = "server")]
+fn serve(address: &str, development: DevelopmentArgs) {
+    match RenderServer::bind(address) {
+        Ok(server) => {
+            let server = server.with_development(move |scene_development| development.apply_to(scene_development));
+            println!("✓ Escuchando en http://{} (POST /render con la escena en JSON)", server.address());
+            server.run();
+        }
+        Err(e) => {
+            println!("❌ No se pudo iniciar el servidor: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(feature = "server"))]
+fn serve(_address: &str, _development: DevelopmentArgs) {
+    println!("❌ El servidor HTTP requiere compilar con --features server");
+    std::process::exit(1);
+}
+
+/// Explorador interactivo para encuadrar la escena antes del render final
+#[cfg(feature = "preview")]
+fn explore(scene: &mut Scene) {
+    let (width, height) = (scene.settings.width, scene.settings.height);
+    match PreviewWindow::open("Raytracer - explorador", width, height) {
+        Ok(window) => {
+            println!("Explorando: WASD/QE para moverse, arrastrar para orbitar, Escape para renderizar");
+            let (tone_mapping, encoding) = (scene.development.tone_mapping, scene.development.encoding);
+            window.with_tone_mapping(tone_mapping, encoding).explore(scene);
+            let (position, look_at) = (scene.camera.position, scene.camera.look_at);
+            println!(
+                "✓ Cámara final: posición ({:.3}, {:.3}, {:.3}), mirando a ({:.3}, {:.3}, {:.3})",
+                position.x, position.y, position.z, look_at.x, look_at.y, look_at.z
+            );
+        }
+        Err(e) => println!("⚠ No se pudo abrir la ventana del explorador: {}", e),
+    }
+}
+
+#[cfg(not(feature = "preview"))]
+fn explore(_scene: &mut Scene) {
+    println!("⚠ El explorador requiere compilar con --features preview");
+}
+
+/// Editor de escenas; al cerrarlo se renderiza la escena editada
+#[cfg(feature = "editor")]
+fn edit_scene(scene: &mut Scene) {
+    println!("Editando la escena: cerrar la ventana para renderizarla");
+    let (tone_mapping, encoding) = (scene.development.tone_mapping, scene.development.encoding);
+    if let Err(e) = editor::edit(scene, tone_mapping, encoding) {
+        println!("⚠ {}", e);
+    }
+}
+
+#[cfg(not(feature = "editor"))]
+fn edit_scene(_scene: &mut Scene) {
+    println!("⚠ El editor de escenas requiere compilar con --features editor");
+}
+
+/// Renderiza y guarda lo que piden los argumentos: la animación, una
+/// región o la imagen completa. `tiles` recibe los bloques terminados
+/// cuando hay ventana de vista previa, con `--stream` o con
+/// `--progress-format json`
+fn run(args: &Args, scene: &mut Scene, cancel: &CancelToken, tiles: Option<&dyn ProgressSink>) {
+    let (output, exr_output) = (args.output(), args.exr_path());
+    let (width, height) = (scene.settings.width, scene.settings.height);
+
+    if let Some(dir) = &args.dataset {
+        if let Err(e) = render_dataset(args, scene, dir, cancel, tiles) {
+            println!("❌ No se pudo generar el dataset: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let frames = args.frames_dir();
+    if frames.is_some() || args.video.is_some() {
+        if let Err(e) = render_animation(args, scene, frames.as_deref(), cancel, tiles) {
+            println!("❌ No se pudo renderizar la animación: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Render parcial: solo se actualiza una zona de la imagen ya guardada
+    if let Some(region) = args.crop {
+        let region = region.clamp_to(width, height);
+        println!("Renderizando región ({}, {})-({}, {})...", region.x0, region.y0, region.x1, region.y1);
+        let pixels = Renderer::render_region(scene, &region, &ConsoleProgress::new(), cancel);
+        let development = &scene.development;
+        paste_region(args, &pixels, &region, (width, height), output, development.tone_mapping, development.encoding)
+            .expect("Error al guardar la región");
+        println!("✓ Región actualizada en: {}", output);
+        return;
+    }
+
+    // Si la escena no cambió desde el último render, no hace falta repetirlo
+    let scene_hash = render_hash(args, scene);
+    let mut cache = RenderCache::load(&args.cache_path());
+    if cache.is_up_to_date(output, scene_hash) && cache.is_up_to_date(&exr_output, scene_hash) {
+        println!("✓ La escena no cambió, se conserva: {}", output);
+        return;
+    }
+
+    println!("Renderizando escena...");
+    let start = std::time::Instant::now();
+    stats::reset();
+    let framebuffer = render_frame(args, scene, cancel, Preview { path: Some(output), tiles, pass: (0, eye_count(args)) });
+    let elapsed = start.elapsed();
+
+    if cancel.is_cancelled() {
+        // La imagen incompleta se guarda pero no se registra en la caché
+        println!("⚠ Render cancelado tras {:.2}s", elapsed.as_secs_f32());
+        save_output(args, &scene.development, &framebuffer, output).expect("Error al guardar la imagen");
+        println!("✓ Imagen parcial guardada en: {}", output);
+        return;
+    }
+    println!("✓ Renderizado completado en {:.2}s", elapsed.as_secs_f32());
+
+    let render_stats = stats::snapshot();
+    println!("Estadísticas ({:.2} Mrayos/s):", render_stats.mrays_per_second(elapsed));
+    println!("{}", render_stats);
+
+    println!("Guardando imagen...");
+    save_output(args, &scene.development, &framebuffer, output).expect("Error al guardar la imagen");
+    println!("✓ Imagen guardada en: {}", output);
+    let exposure = scene.development.exposure;
+    if let Exposure::Auto { .. } = exposure {
+        println!("  Exposición automática: {:+.2} EV", exposure.ev_for(&framebuffer));
+    }
+
+    // Copia HDR sin tone mapping para retocarla o componerla en otras herramientas
+    framebuffer.save_exr(&exr_output).expect("Error al guardar la imagen EXR");
+    println!("✓ Imagen HDR guardada en: {}", exr_output);
+
+    // Las pasadas AOV son de una sola vista y no se combinan en estéreo
+    let aov_outputs = if args.stereo.is_some() { &[] } else { args.aov.as_slice() };
+    for (aov, buffer) in aov::render_aovs(scene, &framebuffer, aov_outputs) {
+        let path = aov.output_path(output);
+        buffer.save_exr(&path).expect("Error al guardar la pasada AOV");
+        println!("✓ Pasada {} guardada en: {}", aov.name(), path);
+        if let Some(kind) = aov.mask_kind() {
+            save_mask_preview(scene, kind, &buffer, aov, output).expect("Error al guardar la máscara de segmentación");
+        }
+    }
+
+    cache.record(output, scene_hash);
+    cache.record(&exr_output, scene_hash);
+    if let Err(e) = cache.save() {
+        println!("⚠ No se pudo guardar el registro de renders: {}", e);
+    }
+}
+
+/// Escena de ejemplo: el cubo de redstone sobre un suelo de piedra
+fn example_scene(assets: &AssetPaths) -> Scene {
+    let settings = RenderSettings::new(800, 600).with_samples_per_pixel(4);
 
     let camera = Camera::new(
         Point3::new(3.0, 2.5, 4.0),
         Point3::new(0.0, 0.5, 0.0),
         Vec3::new(0.0, 1.0, 0.0),
         45.0,
-        WIDTH as f32 / HEIGHT as f32,
-        WIDTH,
-        HEIGHT,
+        settings.aspect_ratio(),
+        settings.width,
+        settings.height,
     );
 
     let mut scene = Scene::new(camera, Color::new(0.2, 0.2, 0.25));
+    scene.set_render_settings(settings);
 
     println!("Cargando texturas...");
 
-    let redstone_tex = match Texture::from_image("textures/redstoneblock.png") {
+    let redstone_tex = match Texture::from_image(&assets.resolve_texture("textures/redstoneblock.png")) {
         Ok(tex) => {
             println!("✓ Textura redstone cargada");
             tex
         }
         Err(e) => {
             println!("⚠ No se encontró redstoneblock.png: {}", e);
-            Texture {
-                width: 1,
-                height: 1,
-                data: vec![vec![Color::new(0.8, 0.2, 0.2)]],
-            }
+            Texture::solid(Color::new(0.8, 0.2, 0.2))
         }
     };
 
-    let stone_tex = match Texture::from_image("textures/stoneblock.png") {
+    let stone_tex = match Texture::from_image(&assets.resolve_texture("textures/stoneblock.png")) {
         Ok(tex) => {
             println!("✓ Textura stone cargada");
             tex
         }
         Err(e) => {
             println!("⚠ No se encontró stoneblock.png: {}", e);
-            Texture {
-                width: 1,
-                height: 1,
-                data: vec![vec![Color::new(0.6, 0.6, 0.6)]],
-            }
+            Texture::solid(Color::new(0.6, 0.6, 0.6))
         }
     };
 
-    let redstone_id = scene.add_texture(redstone_tex);
-    let stone_id = scene.add_texture(stone_tex);
+    // Los objetos seleccionan la textura por ID en get_uv (cubo: 0, plano: 1)
+    scene.add_texture(redstone_tex);
+    scene.add_texture(stone_tex);
 
     scene.add_light(Light::white(Point3::new(5.0, 6.0, 4.0), 1.0));
 
@@ -95,62 +609,322 @@ fn main() {
         Material::diffuse(Color::new(1.0, 1.0, 1.0)),
     ));
 
-    println!("Renderizando escena...");
-    let mut framebuffer: Vec<Vec<Color>> = vec![vec![Color::zero(); WIDTH as usize]; HEIGHT as usize];
+    scene
+}
+
+/// Huella de todo lo que afecta a la imagen guardada: la escena y las
+/// opciones de salida
+fn render_hash(args: &Args, scene: &Scene) -> u64 {
+    let mut state = StableHasher::new();
+    state.write_u64(scene.fingerprint());
+    scene.development.hash_state(&mut state);
+    state.write_u8(args.jpeg_quality);
+    state.write_u8(args.png16 as u8);
+    for aov in &args.aov {
+        state.write(aov.name().as_bytes());
+    }
+    if let Some(stereo) = args.stereo() {
+        stereo.hash_state(&mut state);
+    }
+    state.finish()
+}
+
+/// Renderiza todos los cuadros de la animación de la escena como PNG en
+/// `dir` (0001.png, 0002.png...), como video en `--video`, o ambos. Los PNG
+/// que no cambiaron desde el último render se conservan (y se reutilizan
+/// para el video), así que al retocar un tramo solo se repite ese tramo
+fn render_animation(
+    args: &Args,
+    scene: &mut Scene,
+    dir: Option<&str>,
+    cancel: &CancelToken,
+    tiles: Option<&dyn ProgressSink>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let video = args.video.as_deref();
+    let (frame_count, fps) = match &scene.animation {
+        Some(timeline) => (timeline.frame_count(), timeline.fps),
+        None => return Err("la escena no tiene animación".into()),
+    };
+    let mut cache = match dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            Some(RenderCache::load(&Path::new(dir).join(".render_cache").to_string_lossy()))
+        }
+        None => None,
+    };
+    println!("Renderizando {} cuadros a {} fps", frame_count, fps);
+
+    // ffmpeg se lanza con el primer cuadro, cuando se conoce su tamaño
+    // (el estéreo lado a lado duplica el ancho)
+    let mut encoder: Option<VideoEncoder> = None;
     let start = std::time::Instant::now();
+    for frame in 0..frame_count {
+        let time = frame as f32 / fps;
+        scene.set_time(time)?;
+        let path = dir.map(|dir| Path::new(dir).join(format!("{:04}.png", frame + 1)).to_string_lossy().into_owned());
+        let frame_hash = render_hash(args, scene);
+
+        let cached = match (&cache, &path) {
+            (Some(cache), Some(path)) => cache.is_up_to_date(path, frame_hash),
+            _ => false,
+        };
+        let image = if let (true, Some(path)) = (cached, &path) {
+            println!("  Cuadro {}/{} sin cambios", frame + 1, frame_count);
+            if video.is_none() {
+                continue;
+            }
+            image::open(path)?.to_rgb8()
+        } else {
+            println!("  Cuadro {}/{} (t = {:.2}s)...", frame + 1, frame_count, time);
+            let pass = (frame as usize * eye_count(args), frame_count as usize * eye_count(args));
+            let framebuffer = render_frame(args, scene, cancel, Preview { path: path.as_deref(), tiles, pass });
+            let image = scene.development.to_rgb8(&framebuffer);
+            if let Some(path) = &path {
+                image.save(path)?;
+            }
+            image
+        };
 
-    for y in 0..HEIGHT {
-        if y % 60 == 0 {
-            let percentage = (y as f32 / HEIGHT as f32) * 100.0;
-            println!("  Progreso: {:.1}%", percentage);
+        if let Some(video) = video {
+            let encoder = match &mut encoder {
+                Some(encoder) => encoder,
+                None => encoder.insert(VideoEncoder::start(video, image.width(), image.height(), fps)?),
+            };
+            encoder.write_frame(&image)?;
         }
 
-        for x in 0..WIDTH {
-            let u = x as f32 / WIDTH as f32;
-            let v = 1.0 - (y as f32 / HEIGHT as f32);
+        if cancel.is_cancelled() {
+            println!("⚠ Animación cancelada en el cuadro {}", frame + 1);
+            break;
+        }
 
-            let ray = scene.camera.get_ray(u, v);
-            let color = Renderer::trace_ray(&ray, &scene, MAX_DEPTH);
-            framebuffer[y as usize][x as usize] = color;
+        // Se guarda tras cada cuadro para no perder el avance si se interrumpe
+        if let (Some(cache), Some(path)) = (&mut cache, &path) {
+            cache.record(path, frame_hash);
+            if let Err(e) = cache.save() {
+                println!("⚠ No se pudo guardar el registro de renders: {}", e);
+            }
         }
     }
 
-    let elapsed = start.elapsed();
-    println!("✓ Renderizado completado en {:.2}s", elapsed.as_secs_f32());
+    if let Some(encoder) = encoder {
+        encoder.finish()?;
+        println!("✓ Video guardado en: {}", video.unwrap_or_default());
+    }
+    if !cancel.is_cancelled() {
+        println!("✓ Animación completada en {:.2}s", start.elapsed().as_secs_f32());
+    }
+    Ok(())
+}
 
-    println!("Guardando imagen...");
-    save_image(&framebuffer, "src/output/phase3_cube_textured.png").expect("Error al guardar la imagen");
-    println!("✓ Imagen guardada en: src/output/phase3_cube_textured.png");
+/// Renderiza `--dataset-size` variaciones de la escena con sus pasadas en
+/// `dir` (ver `raytracer::dataset`). Las muestras son de una sola vista
+/// aunque haya estéreo; al terminar la escena vuelve a quedar como estaba
+fn render_dataset(
+    args: &Args,
+    scene: &mut Scene,
+    dir: &str,
+    cancel: &CancelToken,
+    tiles: Option<&dyn ProgressSink>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let count = args.dataset_size;
+    let mut writer = DatasetWriter::create(dir)?;
+    let variator = SceneVariator::new(scene, DATASET_VARIATIONS, scene.seed);
+    println!("Generando {} muestras en {}", count, dir);
+
+    let start = std::time::Instant::now();
+    let mut result = Ok(());
+    for index in 0..count {
+        println!("  Muestra {}/{}...", index + 1, count);
+        variator.apply(scene, index);
+        let preview = Preview { path: None, tiles, pass: (index as usize, count as usize) };
+        preview.start_pass();
+        let image = scene.development.to_rgb8(&render_scene(args, scene, cancel, preview));
+        if cancel.is_cancelled() {
+            println!("⚠ Dataset cancelado en la muestra {}", index + 1);
+            break;
+        }
+        let guides = Renderer::render_guides(scene);
+        result = writer.write(index, variator.sample_seed(index), &image, &guides, &scene.camera);
+        if result.is_err() {
+            break;
+        }
+    }
+    variator.restore(scene);
+    result?;
+
+    writer.finish(scene, &variator)?;
+    if !cancel.is_cancelled() {
+        println!("✓ Dataset completado en {:.2}s: {}", start.elapsed().as_secs_f32(), dir);
+    }
+    Ok(())
 }
 
-/// Convierte un color (0.0-1.0) a RGB (0-255)
-fn color_to_rgb(color: Color) -> Rgb<u8> {
-    let r = (color.x * 255.0).clamp(0.0, 255.0) as u8;
-    let g = (color.y * 255.0).clamp(0.0, 255.0) as u8;
-    let b = (color.z * 255.0).clamp(0.0, 255.0) as u8;
-    Rgb([r, g, b])
+/// Cómo se muestra un render mientras avanza
+#[derive(Clone, Copy)]
+struct Preview<'a> {
+    /// PNG que se sobrescribe cada `PREVIEW_INTERVAL`
+    path: Option<&'a str>,
+    /// Receptor de los bloques terminados (la ventana de `--preview`, la
+    /// transmisión de `--stream` o los eventos de `--progress-format json`);
+    /// con él se renderiza por bloques en lugar de por pasadas
+    tiles: Option<&'a dyn ProgressSink>,
+    /// Número (desde 0) del render dentro de la serie y total de renders
+    /// de la serie (cuadros de la animación por ojos del estéreo)
+    pass: (usize, usize),
 }
 
-/// Guarda el framebuffer como una imagen PNG
-fn save_image(framebuffer: &[Vec<Color>], path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    let height = framebuffer.len() as u32;
-    let width = if height > 0 { framebuffer[0].len() as u32 } else { 0 };
+impl Preview<'_> {
+    /// Avisa al receptor de que empieza el render de esta pasada
+    fn start_pass(&self) {
+        if let Some(tiles) = self.tiles {
+            tiles.on_pass(self.pass.0 + 1, self.pass.1);
+        }
+    }
+}
 
-    let mut img = ImageBuffer::new(width, height);
+/// Renders que forman cada imagen: dos en estéreo, uno por ojo
+fn eye_count(args: &Args) -> usize {
+    if args.stereo.is_some() { 2 } else { 1 }
+}
 
-    for y in 0..height {
-        for x in 0..width {
-            let color = framebuffer[y as usize][x as usize];
-            let rgb = color_to_rgb(color);
-            img.put_pixel(x, y, rgb);
+/// Renderiza una imagen de la escena (o el par estéreo combinado)
+fn render_frame(args: &Args, scene: &mut Scene, cancel: &CancelToken, preview: Preview) -> Framebuffer {
+    match args.stereo() {
+        Some(stereo) => render_stereo(args, scene, stereo, cancel, preview),
+        None => {
+            preview.start_pass();
+            render_scene(args, scene, cancel, preview)
         }
     }
+}
 
-    // Crear directorio si no existe
-    if let Some(parent) = Path::new(path).parent() {
-        std::fs::create_dir_all(parent)?;
+/// Renderiza en CPU guardando una vista previa cada pocos segundos para
+/// revisar renders largos, o mostrando los bloques en la ventana
+fn render_cpu(args: &Args, scene: &Scene, cancel: &CancelToken, preview: Preview) -> Framebuffer {
+    if let Some(tiles) = preview.tiles {
+        return Renderer::render_cancellable(scene, tiles, cancel);
+    }
+    Renderer::render_progressive(scene, PREVIEW_INTERVAL, cancel, |image, samples| {
+        if let (Some(path), true) = (preview.path, samples < scene.settings.samples_per_pixel) {
+            match save_output(args, &scene.development, image, path) {
+                Ok(()) => println!("  Vista previa con {} muestras guardada", samples),
+                Err(e) => println!("⚠ No se pudo guardar la vista previa: {}", e),
+            }
+        }
+    })
+}
+
+#[cfg(not(feature = "gpu"))]
+fn render_scene(args: &Args, scene: &Scene, cancel: &CancelToken, preview: Preview) -> Framebuffer {
+    render_cpu(args, scene, cancel, preview)
+}
+
+/// Con la feature `gpu` se intenta primero el backend de GPU; si no hay
+/// adaptador o la escena usa algo que el shader no admite, se usa la CPU
+#[cfg(feature = "gpu")]
+fn render_scene(args: &Args, scene: &Scene, cancel: &CancelToken, preview: Preview) -> Framebuffer {
+    match raytracer::gpu::render(scene) {
+        Ok(framebuffer) => {
+            println!("✓ Renderizado en GPU");
+            framebuffer
+        }
+        Err(e) => {
+            println!("⚠ No se pudo renderizar en GPU ({}), se usa la CPU", e);
+            render_cpu(args, scene, cancel, preview)
+        }
     }
+}
+
+/// Renderiza la escena desde cada ojo y combina las dos imágenes
+fn render_stereo(args: &Args, scene: &mut Scene, stereo: Stereo, cancel: &CancelToken, preview: Preview) -> Framebuffer {
+    let (left_camera, right_camera) = stereo.eyes(&scene.camera);
+    let center = std::mem::replace(&mut scene.camera, left_camera);
+
+    println!("  Ojo izquierdo...");
+    preview.start_pass();
+    let left = render_scene(args, scene, cancel, preview);
+    scene.camera = right_camera;
+    println!("  Ojo derecho...");
+    let preview = Preview { pass: (preview.pass.0 + 1, preview.pass.1), ..preview };
+    preview.start_pass();
+    let right = render_scene(args, scene, cancel, preview);
+    scene.camera = center;
+
+    stereo.compose(&left, &right)
+}
 
-    img.save(path)?;
+/// Copia en colores de una máscara de segmentación y la leyenda con qué
+/// objeto o material es cada etiqueta, junto a la pasada EXR
+fn save_mask_preview(
+    scene: &Scene,
+    kind: MaskKind,
+    mask: &Framebuffer,
+    aov: Aov,
+    output: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let image_path = aov.path_with_extension(output, "png");
+    segmentation::colorize(mask).save(&image_path)?;
+    let legend_path = aov.path_with_extension(output, "json");
+    let legend = Segmentation::new(scene, kind).legend(scene);
+    std::fs::write(&legend_path, serde_json::to_string_pretty(&legend)?)?;
+    println!("  Máscara en colores: {} (leyenda en {})", image_path, legend_path);
     Ok(())
 }
+
+/// Formato de la imagen final según la extensión de `path`, con la calidad
+/// de `--jpeg-quality`, y si se guarda en 16 bits por canal (`--png16`,
+/// solo si el formato lo admite)
+fn output_format(args: &Args, path: &str) -> Result<(OutputFormat, bool), Box<dyn std::error::Error>> {
+    let format = OutputFormat::from_path(path)?.with_jpeg_quality(args.jpeg_quality);
+    Ok((format, args.png16 && format.supports_16_bit()))
+}
+
+/// Guarda la imagen final, revelada con `development`, en `path` con el
+/// formato que indica su extensión
+fn save_output(
+    args: &Args,
+    development: &Development,
+    framebuffer: &Framebuffer,
+    path: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match output_format(args, path)? {
+        (format, true) => format.save(&development.to_rgb16(framebuffer), path),
+        (format, false) => format.save(&development.to_rgb8(framebuffer), path),
+    }
+}
+
+/// Pega una región renderizada sobre la imagen existente
+/// Si la imagen no existe o tiene otro tamaño, se parte de una imagen negra
+fn paste_region(
+    args: &Args,
+    pixels: &Framebuffer,
+    region: &Tile,
+    (width, height): (u32, u32),
+    path: &str,
+    tone_mapping: ToneMapping,
+    encoding: ColorEncoding,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (format, sixteen_bit) = output_format(args, path)?;
+    let existing = image::open(path).ok().filter(|existing| existing.width() == width && existing.height() == height);
+
+    if sixteen_bit {
+        let mut img = existing.map_or_else(|| ImageBuffer::new(width, height), |existing| existing.to_rgb16());
+        paste_pixels(&mut img, region, |column, row| color_to_rgb16(pixels.get(column, row), tone_mapping, encoding));
+        format.save(&img, path)
+    } else {
+        let mut img = existing.map_or_else(|| ImageBuffer::new(width, height), |existing| existing.to_rgb8());
+        paste_pixels(&mut img, region, |column, row| color_to_rgb(pixels.get(column, row), tone_mapping, encoding));
+        format.save(&img, path)
+    }
+}
+
+/// Escribe en `img` los píxeles de `region`; `pixel` recibe la posición
+/// relativa a la región
+fn paste_pixels<P: Pixel>(img: &mut ImageBuffer<P, Vec<P::Subpixel>>, region: &Tile, pixel: impl Fn(u32, u32) -> P) {
+    for (row, y) in (region.y0..region.y1).enumerate() {
+        for (column, x) in (region.x0..region.x1).enumerate() {
+            img.put_pixel(x, y, pixel(column as u32, row as u32));
+        }
+    }
+}
@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 use crate::vector::Color;
 
 /// Estructura que define las propiedades de un material
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Material {
     pub color: Color,
     pub albedo: f32,         // Reflexión difusa (0.0 a 1.0)
@@ -11,6 +14,31 @@ pub struct Material {
     // Preparación para Fase 3 (texturas)
     pub has_texture: bool,
     pub texture_id: Option<usize>,
+
+    // Luz emitida por la superficie (cero = no emite)
+    pub emission: Color,
+
+    // Transparencia (0.0 = opaco, 1.0 = deja pasar toda la luz filtrada por `color`)
+    pub transparency: f32,
+
+    // Rugosidad de los reflejos (0.0 = espejo perfecto, 1.0 = reflejo muy difuso)
+    pub roughness: f32,
+
+    // Reflexiones/refracciones encadenadas que se siguen desde este material
+    // (None = el límite del integrador)
+    pub max_depth: Option<u32>,
+
+    // Mapa horneado (ID en `Scene::maps`) con la oclusión ambiental de la
+    // superficie (ver `bake`); oscurece la luz ambiental en las mismas UV
+    // que la textura de color
+    #[serde(default)]
+    pub occlusion_map: Option<usize>,
+
+    // Mapa horneado (ID en `Scene::maps`) con la iluminación (lightmap, ver
+    // `bake`); si está, reemplaza a las luces y las sombras en el sombreado
+    // difuso, lo que hace el render mucho más rápido
+    #[serde(default)]
+    pub lightmap: Option<usize>,
 }
 
 impl Material {
@@ -24,6 +52,12 @@ impl Material {
             reflectivity: 0.0,
             has_texture: false,
             texture_id: None,
+            emission: Color::zero(),
+            transparency: 0.0,
+            roughness: 0.0,
+            max_depth: None,
+            occlusion_map: None,
+            lightmap: None,
         }
     }
 
@@ -37,6 +71,12 @@ impl Material {
             reflectivity: 0.0,
             has_texture: false,
             texture_id: None,
+            emission: Color::zero(),
+            transparency: 0.0,
+            roughness: 0.0,
+            max_depth: None,
+            occlusion_map: None,
+            lightmap: None,
         }
     }
 
@@ -50,6 +90,12 @@ impl Material {
             reflectivity: 0.3,
             has_texture: false,
             texture_id: None,
+            emission: Color::zero(),
+            transparency: 0.0,
+            roughness: 0.0,
+            max_depth: None,
+            occlusion_map: None,
+            lightmap: None,
         }
     }
 
@@ -63,6 +109,32 @@ impl Material {
             reflectivity: 0.9,
             has_texture: false,
             texture_id: None,
+            emission: Color::zero(),
+            transparency: 0.0,
+            roughness: 0.0,
+            max_depth: None,
+            occlusion_map: None,
+            lightmap: None,
+        }
+    }
+
+    /// Material transparente teñido (vidrio de color)
+    /// La luz que lo atraviesa se filtra por `color`
+    pub fn transparent(color: Color, transparency: f32) -> Self {
+        Material {
+            color,
+            albedo: 0.2,
+            specular: 0.8,
+            shininess: 128.0,
+            reflectivity: 0.1,
+            has_texture: false,
+            texture_id: None,
+            emission: Color::zero(),
+            transparency: transparency.clamp(0.0, 1.0),
+            roughness: 0.0,
+            max_depth: None,
+            occlusion_map: None,
+            lightmap: None,
         }
     }
 
@@ -72,20 +144,70 @@ impl Material {
         self.texture_id = Some(texture_id);
         self
     }
-}
 
-impl Clone for Material {
-    fn clone(&self) -> Self {
+    /// Material emisivo: brilla con su propio color e ilumina la escena
+    pub fn emissive(color: Color, strength: f32) -> Self {
         Material {
-            color: self.color,
-            albedo: self.albedo,
-            specular: self.specular,
-            shininess: self.shininess,
-            reflectivity: self.reflectivity,
-            has_texture: self.has_texture,
-            texture_id: self.texture_id,
+            color,
+            albedo: 0.0,
+            specular: 0.0,
+            shininess: 1.0,
+            reflectivity: 0.0,
+            has_texture: false,
+            texture_id: None,
+            emission: color * strength,
+            transparency: 0.0,
+            roughness: 0.0,
+            max_depth: None,
+            occlusion_map: None,
+            lightmap: None,
         }
     }
-}
 
-impl Copy for Material {}
+    /// Hace que el material emita luz del color dado
+    pub fn with_emission(mut self, emission: Color) -> Self {
+        self.emission = emission;
+        self
+    }
+
+    /// Ajusta la transparencia del material (0.0 a 1.0)
+    pub fn with_transparency(mut self, transparency: f32) -> Self {
+        self.transparency = transparency.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Ajusta la rugosidad de los reflejos (0.0 a 1.0)
+    pub fn with_roughness(mut self, roughness: f32) -> Self {
+        self.roughness = roughness.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Límite propio de rebotes: un objeto protagonista puede tener reflejos
+    /// profundos mientras el resto de la escena usa el límite general
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Usa una textura de oclusión ambiental horneada (ver `bake::bake_ambient_occlusion`)
+    pub fn with_occlusion_map(mut self, texture_id: usize) -> Self {
+        self.occlusion_map = Some(texture_id);
+        self
+    }
+
+    /// Usa una textura de iluminación horneada (ver `bake::bake_lighting`)
+    pub fn with_lightmap(mut self, texture_id: usize) -> Self {
+        self.lightmap = Some(texture_id);
+        self
+    }
+
+    /// Color de la luz que atraviesa el material (negro si es opaco)
+    pub fn transmission(&self) -> Color {
+        self.color * self.transparency
+    }
+
+    /// Indica si el material emite luz
+    pub fn is_emissive(&self) -> bool {
+        self.emission.x > 0.0 || self.emission.y > 0.0 || self.emission.z > 0.0
+    }
+}
@@ -0,0 +1,121 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Point3, Color};
+use crate::ray::Ray;
+use crate::render_cache::{hash_f32, hash_vec3};
+
+/// Medio participante homogéneo (niebla, humo, bruma)
+/// La luz se atenúa al atravesarlo y parte de la luz de las fuentes se
+/// dispersa hacia la cámara, lo que produce haces de luz volumétricos
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Medium {
+    pub density: f32,       // Coeficiente de extinción por unidad de distancia
+    pub albedo: Color,      // Fracción de la extinción que es dispersión (color de la niebla)
+    pub anisotropy: f32,    // Parámetro g de Henyey-Greenstein (-1 a 1, 0 = isótropo)
+    pub steps: u32,         // Pasos de ray marching por rayo
+    pub max_distance: f32,  // Distancia recorrida por los rayos que no chocan con nada
+    pub bounds: Option<(Point3, Point3)>, // Caja que limita el medio (None = global)
+}
+
+impl Medium {
+    /// Crea un medio global con la densidad y color dados
+    pub fn new(density: f32, albedo: Color) -> Self {
+        Medium {
+            density: density.max(0.0),
+            albedo,
+            anisotropy: 0.0,
+            steps: 32,
+            max_distance: 50.0,
+            bounds: None,
+        }
+    }
+
+    /// Limita el medio al interior de una caja alineada con los ejes
+    pub fn with_bounds(mut self, min: Point3, max: Point3) -> Self {
+        self.bounds = Some((min, max));
+        self
+    }
+
+    /// Ajusta la dirección preferente de dispersión
+    /// (valores positivos concentran la luz hacia adelante, como en la niebla real)
+    pub fn with_anisotropy(mut self, g: f32) -> Self {
+        self.anisotropy = g.clamp(-0.99, 0.99);
+        self
+    }
+
+    /// Ajusta el número de pasos de ray marching
+    pub fn with_steps(mut self, steps: u32) -> Self {
+        self.steps = steps.max(1);
+        self
+    }
+
+    /// Tramo [t0, t1] del rayo que está dentro del medio, si existe
+    pub fn segment(&self, ray: &Ray, t_max: f32) -> Option<(f32, f32)> {
+        let t_max = t_max.min(self.max_distance);
+
+        let (min, max) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return Some((0.0, t_max)),
+        };
+
+        // Intersección rayo-caja con el método de los slabs
+        let mut t0 = 0.0_f32;
+        let mut t1 = t_max;
+        let axes = [
+            (ray.origin.x, ray.direction.x, min.x, max.x),
+            (ray.origin.y, ray.direction.y, min.y, max.y),
+            (ray.origin.z, ray.direction.z, min.z, max.z),
+        ];
+
+        for (origin, direction, lo, hi) in axes {
+            if direction.abs() < 1e-8 {
+                if origin < lo || origin > hi {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv = 1.0 / direction;
+            let (near, far) = {
+                let a = (lo - origin) * inv;
+                let b = (hi - origin) * inv;
+                if a < b { (a, b) } else { (b, a) }
+            };
+            t0 = t0.max(near);
+            t1 = t1.min(far);
+            if t0 > t1 {
+                return None;
+            }
+        }
+
+        Some((t0, t1))
+    }
+
+    /// Transmitancia a lo largo de una distancia (ley de Beer-Lambert)
+    pub fn transmittance(&self, distance: f32) -> f32 {
+        (-self.density * distance).exp()
+    }
+
+    /// Función de fase de Henyey-Greenstein
+    /// `cos_theta` es el coseno entre la dirección del rayo y la dirección hacia la luz
+    pub fn phase(&self, cos_theta: f32) -> f32 {
+        let g = self.anisotropy;
+        let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+        (1.0 - g * g) / (4.0 * std::f32::consts::PI * denom * denom.sqrt())
+    }
+
+    /// Agrega el estado del medio al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_f32(state, self.density);
+        hash_vec3(state, &self.albedo);
+        hash_f32(state, self.anisotropy);
+        state.write_u32(self.steps);
+        hash_f32(state, self.max_distance);
+        if let Some((min, max)) = &self.bounds {
+            hash_vec3(state, min);
+            hash_vec3(state, max);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::packet::{self, RayPacket, PacketHits};
+use crate::gpu::GpuPrimitive;
+use crate::render_cache::{hash_vec3, hash_material};
+
+/// Malla de triángulos con su propia BVH (BLAS)
+/// La BVH se construye una sola vez; las copias transformadas de la malla
+/// se agregan a la escena como instancias que la comparten
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TriangleMesh {
+    pub vertices: Vec<Point3>,
+    pub triangles: Vec<[usize; 3]>,
+    pub material: Material,
+    bvh: Bvh,
+    /// Área acumulada hasta cada triángulo (inclusive), para muestrear la
+    /// malla cuando es emisiva
+    cumulative_area: Vec<f32>,
+}
+
+impl TriangleMesh {
+    /// Crea una malla a partir de sus vértices e índices de triángulos
+    /// Los triángulos con índices fuera de rango se descartan
+    pub fn new(vertices: Vec<Point3>, triangles: Vec<[usize; 3]>, material: Material) -> Self {
+        let triangles: Vec<[usize; 3]> = triangles
+            .into_iter()
+            .filter(|triangle| triangle.iter().all(|&index| index < vertices.len()))
+            .collect();
+
+        let items = triangles
+            .iter()
+            .enumerate()
+            .map(|(index, [a, b, c])| (index, Aabb::from_points(&[vertices[*a], vertices[*b], vertices[*c]])))
+            .collect();
+
+        let cumulative_area = triangles
+            .iter()
+            .scan(0.0, |total, [a, b, c]| {
+                *total += (vertices[*b] - vertices[*a]).cross(&(vertices[*c] - vertices[*a])).length() / 2.0;
+                Some(*total)
+            })
+            .collect();
+
+        TriangleMesh {
+            vertices,
+            triangles,
+            material,
+            bvh: Bvh::build(items),
+            cumulative_area,
+        }
+    }
+
+    fn triangle(&self, index: usize) -> (Point3, Point3, Point3) {
+        let [a, b, c] = self.triangles[index];
+        (self.vertices[a], self.vertices[b], self.vertices[c])
+    }
+
+    /// Normal geométrica de un triángulo (según el orden de sus vértices)
+    fn triangle_normal(&self, index: usize) -> Vec3 {
+        let (v0, v1, v2) = self.triangle(index);
+        (v1 - v0).cross(&(v2 - v0)).normalize()
+    }
+
+    pub fn intersect(&self, ray: &Ray) -> Option<f32> {
+        self.bvh
+            .intersect(ray, |index, ray| {
+                let (v0, v1, v2) = self.triangle(index);
+                intersect_triangle(ray, v0, v1, v2)
+            })
+            .map(|(t, _)| t)
+    }
+
+    /// Intersección con un paquete de rayos (ver `packet`): la BVH de la
+    /// malla se recorre una vez para todo el paquete
+    pub fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        self.bvh
+            .intersect_packet(rays, |index, rays| {
+                let (v0, v1, v2) = self.triangle(index);
+                packet::intersect_triangle(rays, &v0, &v1, &v2)
+            })
+            .map(|hit| hit.map(|(t, _)| t))
+    }
+
+    /// Normal en un punto de la superficie: la del triángulo cuyo plano
+    /// queda más cerca del punto entre los que lo contienen
+    pub fn normal_at(&self, point: &Point3) -> Vec3 {
+        self.closest_normal(point).map_or(Vec3::new(0.0, 1.0, 0.0), |(_, normal)| normal)
+    }
+
+    /// Distancia del punto al plano del triángulo más cercano que lo
+    /// contiene, y su normal; None si ningún triángulo lo contiene
+    pub fn closest_normal(&self, point: &Point3) -> Option<(f32, Vec3)> {
+        let mut closest: Option<(f32, Vec3)> = None;
+
+        self.bvh.query_point(point, 1e-3, |index| {
+            let normal = self.triangle_normal(index);
+            let distance = (*point - self.triangle(index).0).dot(&normal).abs();
+            if distance < closest.map_or(f32::INFINITY, |(best, _)| best) {
+                closest = Some((distance, normal));
+            }
+        });
+
+        closest
+    }
+
+    /// Área de la superficie (la suma de la de los triángulos)
+    pub fn surface_area(&self) -> f32 {
+        self.cumulative_area.last().copied().unwrap_or(0.0)
+    }
+
+    /// Punto de la superficie a partir de (u, v) en [0, 1)², distribuido
+    /// uniformemente: `u` elige el triángulo según su área y se reutiliza,
+    /// reescalado, para la posición dentro de él
+    pub fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        let total = self.surface_area();
+        if total <= 0.0 {
+            return None;
+        }
+        let target = u * total;
+        let index = self.cumulative_area.partition_point(|&area| area <= target).min(self.triangles.len() - 1);
+        let start = if index == 0 { 0.0 } else { self.cumulative_area[index - 1] };
+        let area = self.cumulative_area[index] - start;
+        let s = if area > 0.0 { ((target - start) / area).clamp(0.0, 1.0) } else { 0.0 };
+
+        // Coordenadas baricéntricas uniformes en el triángulo
+        let (v0, v1, v2) = self.triangle(index);
+        let root = s.sqrt();
+        Some(v0 * (1.0 - root) + v1 * (root * (1.0 - v)) + v2 * (root * v))
+    }
+
+    /// Problemas que impiden renderizar la malla (ver `Scene::validate`)
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let invalid = self.vertices.iter().filter(|vertex| !vertex.is_finite()).count();
+        if invalid > 0 {
+            problems.push(format!("malla con {} vértices no finitos (NaN o infinito)", invalid));
+        }
+        if self.triangles.is_empty() {
+            problems.push("malla sin triángulos".to_string());
+        }
+        problems
+    }
+
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.bvh.bounds()
+    }
+
+    /// Los triángulos para el backend de GPU (sin textura, como `get_uv`)
+    pub fn gpu_primitives(&self) -> Vec<GpuPrimitive> {
+        (0..self.triangles.len())
+            .map(|index| {
+                let (v0, v1, v2) = self.triangle(index);
+                GpuPrimitive::triangle(v0, v1, v2)
+            })
+            .collect()
+    }
+
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        state.write_usize(self.vertices.len());
+        for vertex in &self.vertices {
+            hash_vec3(state, vertex);
+        }
+        state.write_usize(self.triangles.len());
+        for triangle in &self.triangles {
+            for index in triangle {
+                state.write_usize(*index);
+            }
+        }
+        hash_material(state, &self.material);
+    }
+}
+
+/// Intersección rayo-triángulo usando el algoritmo de Möller-Trumbore
+pub fn intersect_triangle(ray: &Ray, v0: Point3, v1: Point3, v2: Point3) -> Option<f32> {
+    let epsilon = 1e-6;
+
+    let edge1 = v1 - v0;
+    let edge2 = v2 - v0;
+    let h = ray.direction.cross(&edge2);
+    let a = edge1.dot(&h);
+
+    if a.abs() < epsilon {
+        return None; // Rayo paralelo al triángulo
+    }
+
+    let f = 1.0 / a;
+    let s = ray.origin - v0;
+    let u = f * s.dot(&h);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(&edge1);
+    let v = f * ray.direction.dot(&q);
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = f * edge2.dot(&q);
+    if t > epsilon {
+        Some(t)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,96 @@
+use std::hash::Hasher;
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::material::Material;
+use crate::scene::Intersectable;
+use crate::render_cache::hash_vec3;
+use crate::snapshot::{ObjectSnapshot, SharedObjects};
+
+/// Objeto que se desplaza a velocidad constante durante la exposición
+/// En el instante t el objeto envuelto está trasladado `velocity * t`; en
+/// lugar de mover la geometría se desplaza el rayo en sentido contrario
+pub struct MovingObject {
+    pub object: Box<dyn Intersectable>,
+    pub velocity: Vec3,
+}
+
+impl MovingObject {
+    /// Envuelve un objeto para que se mueva con la velocidad dada
+    pub fn new(object: Box<dyn Intersectable>, velocity: Vec3) -> Self {
+        MovingObject { object, velocity }
+    }
+
+    /// Desplazamiento del objeto en el instante `time`
+    pub fn offset(&self, time: f32) -> Vec3 {
+        self.velocity * time
+    }
+}
+
+impl Intersectable for MovingObject {
+    fn intersect(&self, ray: &Ray) -> Option<f32> {
+        // La dirección no cambia, así que la distancia t es la misma
+        let offset = self.offset(ray.time);
+        let local_ray = Ray { origin: ray.origin - offset, viewpoint: ray.viewpoint - offset, ..*ray };
+        self.object.intersect(&local_ray)
+    }
+
+    fn normal_at(&self, point: &Point3) -> Vec3 {
+        self.object.normal_at(point)
+    }
+
+    fn get_material(&self) -> &Material {
+        self.object.get_material()
+    }
+
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        self.object.material_mut()
+    }
+
+    fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)> {
+        self.object.get_uv(point)
+    }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        self.object.hash_state(state);
+        hash_vec3(state, &self.velocity);
+    }
+
+    fn normal_at_time(&self, point: &Point3, time: f32) -> Vec3 {
+        self.object.normal_at_time(&(*point - self.offset(time)), time)
+    }
+
+    fn get_uv_at_time(&self, point: &Point3, time: f32) -> Option<(f32, f32, usize)> {
+        self.object.get_uv_at_time(&(*point - self.offset(time)), time)
+    }
+
+    fn surface_area(&self) -> f32 {
+        self.object.surface_area()
+    }
+
+    fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        self.object.sample_surface(u, v)
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut problems = self.object.validate();
+        if !self.velocity.is_finite() {
+            problems.push("objeto en movimiento con velocidad no finita (NaN o infinito)".to_string());
+        }
+        problems
+    }
+
+    fn texture_id(&self) -> Option<usize> {
+        self.object.texture_id()
+    }
+
+    /// Posición al abrirse el obturador (t = 0)
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        self.object.tessellate()
+    }
+
+    fn snapshot(&self, shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        let object = Box::new(self.object.snapshot(shared)?);
+        Some(ObjectSnapshot::Moving { object, velocity: self.velocity })
+    }
+}
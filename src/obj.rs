@@ -0,0 +1,63 @@
+// Archivos Wavefront OBJ: solo la geometría (vértices y caras); las
+// normales, coordenadas de textura, grupos y materiales se ignoran.
+
+use crate::vector::Point3;
+use crate::material::Material;
+use crate::mesh::TriangleMesh;
+
+/// Lee una malla de un archivo .obj con el material dado
+pub fn load(path: &str, material: Material) -> Result<TriangleMesh, Box<dyn std::error::Error>> {
+    let text = crate::assets::read_to_string(path)
+        .map_err(|e| format!("no se pudo leer {}: {}", path, e))?;
+    let (vertices, triangles) = parse(&text).map_err(|e| format!("{}: {}", path, e))?;
+    Ok(TriangleMesh::new(vertices, triangles, material))
+}
+
+/// Vértices y triángulos del texto OBJ
+/// Las caras de más de tres vértices se dividen en abanico; los índices
+/// pueden ser negativos (relativos al último vértice) y traer `/vt/vn`.
+pub fn parse(text: &str) -> Result<(Vec<Point3>, Vec<[usize; 3]>), String> {
+    let mut vertices = Vec::new();
+    let mut triangles = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let mut fields = line.split_whitespace();
+        let error = |message: &str| format!("línea {}: {}", number + 1, message);
+
+        match fields.next() {
+            Some("v") => {
+                let coordinates: Vec<f32> = fields
+                    .take(3)
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| error("coordenada de vértice inválida"))?;
+                let [x, y, z] = coordinates[..] else {
+                    return Err(error("un vértice necesita tres coordenadas"));
+                };
+                vertices.push(Point3::new(x, y, z));
+            }
+            Some("f") => {
+                let face: Vec<usize> = fields
+                    .map(|field| vertex_index(field, vertices.len()))
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| error("índice de vértice inválido o fuera de rango"))?;
+                if face.len() < 3 {
+                    return Err(error("una cara necesita al menos tres vértices"));
+                }
+                for i in 1..face.len() - 1 {
+                    triangles.push([face[0], face[i], face[i + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok((vertices, triangles))
+}
+
+/// Índice (desde 0) del vértice de una cara ("7", "7/2/3", "-1//4"...)
+fn vertex_index(field: &str, vertex_count: usize) -> Option<usize> {
+    let index: i64 = field.split('/').next()?.parse().ok()?;
+    let index = if index < 0 { vertex_count as i64 + index } else { index - 1 };
+    (0..vertex_count as i64).contains(&index).then_some(index as usize)
+}
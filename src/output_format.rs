@@ -0,0 +1,88 @@
+#[cfg(feature = "fs")]
+use std::fs::File;
+#[cfg(feature = "fs")]
+use std::io::BufWriter;
+use std::path::Path;
+
+#[cfg(feature = "fs")]
+use image::codecs::pnm::{PnmSubtype, SampleEncoding};
+#[cfg(feature = "fs")]
+use image::{EncodableLayout, ImageBuffer, ImageOutputFormat, PixelWithColorType};
+
+#[cfg(feature = "fs")]
+use crate::framebuffer::create_parent_dir;
+
+/// Calidad JPEG por defecto (de 1 a 100)
+pub const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+/// Formato de la imagen final, elegido por la extensión de la ruta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Png,
+    /// PPM binario (P6): sin compresión, fácil de leer desde cualquier programa
+    Ppm,
+    Bmp,
+    /// Con pérdida; `quality` va de 1 (archivo mínimo) a 100
+    Jpeg { quality: u8 },
+    Tga,
+}
+
+impl OutputFormat {
+    /// Formato según la extensión de `path` (png, ppm, bmp, jpg/jpeg o tga,
+    /// sin distinguir mayúsculas)
+    pub fn from_path(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let extension = Path::new(path)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "png" => Ok(OutputFormat::Png),
+            "ppm" => Ok(OutputFormat::Ppm),
+            "bmp" => Ok(OutputFormat::Bmp),
+            "jpg" | "jpeg" => Ok(OutputFormat::Jpeg { quality: DEFAULT_JPEG_QUALITY }),
+            "tga" => Ok(OutputFormat::Tga),
+            _ => Err(format!(
+                "formato de imagen no admitido: '{}' (se admiten png, ppm, bmp, jpg y tga)",
+                path
+            )
+            .into()),
+        }
+    }
+
+    /// Cambia la calidad si el formato es JPEG (los demás no tienen pérdida)
+    pub fn with_jpeg_quality(self, quality: u8) -> Self {
+        match self {
+            OutputFormat::Jpeg { .. } => OutputFormat::Jpeg { quality: quality.clamp(1, 100) },
+            format => format,
+        }
+    }
+
+    /// Si el formato admite 16 bits por canal (solo PNG); los demás guardan 8
+    pub fn supports_16_bit(&self) -> bool {
+        matches!(self, OutputFormat::Png)
+    }
+
+    /// Guarda `image` en `path` con este formato, creando el directorio si falta
+    /// Las imágenes de 16 bits (`Rgb16Image`) solo se pueden guardar en los
+    /// formatos que lo admiten (ver `supports_16_bit`).
+    #[cfg(feature = "fs")]
+    pub fn save<P>(&self, image: &ImageBuffer<P, Vec<P::Subpixel>>, path: &str) -> Result<(), Box<dyn std::error::Error>>
+    where
+        P: PixelWithColorType,
+        [P::Subpixel]: EncodableLayout,
+    {
+        let format = match *self {
+            OutputFormat::Png => ImageOutputFormat::Png,
+            OutputFormat::Ppm => ImageOutputFormat::Pnm(PnmSubtype::Pixmap(SampleEncoding::Binary)),
+            OutputFormat::Bmp => ImageOutputFormat::Bmp,
+            OutputFormat::Jpeg { quality } => ImageOutputFormat::Jpeg(quality),
+            OutputFormat::Tga => ImageOutputFormat::Tga,
+        };
+
+        create_parent_dir(path)?;
+        let mut file = BufWriter::new(File::create(path)?);
+        image.write_to(&mut file, format)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+// Los bucles por posición del paquete indexan varios arreglos a la vez a
+// propósito: es la forma que el compilador vectoriza
+#![allow(clippy::needless_range_loop)]
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::aabb::Aabb;
+
+/// Número de rayos por paquete
+pub const LANES: usize = 4;
+
+/// Paquete de rayos en formato "estructura de arreglos" (SoA)
+/// Cada componente se guarda en un arreglo de `LANES` valores para que las
+/// pruebas de intersección recorran los rayos en bucles simples sobre
+/// arreglos fijos, que el compilador convierte en instrucciones SIMD (SSE
+/// en x86_64, NEON en ARM) sin código inseguro ni `std::simd` (inestable).
+/// Los rayos primarios de píxeles vecinos son muy coherentes: casi siempre
+/// atraviesan los mismos nodos de la BVH y chocan con los mismos objetos.
+#[derive(Debug, Clone, Copy)]
+pub struct RayPacket {
+    pub ox: [f32; LANES],
+    pub oy: [f32; LANES],
+    pub oz: [f32; LANES],
+    pub dx: [f32; LANES],
+    pub dy: [f32; LANES],
+    pub dz: [f32; LANES],
+    pub time: [f32; LANES],
+    pub active: [bool; LANES],
+}
+
+/// Resultado por rayo de una prueba de intersección de paquete
+pub type PacketHits = [Option<f32>; LANES];
+
+impl RayPacket {
+    /// Agrupa hasta `LANES` rayos; las posiciones sobrantes quedan inactivas
+    pub fn new(rays: &[Ray]) -> Self {
+        let mut packet = RayPacket {
+            ox: [0.0; LANES],
+            oy: [0.0; LANES],
+            oz: [0.0; LANES],
+            dx: [0.0; LANES],
+            dy: [0.0; LANES],
+            dz: [1.0; LANES],
+            time: [0.0; LANES],
+            active: [false; LANES],
+        };
+
+        for (lane, ray) in rays.iter().take(LANES).enumerate() {
+            packet.ox[lane] = ray.origin.x;
+            packet.oy[lane] = ray.origin.y;
+            packet.oz[lane] = ray.origin.z;
+            packet.dx[lane] = ray.direction.x;
+            packet.dy[lane] = ray.direction.y;
+            packet.dz[lane] = ray.direction.z;
+            packet.time[lane] = ray.time;
+            packet.active[lane] = true;
+        }
+
+        packet
+    }
+
+    /// Rayo individual de una posición del paquete
+    pub fn ray(&self, lane: usize) -> Ray {
+        Ray::new(
+            Point3::new(self.ox[lane], self.oy[lane], self.oz[lane]),
+            Vec3::new(self.dx[lane], self.dy[lane], self.dz[lane]),
+        )
+        .with_time(self.time[lane])
+    }
+}
+
+/// Intersección paquete-esfera (misma fórmula que `Sphere::intersect`)
+pub fn intersect_sphere(packet: &RayPacket, center: &Point3, radius: f32) -> PacketHits {
+    let mut hits = [None; LANES];
+
+    for lane in 0..LANES {
+        let ocx = packet.ox[lane] - center.x;
+        let ocy = packet.oy[lane] - center.y;
+        let ocz = packet.oz[lane] - center.z;
+        let (dx, dy, dz) = (packet.dx[lane], packet.dy[lane], packet.dz[lane]);
+
+        let a = dx * dx + dy * dy + dz * dz;
+        let b = 2.0 * (ocx * dx + ocy * dy + ocz * dz);
+        let c = ocx * ocx + ocy * ocy + ocz * ocz - radius * radius;
+        let discriminant = b * b - 4.0 * a * c;
+
+        let root = discriminant.max(0.0).sqrt();
+        let t1 = (-b - root) / (2.0 * a);
+        let t2 = (-b + root) / (2.0 * a);
+        let t = if t1 > 1e-4 { t1 } else { t2 };
+
+        if packet.active[lane] && discriminant >= 0.0 && t > 1e-4 {
+            hits[lane] = Some(t);
+        }
+    }
+
+    hits
+}
+
+/// Intersección paquete-caja sólida (misma lógica que `Cube::intersect`)
+pub fn intersect_box(packet: &RayPacket, bounds: &Aabb) -> PacketHits {
+    let mut hits = [None; LANES];
+
+    for lane in 0..LANES {
+        let mut t_min = f32::NEG_INFINITY;
+        let mut t_max = f32::INFINITY;
+        let mut missed = false;
+
+        for (start, dir, min, max) in [
+            (packet.ox[lane], packet.dx[lane], bounds.min.x, bounds.max.x),
+            (packet.oy[lane], packet.dy[lane], bounds.min.y, bounds.max.y),
+            (packet.oz[lane], packet.dz[lane], bounds.min.z, bounds.max.z),
+        ] {
+            if dir.abs() > 1e-6 {
+                let t0 = (min - start) / dir;
+                let t1 = (max - start) / dir;
+                t_min = t_min.max(t0.min(t1));
+                t_max = t_max.min(t0.max(t1));
+            } else if start < min || start > max {
+                missed = true;
+            }
+        }
+
+        if !packet.active[lane] || missed || t_min > t_max {
+            continue;
+        }
+        if t_min > 1e-4 {
+            hits[lane] = Some(t_min);
+        } else if t_max > 1e-4 {
+            hits[lane] = Some(t_max);
+        }
+    }
+
+    hits
+}
+
+/// Prueba paquete-caja envolvente para recorrer la BVH: indica qué rayos
+/// atraviesan la caja antes de su distancia máxima `t_max`
+pub fn hit_bounds(packet: &RayPacket, bounds: &Aabb, t_max: &[f32; LANES]) -> [bool; LANES] {
+    let mut result = [false; LANES];
+
+    for lane in 0..LANES {
+        let mut near = 0.0_f32;
+        let mut far = t_max[lane];
+
+        for (start, dir, min, max) in [
+            (packet.ox[lane], packet.dx[lane], bounds.min.x, bounds.max.x),
+            (packet.oy[lane], packet.dy[lane], bounds.min.y, bounds.max.y),
+            (packet.oz[lane], packet.dz[lane], bounds.min.z, bounds.max.z),
+        ] {
+            let inv = 1.0 / dir;
+            let t0 = (min - start) * inv;
+            let t1 = (max - start) * inv;
+            // Los NaN (rayo paralelo sobre el borde) no reducen el intervalo
+            let (lo, hi) = if t0 > t1 { (t1, t0) } else { (t0, t1) };
+            if lo > near {
+                near = lo;
+            }
+            if hi < far {
+                far = hi;
+            }
+        }
+
+        result[lane] = packet.active[lane] && near <= far;
+    }
+
+    result
+}
+
+/// Intersección paquete-triángulo (Möller-Trumbore, igual que la versión escalar)
+pub fn intersect_triangle(packet: &RayPacket, v0: &Point3, v1: &Point3, v2: &Point3) -> PacketHits {
+    let epsilon = 1e-6;
+    let e1 = *v1 - *v0;
+    let e2 = *v2 - *v0;
+    let mut hits = [None; LANES];
+
+    for lane in 0..LANES {
+        let (dx, dy, dz) = (packet.dx[lane], packet.dy[lane], packet.dz[lane]);
+
+        // h = d × e2
+        let hx = dy * e2.z - dz * e2.y;
+        let hy = dz * e2.x - dx * e2.z;
+        let hz = dx * e2.y - dy * e2.x;
+        let a = e1.x * hx + e1.y * hy + e1.z * hz;
+
+        let f = 1.0 / a;
+        let sx = packet.ox[lane] - v0.x;
+        let sy = packet.oy[lane] - v0.y;
+        let sz = packet.oz[lane] - v0.z;
+        let u = f * (sx * hx + sy * hy + sz * hz);
+
+        // q = s × e1
+        let qx = sy * e1.z - sz * e1.y;
+        let qy = sz * e1.x - sx * e1.z;
+        let qz = sx * e1.y - sy * e1.x;
+        let v = f * (dx * qx + dy * qy + dz * qz);
+        let t = f * (e2.x * qx + e2.y * qy + e2.z * qz);
+
+        let inside = a.abs() >= epsilon && (0.0..=1.0).contains(&u) && v >= 0.0 && u + v <= 1.0;
+        if packet.active[lane] && inside && t > epsilon {
+            hits[lane] = Some(t);
+        }
+    }
+
+    hits
+}
+
+/// Se queda con la intersección más cercana de cada rayo
+pub fn closest(a: PacketHits, b: PacketHits) -> PacketHits {
+    let mut result = a;
+    for lane in 0..LANES {
+        if let Some(t) = b[lane] {
+            if result[lane].is_none_or(|current| t < current) {
+                result[lane] = Some(t);
+            }
+        }
+    }
+    result
+}
@@ -1,10 +1,16 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
 use crate::vector::{Point3, Vec3};
 use crate::ray::Ray;
 use crate::material::Material;
+use crate::gpu::GpuPrimitive;
+use crate::render_cache::{hash_vec3, hash_material};
 
 /// Estructura que representa un plano infinito en el espacio 3D
 /// Ecuación del plano: (P - point) · normal = 0
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Plane {
     pub point: Point3,      // Punto en el plano
     pub normal: Vec3,       // Normal del plano (debe estar normalizada)
@@ -40,6 +46,33 @@ impl Plane {
         }
     }
 
+    /// Problemas que impiden renderizar el plano (ver `Scene::validate`)
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.point.is_finite() {
+            problems.push("plano con un punto no finito (NaN o infinito)".to_string());
+        }
+        if !self.normal.is_finite() || self.normal.length_squared() == 0.0 {
+            problems.push("plano con normal nula o no finita".to_string());
+        }
+        problems
+    }
+
+    /// Cuadrado de lado `2 * half_size` centrado en `point` que representa
+    /// al plano (que es infinito) al exportarlo
+    pub fn tessellate(&self, half_size: f32) -> (Vec<Point3>, Vec<[usize; 3]>) {
+        let helper = if self.normal.x.abs() > 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+        let tangent = helper.cross(&self.normal).normalize() * half_size;
+        let bitangent = self.normal.cross(&tangent);
+        let vertices = vec![
+            self.point - tangent - bitangent,
+            self.point + tangent - bitangent,
+            self.point + tangent + bitangent,
+            self.point - tangent + bitangent,
+        ];
+        (vertices, vec![[0, 1, 2], [0, 2, 3]])
+    }
+
     /// Retorna la normal en cualquier punto del plano
     pub fn normal_at(&self, _point: &Point3) -> Vec3 {
         self.normal
@@ -60,4 +93,16 @@ impl Plane {
 
         Some((u.abs(), v.abs(), 1))
     }
+
+    /// Primitiva para el backend de GPU (UV en baldosas sobre la textura 1)
+    pub fn gpu_primitive(&self) -> GpuPrimitive {
+        GpuPrimitive::plane(self.point, self.normal).with_texture(1)
+    }
+
+    /// Agrega el estado de el plano al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_vec3(state, &self.point);
+        hash_vec3(state, &self.normal);
+        hash_material(state, &self.material);
+    }
 }
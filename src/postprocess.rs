@@ -0,0 +1,145 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Color;
+use crate::framebuffer::Framebuffer;
+use crate::render_cache::hash_f32;
+use crate::sampling::hash_u64;
+use crate::tonemap::luminance;
+
+/// Efecto aplicado a la imagen HDR terminada, antes del tone mapping
+/// Los efectos se encadenan en el orden en que se listan, así que se puede
+/// armar el acabado final sin pasar por un compositor externo.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum PostEffect {
+    /// Resplandor alrededor de las zonas brillantes: la luz que supera
+    /// `threshold` (en luminancia) se desenfoca con un radio de `radius`
+    /// píxeles y se suma a la imagen multiplicada por `intensity`
+    Bloom { threshold: f32, intensity: f32, radius: u32 },
+    /// Oscurece los bordes: a partir de `radius` (0 = centro, 1 = esquina)
+    /// la imagen se oscurece de forma suave hasta perder `strength` en las esquinas
+    Vignette { strength: f32, radius: f32 },
+    /// Grano de película: ruido multiplicativo de amplitud `amount`
+    /// Es determinista: la misma semilla produce siempre el mismo grano
+    Grain {
+        amount: f32,
+        #[serde(default)]
+        seed: u64,
+    },
+}
+
+impl PostEffect {
+    pub fn apply(&self, framebuffer: &Framebuffer) -> Framebuffer {
+        match *self {
+            PostEffect::Bloom { threshold, intensity, radius } => bloom(framebuffer, threshold, intensity, radius),
+            PostEffect::Vignette { strength, radius } => vignette(framebuffer, strength, radius),
+            PostEffect::Grain { amount, seed } => grain(framebuffer, amount, seed),
+        }
+    }
+
+    /// Agrega el efecto al hash del render (cambia la imagen guardada)
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        match *self {
+            PostEffect::Bloom { threshold, intensity, radius } => {
+                state.write_u8(0);
+                hash_f32(state, threshold);
+                hash_f32(state, intensity);
+                state.write_u32(radius);
+            }
+            PostEffect::Vignette { strength, radius } => {
+                state.write_u8(1);
+                hash_f32(state, strength);
+                hash_f32(state, radius);
+            }
+            PostEffect::Grain { amount, seed } => {
+                state.write_u8(2);
+                hash_f32(state, amount);
+                state.write_u64(seed);
+            }
+        }
+    }
+}
+
+/// Aplica la cadena de efectos en orden
+pub fn apply_all(framebuffer: &Framebuffer, effects: &[PostEffect]) -> Framebuffer {
+    effects
+        .iter()
+        .fold(framebuffer.clone(), |image, effect| effect.apply(&image))
+}
+
+fn bloom(framebuffer: &Framebuffer, threshold: f32, intensity: f32, radius: u32) -> Framebuffer {
+    // Solo la parte de la luz que supera el umbral, conservando el tono
+    let bright = framebuffer.map(|color| {
+        let l = luminance(color);
+        if l > threshold {
+            color * ((l - threshold) / l)
+        } else {
+            Color::zero()
+        }
+    });
+
+    let glow = gaussian_blur(&bright, radius);
+    let mut result = framebuffer.clone();
+    for (pixel, glow) in result.pixels_mut().iter_mut().zip(glow.pixels()) {
+        *pixel += *glow * intensity;
+    }
+    result
+}
+
+/// Desenfoque gaussiano separable (horizontal y luego vertical)
+/// Los bordes se extienden repitiendo el último píxel
+fn gaussian_blur(framebuffer: &Framebuffer, radius: u32) -> Framebuffer {
+    if radius == 0 {
+        return framebuffer.clone();
+    }
+
+    let sigma = radius as f32 / 3.0;
+    let weights: Vec<f32> = (-(radius as i32)..=radius as i32)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+
+    let (width, height) = (framebuffer.width() as i32, framebuffer.height() as i32);
+    let blur = |input: &Framebuffer, dx: i32, dy: i32| {
+        Framebuffer::from_fn(input.width(), input.height(), |x, y| {
+            let mut sum = Color::zero();
+            for (k, weight) in weights.iter().enumerate() {
+                let offset = k as i32 - radius as i32;
+                let sx = (x as i32 + offset * dx).clamp(0, width - 1);
+                let sy = (y as i32 + offset * dy).clamp(0, height - 1);
+                sum += input.get(sx as u32, sy as u32) * *weight;
+            }
+            sum / total
+        })
+    };
+
+    blur(&blur(framebuffer, 1, 0), 0, 1)
+}
+
+fn vignette(framebuffer: &Framebuffer, strength: f32, radius: f32) -> Framebuffer {
+    let center_x = framebuffer.width() as f32 / 2.0;
+    let center_y = framebuffer.height() as f32 / 2.0;
+    let half_diagonal = (center_x * center_x + center_y * center_y).sqrt().max(1e-6);
+    let radius = radius.clamp(0.0, 0.999);
+
+    Framebuffer::from_fn(framebuffer.width(), framebuffer.height(), |x, y| {
+        let dx = x as f32 + 0.5 - center_x;
+        let dy = y as f32 + 0.5 - center_y;
+        let distance = (dx * dx + dy * dy).sqrt() / half_diagonal;
+
+        let t = ((distance - radius) / (1.0 - radius)).clamp(0.0, 1.0);
+        let falloff = t * t * (3.0 - 2.0 * t);
+        framebuffer.get(x, y) * (1.0 - strength.clamp(0.0, 1.0) * falloff)
+    })
+}
+
+fn grain(framebuffer: &Framebuffer, amount: f32, seed: u64) -> Framebuffer {
+    let width = framebuffer.width() as u64;
+    Framebuffer::from_fn(framebuffer.width(), framebuffer.height(), |x, y| {
+        let hash = hash_u64((y as u64 * width + x as u64) ^ seed.wrapping_mul(0x9e3779b97f4a7c15));
+        let noise = (hash >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0;
+        framebuffer.get(x, y) * (1.0 + noise * amount).max(0.0)
+    })
+}
@@ -0,0 +1,147 @@
+use std::hash::Hasher;
+
+use crate::vector::{Point3, Vec3};
+use crate::ray::Ray;
+use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::transform::Transform;
+use crate::scene::Intersectable;
+use crate::packet::{RayPacket, PacketHits};
+use crate::gpu::{GpuPrimitive, NO_TEXTURE};
+use crate::snapshot::{ObjectSnapshot, SharedObjects};
+use crate::render_cache::hash_material;
+
+/// Objeto de una escena combinada con `Scene::merge`: las texturas del
+/// prefab se agregan al final de las de la escena, así que los IDs de
+/// textura que devuelve el objeto se desplazan `offset` posiciones. Lo
+/// mismo pasa con los mapas horneados (ver `with_map_offset`).
+pub struct TextureOffset {
+    pub object: Box<dyn Intersectable>,
+    pub offset: usize,
+    /// Copia del material con los mapas horneados renumerados, cuando el
+    /// material del objeto no se puede modificar (instancia compartida)
+    pub material: Option<Material>,
+}
+
+impl TextureOffset {
+    pub fn new(object: Box<dyn Intersectable>, offset: usize) -> Self {
+        TextureOffset { object, offset, material: None }
+    }
+
+    /// Desplaza `map_offset` posiciones los IDs de los mapas horneados
+    /// (`occlusion_map` y `lightmap`) del material del objeto. Si el objeto
+    /// es una instancia compartida, renumera una copia del material.
+    pub fn with_map_offset(mut self, map_offset: usize) -> Self {
+        let material = self.object.get_material();
+        if map_offset == 0 || (material.occlusion_map.is_none() && material.lightmap.is_none()) {
+            return self;
+        }
+        let shift = |material: &mut Material| {
+            material.occlusion_map = material.occlusion_map.map(|map| map + map_offset);
+            material.lightmap = material.lightmap.map(|map| map + map_offset);
+        };
+        match self.object.material_mut() {
+            Some(material) => shift(material),
+            None => {
+                let mut material = *self.object.get_material();
+                shift(&mut material);
+                self.material = Some(material);
+            }
+        }
+        self
+    }
+
+    fn remap(&self, uv: Option<(f32, f32, usize)>) -> Option<(f32, f32, usize)> {
+        uv.map(|(u, v, texture)| (u, v, texture + self.offset))
+    }
+}
+
+impl Intersectable for TextureOffset {
+    fn intersect(&self, ray: &Ray) -> Option<f32> {
+        self.object.intersect(ray)
+    }
+
+    fn normal_at(&self, point: &Point3) -> Vec3 {
+        self.object.normal_at(point)
+    }
+
+    fn get_material(&self) -> &Material {
+        self.material.as_ref().unwrap_or_else(|| self.object.get_material())
+    }
+
+    fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)> {
+        self.remap(self.object.get_uv(point))
+    }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        self.object.hash_state(state);
+        state.write_u64(self.offset as u64);
+        if let Some(material) = &self.material {
+            hash_material(state, material);
+        }
+    }
+
+    fn normal_at_time(&self, point: &Point3, time: f32) -> Vec3 {
+        self.object.normal_at_time(point, time)
+    }
+
+    fn get_uv_at_time(&self, point: &Point3, time: f32) -> Option<(f32, f32, usize)> {
+        self.remap(self.object.get_uv_at_time(point, time))
+    }
+
+    fn surface_area(&self) -> f32 {
+        self.object.surface_area()
+    }
+
+    fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        self.object.sample_surface(u, v)
+    }
+
+    fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        self.object.intersect_packet(rays)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        self.object.bounds()
+    }
+
+    fn set_transform(&mut self, transform: Transform) -> bool {
+        self.object.set_transform(transform)
+    }
+
+    fn transform(&self) -> Option<Transform> {
+        self.object.transform()
+    }
+
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        match &mut self.material {
+            Some(material) => Some(material),
+            None => self.object.material_mut(),
+        }
+    }
+
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        let mut primitives = self.object.gpu_primitives()?;
+        for primitive in primitives.iter_mut().filter(|primitive| primitive.texture != NO_TEXTURE) {
+            primitive.texture += self.offset as u32;
+        }
+        Some(primitives)
+    }
+
+    fn validate(&self) -> Vec<String> {
+        self.object.validate()
+    }
+
+    fn texture_id(&self) -> Option<usize> {
+        self.object.texture_id().map(|texture| texture + self.offset)
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        self.object.tessellate()
+    }
+
+    fn snapshot(&self, shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        let object = Box::new(self.object.snapshot(shared)?);
+        Some(ObjectSnapshot::TextureOffset { object, offset: self.offset, material: self.material })
+    }
+}
@@ -0,0 +1,300 @@
+// Ventana de vista previa (feature `preview`): muestra los bloques del
+// render a medida que los hilos los terminan, o sirve de explorador
+// interactivo de la escena. La ventana tiene que atenderse desde el hilo que
+// la creó, así que el render se ejecuta en otro hilo y le envía los
+// resultados por un canal.
+
+use std::error::Error;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::{Duration, Instant};
+
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, ScaleMode, Window, WindowOptions};
+
+use crate::vector::{Vec3, Color};
+use crate::camera::Camera;
+use crate::scene::Scene;
+use crate::framebuffer::Framebuffer;
+use crate::renderer::{Renderer, Tile};
+use crate::progress::ProgressSink;
+use crate::cancel::CancelToken;
+use crate::framebuffer::color_to_rgb;
+use crate::tonemap::ToneMapping;
+use crate::gamma::{self, ColorEncoding};
+
+/// Refrescos por segundo de la ventana mientras se renderiza
+const REFRESH_RATE: usize = 30;
+
+/// Fracción de la resolución a la que se renderiza al explorar la escena
+const EXPLORE_SCALE: f32 = 0.25;
+/// Velocidad de las teclas de movimiento, en fracciones de la distancia al
+/// punto de mira por segundo (así sirve igual en escenas grandes y chicas)
+const MOVE_SPEED: f32 = 0.75;
+/// Grados de órbita por píxel de arrastre del ratón
+const ORBIT_SENSITIVITY: f32 = 0.3;
+/// Cuánto se acerca la cámara por cada paso de la rueda del ratón
+const ZOOM_STEP: f32 = 0.1;
+
+enum Update {
+    Tile(Tile, Vec<Color>),
+    Progress(usize, usize),
+}
+
+/// Receptor que reenvía los bloques terminados a la ventana
+struct WindowSink {
+    sender: Sender<Update>,
+}
+
+impl ProgressSink for WindowSink {
+    fn on_progress(&self, done: usize, total: usize) {
+        // Si la ventana ya no escucha, el render sigue igual
+        let _ = self.sender.send(Update::Progress(done, total));
+    }
+
+    fn on_tile(&self, tile: &Tile, pixels: &[Color]) {
+        let _ = self.sender.send(Update::Tile(*tile, pixels.to_vec()));
+    }
+}
+
+/// Ventana que muestra el render mientras se forma
+/// Cerrarla (o pulsar Escape) cancela el render en curso.
+pub struct PreviewWindow {
+    window: Window,
+    title: String,
+    buffer: Vec<u32>,
+    width: u32,
+    height: u32,
+    tone_mapping: ToneMapping,
+    encoding: ColorEncoding,
+    // Última posición del ratón mientras se arrastra
+    drag: Option<(f32, f32)>,
+}
+
+impl PreviewWindow {
+    /// Abre una ventana en negro de `width`×`height` píxeles de imagen
+    /// Falla si no hay entorno gráfico
+    pub fn open(title: &str, width: u32, height: u32) -> Result<Self, Box<dyn Error>> {
+        let options = WindowOptions {
+            resize: true,
+            scale_mode: ScaleMode::AspectRatioStretch,
+            ..WindowOptions::default()
+        };
+        let mut window = Window::new(title, width as usize, height as usize, options)?;
+        window.set_target_fps(REFRESH_RATE);
+
+        Ok(PreviewWindow {
+            window,
+            title: title.to_string(),
+            buffer: vec![0; (width * height) as usize],
+            width,
+            height,
+            tone_mapping: ToneMapping::default(),
+            encoding: ColorEncoding::Gamma(gamma::DEFAULT_GAMMA),
+            drag: None,
+        })
+    }
+
+    /// Conversión a 8 bits de los colores mostrados (la misma que la del
+    /// PNG final para que la vista previa se le parezca)
+    pub fn with_tone_mapping(mut self, tone_mapping: ToneMapping, encoding: ColorEncoding) -> Self {
+        self.tone_mapping = tone_mapping;
+        self.encoding = encoding;
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.window.is_open() && !self.window.is_key_down(Key::Escape)
+    }
+
+    /// Ejecuta `work` en otro hilo pasándole un receptor de avance: los
+    /// bloques que le lleguen se dibujan en la ventana. Si la ventana se
+    /// cierra, se cancela `cancel` y se espera a que `work` termine.
+    pub fn run<R: Send>(&mut self, cancel: &CancelToken, work: impl FnOnce(&dyn ProgressSink) -> R + Send) -> R {
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::scope(|scope| {
+            let worker = scope.spawn(move || work(&WindowSink { sender }));
+
+            while !worker.is_finished() {
+                if !self.is_open() {
+                    cancel.cancel();
+                }
+                self.drain(&receiver);
+                self.refresh();
+            }
+            self.drain(&receiver);
+            self.refresh();
+
+            match worker.join() {
+                Ok(result) => result,
+                Err(panic) => std::panic::resume_unwind(panic),
+            }
+        })
+    }
+
+    /// Explorador interactivo: la escena se renderiza a baja resolución y
+    /// cada movimiento de la cámara reinicia el render progresivo.
+    ///
+    /// - W/S: avanzar y retroceder; A/D: desplazarse a los lados; Q/E: bajar y subir
+    /// - Arrastrar con el botón izquierdo: orbitar alrededor del punto de mira
+    /// - Rueda del ratón: acercarse o alejarse del punto de mira
+    /// - Enter: imprimir la posición de la cámara (para copiarla a la escena)
+    /// - Escape o cerrar la ventana: terminar
+    ///
+    /// Al terminar, la escena conserva la cámara donde quedó y sus ajustes
+    /// de render originales.
+    pub fn explore(&mut self, scene: &mut Scene) {
+        let settings = scene.settings;
+        scene.set_render_settings(settings.with_scale(EXPLORE_SCALE));
+        let mut camera = scene.camera.clone();
+
+        while self.is_open() {
+            scene.camera = camera.clone();
+            let scene = &*scene;
+            let cancel = CancelToken::new();
+            let (sender, receiver) = mpsc::channel();
+
+            std::thread::scope(|scope| {
+                let worker = scope.spawn(|| {
+                    Renderer::render_progressive(scene, Duration::ZERO, &cancel, |image, _| {
+                        let _ = sender.send(image.clone());
+                    })
+                });
+
+                // Tras terminar el render se sigue atendiendo la ventana hasta
+                // que la cámara se mueva. El movimiento queda pendiente hasta
+                // que el render cancelado termine su pasada
+                let mut last_frame = Instant::now();
+                let mut restart = false;
+                loop {
+                    let elapsed = last_frame.elapsed().as_secs_f32();
+                    last_frame = Instant::now();
+                    if self.navigate(&mut camera, elapsed) || !self.is_open() {
+                        restart = true;
+                        cancel.cancel();
+                    }
+                    if restart && worker.is_finished() {
+                        break;
+                    }
+
+                    let image = receiver.try_iter().last();
+                    match image {
+                        Some(image) => self.show(&image),
+                        None => self.window.update(),
+                    }
+                }
+            });
+        }
+
+        scene.set_render_settings(settings);
+    }
+
+    /// Aplica a `camera` el teclado y el ratón de los últimos `elapsed`
+    /// segundos. Retorna true si la cámara se movió
+    fn navigate(&mut self, camera: &mut Camera, elapsed: f32) -> bool {
+        let offset = camera.position - camera.look_at;
+        let radius = offset.length().max(1e-3);
+        let forward = -offset / radius;
+        let right = forward.cross(&camera.up).normalize();
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        let mut moved = false;
+
+        let keys = [(Key::W, forward), (Key::S, -forward), (Key::D, right), (Key::A, -right), (Key::E, up), (Key::Q, -up)];
+        let direction = keys
+            .iter()
+            .filter(|(key, _)| self.window.is_key_down(*key))
+            .fold(Vec3::zero(), |total, (_, direction)| total + *direction);
+        if direction.length_squared() > 0.0 {
+            let step = direction * (radius * MOVE_SPEED * elapsed);
+            camera.set_view(camera.position + step, camera.look_at + step);
+            moved = true;
+        }
+
+        // Ángulos actuales, con la misma convención que `Camera::orbit`
+        let mut azimuth = offset.x.atan2(offset.z).to_degrees();
+        let mut elevation = (offset.y / radius).clamp(-1.0, 1.0).asin().to_degrees();
+        let mut distance = radius;
+        let mut orbited = false;
+
+        let mouse = self.window.get_mouse_pos(MouseMode::Discard);
+        match (mouse, self.window.get_mouse_down(MouseButton::Left)) {
+            (Some((x, y)), true) => {
+                if let Some((last_x, last_y)) = self.drag.filter(|&last| last != (x, y)) {
+                    azimuth -= (x - last_x) * ORBIT_SENSITIVITY;
+                    elevation += (y - last_y) * ORBIT_SENSITIVITY;
+                    orbited = true;
+                }
+                self.drag = Some((x, y));
+            }
+            _ => self.drag = None,
+        }
+        if let Some((_, scroll)) = self.window.get_scroll_wheel().filter(|&(_, scroll)| scroll != 0.0) {
+            distance *= (1.0 - scroll.signum() * ZOOM_STEP).max(0.01);
+            orbited = true;
+        }
+        if orbited {
+            *camera = camera.orbit(camera.look_at, distance, azimuth, elevation);
+            moved = true;
+        }
+
+        if self.window.is_key_pressed(Key::Enter, KeyRepeat::No) {
+            let (p, l) = (camera.position, camera.look_at);
+            println!(
+                "Cámara: \"position\": [{:.3}, {:.3}, {:.3}], \"look_at\": [{:.3}, {:.3}, {:.3}]",
+                p.x, p.y, p.z, l.x, l.y, l.z
+            );
+        }
+        moved
+    }
+
+    /// Muestra una imagen completa (de cualquier tamaño: la ventana la escala)
+    fn show(&mut self, image: &Framebuffer) {
+        let buffer: Vec<u32> = image
+            .pixels()
+            .iter()
+            .map(|color| {
+                let [r, g, b] = color_to_rgb(*color, self.tone_mapping, self.encoding).0;
+                u32::from_be_bytes([0, r, g, b])
+            })
+            .collect();
+        let _ = self.window.update_with_buffer(&buffer, image.width() as usize, image.height() as usize);
+    }
+
+    /// Mantiene la última imagen en pantalla hasta que se cierre la ventana
+    pub fn wait_until_closed(&mut self) {
+        self.window.set_title(&self.title);
+        while self.is_open() {
+            self.window.update();
+        }
+    }
+
+    fn drain(&mut self, receiver: &Receiver<Update>) {
+        for update in receiver.try_iter() {
+            match update {
+                Update::Tile(tile, pixels) => self.draw_tile(&tile, &pixels),
+                Update::Progress(done, total) => {
+                    let percentage = done as f32 / total.max(1) as f32 * 100.0;
+                    self.window.set_title(&format!("{} ({:.0}%)", self.title, percentage));
+                }
+            }
+        }
+    }
+
+    fn draw_tile(&mut self, tile: &Tile, pixels: &[Color]) {
+        if tile.x1 > self.width || tile.y1 > self.height {
+            return;
+        }
+        let rows = pixels.chunks_exact(tile.width().max(1) as usize);
+        for (y, row) in (tile.y0..tile.y1).zip(rows) {
+            let start = (y * self.width + tile.x0) as usize;
+            for (target, color) in self.buffer[start..].iter_mut().zip(row) {
+                let [r, g, b] = color_to_rgb(*color, self.tone_mapping, self.encoding).0;
+                *target = u32::from_be_bytes([0, r, g, b]);
+            }
+        }
+    }
+
+    fn refresh(&mut self) {
+        // Un fallo al dibujar solo afecta a la vista previa, no al render
+        let _ = self.window.update_with_buffer(&self.buffer, self.width as usize, self.height as usize);
+    }
+}
@@ -0,0 +1,150 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use serde_json::json;
+
+use crate::vector::Color;
+use crate::renderer::Tile;
+
+/// Receptor del avance de un render
+/// El renderer llama a `on_progress` desde sus hilos cada vez que termina
+/// un bloque, con los bloques terminados y el total; el receptor decide
+/// cada cuánto mostrarlo (consola, ventana, servidor...)
+pub trait ProgressSink: Sync {
+    fn on_progress(&self, done: usize, total: usize);
+
+    /// Píxeles de un bloque recién terminado, fila por fila y antes del
+    /// denoiser, para ir mostrando la imagen mientras se forma. Se llama
+    /// justo antes de `on_progress`; por defecto se ignoran
+    fn on_tile(&self, _tile: &Tile, _pixels: &[Color]) {}
+
+    /// Empieza el render `pass` (desde 1) de una serie de `passes`: los
+    /// cuadros de una animación o los ojos del estéreo. Sin series, cada
+    /// render es la pasada 1 de 1; por defecto se ignora
+    fn on_pass(&self, _pass: usize, _passes: usize) {}
+}
+
+/// Cualquier closure `|done, total| ...` sirve como receptor
+impl<F> ProgressSink for F
+where
+    F: Fn(usize, usize) + Sync,
+{
+    fn on_progress(&self, done: usize, total: usize) {
+        self(done, total)
+    }
+}
+
+/// Receptor que ignora el avance
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn on_progress(&self, _done: usize, _total: usize) {}
+}
+
+/// Imprime el porcentaje por consola cada vez que se supera un escalón
+/// (por defecto cada 10%)
+pub struct ConsoleProgress {
+    steps: usize,
+    last_step: AtomicUsize,
+}
+
+impl ConsoleProgress {
+    pub fn new() -> Self {
+        Self::with_steps(10)
+    }
+
+    /// Divide el avance en `steps` escalones
+    pub fn with_steps(steps: usize) -> Self {
+        ConsoleProgress {
+            steps: steps.max(1),
+            last_step: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl Default for ConsoleProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressSink for ConsoleProgress {
+    fn on_progress(&self, done: usize, total: usize) {
+        let step = done * self.steps / total.max(1);
+        // Solo el hilo que avanza el escalón imprime, aunque terminen varios a la vez
+        if step > self.last_step.fetch_max(step, Ordering::Relaxed) {
+            let percentage = (done as f32 / total.max(1) as f32) * 100.0;
+            println!("  Progreso: {:.1}%", percentage);
+        }
+    }
+}
+
+/// Emite el avance como JSON, un evento por línea, en la salida de errores
+/// (que así no se mezcla con los mensajes de la salida estándar), para que
+/// lo lean scripts y granjas de render:
+///
+/// ```text
+/// {"event":"pass","pass":1,"passes":2}
+/// {"elapsed_seconds":0.8,"eta_seconds":5.6,"event":"progress","pass":1,"passes":2,"percent":12.5,"tiles_done":12,"tiles_total":48}
+/// ```
+///
+/// `percent` y `eta_seconds` son de la serie completa; `eta_seconds` es
+/// `null` hasta que termina el primer bloque
+pub struct JsonProgress {
+    pass: AtomicUsize,
+    passes: AtomicUsize,
+    start: Mutex<Instant>,
+}
+
+impl JsonProgress {
+    pub fn new() -> Self {
+        JsonProgress {
+            pass: AtomicUsize::new(1),
+            passes: AtomicUsize::new(1),
+            start: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl Default for JsonProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Redondea a centésimas para que las líneas sean más cortas
+fn hundredths(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+impl ProgressSink for JsonProgress {
+    fn on_progress(&self, done: usize, total: usize) {
+        let pass = self.pass.load(Ordering::Relaxed);
+        let passes = self.passes.load(Ordering::Relaxed).max(1);
+        let fraction = ((pass - 1) as f64 + done as f64 / total.max(1) as f64) / passes as f64;
+        let elapsed = self.start.lock().unwrap().elapsed().as_secs_f64();
+        let eta = (fraction > 0.0).then(|| hundredths(elapsed * (1.0 - fraction) / fraction));
+        let event = json!({
+            "event": "progress",
+            "pass": pass,
+            "passes": passes,
+            "tiles_done": done,
+            "tiles_total": total,
+            "percent": hundredths(fraction * 100.0),
+            "elapsed_seconds": hundredths(elapsed),
+            "eta_seconds": eta,
+        });
+        eprintln!("{}", event);
+    }
+
+    fn on_pass(&self, pass: usize, passes: usize) {
+        // La primera pasada marca el inicio de una serie (con --watch hay varias)
+        if pass <= 1 {
+            *self.start.lock().unwrap() = Instant::now();
+        }
+        self.pass.store(pass.max(1), Ordering::Relaxed);
+        self.passes.store(passes.max(1), Ordering::Relaxed);
+        eprintln!("{}", json!({ "event": "pass", "pass": pass.max(1), "passes": passes.max(1) }));
+    }
+}
@@ -1,10 +1,18 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
 use crate::vector::{Point3, Vec3};
 use crate::ray::Ray;
 use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::packet::{self, RayPacket, PacketHits, LANES};
+use crate::gpu::GpuPrimitive;
+use crate::render_cache::{hash_f32, hash_vec3, hash_material};
 
 /// Estructura que representa una pirámide triangular (tetraedro)
 /// Formada por 4 caras triangulares
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Pyramid {
     pub apex: Point3,       // Vértice superior (punta)
     pub base_center: Point3, // Centro de la base
@@ -83,7 +91,7 @@ impl Pyramid {
         let s = ray.origin - v0;
         let u = f * s.dot(&h);
 
-        if u < 0.0 || u > 1.0 {
+        if !(0.0..=1.0).contains(&u) {
             return None;
         }
 
@@ -177,7 +185,7 @@ impl Pyramid {
 
             // Si la normal apunta hacia adentro, invertirla
             if normal.dot(&outward) < 0.0 {
-                normal = normal * -1.0;
+                normal *= -1.0;
             }
 
             // Calcular distancia del punto al plano de esta cara
@@ -198,4 +206,109 @@ impl Pyramid {
         // Implementación básica para texturas en Fase 3
         Some((0.0, 0.0, 0))
     }
+
+    /// Las 4 caras triangulares de la pirámide (3 laterales y la base)
+    fn get_faces(&self) -> [[Point3; 3]; 4] {
+        let base = self.get_base_vertices();
+        [
+            [self.apex, base[0], base[1]],
+            [self.apex, base[1], base[2]],
+            [self.apex, base[2], base[0]],
+            [base[0], base[1], base[2]],
+        ]
+    }
+
+    /// Intersección con un paquete de rayos (ver `packet`)
+    pub fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        self.get_faces()
+            .iter()
+            .fold([None; LANES], |hits, [v0, v1, v2]| {
+                packet::closest(hits, packet::intersect_triangle(rays, v0, v1, v2))
+            })
+    }
+
+    /// Problemas que impiden renderizar la pirámide (ver `Scene::validate`)
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.apex.is_finite() || !self.base_center.is_finite() {
+            problems.push("pirámide con vértices no finitos (NaN o infinito)".to_string());
+        }
+        if !(self.height > 0.0 && self.base_radius > 0.0) {
+            problems.push(format!(
+                "pirámide degenerada: altura {} y radio de la base {} (deben ser positivos)",
+                self.height, self.base_radius
+            ));
+        }
+        problems
+    }
+
+    /// Caja envolvente de los vértices de la pirámide
+    pub fn bounds(&self) -> Aabb {
+        let base = self.get_base_vertices();
+        Aabb::from_points(&[self.apex, base[0], base[1], base[2]])
+    }
+
+    /// Área de la superficie de la pirámide
+    pub fn surface_area(&self) -> f32 {
+        self.get_faces()
+            .iter()
+            .map(|[a, b, c]| (*b - *a).cross(&(*c - *a)).length() * 0.5)
+            .sum()
+    }
+
+    /// Punto de la superficie a partir de (u, v) en [0, 1)², distribuido uniformemente
+    pub fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        let faces = self.get_faces();
+        let areas: Vec<f32> = faces
+            .iter()
+            .map(|[a, b, c]| (*b - *a).cross(&(*c - *a)).length() * 0.5)
+            .collect();
+        let total: f32 = areas.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        // Elegir la cara proporcionalmente a su área
+        let mut start = 0.0;
+        for (i, [a, b, c]) in faces.iter().enumerate() {
+            let fraction = areas[i] / total;
+            if u < start + fraction || i == faces.len() - 1 {
+                let local = ((u - start) / fraction).clamp(0.0, 1.0);
+
+                // Coordenadas baricéntricas uniformes sobre el triángulo
+                let su = local.sqrt();
+                let b0 = 1.0 - su;
+                let b1 = v * su;
+                return Some(*a * b0 + *b * b1 + *c * (1.0 - b0 - b1));
+            }
+            start += fraction;
+        }
+
+        None
+    }
+
+    /// Las caras como triángulos para el backend de GPU (textura 0 en UV (0, 0),
+    /// igual que `get_uv`)
+    pub fn gpu_primitives(&self) -> Vec<GpuPrimitive> {
+        self.get_faces()
+            .iter()
+            .map(|[v0, v1, v2]| GpuPrimitive::triangle(*v0, *v1, *v2).with_texture(0))
+            .collect()
+    }
+
+    /// Triángulos de las caras de la pirámide
+    pub fn tessellate(&self) -> (Vec<Point3>, Vec<[usize; 3]>) {
+        let vertices: Vec<Point3> = self.get_faces().into_iter().flatten().collect();
+        let triangles = (0..vertices.len() / 3).map(|i| [3 * i, 3 * i + 1, 3 * i + 2]).collect();
+        (vertices, triangles)
+    }
+
+    /// Agrega el estado de la pirámide al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_vec3(state, &self.apex);
+        hash_vec3(state, &self.base_center);
+        hash_f32(state, self.height);
+        hash_f32(state, self.base_radius);
+        hash_material(state, &self.material);
+    }
 }
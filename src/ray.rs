@@ -7,12 +7,29 @@ use crate::vector::{Point3, Vec3};
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
+    pub time: f32, // Instante dentro del intervalo de obturación (motion blur)
+    /// Origen del rayo de cámara del que desciende; con él las mallas con
+    /// niveles de detalle eligen el mismo nivel en todo el camino
+    pub viewpoint: Point3,
 }
 
 impl Ray {
     /// Crea un nuevo rayo
     pub fn new(origin: Point3, direction: Vec3) -> Self {
-        Ray { origin, direction }
+        Ray { origin, direction, time: 0.0, viewpoint: origin }
+    }
+
+    /// Rayo secundario (sombra, reflejo, rebote) que sale de `origin`:
+    /// hereda el instante y el punto de vista de este
+    pub fn spawn(&self, origin: Point3, direction: Vec3) -> Ray {
+        Ray { origin, direction, time: self.time, viewpoint: self.viewpoint }
+    }
+
+    /// Mismo rayo en el instante `time`; los rayos secundarios heredan el
+    /// instante del rayo de cámara para ver la escena en el mismo momento
+    pub fn with_time(mut self, time: f32) -> Self {
+        self.time = time;
+        self
     }
 
     /// Retorna el punto en el rayo a una distancia t
@@ -0,0 +1,129 @@
+#[cfg(feature = "fs")]
+use std::collections::HashMap;
+use std::hash::Hasher;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use crate::vector::Vec3;
+use crate::material::Material;
+
+/// Hasher FNV-1a de 64 bits
+/// A diferencia de DefaultHasher, su resultado es estable entre ejecuciones
+/// y versiones del compilador, por lo que se puede guardar en disco.
+pub struct StableHasher {
+    state: u64,
+}
+
+impl StableHasher {
+    pub fn new() -> Self {
+        StableHasher {
+            state: 0xcbf29ce484222325,
+        }
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.state
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Agrega un f32 al hash usando su representación binaria
+pub fn hash_f32(state: &mut dyn Hasher, value: f32) {
+    state.write(&value.to_bits().to_le_bytes());
+}
+
+/// Agrega un vector al hash
+pub fn hash_vec3(state: &mut dyn Hasher, v: &Vec3) {
+    hash_f32(state, v.x);
+    hash_f32(state, v.y);
+    hash_f32(state, v.z);
+}
+
+/// Agrega todas las propiedades de un material al hash
+pub fn hash_material(state: &mut dyn Hasher, material: &Material) {
+    hash_vec3(state, &material.color);
+    hash_f32(state, material.albedo);
+    hash_f32(state, material.specular);
+    hash_f32(state, material.shininess);
+    hash_f32(state, material.reflectivity);
+    state.write(&[material.has_texture as u8]);
+    state.write(&(material.texture_id.map_or(u64::MAX, |id| id as u64)).to_le_bytes());
+    hash_vec3(state, &material.emission);
+    hash_f32(state, material.transparency);
+    hash_f32(state, material.roughness);
+    state.write(&(material.max_depth.map_or(u64::MAX, |depth| depth as u64)).to_le_bytes());
+    state.write(&(material.occlusion_map.map_or(u64::MAX, |id| id as u64)).to_le_bytes());
+    state.write(&(material.lightmap.map_or(u64::MAX, |id| id as u64)).to_le_bytes());
+}
+
+/// Registro de los hashes de escena con los que se generó cada imagen
+/// Se guarda como un archivo de texto con líneas `<hash> <ruta>`
+#[cfg(feature = "fs")]
+pub struct RenderCache {
+    manifest_path: String,
+    entries: HashMap<String, u64>,
+}
+
+#[cfg(feature = "fs")]
+impl RenderCache {
+    /// Carga el registro desde disco (vacío si el archivo no existe)
+    pub fn load(manifest_path: &str) -> Self {
+        let mut entries = HashMap::new();
+
+        if let Ok(contents) = std::fs::read_to_string(manifest_path) {
+            for line in contents.lines() {
+                if let Some((hash, output)) = line.split_once(' ') {
+                    if let Ok(hash) = u64::from_str_radix(hash, 16) {
+                        entries.insert(output.to_string(), hash);
+                    }
+                }
+            }
+        }
+
+        RenderCache {
+            manifest_path: manifest_path.to_string(),
+            entries,
+        }
+    }
+
+    /// Indica si la imagen ya existe y fue generada con el mismo hash de escena
+    pub fn is_up_to_date(&self, output: &str, hash: u64) -> bool {
+        self.entries.get(output) == Some(&hash) && Path::new(output).exists()
+    }
+
+    /// Registra el hash con el que se generó una imagen
+    pub fn record(&mut self, output: &str, hash: u64) {
+        self.entries.insert(output.to_string(), hash);
+    }
+
+    /// Guarda el registro en disco
+    pub fn save(&self) -> std::io::Result<()> {
+        let mut outputs: Vec<&String> = self.entries.keys().collect();
+        outputs.sort();
+
+        let mut contents = String::new();
+        for output in outputs {
+            contents.push_str(&format!("{:016x} {}\n", self.entries[output], output));
+        }
+
+        if let Some(parent) = Path::new(&self.manifest_path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&self.manifest_path, contents)
+    }
+}
@@ -1,98 +1,1130 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
 use crate::vector::{Vec3, Color, Point3};
 use crate::ray::Ray;
-use crate::scene::Scene;
+use crate::scene::{Scene, Intersectable};
+use crate::sampling::{stratified_square, cosine_hemisphere};
+use crate::sampler::Sampler;
+use crate::integrator::{Integrator, PathTracingIntegrator};
+use crate::packet::{RayPacket, LANES};
+use crate::brdf::{roughness_to_alpha, sample_ggx_half_vector, ggx_sample_weight};
+use crate::stats::{self, Counter};
+use crate::progress::{ProgressSink, NoProgress};
+use crate::cancel::CancelToken;
+use crate::framebuffer::Framebuffer;
+
+const TILE_SIZE: u32 = 32;
+const ENVIRONMENT_SAMPLES: u32 = 4;
+const EMISSIVE_SAMPLES: u32 = 4;
+const MAX_SHADOW_LAYERS: u32 = 16;
+
+/// Información de una intersección rayo-objeto
+#[derive(Debug, Clone, Copy)]
+pub struct HitRecord {
+    pub t: f32,
+    pub point: Point3,
+    pub normal: Vec3,
+    pub uv: Option<(f32, f32, usize)>,
+    pub object_id: usize,
+    pub time: f32,
+    /// Punto de vista del rayo que llegó al punto (ver `Ray::viewpoint`)
+    pub viewpoint: Point3,
+}
+
+impl HitRecord {
+    /// Rayo secundario que sale del punto de impacto (ver `Ray::spawn`)
+    pub fn spawn_ray(&self, origin: Point3, direction: Vec3) -> Ray {
+        Ray { origin, direction, time: self.time, viewpoint: self.viewpoint }
+    }
+}
+
+/// Datos geométricos del primer impacto de cada píxel (G-buffer)
+pub struct GBuffer {
+    pub depth: Framebuffer,   // Distancia desde la cámara (igual en los tres canales)
+    pub normals: Framebuffer, // Normal en espacio de mundo
+    pub albedo: Framebuffer,  // Color de la superficie (textura o material)
+}
+
+/// Bloque rectangular de la imagen [x0, x1) × [y0, y1)
+#[derive(Debug, Clone, Copy)]
+pub struct Tile {
+    pub x0: u32,
+    pub y0: u32,
+    pub x1: u32,
+    pub y1: u32,
+}
+
+impl Tile {
+    /// Bloque que cubre la imagen completa
+    pub fn full(width: u32, height: u32) -> Self {
+        Tile { x0: 0, y0: 0, x1: width, y1: height }
+    }
+
+    /// Divide una imagen en bloques de `size`×`size` (los del borde pueden ser menores)
+    pub fn split(width: u32, height: u32, size: u32) -> Vec<Tile> {
+        Tile::full(width, height).subdivide(size)
+    }
+
+    /// Divide el bloque en bloques de `size`×`size` (los del borde pueden ser menores)
+    pub fn subdivide(&self, size: u32) -> Vec<Tile> {
+        let size = size.max(1);
+        let mut tiles = Vec::new();
 
-const EPSILON: f32 = 1e-4;
-const MAX_DEPTH: u32 = 5;
-const AMBIENT_STRENGTH: f32 = 0.2;
+        for y0 in (self.y0..self.y1).step_by(size as usize) {
+            for x0 in (self.x0..self.x1).step_by(size as usize) {
+                tiles.push(Tile {
+                    x0,
+                    y0,
+                    x1: (x0 + size).min(self.x1),
+                    y1: (y0 + size).min(self.y1),
+                });
+            }
+        }
+
+        tiles
+    }
+
+    /// Recorta el bloque para que quede dentro de una imagen de `width`×`height`
+    pub fn clamp_to(&self, width: u32, height: u32) -> Tile {
+        let x1 = self.x1.min(width);
+        let y1 = self.y1.min(height);
+        Tile {
+            x0: self.x0.min(x1),
+            y0: self.y0.min(y1),
+            x1,
+            y1,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.x1 - self.x0
+    }
+
+    pub fn height(&self) -> u32 {
+        self.y1 - self.y0
+    }
+
+    /// Número de píxeles del bloque
+    pub fn pixel_count(&self) -> usize {
+        ((self.x1 - self.x0) * (self.y1 - self.y0)) as usize
+    }
+
+    /// Copia los píxeles del bloque (ordenados por filas) al framebuffer
+    pub fn write_into(&self, framebuffer: &mut Framebuffer, pixels: &[Color]) {
+        self.write_into_region(framebuffer, &Tile::full(self.x1, self.y1), pixels);
+    }
+
+    /// Igual que `write_into`, pero el framebuffer solo cubre `region`
+    /// (sus coordenadas se cuentan desde la esquina de la región)
+    pub fn write_into_region(&self, framebuffer: &mut Framebuffer, region: &Tile, pixels: &[Color]) {
+        let tile_width = self.width() as usize;
+        let x0 = (self.x0 - region.x0) as usize;
+        for (row, y) in (self.y0..self.y1).enumerate() {
+            let start = row * tile_width;
+            framebuffer.row_mut(y - region.y0)[x0..x0 + tile_width]
+                .copy_from_slice(&pixels[start..start + tile_width]);
+        }
+    }
+}
 
 pub struct Renderer;
 
 impl Renderer {
-    pub fn find_closest_intersection<'a>(
+    /// Renderiza la escena completa usando todos los núcleos disponibles
+    /// La imagen se divide en bloques de TILE_SIZE×TILE_SIZE que los hilos
+    /// toman de una cola compartida: cuando un hilo termina un bloque toma el
+    /// siguiente, así las zonas costosas (reflejos) no dejan hilos ociosos
+    pub fn render(scene: &Scene) -> Framebuffer {
+        Self::render_with_progress(scene, NoProgress)
+    }
+
+    /// Igual que `render`, informando del avance a `progress` cada vez que
+    /// se termina un bloque, p. ej. `render_with_progress(scene, |done, total| ...)`
+    pub fn render_with_progress(scene: &Scene, progress: impl ProgressSink) -> Framebuffer {
+        Self::render_cancellable(scene, &progress, &CancelToken::new())
+    }
+
+    /// Render que se puede detener con `cancel` desde otro hilo
+    /// Los bloques que no se llegaron a empezar quedan en negro
+    pub fn render_cancellable(scene: &Scene, progress: &dyn ProgressSink, cancel: &CancelToken) -> Framebuffer {
+        let integrator = scene.integrator.build(scene, scene.settings.max_depth);
+        let region = Tile::full(scene.camera.width, scene.camera.height);
+        let framebuffer = Self::render_region_with(scene, integrator.as_ref(), &region, progress, cancel);
+        Self::filter_noise(scene, framebuffer)
+    }
+
+    /// Limpia la imagen terminada: primero se rechazan los píxeles atípicos
+    /// y luego se aplica el denoiser de la escena (si hay uno)
+    fn filter_noise(scene: &Scene, framebuffer: Framebuffer) -> Framebuffer {
+        let framebuffer = scene.firefly.reject_outliers(framebuffer);
+        match &scene.denoiser {
+            Some(denoiser) => {
+                let guides = Self::render_guides(scene);
+                denoiser.apply(&framebuffer, &guides.normals, &guides.albedo)
+            }
+            None => framebuffer,
+        }
+    }
+
+    /// Buffers auxiliares del primer impacto en el centro de cada píxel,
+    /// usados por el denoiser y como pasadas AOV. Donde no hay impacto la
+    /// distancia es infinita, la normal es cero y el albedo es el fondo.
+    /// Los rayos de píxeles vecinos se trazan juntos en paquetes.
+    pub fn render_guides(scene: &Scene) -> GBuffer {
+        let width = scene.camera.width;
+        let height = scene.camera.height;
+
+        let rows: Vec<Vec<(f32, Vec3, Color)>> = (0..height)
+            .into_par_iter()
+            .map(|y| {
+                let rays: Vec<Ray> = (0..width)
+                    .map(|x| {
+                        let u = (x as f32 + 0.5) / width as f32;
+                        let v = 1.0 - ((y as f32 + 0.5) / height as f32);
+                        scene.camera.get_ray(u, v)
+                    })
+                    .collect();
+                stats::add(Counter::PrimaryRays, rays.len() as u64);
+
+                let row = rays
+                    .chunks(LANES)
+                    .flat_map(|chunk| {
+                        let hits = scene.find_closest_hits(&RayPacket::new(chunk));
+                        chunk.iter().zip(hits).map(|(ray, hit)| match hit {
+                            Some((t, id)) => {
+                                let (hit, object) = Self::hit_record(ray, scene, t, id);
+                                (hit.t, hit.normal, Self::surface_color(&hit, object.get_material(), scene))
+                            }
+                            None => (f32::INFINITY, Vec3::zero(), scene.background(&ray.direction)),
+                        })
+                    })
+                    .collect();
+                stats::flush();
+                row
+            })
+            .collect();
+
+        let buffer = |channel: &dyn Fn(&(f32, Vec3, Color)) -> Color| {
+            Framebuffer::from_pixels(width, height, rows.iter().flatten().map(channel).collect())
+        };
+        GBuffer {
+            depth: buffer(&|(t, _, _)| Color::new(*t, *t, *t)),
+            normals: buffer(&|(_, normal, _)| *normal),
+            albedo: buffer(&|(_, _, albedo)| *albedo),
+        }
+    }
+
+    /// Renderiza la escena con un integrador concreto, ignorando el de la escena
+    pub fn render_with(scene: &Scene, integrator: &dyn Integrator) -> Framebuffer {
+        let region = Tile::full(scene.camera.width, scene.camera.height);
+        Self::render_region_with(scene, integrator, &region, &NoProgress, &CancelToken::new())
+    }
+
+    /// Renderiza solo un rectángulo de la imagen (para iterar sobre una zona
+    /// sin repetir el cuadro completo). El resultado tiene el tamaño de la
+    /// región; cada píxel es idéntico al del render completo, salvo que no
+    /// se aplica el denoiser, que necesita la imagen entera
+    pub fn render_region(
+        scene: &Scene,
+        region: &Tile,
+        progress: &dyn ProgressSink,
+        cancel: &CancelToken,
+    ) -> Framebuffer {
+        let integrator = scene.integrator.build(scene, scene.settings.max_depth);
+        let region = region.clamp_to(scene.camera.width, scene.camera.height);
+        Self::render_region_with(scene, integrator.as_ref(), &region, progress, cancel)
+    }
+
+    fn render_region_with(
+        scene: &Scene,
+        integrator: &dyn Integrator,
+        region: &Tile,
+        progress: &dyn ProgressSink,
+        cancel: &CancelToken,
+    ) -> Framebuffer {
+        let tiles = region.subdivide(TILE_SIZE);
+
+        let next_tile = AtomicUsize::new(0);
+        let tiles_done = AtomicUsize::new(0);
+
+        let rendered: Vec<(Tile, Vec<Color>)> = (0..rayon::current_num_threads())
+            .into_par_iter()
+            .flat_map_iter(|_| {
+                let mut finished = Vec::new();
+
+                while !cancel.is_cancelled() {
+                    let index = next_tile.fetch_add(1, Ordering::Relaxed);
+                    let tile = match tiles.get(index) {
+                        Some(tile) => *tile,
+                        None => break,
+                    };
+
+                    let pixels = Self::render_tile(scene, integrator, &tile);
+                    progress.on_tile(&tile, &pixels);
+                    finished.push((tile, pixels));
+                    stats::flush();
+
+                    let done = tiles_done.fetch_add(1, Ordering::Relaxed) + 1;
+                    progress.on_progress(done, tiles.len());
+                }
+
+                finished
+            })
+            .collect();
+
+        let mut framebuffer = Framebuffer::new(region.width(), region.height());
+        for (tile, pixels) in rendered {
+            tile.write_into_region(&mut framebuffer, region, &pixels);
+        }
+        framebuffer
+    }
+
+    /// Renderiza los píxeles de un bloque, fila por fila, en grupos de
+    /// `LANES` píxeles vecinos
+    fn render_tile(scene: &Scene, integrator: &dyn Integrator, tile: &Tile) -> Vec<Color> {
+        let mut pixels = Vec::with_capacity(tile.pixel_count());
+        for y in tile.y0..tile.y1 {
+            for x0 in (tile.x0..tile.x1).step_by(LANES) {
+                let x1 = (x0 + LANES as u32).min(tile.x1);
+                pixels.extend(Self::render_span(scene, integrator, x0, x1, y));
+            }
+        }
+        pixels
+    }
+
+    /// Calcula el color de un píxel de la imagen
+    /// Con varias muestras por píxel cada rayo se desplaza dentro del soporte
+    /// del filtro de la escena y se promedian los resultados ponderados por
+    /// el filtro (anti-aliasing)
+    pub fn render_pixel(scene: &Scene, integrator: &dyn Integrator, x: u32, y: u32) -> Color {
+        Self::render_span(scene, integrator, x, x + 1, y)[0]
+    }
+
+    /// Calcula los píxeles `x0..x1` (hasta `LANES`) de la fila `y` como
+    /// `render_pixel`; cada muestra de todos ellos se traza en un paquete
+    fn render_span(scene: &Scene, integrator: &dyn Integrator, x0: u32, x1: u32, y: u32) -> Vec<Color> {
+        let samples = scene.settings.samples_per_pixel.max(1);
+        let mut samplers: Vec<Box<dyn Sampler>> =
+            (x0..x1).map(|x| scene.sampler.create(x, y, samples, scene.seed)).collect();
+
+        let mut colors = vec![Color::zero(); samplers.len()];
+        let mut total_weights = vec![0.0f32; samplers.len()];
+        for index in 0..samples {
+            let lanes = Self::render_samples(scene, integrator, x0, y, index, &mut samplers);
+            for (lane, (sample, weight)) in lanes.into_iter().enumerate() {
+                colors[lane] += sample * weight;
+                total_weights[lane] += weight;
+            }
+        }
+
+        colors
+            .iter()
+            .zip(&total_weights)
+            .map(|(color, total_weight)| *color / total_weight.max(f32::MIN_POSITIVE))
+            .collect()
+    }
+
+    /// Calcula la muestra número `index` de los píxeles vecinos que empiezan
+    /// en `x0` (uno por sampler de `samplers`, hasta `LANES`) y su peso según
+    /// el filtro. Sus rayos de cámara son coherentes: el primer impacto de
+    /// todos se busca junto en un paquete
+    fn render_samples(
+        scene: &Scene,
+        integrator: &dyn Integrator,
+        x0: u32,
+        y: u32,
+        index: u32,
+        samplers: &mut [Box<dyn Sampler>],
+    ) -> Vec<(Color, f32)> {
+        let primary: Vec<(Ray, f32)> = samplers
+            .iter_mut()
+            .enumerate()
+            .map(|(lane, sampler)| Self::primary_ray(scene, x0 + lane as u32, y, index, sampler.as_mut()))
+            .collect();
+        let rays: Vec<Ray> = primary.iter().map(|(ray, _)| *ray).collect();
+        let hits = scene.find_closest_hits(&RayPacket::new(&rays));
+
+        primary
+            .iter()
+            .zip(hits)
+            .zip(samplers.iter_mut())
+            .map(|(((ray, weight), hit), sampler)| (Self::sample_color(scene, integrator, ray, hit, sampler.as_mut()), *weight))
+            .collect()
+    }
+
+    /// Rayo de cámara de la muestra número `index` de un píxel y su peso
+    /// según el filtro. Con una sola muestra por píxel el rayo pasa por la
+    /// esquina del píxel (sin jitter); con varias se desplaza según el sampler
+    fn primary_ray(scene: &Scene, x: u32, y: u32, index: u32, sampler: &mut dyn Sampler) -> (Ray, f32) {
+        let width = scene.camera.width as f32;
+        let height = scene.camera.height as f32;
+
+        sampler.start_sample(index);
+        let (offset_x, offset_y, weight) = if scene.settings.samples_per_pixel > 1 {
+            let (jitter_x, jitter_y) = sampler.next_2d();
+            let (dx, dy, weight) = scene.pixel_filter.sample(jitter_x, jitter_y);
+            (0.5 + dx, 0.5 + dy, weight)
+        } else {
+            (0.0, 0.0, 1.0)
+        };
+        let u = (x as f32 + offset_x) / width;
+        let v = 1.0 - ((y as f32 + offset_y) / height);
+
+        stats::count(Counter::PrimaryRays);
+        (Self::camera_ray(scene, u, v, sampler), weight)
+    }
+
+    /// Color de una muestra a partir de su rayo de cámara y el impacto más
+    /// cercano de este
+    fn sample_color(
+        scene: &Scene,
+        integrator: &dyn Integrator,
         ray: &Ray,
-        scene: &'a Scene,
-    ) -> Option<(f32, Point3, Vec3, &'a std::boxed::Box<dyn crate::scene::Intersectable>)> {
-        if let Some((t, object)) = scene.find_closest_intersection(ray) {
-            let hit_point = ray.at(t);
-            let normal = object.normal_at(&hit_point);
-            Some((t, hit_point, normal, object))
+        hit: Option<(f32, usize)>,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let color = scene.firefly.clamp_sample(integrator.li_with_hit(ray, hit, scene, sampler));
+
+        // Fundido hacia el fondo cerca del plano lejano
+        let color = match scene.far_clip {
+            Some(far_clip) if far_clip.fade_start.is_some() => match hit {
+                Some((t, _)) => {
+                    let visibility = far_clip.visibility(t);
+                    color * visibility + scene.background(&ray.direction) * (1.0 - visibility)
+                }
+                None => color,
+            },
+            _ => color,
+        };
+
+        color * scene.camera.exposure_scale()
+    }
+
+    /// Renderizado progresivo: cada pasada agrega una muestra a todos los
+    /// píxeles, de modo que la imagen completa se va refinando. Cada vez que
+    /// pasa `update_interval` (y al terminar) se llama a `on_update` con el
+    /// promedio actual y el número de muestras acumuladas, para guardar o
+    /// mostrar una vista previa. El resultado final es el mismo que `render`.
+    /// Si se cancela con `cancel`, abandona la pasada en curso y retorna el
+    /// promedio de las pasadas completas (una imagen entera con menos muestras,
+    /// o negra si no llegó a terminar ninguna).
+    pub fn render_progressive<F>(
+        scene: &Scene,
+        update_interval: Duration,
+        cancel: &CancelToken,
+        mut on_update: F,
+    ) -> Framebuffer
+    where
+        F: FnMut(&Framebuffer, u32),
+    {
+        let integrator = scene.integrator.build(scene, scene.settings.max_depth);
+        let integrator = integrator.as_ref();
+        let width = scene.camera.width;
+        let height = scene.camera.height;
+        let samples = scene.settings.samples_per_pixel.max(1);
+
+        // Suma de muestras ponderadas y suma de pesos de cada píxel. Cada
+        // pasada se renderiza aparte y solo se acumula si termina, así una
+        // cancelación a mitad de pasada no deja filas con una muestra más
+        let mut accumulated = Framebuffer::new(width, height);
+        let mut weights = vec![0.0f32; (width * height) as usize];
+        let mut pass_colors = Framebuffer::new(width, height);
+        let mut pass_weights = vec![0.0f32; (width * height) as usize];
+        let mut last_update = Instant::now();
+
+        for pass in 0..samples {
+            pass_colors
+                .par_rows_mut()
+                .zip(pass_weights.par_chunks_mut(width.max(1) as usize))
+                .enumerate()
+                .for_each(|(y, (row, row_weights))| {
+                    if cancel.is_cancelled() {
+                        return;
+                    }
+                    let y = y as u32;
+                    for (chunk, (pixels, totals)) in row.chunks_mut(LANES).zip(row_weights.chunks_mut(LANES)).enumerate() {
+                        // Los samplers se recrean en cada pasada: sus secuencias dependen solo
+                        // del píxel y del número de muestra
+                        let x0 = (chunk * LANES) as u32;
+                        let mut samplers: Vec<Box<dyn Sampler>> = (x0..x0 + pixels.len() as u32)
+                            .map(|x| scene.sampler.create(x, y, samples, scene.seed))
+                            .collect();
+                        let lanes = Self::render_samples(scene, integrator, x0, y, pass, &mut samplers);
+                        for ((pixel, total), (color, weight)) in pixels.iter_mut().zip(totals).zip(lanes) {
+                            *pixel = color * weight;
+                            *total = weight;
+                        }
+                    }
+                    stats::flush();
+                });
+
+            if cancel.is_cancelled() {
+                return Self::filter_noise(scene, Self::average(&accumulated, &weights));
+            }
+
+            for (pixel, color) in accumulated.pixels_mut().iter_mut().zip(pass_colors.pixels()) {
+                *pixel += *color;
+            }
+            for (total, weight) in weights.iter_mut().zip(&pass_weights) {
+                *total += weight;
+            }
+
+            let done = pass + 1;
+            if done == samples || last_update.elapsed() >= update_interval {
+                on_update(&Self::average(&accumulated, &weights), done);
+                last_update = Instant::now();
+            }
+        }
+
+        Self::filter_noise(scene, Self::average(&accumulated, &weights))
+    }
+
+    /// Divide la suma de muestras ponderadas de cada píxel por la suma de sus pesos
+    fn average(accumulated: &Framebuffer, weights: &[f32]) -> Framebuffer {
+        let pixels = accumulated
+            .pixels()
+            .iter()
+            .zip(weights)
+            .map(|(color, weight)| *color / weight.max(f32::MIN_POSITIVE))
+            .collect();
+        Framebuffer::from_pixels(accumulated.width(), accumulated.height(), pixels)
+    }
+
+    /// Rayo de cámara para las coordenadas (u, v), muestreando la lente si
+    /// la cámara tiene profundidad de campo y el instante si el obturador
+    /// está abierto un intervalo (motion blur)
+    fn camera_ray(scene: &Scene, u: f32, v: f32, sampler: &mut dyn Sampler) -> Ray {
+        let camera = &scene.camera;
+        let ray = if camera.aperture > 0.0 {
+            let (lens_u, lens_v) = sampler.next_2d();
+            camera.get_ray_lens(u, v, lens_u, lens_v)
+        } else {
+            camera.get_ray(u, v)
+        };
+
+        if camera.has_motion_blur() {
+            let time = camera.shutter_time(sampler.next_1d());
+            camera.at_time(ray, time)
         } else {
-            None
+            ray
         }
     }
 
+    pub fn find_closest_intersection<'a>(
+        ray: &Ray,
+        scene: &'a Scene,
+    ) -> Option<(HitRecord, &'a dyn Intersectable)> {
+        scene.find_closest_hit(ray).map(|(t, id)| Self::hit_record(ray, scene, t, id))
+    }
+
+    /// Completa la información de una intersección ya encontrada
+    pub fn hit_record<'a>(ray: &Ray, scene: &'a Scene, t: f32, id: usize) -> (HitRecord, &'a dyn Intersectable) {
+        let object = scene.objects[id].as_ref();
+        let point = ray.at(t);
+        let hit = HitRecord {
+            t,
+            point,
+            normal: object.normal_at_time(&point, ray.time),
+            uv: object.get_uv_at_time(&point, ray.time),
+            object_id: scene.object_id(id),
+            time: ray.time,
+            viewpoint: ray.viewpoint,
+        };
+        (hit, object)
+    }
+
     pub fn shade(
-        hit_point: &Point3,
-        normal: &Vec3,
+        hit: &HitRecord,
         material: &crate::material::Material,
         scene: &Scene,
         view_dir: &Vec3,
-        uv_data: Option<(f32, f32, usize)>,
+        sampler: &mut dyn Sampler,
     ) -> Color {
-        let base_color = if let Some((u, v, tex_id)) = uv_data {
-            if tex_id < scene.textures.len() {
-                scene.textures[tex_id].sample(u, v)
-            } else {
-                material.color
-            }
+        let normal = &hit.normal;
+
+        let base_color = Self::surface_color(hit, material, scene);
+
+        // La iluminación horneada reemplaza a las luces, sombras y luz ambiental
+        if let Some(lighting) = Self::baked_lighting(hit, material, scene) {
+            return base_color * lighting * material.albedo + material.emission;
+        }
+
+        let ambient = if scene.environment.is_some() {
+            // Con un mapa de entorno la luz ambiental proviene de la imagen
+            let irradiance = Self::environment_irradiance(hit, scene, sampler);
+            base_color * irradiance * material.albedo
         } else {
-            material.color
+            // La oclusión ambiental (si está activa) oscurece esquinas y grietas
+            let ambient_visibility = match &scene.ambient_occlusion {
+                Some(ao) => ao.visibility(scene, hit, normal, scene.settings.bias, sampler),
+                None => 1.0,
+            };
+            base_color * scene.ambient_light.radiance() * ambient_visibility * Self::baked_occlusion(hit, material, scene)
         };
+        // Las superficies emisivas brillan con su propia luz
+        let mut color = ambient + material.emission;
+        color += base_color * Self::emissive_lighting(hit, scene, sampler) * material.albedo;
 
-        let ambient = base_color * AMBIENT_STRENGTH;
-        let mut color = ambient;
+        color += Self::direct_lighting(hit, material, base_color, scene, Some(view_dir), sampler);
 
-        for light in &scene.lights {
-            let light_dir = (light.position - *hit_point).normalize();
+        // Sin recortar: el framebuffer es HDR y el tone mapping se aplica al guardar
+        color
+    }
 
-            let shadow_ray = Ray::new(*hit_point + *normal * EPSILON, light_dir);
-            let distance_to_light = (light.position - *hit_point).length();
+    /// Luz que llega directamente de las fuentes al punto (sin rebotes):
+    /// emisión propia, luces de la escena y objetos emisivos
+    pub fn direct_radiance(
+        hit: &HitRecord,
+        material: &crate::material::Material,
+        scene: &Scene,
+        view_dir: &Vec3,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let base_color = Self::surface_color(hit, material, scene);
+        material.emission
+            + base_color * Self::emissive_lighting(hit, scene, sampler) * material.albedo
+            + Self::direct_lighting(hit, material, base_color, scene, Some(view_dir), sampler)
+    }
 
-            let is_in_shadow = if let Some((t, _, _, _)) = Self::find_closest_intersection(&shadow_ray, scene) {
-                t < distance_to_light
-            } else {
-                false
+    /// Luz directa de las luces de la escena en el punto de impacto
+    /// Incluye el término difuso y, si se indica la dirección de vista, el
+    /// brillo especular de Phong. Los rayos de sombra respetan los enlaces
+    /// de luz y la transparencia de los objetos que se interponen.
+    fn direct_lighting(
+        hit: &HitRecord,
+        material: &crate::material::Material,
+        base_color: Color,
+        scene: &Scene,
+        view_dir: Option<&Vec3>,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let hit_point = &hit.point;
+        let normal = &hit.normal;
+        let mut color = Color::zero();
+
+        // Con muchas luces solo se evalúan algunas, ponderadas por su probabilidad
+        let selected_lights = match &scene.light_sampler {
+            Some(light_sampler) => light_sampler.select(sampler),
+            None => (0..scene.lights.len()).map(|id| (id, 1.0)).collect(),
+        };
+
+        for (light_id, light_weight) in selected_lights {
+            let light = &scene.lights[light_id];
+
+            // Los enlaces de luz pueden excluir este objeto
+            if !scene.light_affects(light_id, hit.object_id) {
+                continue;
+            }
+
+            // Las luces de área y esféricas aportan varias muestras; se promedian
+            let samples = light.sample_points(hit_point, sampler);
+            let weight = light_weight / samples.len() as f32;
+
+            for light_point in samples {
+                let light_dir = (light_point - *hit_point).normalize();
+
+                // Los focos solo iluminan dentro de su cono
+                let attenuation = light.cone_attenuation(&light_dir) * weight;
+                if attenuation <= 0.0 {
+                    continue;
+                }
+
+                let distance_to_light = (light_point - *hit_point).length();
+
+                // Los objetos transparentes tiñen la luz en lugar de bloquearla
+                let transmittance = if light.casts_shadows {
+                    let shadow_ray = hit.spawn_ray(*hit_point + *normal * scene.settings.bias, light_dir);
+                    Self::shadow_transmittance(&shadow_ray, distance_to_light, scene)
+                } else {
+                    Color::new(1.0, 1.0, 1.0)
+                };
+
+                if transmittance.x <= 0.0 && transmittance.y <= 0.0 && transmittance.z <= 0.0 {
+                    continue;
+                }
+
+                let diffuse_intensity = normal.dot(&light_dir).max(0.0);
+                let diffuse = base_color * diffuse_intensity * material.albedo * light.intensity * attenuation;
+
+                let specular = match view_dir {
+                    Some(view_dir) => {
+                        let reflected_light = (-light_dir).reflect(normal);
+                        let specular_intensity = reflected_light.dot(view_dir).max(0.0).powf(material.shininess);
+                        (light.color * specular_intensity * material.specular) * light.intensity * attenuation
+                    }
+                    None => Color::zero(),
+                };
+
+                color += (diffuse + specular) * transmittance;
+            }
+        }
+
+        color
+    }
+
+    /// Fracción de luz (por canal) que llega a lo largo de `ray` hasta la
+    /// luz, a `distance` de su origen
+    /// Los objetos opacos la bloquean por completo; los transparentes la
+    /// atenúan con su color de transmisión y el rayo continúa tras ellos.
+    /// Si quedan superficies tras `MAX_SHADOW_LAYERS` capas se considera
+    /// ocluido: la transmitancia parcial ignoraría las capas restantes
+    fn shadow_transmittance(ray: &Ray, distance: f32, scene: &Scene) -> Color {
+        let bias = scene.settings.bias;
+        let direction = &ray.direction;
+        let mut transmittance = Color::new(1.0, 1.0, 1.0);
+        let mut origin = ray.origin;
+        let mut remaining = distance;
+
+        for _ in 0..MAX_SHADOW_LAYERS {
+            let shadow_ray = ray.spawn(origin, *direction);
+            stats::count(Counter::ShadowRays);
+            let (t, id) = match scene.find_closest_hit(&shadow_ray) {
+                Some(hit) if hit.0 < remaining => hit,
+                _ => return transmittance,
             };
 
-            if is_in_shadow {
+            let material = scene.objects[id].get_material();
+            if material.transparency <= 0.0 {
+                return Color::zero();
+            }
+
+            transmittance *= material.transmission();
+            origin = shadow_ray.at(t) + *direction * bias;
+            remaining -= t + bias;
+        }
+
+        // Solo cuenta como ocluido si de verdad queda otra superficie
+        let shadow_ray = ray.spawn(origin, *direction);
+        stats::count(Counter::ShadowRays);
+        match scene.find_closest_hit(&shadow_ray) {
+            Some((t, _)) if t < remaining => Color::zero(),
+            _ => transmittance,
+        }
+    }
+
+    /// Luz directa que llega desde los objetos emisivos de la escena
+    /// Cada emisor se muestrea sobre su superficie como si fuera una luz de
+    /// área: E = Le · A · cosθ · cosθe / d² (promediado sobre las muestras)
+    fn emissive_lighting(hit: &HitRecord, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        let bias = scene.settings.bias;
+        let hit_point = &hit.point;
+        let normal = &hit.normal;
+        let mut irradiance = Color::zero();
+
+        for emitter in &scene.objects {
+            let emission = emitter.get_material().emission;
+            let area = emitter.surface_area();
+            if area <= 0.0 || !emitter.get_material().is_emissive() {
                 continue;
             }
 
-            let diffuse_intensity = normal.dot(&light_dir).max(0.0);
-            let diffuse = base_color * diffuse_intensity * material.albedo * light.intensity;
+            let samples = stratified_square(EMISSIVE_SAMPLES, sampler);
+            let weight = area / samples.len() as f32;
+
+            for (u, v) in samples {
+                let light_point = match emitter.sample_surface(u, v) {
+                    Some(point) => point,
+                    None => continue,
+                };
+
+                let to_light = light_point - *hit_point;
+                let distance = to_light.length();
+                if distance < bias {
+                    continue;
+                }
+                let light_dir = to_light / distance;
+
+                // La muestra debe mirar hacia el punto iluminado y viceversa
+                let cos_theta = normal.dot(&light_dir);
+                let cos_emitter = emitter.normal_at_time(&light_point, hit.time).dot(&(-light_dir));
+                if cos_theta <= 0.0 || cos_emitter <= 0.0 {
+                    continue;
+                }
+
+                // Visible si lo primero que encuentra el rayo es la propia muestra
+                let shadow_ray = hit.spawn_ray(*hit_point + *normal * bias, light_dir);
+                stats::count(Counter::ShadowRays);
+                if let Some((t, _)) = scene.find_closest_intersection(&shadow_ray) {
+                    if t < distance - 1e-3 {
+                        continue;
+                    }
+                }
+
+                irradiance += emission * (cos_theta * cos_emitter * weight / (distance * distance));
+            }
+        }
+
+        irradiance / std::f32::consts::PI
+    }
+
+    /// Luz promedio que llega desde el entorno al hemisferio de la normal
+    /// Las direcciones se muestrean proporcionalmente al coseno, por lo que
+    /// el promedio simple ya incluye el término de Lambert. Los rayos
+    /// bloqueados por geometría (dentro del radio de AO, si está activa) no aportan.
+    fn environment_irradiance(hit: &HitRecord, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        let normal = &hit.normal;
+        let (samples, max_distance) = match &scene.ambient_occlusion {
+            Some(ao) => (ao.samples, ao.radius),
+            None => (ENVIRONMENT_SAMPLES, f32::INFINITY),
+        };
+
+        let origin = hit.point + *normal * scene.settings.bias;
+        let directions = stratified_square(samples, sampler);
+        let weight = 1.0 / directions.len() as f32;
+        let mut irradiance = Color::zero();
 
-            let reflected_light = (-light_dir).reflect(normal);
-            let specular_intensity = reflected_light.dot(view_dir).max(0.0).powf(material.shininess);
-            let specular = (light.color * specular_intensity * material.specular) * light.intensity;
+        for (u, v) in directions {
+            let direction = cosine_hemisphere(normal, u, v);
+            let ray = hit.spawn_ray(origin, direction);
+            stats::count(Counter::ShadowRays);
 
-            color = color + diffuse + specular;
+            let occluded = match scene.find_closest_intersection(&ray) {
+                Some((t, _)) => t < max_distance,
+                None => false,
+            };
+
+            if !occluded {
+                irradiance += scene.background(&direction) * weight;
+            }
         }
 
-        color.clamp()
+        irradiance
     }
 
-    pub fn trace_ray(ray: &Ray, scene: &Scene, depth: u32) -> Color {
+    pub fn trace_ray(ray: &Ray, scene: &Scene, depth: u32, sampler: &mut dyn Sampler) -> Color {
         if depth == 0 {
-            return scene.background_color;
+            return scene.background(&ray.direction);
         }
+        Self::trace_bounce(ray, scene.find_closest_hit(ray), scene, 0, depth, sampler)
+    }
 
-        if let Some((_t, hit_point, normal, object)) = Self::find_closest_intersection(ray, scene) {
+    /// `trace_ray` con el primer impacto del rayo ya buscado (ver
+    /// `Integrator::li_with_hit`)
+    pub fn trace_ray_with_hit(
+        ray: &Ray,
+        first_hit: Option<(f32, usize)>,
+        scene: &Scene,
+        depth: u32,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        if depth == 0 {
+            return scene.background(&ray.direction);
+        }
+        Self::trace_bounce(ray, first_hit, scene, 0, depth, sampler)
+    }
+
+    /// Rayo número `bounce` de la cadena de reflexiones/transmisiones, con
+    /// su impacto más cercano `found`
+    /// Los rayos secundarios se siguen mientras no se alcance el límite del
+    /// material impactado (o `max_depth` si el material no define uno)
+    fn trace_bounce(
+        ray: &Ray,
+        found: Option<(f32, usize)>,
+        scene: &Scene,
+        bounce: u32,
+        max_depth: u32,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let bias = scene.settings.bias;
+        if let Some((hit, object)) = found.map(|(t, id)| Self::hit_record(ray, scene, t, id)) {
             let material = object.get_material();
-            let view_dir = (scene.camera.position - hit_point).normalize();
-            let uv_data = object.get_uv(&hit_point);
-            let mut local_color = Self::shade(&hit_point, &normal, material, scene, &view_dir, uv_data);
-
-            if material.reflectivity > 0.0 && depth > 1 {
-                let reflected_dir = ray.direction.reflect(&normal);
-                let reflected_ray = Ray::new(hit_point + normal * EPSILON, reflected_dir);
-                let reflected_color = Self::trace_ray(&reflected_ray, scene, depth - 1);
+            let view_dir = (scene.camera.position - hit.point).normalize();
+            let mut local_color = Self::shade(&hit, material, scene, &view_dir, sampler);
+            let continues = bounce + 1 < material.max_depth.unwrap_or(max_depth);
+
+            if material.reflectivity > 0.0 && continues {
+                let reflected_dir = ray.direction.reflect(&hit.normal);
+                let reflected_ray = ray.spawn(hit.point + hit.normal * bias, reflected_dir);
+                stats::count(Counter::SecondaryRays);
+                let reflected_hit = scene.find_closest_hit(&reflected_ray);
+                let reflected_color =
+                    Self::trace_bounce(&reflected_ray, reflected_hit, scene, bounce + 1, max_depth, sampler);
                 local_color = local_color * (1.0 - material.reflectivity) + reflected_color * material.reflectivity;
             }
 
-            local_color
+            // La luz que atraviesa un objeto transparente continúa en línea recta
+            if material.transparency > 0.0 && continues {
+                let transmitted_ray = ray.spawn(hit.point + ray.direction * bias, ray.direction);
+                stats::count(Counter::SecondaryRays);
+                let transmitted_hit = scene.find_closest_hit(&transmitted_ray);
+                let transmitted_color =
+                    Self::trace_bounce(&transmitted_ray, transmitted_hit, scene, bounce + 1, max_depth, sampler);
+                local_color = local_color * (1.0 - material.transparency)
+                    + transmitted_color * material.color * material.transparency;
+            }
+
+            Self::apply_medium(ray, hit.t, local_color, scene, sampler)
         } else {
-            scene.background_color
+            Self::apply_medium(ray, f32::INFINITY, scene.background(&ray.direction), scene, sampler)
         }
     }
+
+    /// Path tracing: sigue un camino aleatorio de hasta `max_depth` rebotes
+    /// En cada superficie se elige al azar un único evento (reflexión especular,
+    /// transmisión o rebote difuso con muestreo proporcional al coseno) según
+    /// las propiedades del material. La luz proviene de los objetos emisivos y
+    /// del entorno; las luces puntuales no se pueden alcanzar por rebotes.
+    ///
+    /// Con `next_event` se muestrean además las luces y los emisores de forma
+    /// explícita en cada rebote difuso (next-event estimation), lo que también
+    /// ilumina con las luces puntuales. Para no contar dos veces la misma luz,
+    /// la emisión que se encuentra tras un rebote difuso se descarta.
+    ///
+    /// Los materiales con `max_depth` propio reemplazan el límite para los
+    /// rebotes que salen de ellos.
+    ///
+    /// A partir del rebote `roulette_depth` (si se indica) se aplica ruleta
+    /// rusa: los caminos que aportan poca luz terminan con cierta
+    /// probabilidad y los que sobreviven se ponderan para compensar.
+    pub fn trace_path(
+        ray: &Ray,
+        scene: &Scene,
+        max_depth: u32,
+        roulette_depth: Option<u32>,
+        next_event: bool,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let path = PathTracingIntegrator { max_depth, roulette_depth, next_event };
+        Self::trace_path_from(ray, scene.find_closest_hit(ray), scene, &path, true, sampler)
+    }
+
+    /// `trace_path` con las opciones de `path` y el primer impacto del rayo
+    /// ya buscado; sin `first_emission` se descarta la emisión del primer
+    /// impacto (igual que tras un rebote difuso) porque ya se muestreó de
+    /// forma explícita
+    pub(crate) fn trace_path_from(
+        ray: &Ray,
+        first_hit: Option<(f32, usize)>,
+        scene: &Scene,
+        path: &PathTracingIntegrator,
+        first_emission: bool,
+        sampler: &mut dyn Sampler,
+    ) -> Color {
+        let PathTracingIntegrator { max_depth, roulette_depth, next_event } = *path;
+        let bias = scene.settings.bias;
+        let mut radiance = Color::zero();
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        let mut ray = *ray;
+        // Indica si el último evento fue especular (la emisión aún no se contó)
+        let mut specular_bounce = first_emission;
+
+        // Cada superficie decide cuántos rebotes se permiten tras ella
+        // (su `max_depth` o el del integrador)
+        let mut depth_limit = max_depth;
+        let mut bounce = 0;
+
+        while bounce < depth_limit {
+            if bounce > 0 {
+                stats::count(Counter::SecondaryRays);
+            }
+            let found = if bounce == 0 { first_hit } else { scene.find_closest_hit(&ray) };
+            let (hit, object) = match found.map(|(t, id)| Self::hit_record(&ray, scene, t, id)) {
+                Some(found) => found,
+                None => {
+                    radiance += throughput * scene.background(&ray.direction);
+                    break;
+                }
+            };
+
+            let material = object.get_material();
+            if !next_event || specular_bounce {
+                radiance += throughput * material.emission;
+            }
+            depth_limit = material.max_depth.unwrap_or(max_depth);
+
+            // La normal debe mirar hacia el lado por el que llega el rayo
+            let normal = if hit.normal.dot(&ray.direction) > 0.0 { -hit.normal } else { hit.normal };
+
+            let reflect_probability = material.reflectivity.clamp(0.0, 1.0);
+            let transmit_probability = material.transparency.clamp(0.0, 1.0) * (1.0 - reflect_probability);
+            let choice = sampler.next_1d();
+
+            if choice < reflect_probability && material.roughness > 0.0 {
+                // Reflejo brillante: se muestrea una microfaceta según la distribución GGX
+                let alpha = roughness_to_alpha(material.roughness);
+                let (u, v) = sampler.next_2d();
+                let half = sample_ggx_half_vector(&normal, alpha, u, v);
+                let direction = ray.direction.reflect(&half);
+
+                let weight = ggx_sample_weight(&normal, &(-ray.direction), &direction, &half, alpha);
+                if weight <= 0.0 {
+                    break;
+                }
+                throughput *= weight;
+                ray = ray.spawn(hit.point + normal * bias, direction);
+                specular_bounce = true;
+            } else if choice < reflect_probability {
+                // Reflexión especular perfecta
+                let direction = ray.direction.reflect(&normal);
+                ray = ray.spawn(hit.point + normal * bias, direction);
+                specular_bounce = true;
+            } else if choice < reflect_probability + transmit_probability {
+                // Transmisión en línea recta, filtrada por el color del material
+                throughput *= material.color;
+                ray = ray.spawn(hit.point + ray.direction * bias, ray.direction);
+                specular_bounce = true;
+            } else {
+                // Rebote difuso: con muestreo por coseno el peso es simplemente el albedo
+                let base_color = Self::surface_color(&hit, material, scene);
+
+                // Con la iluminación horneada el camino termina aquí
+                if let Some(lighting) = Self::baked_lighting(&hit, material, scene) {
+                    radiance += throughput * (base_color * lighting * material.albedo);
+                    break;
+                }
+
+                // Luz directa muestreada explícitamente desde este punto
+                if next_event {
+                    let shading_hit = HitRecord { normal, ..hit };
+                    let direct = Self::direct_lighting(&shading_hit, material, base_color, scene, None, sampler)
+                        + base_color * Self::emissive_lighting(&shading_hit, scene, sampler) * material.albedo;
+                    radiance += throughput * direct;
+                }
+
+                throughput = throughput * base_color * material.albedo;
+
+                let (u, v) = sampler.next_2d();
+                let direction = cosine_hemisphere(&normal, u, v);
+                ray = ray.spawn(hit.point + normal * bias, direction);
+                specular_bounce = false;
+            }
+
+            if throughput.x <= 0.0 && throughput.y <= 0.0 && throughput.z <= 0.0 {
+                break;
+            }
+
+            // Ruleta rusa: la probabilidad de continuar depende de la energía restante
+            if let Some(start) = roulette_depth {
+                if bounce >= start {
+                    let survival = throughput.x.max(throughput.y).max(throughput.z).clamp(0.05, 0.95);
+                    if sampler.next_1d() > survival {
+                        break;
+                    }
+                    throughput = throughput / survival;
+                }
+            }
+
+            bounce += 1;
+        }
+
+        radiance
+    }
+
+    /// Color de la superficie en el punto de impacto (textura o color del material)
+    fn surface_color(hit: &HitRecord, material: &crate::material::Material, scene: &Scene) -> Color {
+        if let Some((u, v, tex_id)) = hit.uv {
+            if tex_id < scene.textures.len() {
+                return scene.textures[tex_id].sample(u, v);
+            }
+        }
+        material.color
+    }
+
+    /// Oclusión horneada del material en el punto (1.0 si no tiene)
+    fn baked_occlusion(hit: &HitRecord, material: &crate::material::Material, scene: &Scene) -> f32 {
+        match (material.occlusion_map.and_then(|id| scene.maps.get(id)), hit.uv) {
+            (Some(map), Some((u, v, _))) => map.sample_bilinear(u, v).x,
+            _ => 1.0,
+        }
+    }
+
+    /// Luz del lightmap del material en el punto (None si no tiene)
+    fn baked_lighting(hit: &HitRecord, material: &crate::material::Material, scene: &Scene) -> Option<Color> {
+        let lightmap = scene.maps.get(material.lightmap?)?;
+        let (u, v, _) = hit.uv?;
+        Some(lightmap.sample_bilinear(u, v))
+    }
+
+    /// Luz que reflejaría en el punto una superficie difusa blanca ideal (la
+    /// irradiancia / π): las luces y los objetos emisivos muestreados de
+    /// forma explícita más un camino difuso de hasta `bounces` rebotes para
+    /// la luz indirecta y el entorno. Es lo que guarda un lightmap: el color
+    /// final de la superficie es su color por su albedo por este valor.
+    /// La luz ambiental de Whitted no se incluye; la reemplaza la indirecta
+    pub fn diffuse_lighting(hit: &HitRecord, scene: &Scene, bounces: u32, sampler: &mut dyn Sampler) -> Color {
+        let white = crate::material::Material { albedo: 1.0, ..crate::material::Material::diffuse(Color::new(1.0, 1.0, 1.0)) };
+        let hit = HitRecord { uv: None, ..*hit };
+        let direct = Self::direct_lighting(&hit, &white, white.color, scene, None, sampler)
+            + Self::emissive_lighting(&hit, scene, sampler);
+        if bounces == 0 {
+            return direct;
+        }
+
+        let (u, v) = sampler.next_2d();
+        let direction = cosine_hemisphere(&hit.normal, u, v);
+        let ray = hit.spawn_ray(hit.point + hit.normal * scene.settings.bias, direction);
+        stats::count(Counter::SecondaryRays);
+        let path = PathTracingIntegrator::new(bounces).with_next_event_estimation();
+        let indirect = Self::trace_path_from(&ray, scene.find_closest_hit(&ray), scene, &path, false, sampler);
+        direct + indirect
+    }
+
+    /// Aplica el medio participante (si existe) al tramo del rayo hasta `t_hit`
+    /// Se avanza con ray marching: en cada paso se atenúa la luz y se suma la
+    /// luz de las fuentes dispersada hacia la cámara (haces volumétricos)
+    fn apply_medium(ray: &Ray, t_hit: f32, color: Color, scene: &Scene, sampler: &mut dyn Sampler) -> Color {
+        let medium = match &scene.medium {
+            Some(medium) if medium.density > 0.0 => medium,
+            _ => return color,
+        };
+
+        let (t0, t1) = match medium.segment(ray, t_hit) {
+            Some(segment) => segment,
+            None => return color,
+        };
+
+        let step = (t1 - t0) / medium.steps as f32;
+        if step <= 0.0 {
+            return color;
+        }
+
+        // Desplazamiento aleatorio del inicio para evitar bandas entre pasos
+        let jitter = sampler.next_1d();
+
+        let step_transmittance = medium.transmittance(step);
+        let mut transmittance = 1.0;
+        let mut scattered = Color::zero();
+
+        for i in 0..medium.steps {
+            let point = ray.at(t0 + (i as f32 + jitter) * step);
+
+            for light in &scene.lights {
+                let light_point = light.sample_points(&point, sampler)[0];
+                let to_light = light_point - point;
+                let distance = to_light.length();
+                if distance <= 0.0 {
+                    continue;
+                }
+                let light_dir = to_light / distance;
+
+                let cone = light.cone_attenuation(&light_dir);
+                if cone <= 0.0 {
+                    continue;
+                }
+
+                let visibility = if light.casts_shadows {
+                    Self::shadow_transmittance(&ray.spawn(point, light_dir), distance, scene)
+                } else {
+                    Color::new(1.0, 1.0, 1.0)
+                };
+
+                // Atenuación de la luz dentro del medio hasta el punto
+                let light_in_medium = match medium.segment(&Ray::new(point, light_dir), distance) {
+                    Some((a, b)) => medium.transmittance(b - a),
+                    None => 1.0,
+                };
+
+                let phase = medium.phase(ray.direction.dot(&light_dir));
+                let radiance = light.color * visibility * (light.intensity * cone * light_in_medium * phase);
+                scattered += radiance * medium.albedo * (transmittance * medium.density * step);
+            }
+
+            transmittance *= step_transmittance;
+        }
+
+        color * transmittance + scattered
+    }
 }
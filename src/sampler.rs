@@ -0,0 +1,386 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sampling::{Rng, hash_u64};
+
+/// Fuente de números en [0, 1) para las decisiones aleatorias del render
+/// (posición dentro del píxel, punto de la lente, muestras de luces...)
+/// Cada píxel recibe su propio sampler; antes de cada muestra del píxel se
+/// llama a `start_sample`, y luego cada dimensión se consume en orden
+pub trait Sampler {
+    /// Comienza la muestra `index` del píxel actual
+    fn start_sample(&mut self, index: u32);
+
+    /// Siguiente valor en [0, 1)
+    fn next_1d(&mut self) -> f32;
+
+    /// Siguiente par de valores en [0, 1)²
+    fn next_2d(&mut self) -> (f32, f32) {
+        (self.next_1d(), self.next_1d())
+    }
+}
+
+/// Muestreo independiente: números pseudoaleatorios sin estructura
+impl Sampler for Rng {
+    fn start_sample(&mut self, _index: u32) {}
+
+    fn next_1d(&mut self) -> f32 {
+        self.next_f32()
+    }
+}
+
+/// Tipos de sampler disponibles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplerKind {
+    /// Números aleatorios independientes
+    Random,
+    /// Muestras estratificadas con jitter: cada muestra del píxel cae en un
+    /// estrato distinto de cada dimensión
+    #[default]
+    Stratified,
+    /// Secuencia de baja discrepancia de Halton con rotación por píxel
+    Halton,
+    /// Secuencias de baja discrepancia rotadas con una máscara de ruido azul:
+    /// el error que queda con pocas muestras se reparte en alta frecuencia
+    /// entre píxeles vecinos en lugar de formar manchas
+    BlueNoise,
+}
+
+impl SamplerKind {
+    /// Crea el sampler para el píxel (x, y) con `samples_per_pixel` muestras
+    /// Las secuencias dependen solo del píxel y de `seed`, así que la misma
+    /// escena con la misma semilla produce siempre la misma imagen, sin
+    /// importar el orden en que los hilos procesen los píxeles
+    pub fn create(&self, x: u32, y: u32, samples_per_pixel: u32, seed: u64) -> Box<dyn Sampler> {
+        let pixel_seed = hash_u64((((y as u64) << 32) | x as u64) ^ seed.wrapping_mul(0x9e3779b97f4a7c15));
+
+        match self {
+            SamplerKind::Random => Box::new(RandomSampler::new(pixel_seed)),
+            SamplerKind::Stratified => Box::new(StratifiedSampler::new(pixel_seed, samples_per_pixel)),
+            SamplerKind::Halton => Box::new(HaltonSampler::new(pixel_seed)),
+            SamplerKind::BlueNoise => Box::new(BlueNoiseSampler::new(x, y, seed)),
+        }
+    }
+}
+
+/// Sampler de números aleatorios independientes por píxel
+/// Cada muestra parte de una semilla propia (la del píxel mezclada con el
+/// número de muestra), así que la muestra `index` es la misma aunque el
+/// sampler se cree de nuevo, como en el renderizado progresivo
+pub struct RandomSampler {
+    pixel_seed: u64,
+    rng: Rng,
+}
+
+impl RandomSampler {
+    pub fn new(pixel_seed: u64) -> Self {
+        RandomSampler { pixel_seed, rng: Rng::new(pixel_seed) }
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn start_sample(&mut self, index: u32) {
+        self.rng = Rng::new(hash_u64(self.pixel_seed ^ ((index as u64) << 40)));
+    }
+
+    fn next_1d(&mut self) -> f32 {
+        self.rng.next_f32()
+    }
+}
+
+/// Sampler estratificado con jitter
+/// En 1D se divide [0, 1) en `samples` estratos; en 2D se usa una cuadrícula
+/// n×n. El orden de los estratos se rota por dimensión para que las
+/// distintas dimensiones no queden correlacionadas entre sí.
+pub struct StratifiedSampler {
+    pixel_seed: u64,
+    samples: u32,
+    grid: u32,
+    index: u32,
+    dimension: u64,
+    rng: Rng,
+}
+
+impl StratifiedSampler {
+    pub fn new(pixel_seed: u64, samples: u32) -> Self {
+        let samples = samples.max(1);
+        StratifiedSampler {
+            pixel_seed,
+            samples,
+            grid: (samples as f32).sqrt().ceil() as u32,
+            index: 0,
+            dimension: 0,
+            rng: Rng::new(pixel_seed),
+        }
+    }
+
+    /// Desplazamiento pseudoaleatorio del orden de estratos para esta dimensión
+    fn offset(&self, strata: u32) -> u32 {
+        (hash_u64(self.pixel_seed ^ self.dimension.wrapping_mul(0x9e3779b97f4a7c15)) % strata as u64) as u32
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+        self.rng = Rng::new(hash_u64(self.pixel_seed ^ ((index as u64) << 40)));
+    }
+
+    fn next_1d(&mut self) -> f32 {
+        let stratum = (self.index + self.offset(self.samples)) % self.samples;
+        self.dimension += 1;
+        (stratum as f32 + self.rng.next_f32()) / self.samples as f32
+    }
+
+    fn next_2d(&mut self) -> (f32, f32) {
+        let strata = self.grid * self.grid;
+        let stratum = (self.index + self.offset(strata)) % strata;
+        self.dimension += 1;
+
+        let sx = stratum % self.grid;
+        let sy = stratum / self.grid;
+        (
+            (sx as f32 + self.rng.next_f32()) / self.grid as f32,
+            (sy as f32 + self.rng.next_f32()) / self.grid as f32,
+        )
+    }
+}
+
+/// Bases primas para las dimensiones de la secuencia de Halton
+const HALTON_PRIMES: [u32; 16] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+/// Sampler de baja discrepancia basado en la secuencia de Halton
+/// Cada dimensión usa una base prima distinta y se rota por píxel
+/// (Cranley-Patterson) para que píxeles vecinos no repitan el mismo patrón.
+/// Las dimensiones más allá de la tabla de primos usan números aleatorios.
+pub struct HaltonSampler {
+    pixel_seed: u64,
+    index: u32,
+    dimension: usize,
+    rng: Rng,
+}
+
+impl HaltonSampler {
+    pub fn new(pixel_seed: u64) -> Self {
+        HaltonSampler {
+            pixel_seed,
+            index: 0,
+            dimension: 0,
+            rng: Rng::new(pixel_seed),
+        }
+    }
+
+    /// Inverso radical de `index` en la base dada
+    fn radical_inverse(base: u32, mut index: u32) -> f32 {
+        let inv_base = 1.0 / base as f32;
+        let mut inv = inv_base;
+        let mut result = 0.0;
+
+        while index > 0 {
+            result += (index % base) as f32 * inv;
+            index /= base;
+            inv *= inv_base;
+        }
+
+        result
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+        self.rng = Rng::new(hash_u64(self.pixel_seed ^ ((index as u64) << 40)));
+    }
+
+    fn next_1d(&mut self) -> f32 {
+        let dimension = self.dimension;
+        self.dimension += 1;
+
+        if dimension >= HALTON_PRIMES.len() {
+            return self.rng.next_f32();
+        }
+
+        let rotation = (hash_u64(self.pixel_seed ^ dimension as u64) >> 40) as f32 / (1u64 << 24) as f32;
+        let value = Self::radical_inverse(HALTON_PRIMES[dimension], self.index + 1) + rotation;
+        value.fract()
+    }
+}
+
+/// Lado de la máscara de ruido azul (se repite en mosaico por la imagen)
+const BLUE_NOISE_SIZE: usize = 64;
+
+/// Desviación del núcleo gaussiano del algoritmo void-and-cluster
+const BLUE_NOISE_SIGMA: f32 = 1.9;
+
+/// Máscara de ruido azul compartida por todos los píxeles
+/// Se genera una sola vez, la primera vez que se necesita
+fn blue_noise_mask() -> &'static [f32] {
+    static MASK: OnceLock<Vec<f32>> = OnceLock::new();
+    MASK.get_or_init(|| void_and_cluster(BLUE_NOISE_SIZE, BLUE_NOISE_SIGMA, 0x5eed))
+}
+
+/// Genera una máscara de ruido azul periódica de `size`×`size` con el
+/// algoritmo void-and-cluster de Ulichney: los píxeles se ordenan de forma
+/// que cada prefijo del orden quede lo más repartido posible, y el rango de
+/// cada píxel, normalizado a [0, 1), es su valor en la máscara
+fn void_and_cluster(size: usize, sigma: f32, seed: u64) -> Vec<f32> {
+    let count = size * size;
+
+    // Núcleo gaussiano con distancias toroidales, indexado por desplazamiento
+    let mut kernel = vec![0.0f32; count];
+    for dy in 0..size {
+        for dx in 0..size {
+            let tx = dx.min(size - dx) as f32;
+            let ty = dy.min(size - dy) as f32;
+            kernel[dy * size + dx] = (-(tx * tx + ty * ty) / (2.0 * sigma * sigma)).exp();
+        }
+    }
+
+    // energy[i] = suma del núcleo centrado en cada píxel activo
+    let update = |energy: &mut [f32], index: usize, sign: f32| {
+        let (px, py) = (index % size, index / size);
+        for y in 0..size {
+            let dy = (y + size - py) % size;
+            for x in 0..size {
+                let dx = (x + size - px) % size;
+                energy[y * size + x] += sign * kernel[dy * size + dx];
+            }
+        }
+    };
+
+    // Píxel activo con más energía (centro del cúmulo más apretado)
+    let tightest_cluster = |pattern: &[bool], energy: &[f32]| {
+        (0..count).filter(|&i| pattern[i]).max_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+    };
+    // Píxel inactivo con menos energía (centro del hueco más grande)
+    let largest_void = |pattern: &[bool], energy: &[f32]| {
+        (0..count).filter(|&i| !pattern[i]).min_by(|&a, &b| energy[a].total_cmp(&energy[b]))
+    };
+
+    // Patrón inicial: un 10% de píxeles al azar, luego se mueven los
+    // puntos de los cúmulos a los huecos hasta que el patrón se estabiliza
+    let mut rng = Rng::new(seed);
+    let mut pattern = vec![false; count];
+    let mut energy = vec![0.0f32; count];
+    let initial = (count / 10).max(1);
+    let mut placed = 0;
+    while placed < initial {
+        let index = (rng.next_u64() % count as u64) as usize;
+        if !pattern[index] {
+            pattern[index] = true;
+            update(&mut energy, index, 1.0);
+            placed += 1;
+        }
+    }
+
+    loop {
+        let cluster = tightest_cluster(&pattern, &energy).unwrap();
+        pattern[cluster] = false;
+        update(&mut energy, cluster, -1.0);
+
+        let void = largest_void(&pattern, &energy).unwrap();
+        pattern[void] = true;
+        update(&mut energy, void, 1.0);
+
+        if void == cluster {
+            break;
+        }
+    }
+
+    let mut rank = vec![0usize; count];
+
+    // Fase 1: se quitan los puntos iniciales empezando por los cúmulos
+    {
+        let mut pattern = pattern.clone();
+        let mut energy = energy.clone();
+        for r in (0..initial).rev() {
+            let cluster = tightest_cluster(&pattern, &energy).unwrap();
+            pattern[cluster] = false;
+            update(&mut energy, cluster, -1.0);
+            rank[cluster] = r;
+        }
+    }
+
+    // Fase 2: se rellenan los huecos hasta completar la máscara
+    for r in initial..count {
+        let void = largest_void(&pattern, &energy).unwrap();
+        pattern[void] = true;
+        update(&mut energy, void, 1.0);
+        rank[void] = r;
+    }
+
+    rank.iter().map(|&r| (r as f32 + 0.5) / count as f32).collect()
+}
+
+/// Sampler con rotaciones de ruido azul
+/// Cada dimensión recorre una secuencia de baja discrepancia (razón áurea en
+/// 1D, secuencia R2 en 2D) y se rota (Cranley-Patterson) con el valor de la
+/// máscara de ruido azul en el píxel. La máscara se desplaza por dimensión
+/// y por semilla, pero igual para todos los píxeles, así que píxeles vecinos
+/// reciben rotaciones distintas y bien repartidas: con pocas muestras el
+/// ruido residual se ve como un grano fino en lugar de manchas.
+pub struct BlueNoiseSampler {
+    x: u32,
+    y: u32,
+    seed: u64,
+    index: u32,
+    dimension: u64,
+}
+
+/// Incremento de la secuencia de razón áurea en 1D
+const GOLDEN_1D: f64 = 0.618_033_988_749_894_8;
+
+/// Incrementos de la secuencia R2 (inversos del número plástico)
+const R2: (f64, f64) = (0.754_877_666_246_692_7, 0.569_840_290_998_053_3);
+
+impl BlueNoiseSampler {
+    pub fn new(x: u32, y: u32, seed: u64) -> Self {
+        BlueNoiseSampler {
+            x,
+            y,
+            seed: hash_u64(seed ^ 0xb1ae),
+            index: 0,
+            dimension: 0,
+        }
+    }
+
+    /// Valor de la máscara para este píxel, desplazada según la dimensión
+    /// y el canal (0 o 1 en las dimensiones 2D)
+    fn rotation(&self, channel: u64) -> f64 {
+        let mask = blue_noise_mask();
+        let shift = hash_u64(self.seed ^ (self.dimension * 2 + channel).wrapping_mul(0x9e3779b97f4a7c15));
+        let size = BLUE_NOISE_SIZE as u64;
+        let mx = (self.x as u64 + (shift & 0xffff)) % size;
+        let my = (self.y as u64 + (shift >> 16 & 0xffff)) % size;
+        mask[(my * size + mx) as usize] as f64
+    }
+}
+
+impl Sampler for BlueNoiseSampler {
+    fn start_sample(&mut self, index: u32) {
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f32 {
+        let value = (self.index as f64 * GOLDEN_1D + self.rotation(0)).fract();
+        self.dimension += 1;
+        unit_f32(value)
+    }
+
+    fn next_2d(&mut self) -> (f32, f32) {
+        let u = (self.index as f64 * R2.0 + self.rotation(0)).fract();
+        let v = (self.index as f64 * R2.1 + self.rotation(1)).fract();
+        self.dimension += 1;
+        (unit_f32(u), unit_f32(v))
+    }
+}
+
+/// Convierte un valor en [0, 1) a f32 sin que el redondeo llegue a 1.0
+fn unit_f32(value: f64) -> f32 {
+    (value as f32).min(1.0 - f32::EPSILON / 2.0)
+}
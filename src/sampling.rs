@@ -0,0 +1,93 @@
+use crate::vector::Vec3;
+use crate::sampler::Sampler;
+
+/// Generador de números pseudoaleatorios (SplitMix64)
+/// Es pequeño, rápido y determinista: la misma semilla produce siempre la
+/// misma secuencia, por lo que los renders son reproducibles.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Crea un generador a partir de una semilla
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Retorna el siguiente entero de 64 bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Retorna un número uniforme en [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Mezcla los bits de un entero (finalizador de SplitMix64)
+/// Útil para derivar semillas independientes a partir de índices
+pub fn hash_u64(value: u64) -> u64 {
+    Rng::new(value).next_u64()
+}
+
+/// Genera n×n puntos estratificados con jitter en el cuadrado unitario
+/// Cada celda de la cuadrícula recibe exactamente una muestra; el jitter
+/// dentro de cada celda lo decide el sampler
+pub fn stratified_square(n: u32, sampler: &mut dyn Sampler) -> Vec<(f32, f32)> {
+    let n = n.max(1);
+    let inv = 1.0 / n as f32;
+    let mut samples = Vec::with_capacity((n * n) as usize);
+
+    for j in 0..n {
+        for i in 0..n {
+            let (jitter_u, jitter_v) = sampler.next_2d();
+            let u = (i as f32 + jitter_u) * inv;
+            let v = (j as f32 + jitter_v) * inv;
+            samples.push((u, v));
+        }
+    }
+
+    samples
+}
+
+/// Transforma un punto del cuadrado unitario al disco unitario
+/// (mapeo concéntrico de Shirley-Chiu, conserva la estratificación)
+pub fn concentric_disk(u: f32, v: f32) -> (f32, f32) {
+    let a = 2.0 * u - 1.0;
+    let b = 2.0 * v - 1.0;
+
+    if a == 0.0 && b == 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let (r, theta) = if a.abs() > b.abs() {
+        (a, std::f32::consts::FRAC_PI_4 * (b / a))
+    } else {
+        (b, std::f32::consts::FRAC_PI_2 - std::f32::consts::FRAC_PI_4 * (a / b))
+    };
+
+    (r * theta.cos(), r * theta.sin())
+}
+
+/// Construye dos vectores tangentes que forman una base ortonormal con `n`
+pub fn orthonormal_basis(n: &Vec3) -> (Vec3, Vec3) {
+    let helper = if n.x.abs() > 0.9 { Vec3::new(0.0, 1.0, 0.0) } else { Vec3::new(1.0, 0.0, 0.0) };
+    let tangent = helper.cross(n).normalize();
+    let bitangent = n.cross(&tangent);
+    (tangent, bitangent)
+}
+
+/// Dirección en el hemisferio alrededor de `normal` con densidad
+/// proporcional al coseno (más muestras cerca de la normal)
+pub fn cosine_hemisphere(normal: &Vec3, u: f32, v: f32) -> Vec3 {
+    let (dx, dy) = concentric_disk(u, v);
+    let dz = (1.0 - dx * dx - dy * dy).max(0.0).sqrt();
+    let (tangent, bitangent) = orthonormal_basis(normal);
+    (tangent * dx + bitangent * dy + *normal * dz).normalize()
+}
@@ -1,13 +1,50 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hasher;
+use std::sync::{Arc, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
 use crate::vector::{Point3, Vec3, Color};
 use crate::ray::Ray;
 use crate::material::Material;
-use crate::light::Light;
+use crate::light::{Light, LightKind, AmbientLight};
 use crate::camera::Camera;
 use crate::sphere::Sphere;
 use crate::plane::Plane;
 use crate::cube::Cube;
 use crate::pyramid::Pyramid;
 use crate::texture::Texture;
+use crate::ambient_occlusion::AmbientOcclusion;
+use crate::environment::Environment;
+use crate::sky::SkyModel;
+use crate::medium::Medium;
+use crate::moving::MovingObject;
+use crate::mesh::TriangleMesh;
+use crate::lod::LodMesh;
+use crate::instance::Instance;
+use crate::transform::Transform;
+use crate::prefab::TextureOffset;
+use crate::animation::Timeline;
+use crate::aabb::Aabb;
+use crate::bvh::Bvh;
+use crate::packet::{RayPacket, PacketHits, LANES};
+use crate::gpu::GpuPrimitive;
+use crate::stats::{self, Counter};
+use crate::light_sampler::LightSampler;
+use crate::sampler::SamplerKind;
+use crate::far_clip::FarClip;
+use crate::development::Development;
+use crate::settings::RenderSettings;
+use crate::filter::PixelFilter;
+use crate::integrator::IntegratorKind;
+use crate::denoise::Denoiser;
+use crate::firefly::FireflyFilter;
+use crate::render_cache::{StableHasher, hash_f32, hash_vec3};
+use crate::snapshot::{ObjectSnapshot, SharedObjects};
+
+/// Cuánto puede empeorar el costo SAH de la TLAS con refits antes de reconstruirla
+const TLAS_REFIT_LIMIT: f32 = 2.0;
 
 /// Trait que define la interfaz común para todos los objetos intersectables
 pub trait Intersectable: Send + Sync {
@@ -15,8 +52,100 @@ pub trait Intersectable: Send + Sync {
     fn normal_at(&self, point: &Point3) -> Vec3;
     fn get_material(&self) -> &Material;
     fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)>;
+    fn hash_state(&self, state: &mut dyn Hasher);
+
+    /// Normal en `point` en el instante `time` (solo difiere en objetos en movimiento)
+    fn normal_at_time(&self, point: &Point3, _time: f32) -> Vec3 {
+        self.normal_at(point)
+    }
+
+    /// Coordenadas UV en `point` en el instante `time`
+    fn get_uv_at_time(&self, point: &Point3, _time: f32) -> Option<(f32, f32, usize)> {
+        self.get_uv(point)
+    }
+
+    /// Área de la superficie (0.0 si el objeto no se puede muestrear)
+    fn surface_area(&self) -> f32 {
+        0.0
+    }
+
+    /// Punto de la superficie para (u, v) en [0, 1)², usado para muestrear
+    /// objetos emisivos como fuentes de luz
+    fn sample_surface(&self, _u: f32, _v: f32) -> Option<Point3> {
+        None
+    }
+
+    /// Intersección con un paquete de rayos coherentes
+    /// Por defecto prueba los rayos uno por uno; las formas simples tienen
+    /// versiones que procesan todo el paquete a la vez
+    fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        let mut hits = [None; LANES];
+        for (lane, hit) in hits.iter_mut().enumerate() {
+            if rays.active[lane] {
+                *hit = self.intersect(&rays.ray(lane));
+            }
+        }
+        hits
+    }
+
+    /// Caja envolvente para la BVH de la escena (None = objeto infinito,
+    /// se prueba siempre)
+    fn bounds(&self) -> Option<Aabb> {
+        None
+    }
+
+    /// Cambia la transformación del objeto; retorna false si no la admite
+    fn set_transform(&mut self, _transform: Transform) -> bool {
+        false
+    }
+
+    /// Transformación actual (None si el objeto no admite `set_transform`)
+    fn transform(&self) -> Option<Transform> {
+        None
+    }
+
+    /// Acceso mutable al material, p. ej. para un editor. None si el objeto
+    /// no lo permite (como una instancia cuyo objeto comparten otras)
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        None
+    }
+
+    /// Primitivas equivalentes para el backend de GPU (None si el shader no
+    /// sabe trazar el objeto y hay que renderizar en CPU)
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        None
+    }
+
+    /// Problemas de la geometría que impedirían renderizarla bien, como
+    /// mensajes para el usuario (ver `Scene::validate`)
+    fn validate(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// ID (en la escena) de la textura que usa el objeto, si usa alguna
+    fn texture_id(&self) -> Option<usize> {
+        self.get_material().texture_id
+    }
+
+    /// Vértices y triángulos que aproximan la superficie, para exportarla
+    /// (None si el objeto no sabe convertirse en triángulos)
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        None
+    }
+
+    /// Copia serializable del objeto para guardar la escena en una
+    /// instantánea (ver `snapshot`); los objetos compartidos de las
+    /// instancias se registran en `shared`. None si no se puede guardar
+    fn snapshot(&self, _shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        None
+    }
 }
 
+/// Meridianos de las esferas al convertirlas en triángulos
+const SPHERE_SEGMENTS: usize = 32;
+/// Mitad del lado del cuadrado que representa a un plano infinito
+const PLANE_HALF_SIZE: f32 = 50.0;
+
 // Implementar trait para Sphere
 impl Intersectable for Sphere {
     fn intersect(&self, ray: &Ray) -> Option<f32> {
@@ -31,9 +160,49 @@ impl Intersectable for Sphere {
         &self.material
     }
 
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        Some(&mut self.material)
+    }
+
     fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)> {
         Sphere::get_uv(self, point)
     }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        Sphere::hash_state(self, state)
+    }
+
+    fn surface_area(&self) -> f32 {
+        Sphere::surface_area(self)
+    }
+
+    fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        Sphere::sample_surface(self, u, v)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Sphere::bounds(self))
+    }
+
+    fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        Sphere::intersect_packet(self, rays)
+    }
+
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        Some(vec![Sphere::gpu_primitive(self)])
+    }
+
+    fn validate(&self) -> Vec<String> {
+        Sphere::validate(self)
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        Some(Sphere::tessellate(self, SPHERE_SEGMENTS))
+    }
+
+    fn snapshot(&self, _shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        Some(ObjectSnapshot::Sphere(*self))
+    }
 }
 
 // Implementar trait para Plane
@@ -50,9 +219,33 @@ impl Intersectable for Plane {
         &self.material
     }
 
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        Some(&mut self.material)
+    }
+
     fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)> {
         Plane::get_uv(self, point)
     }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        Plane::hash_state(self, state)
+    }
+
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        Some(vec![Plane::gpu_primitive(self)])
+    }
+
+    fn validate(&self) -> Vec<String> {
+        Plane::validate(self)
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        Some(Plane::tessellate(self, PLANE_HALF_SIZE))
+    }
+
+    fn snapshot(&self, _shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        Some(ObjectSnapshot::Plane(*self))
+    }
 }
 
 // Implementar trait para Cube
@@ -69,9 +262,49 @@ impl Intersectable for Cube {
         &self.material
     }
 
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        Some(&mut self.material)
+    }
+
     fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)> {
         Cube::get_uv(self, point)
     }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        Cube::hash_state(self, state)
+    }
+
+    fn surface_area(&self) -> f32 {
+        Cube::surface_area(self)
+    }
+
+    fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        Cube::sample_surface(self, u, v)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Cube::bounds(self))
+    }
+
+    fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        Cube::intersect_packet(self, rays)
+    }
+
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        Some(vec![Cube::gpu_primitive(self)])
+    }
+
+    fn validate(&self) -> Vec<String> {
+        Cube::validate(self)
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        Some(Cube::tessellate(self))
+    }
+
+    fn snapshot(&self, _shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        Some(ObjectSnapshot::Cube(*self))
+    }
 }
 
 // Implementar trait para Pyramid
@@ -88,59 +321,715 @@ impl Intersectable for Pyramid {
         &self.material
     }
 
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        Some(&mut self.material)
+    }
+
     fn get_uv(&self, point: &Point3) -> Option<(f32, f32, usize)> {
         Pyramid::get_uv(self, point)
     }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        Pyramid::hash_state(self, state)
+    }
+
+    fn surface_area(&self) -> f32 {
+        Pyramid::surface_area(self)
+    }
+
+    fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        Pyramid::sample_surface(self, u, v)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        Some(Pyramid::bounds(self))
+    }
+
+    fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        Pyramid::intersect_packet(self, rays)
+    }
+
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        Some(Pyramid::gpu_primitives(self))
+    }
+
+    fn validate(&self) -> Vec<String> {
+        Pyramid::validate(self)
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        Some(Pyramid::tessellate(self))
+    }
+
+    fn snapshot(&self, _shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        Some(ObjectSnapshot::Pyramid(*self))
+    }
+}
+
+// Implementar trait para TriangleMesh
+impl Intersectable for TriangleMesh {
+    fn intersect(&self, ray: &Ray) -> Option<f32> {
+        TriangleMesh::intersect(self, ray)
+    }
+
+    fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        TriangleMesh::intersect_packet(self, rays)
+    }
+
+    fn normal_at(&self, point: &Point3) -> Vec3 {
+        TriangleMesh::normal_at(self, point)
+    }
+
+    fn get_material(&self) -> &Material {
+        &self.material
+    }
+
+    fn material_mut(&mut self) -> Option<&mut Material> {
+        Some(&mut self.material)
+    }
+
+    fn get_uv(&self, _point: &Point3) -> Option<(f32, f32, usize)> {
+        None
+    }
+
+    fn hash_state(&self, state: &mut dyn Hasher) {
+        TriangleMesh::hash_state(self, state)
+    }
+
+    fn bounds(&self) -> Option<Aabb> {
+        TriangleMesh::bounds(self)
+    }
+
+    fn gpu_primitives(&self) -> Option<Vec<GpuPrimitive>> {
+        Some(TriangleMesh::gpu_primitives(self))
+    }
+
+    fn validate(&self) -> Vec<String> {
+        TriangleMesh::validate(self)
+    }
+
+    fn surface_area(&self) -> f32 {
+        TriangleMesh::surface_area(self)
+    }
+
+    fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        TriangleMesh::sample_surface(self, u, v)
+    }
+
+    fn tessellate(&self) -> Option<(Vec<Point3>, Vec<[usize; 3]>)> {
+        Some((self.vertices.clone(), self.triangles.clone()))
+    }
+
+    fn snapshot(&self, _shared: &mut SharedObjects) -> Option<ObjectSnapshot> {
+        Some(ObjectSnapshot::Mesh(self.clone()))
+    }
+}
+
+/// Elemento de la escena identificado por su ID, para darle un nombre
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SceneItem {
+    Object(usize),
+    Light(usize),
+    Texture(usize),
+}
+
+/// Acceso mutable a un elemento con nombre (ver `Scene::get_mut`)
+pub enum SceneItemMut<'a> {
+    Object(&'a mut Box<dyn Intersectable>),
+    Light(&'a mut Light),
+    Texture(&'a mut Texture),
 }
 
+/// Gravedad de un problema encontrado por `Scene::validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// La escena se puede renderizar, pero probablemente no como se espera
+    Warning,
+    /// La imagen saldría negra, rota o distinta de lo que describe la escena
+    Error,
+}
+
+/// Problema de una escena: qué elemento lo tiene y qué le pasa
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Elemento afectado (None = la cámara o la escena en general)
+    pub item: Option<SceneItem>,
+    /// Nombre del elemento, si tiene uno
+    pub name: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "aviso")?,
+            Severity::Error => write!(f, "error")?,
+        }
+        match self.item {
+            Some(SceneItem::Object(id)) => write!(f, " en el objeto {}", id)?,
+            Some(SceneItem::Light(id)) => write!(f, " en la luz {}", id)?,
+            Some(SceneItem::Texture(id)) => write!(f, " en la textura {}", id)?,
+            None => {}
+        }
+        if let Some(name) = &self.name {
+            write!(f, " ('{}')", name)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Enlace de luz: qué objetos ilumina una luz concreta
+/// Si `include` tiene valor, la luz solo afecta a esos objetos; los objetos
+/// en `exclude` nunca reciben su luz
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LightLink {
+    pub include: Option<HashSet<usize>>,
+    pub exclude: HashSet<usize>,
+}
+
+impl LightLink {
+    /// Indica si el enlace permite iluminar al objeto
+    pub fn affects(&self, object_id: usize) -> bool {
+        if self.exclude.contains(&object_id) {
+            return false;
+        }
+
+        match &self.include {
+            Some(include) => include.contains(&object_id),
+            None => true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
 pub struct Scene {
+    /// Objetos en orden de inserción. Los IDs que devuelven los `add_*` no
+    /// son posiciones en este vector sino identificadores estables que
+    /// siguen valiendo al quitar otros objetos (ver `object_index`).
+    /// Se puede leer y modificar cada objeto, pero los objetos se agregan y
+    /// quitan solo con `add_object`/`remove_object` (y los `add_*`), que
+    /// mantienen al día sus IDs; agregarlos a mano rompe `object_id`
+    #[serde(with = "crate::snapshot::objects")]
     pub objects: Vec<Box<dyn Intersectable>>,
     pub lights: Vec<Light>,
     pub camera: Camera,
     pub background_color: Color,
+    pub ambient_light: AmbientLight,
     pub textures: Vec<Texture>,
+    /// Texturas de datos horneadas (oclusión ambiental, lightmaps; ver
+    /// `bake`). Los materiales las usan por su posición en esta lista y
+    /// nunca como color, a diferencia de `textures`
+    #[serde(default)]
+    pub maps: Vec<Texture>,
+    pub ambient_occlusion: Option<AmbientOcclusion>,
+    pub environment: Option<Environment>,
+    pub light_links: HashMap<usize, LightLink>,
+    pub medium: Option<Medium>,
+    pub light_sampler: Option<LightSampler>,
+    pub settings: RenderSettings,
+    pub sampler: SamplerKind,
+    pub pixel_filter: PixelFilter,
+    pub integrator: IntegratorKind,
+    pub denoiser: Option<Denoiser>,
+    pub seed: u64,
+    pub firefly: FireflyFilter,
+    pub far_clip: Option<FarClip>,
+    /// Cómo se convierte la imagen HDR en la imagen final (no afecta al
+    /// framebuffer ni a `fingerprint`)
+    pub development: Development,
+    /// Animación de la escena (ver `set_time`)
+    pub animation: Option<Timeline>,
+
+    // ID estable de cada objeto (en el mismo orden que `objects`, así que
+    // está ordenado) y siguiente ID libre
+    object_ids: Vec<usize>,
+    next_object_id: usize,
+
+    // Nombres de objetos, luces y texturas (no afectan al render)
+    names: HashMap<String, SceneItem>,
+
+    // Aceleración (TLAS, ver `Tlas`). Agregar o quitar objetos solo la
+    // descarta; se construye una vez, con el primer rayo que la necesita,
+    // así que cargar una escena con miles de objetos no la reconstruye con
+    // cada uno
+    #[serde(with = "lazy_tlas")]
+    tlas: OnceLock<Tlas>,
+}
+
+/// BVH sobre las cajas de los objetos acotados de la escena; los infinitos
+/// (planos) se prueban aparte
+#[derive(Default, Serialize, Deserialize)]
+struct Tlas {
+    bvh: Bvh,
+    unbounded: Vec<usize>,
+    /// Cantidad de objetos cuando se construyó
+    object_count: usize,
+    build_cost: f32,
+}
+
+impl Tlas {
+    /// Es barata porque solo tiene un nodo hoja por objeto o instancia
+    fn build(objects: &[Box<dyn Intersectable>]) -> Self {
+        let mut items = Vec::new();
+        let mut unbounded = Vec::new();
+
+        for (id, object) in objects.iter().enumerate() {
+            match object.bounds() {
+                Some(bounds) => items.push((id, bounds)),
+                None => unbounded.push(id),
+            }
+        }
+
+        let bvh = Bvh::build(items);
+        let build_cost = bvh.cost();
+        Tlas { bvh, unbounded, object_count: objects.len(), build_cost }
+    }
+
+    /// Ajusta las cajas a la posición actual de los objetos; false si la
+    /// estructura ya no sirve y hay que reconstruirla (ver `Scene::refit_tlas`)
+    fn refit(&mut self, objects: &[Box<dyn Intersectable>]) -> bool {
+        self.object_count == objects.len()
+            && self.bvh.refit(|id| objects[id].bounds())
+            && self.bvh.cost() <= self.build_cost * TLAS_REFIT_LIMIT
+    }
+}
+
+/// (De)serialización de la TLAS: se guarda si ya estaba construida
+mod lazy_tlas {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(tlas: &OnceLock<Tlas>, serializer: S) -> Result<S::Ok, S::Error> {
+        tlas.get().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<OnceLock<Tlas>, D::Error> {
+        let tlas: Option<Tlas> = Deserialize::deserialize(deserializer)?;
+        Ok(tlas.map_or_else(OnceLock::new, OnceLock::from))
+    }
 }
 
 impl Scene {
     /// Crea una nueva escena vacía
     pub fn new(camera: Camera, background_color: Color) -> Self {
+        let settings = RenderSettings::new(camera.width, camera.height);
         Scene {
             objects: Vec::new(),
             lights: Vec::new(),
             camera,
             background_color,
+            ambient_light: AmbientLight::default(),
             textures: Vec::new(),
+            maps: Vec::new(),
+            ambient_occlusion: None,
+            environment: None,
+            light_links: HashMap::new(),
+            medium: None,
+            light_sampler: None,
+            settings,
+            sampler: SamplerKind::default(),
+            pixel_filter: PixelFilter::default(),
+            integrator: IntegratorKind::default(),
+            denoiser: None,
+            seed: 0,
+            firefly: FireflyFilter::default(),
+            far_clip: None,
+            development: Development::default(),
+            animation: None,
+            object_ids: Vec::new(),
+            next_object_id: 0,
+            names: HashMap::new(),
+            tlas: OnceLock::new(),
         }
     }
 
-    /// Agrega un objeto a la escena
-    pub fn add_object(&mut self, object: Box<dyn Intersectable>) {
+    /// Carga una escena desde un archivo JSON (formato en `scene_file`)
+    pub fn from_file(path: &str) -> Result<Scene, Box<dyn std::error::Error>> {
+        crate::scene_file::load(path)
+    }
+
+    /// Agrega un objeto a la escena y retorna su ID
+    pub fn add_object(&mut self, object: Box<dyn Intersectable>) -> usize {
+        let id = self.next_object_id;
+        self.next_object_id += 1;
         self.objects.push(object);
+        self.object_ids.push(id);
+        self.invalidate_tlas();
+        id
+    }
+
+    /// Agrega los objetos, luces y texturas de `other` (un "prefab": una
+    /// habitación amueblada, un árbol...) a esta escena, opcionalmente
+    /// transformados. Los IDs de textura, de luz y de objeto de `other` se
+    /// renumeran, y sus enlaces de luz se conservan. Los nombres se copian
+    /// salvo los que ya están en uso. La cámara, el fondo, los ajustes de
+    /// render, el revelado y la animación de `other` se ignoran.
+    /// Retorna los nuevos IDs de los objetos agregados, en el orden de `other`
+    pub fn merge(&mut self, other: Scene, transform: Option<Transform>) -> Vec<usize> {
+        let texture_offset = self.textures.len();
+        let map_offset = self.maps.len();
+        let light_offset = self.lights.len();
+        self.textures.extend(other.textures);
+        self.maps.extend(other.maps);
+
+        let mut object_map = HashMap::new();
+        for (object, old_id) in other.objects.into_iter().zip(other.object_ids) {
+            let mut object = object;
+            if texture_offset > 0 || map_offset > 0 {
+                object = Box::new(TextureOffset::new(object, texture_offset).with_map_offset(map_offset));
+            }
+            if let Some(transform) = transform {
+                object = Box::new(Instance::new(Arc::from(object), transform));
+            }
+
+            object_map.insert(old_id, self.add_object(object));
+        }
+
+        self.lights.extend(other.lights.iter().map(|light| match &transform {
+            Some(transform) => light.transformed(transform),
+            None => *light,
+        }));
+
+        for (light_id, link) in other.light_links {
+            let remap = |ids: HashSet<usize>| ids.iter().filter_map(|id| object_map.get(id).copied()).collect();
+            self.light_links.insert(light_id + light_offset, LightLink {
+                include: link.include.map(remap),
+                exclude: remap(link.exclude),
+            });
+        }
+
+        for (name, item) in other.names {
+            let item = match item {
+                SceneItem::Object(id) => SceneItem::Object(object_map[&id]),
+                SceneItem::Light(id) => SceneItem::Light(id + light_offset),
+                SceneItem::Texture(id) => SceneItem::Texture(id + texture_offset),
+            };
+            self.names.entry(name).or_insert(item);
+        }
+
+        self.invalidate_tlas();
+        self.rebuild_light_sampler();
+
+        let mut ids: Vec<usize> = object_map.into_values().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// ID del objeto que ocupa la posición `index` de `objects`
+    pub fn object_id(&self, index: usize) -> usize {
+        self.object_ids[index]
+    }
+
+    /// Posición actual en `objects` del objeto con ID `id`
+    pub fn object_index(&self, id: usize) -> Option<usize> {
+        self.object_ids.binary_search(&id).ok()
+    }
+
+    pub fn object(&self, id: usize) -> Option<&dyn Intersectable> {
+        self.object_index(id).map(|index| self.objects[index].as_ref())
+    }
+
+    /// Material del objeto con ID `id` para cambiarlo (ver
+    /// `Intersectable::material_mut`)
+    pub fn material_mut(&mut self, id: usize) -> Option<&mut Material> {
+        let index = self.object_index(id)?;
+        self.objects[index].material_mut()
+    }
+
+    /// Caja que envuelve todos los objetos acotados; los planos infinitos
+    /// no cuentan. None si no hay ningún objeto acotado
+    pub fn bounds(&self) -> Option<Aabb> {
+        self.objects.iter().filter_map(|object| object.bounds()).reduce(|a, b| a.union(&b))
+    }
+
+    /// Quita un objeto de la escena y lo retorna. Los demás objetos
+    /// conservan sus IDs; los enlaces de luz y el nombre del objeto quitado
+    /// se descartan
+    pub fn remove_object(&mut self, id: usize) -> Option<Box<dyn Intersectable>> {
+        let index = self.object_index(id)?;
+        self.object_ids.remove(index);
+        let object = self.objects.remove(index);
+
+        for link in self.light_links.values_mut() {
+            link.exclude.remove(&id);
+            if let Some(include) = &mut link.include {
+                include.remove(&id);
+            }
+        }
+        self.names.retain(|_, item| *item != SceneItem::Object(id));
+
+        self.invalidate_tlas();
+        Some(object)
+    }
+
+    /// Reemplaza un objeto conservando su ID, su nombre y sus enlaces de
+    /// luz, y retorna el anterior (None si el ID no existe)
+    pub fn replace_object(&mut self, id: usize, object: Box<dyn Intersectable>) -> Option<Box<dyn Intersectable>> {
+        let index = self.object_index(id)?;
+        let previous = std::mem::replace(&mut self.objects[index], object);
+        self.refit_tlas();
+        Some(previous)
+    }
+
+    /// Agrega una malla de triángulos a la escena
+    pub fn add_mesh(&mut self, mesh: TriangleMesh) -> usize {
+        self.add_object(Box::new(mesh))
+    }
+
+    /// Agrega una malla con niveles de detalle (ver `LodMesh`)
+    pub fn add_lod_mesh(&mut self, mesh: LodMesh) -> usize {
+        self.add_object(Box::new(mesh))
+    }
+
+    /// Agrega una instancia transformada de un objeto compartido (por
+    /// ejemplo una malla con su BVH); varias instancias pueden usar el mismo
+    pub fn add_instance(&mut self, object: Arc<dyn Intersectable>, transform: Transform) -> usize {
+        self.add_object(Box::new(Instance::new(object, transform)))
+    }
+
+    /// Cambia la transformación de una instancia y actualiza la TLAS
+    /// Retorna false si el objeto no existe o no admite transformaciones
+    pub fn set_transform(&mut self, object_id: usize, transform: Transform) -> bool {
+        let changed = self
+            .object_index(object_id)
+            .is_some_and(|index| self.objects[index].set_transform(transform));
+        if changed {
+            self.refit_tlas();
+        }
+        changed
+    }
+
+    /// Envuelve un objeto en una instancia con la transformación identidad
+    /// para que admita `set_transform` (conserva su ID, nombre y enlaces de
+    /// luz). Retorna false si el objeto no existe
+    pub fn make_transformable(&mut self, object_id: usize) -> bool {
+        let Some(index) = self.object_index(object_id) else {
+            return false;
+        };
+        let object = self.objects.remove(index);
+        self.objects.insert(index, Box::new(Instance::new(Arc::from(object), Transform::identity())));
+        self.refit_tlas();
+        true
+    }
+
+    pub fn set_animation(&mut self, timeline: Timeline) {
+        self.animation = Some(timeline);
+    }
+
+    /// Evalúa la animación en el instante `time` (en segundos)
+    /// Sin animación no hace nada
+    pub fn set_time(&mut self, time: f32) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(timeline) = self.animation.take() else {
+            return Ok(());
+        };
+        let result = timeline.apply(self, time);
+        self.animation = Some(timeline);
+        result
+    }
+
+    /// Ajusta las cajas de la TLAS a la posición actual de los objetos sin
+    /// reconstruirla. Si la estructura ya no sirve (se agregaron objetos o
+    /// alguno dejó de estar acotado) o el refit la degradó demasiado, se
+    /// descarta y se reconstruye desde cero con el próximo rayo.
+    pub fn refit_tlas(&mut self) {
+        let objects = &self.objects;
+        if let Some(tlas) = self.tlas.get_mut() {
+            if !tlas.refit(objects) {
+                self.invalidate_tlas();
+            }
+        }
+    }
+
+    /// Reconstruye la BVH de la escena sobre las cajas de los objetos ya
+    /// mismo, en lugar de esperar al primer rayo (p. ej. para no medir la
+    /// construcción en un benchmark)
+    pub fn rebuild_tlas(&mut self) {
+        self.tlas = OnceLock::from(Tlas::build(&self.objects));
+    }
+
+    /// Descarta la TLAS; se vuelve a construir con el próximo rayo
+    fn invalidate_tlas(&mut self) {
+        self.tlas = OnceLock::new();
+    }
+
+    /// Si la TLAS está construida y al día con los objetos
+    #[cfg(test)]
+    fn tlas_is_current(&self) -> bool {
+        self.tlas.get().is_some_and(|tlas| tlas.object_count == self.objects.len())
+    }
+
+    /// La TLAS, construyéndola si hace falta
+    fn tlas(&self) -> &Tlas {
+        self.tlas.get_or_init(|| Tlas::build(&self.objects))
+    }
+
+    /// Construye la TLAS si hace falta (antes de guardar la escena en una
+    /// instantánea, para que no haya que construirla al cargarla)
+    pub(crate) fn prepare_tlas(&self) {
+        self.tlas();
+    }
+
+    /// Agrega un objeto que se mueve a velocidad constante mientras el
+    /// obturador está abierto (ver `Camera::with_shutter`)
+    pub fn add_moving_object(&mut self, object: Box<dyn Intersectable>, velocity: Vec3) -> usize {
+        self.add_object(Box::new(MovingObject::new(object, velocity)))
     }
 
     /// Agrega una esfera a la escena
-    pub fn add_sphere(&mut self, sphere: Sphere) {
-        self.objects.push(Box::new(sphere));
+    pub fn add_sphere(&mut self, sphere: Sphere) -> usize {
+        self.add_object(Box::new(sphere))
     }
 
     /// Agrega un plano a la escena
-    pub fn add_plane(&mut self, plane: Plane) {
-        self.objects.push(Box::new(plane));
+    pub fn add_plane(&mut self, plane: Plane) -> usize {
+        self.add_object(Box::new(plane))
     }
 
     /// Agrega un cubo a la escena
-    pub fn add_cube(&mut self, cube: Cube) {
-        self.objects.push(Box::new(cube));
+    pub fn add_cube(&mut self, cube: Cube) -> usize {
+        self.add_object(Box::new(cube))
     }
 
     /// Agrega una pirámide a la escena
-    pub fn add_pyramid(&mut self, pyramid: Pyramid) {
-        self.objects.push(Box::new(pyramid));
+    pub fn add_pyramid(&mut self, pyramid: Pyramid) -> usize {
+        self.add_object(Box::new(pyramid))
     }
 
     /// Agrega una luz a la escena
-    pub fn add_light(&mut self, light: Light) {
+    pub fn add_light(&mut self, light: Light) -> usize {
         self.lights.push(light);
+        self.rebuild_light_sampler();
+        self.lights.len() - 1
+    }
+
+    /// Evalúa solo `samples_per_hit` luces por punto, elegidas según su potencia
+    /// Pensado para escenas con muchas luces; con pocas luces no tiene efecto
+    pub fn set_light_sampling(&mut self, samples_per_hit: u32) {
+        self.light_sampler = Some(LightSampler::new(&self.lights, samples_per_hit));
+    }
+
+    /// Reconstruye la distribución de luces tras modificar `lights`
+    pub fn rebuild_light_sampler(&mut self) {
+        if let Some(sampler) = &self.light_sampler {
+            self.light_sampler = Some(LightSampler::new(&self.lights, sampler.samples_per_hit));
+        }
+    }
+
+    /// Restringe la luz para que solo ilumine los objetos enlazados
+    /// Se puede llamar varias veces para enlazar más objetos
+    pub fn link_light(&mut self, light_id: usize, object_id: usize) {
+        self.light_links
+            .entry(light_id)
+            .or_default()
+            .include
+            .get_or_insert_with(HashSet::new)
+            .insert(object_id);
+    }
+
+    /// Excluye un objeto de la iluminación de una luz
+    pub fn exclude_from_light(&mut self, light_id: usize, object_id: usize) {
+        self.light_links.entry(light_id).or_default().exclude.insert(object_id);
+    }
+
+    /// Indica si una luz ilumina a un objeto según los enlaces de luz
+    pub fn light_affects(&self, light_id: usize, object_id: usize) -> bool {
+        match self.light_links.get(&light_id) {
+            Some(link) => link.affects(object_id),
+            None => true,
+        }
+    }
+
+    /// Define la luz ambiental (independiente del color de fondo)
+    pub fn set_ambient_light(&mut self, color: Color, intensity: f32) {
+        self.ambient_light = AmbientLight::new(color, intensity);
+    }
+
+    /// Número de rayos por píxel para el anti-aliasing (1 = desactivado)
+    pub fn set_samples_per_pixel(&mut self, samples: u32) {
+        self.settings.samples_per_pixel = samples.max(1);
+    }
+
+    /// Aplica resolución, muestras, profundidad y sesgo de una vez
+    /// La cámara pasa a renderizar con la resolución de los ajustes
+    pub fn set_render_settings(&mut self, settings: RenderSettings) {
+        self.camera.width = settings.width;
+        self.camera.height = settings.height;
+        self.settings = RenderSettings {
+            samples_per_pixel: settings.samples_per_pixel.max(1),
+            ..settings
+        };
+    }
+
+    /// Elige el generador de muestras (aleatorio, estratificado, Halton o ruido azul)
+    pub fn set_sampler(&mut self, sampler: SamplerKind) {
+        self.sampler = sampler;
+    }
+
+    /// Filtro con el que se combinan las muestras de cada píxel
+    pub fn set_pixel_filter(&mut self, filter: PixelFilter) {
+        self.pixel_filter = filter;
+    }
+
+    /// Semilla de todo el muestreo aleatorio; cambiarla da otra realización
+    /// del ruido con la misma escena
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+
+    /// Configura la supresión de fireflies (límite por muestra y rechazo de atípicos)
+    pub fn set_firefly_filter(&mut self, firefly: FireflyFilter) {
+        self.firefly = firefly;
+    }
+
+    /// Limita la distancia de los rayos; lo que queda más lejos no se ve
+    pub fn set_far_clip(&mut self, far_clip: FarClip) {
+        self.far_clip = Some(far_clip);
+    }
+
+    /// Distancia máxima a la que un rayo puede chocar con un objeto
+    pub fn max_ray_distance(&self) -> f32 {
+        self.far_clip.map_or(f32::INFINITY, |far_clip| far_clip.distance)
+    }
+
+    /// Elige el integrador (Whitted, solo oclusión ambiental o path tracing)
+    pub fn set_integrator(&mut self, integrator: IntegratorKind) {
+        self.integrator = integrator;
+    }
+
+    /// Filtra la imagen final para quitar el ruido de pocas muestras
+    pub fn set_denoiser(&mut self, denoiser: Denoiser) {
+        self.denoiser = Some(denoiser);
+    }
+
+    /// Activa la oclusión ambiental con el número de muestras y radio dados
+    pub fn set_ambient_occlusion(&mut self, samples: u32, radius: f32) {
+        self.ambient_occlusion = Some(AmbientOcclusion::new(samples, radius));
+    }
+
+    /// Usa un mapa de entorno como fondo y fuente de luz ambiental
+    pub fn set_environment(&mut self, environment: Environment) {
+        self.environment = Some(environment);
+    }
+
+    /// Usa un cielo físico como entorno y agrega el sol como luz direccional
+    /// Retorna el índice de la luz del sol
+    pub fn set_sky(&mut self, sky: SkyModel) -> usize {
+        self.environment = Some(Environment::Sky(sky));
+        self.add_light(Light::directional(sky.sun_direction(), sky.sun_color(), sky.sun_intensity))
+    }
+
+    /// Llena la escena (o una caja, según `medium.bounds`) con un medio participante
+    pub fn set_medium(&mut self, medium: Medium) {
+        self.medium = Some(medium);
+    }
+
+    /// Color que ve un rayo que no choca con ningún objeto
+    pub fn background(&self, direction: &Vec3) -> Color {
+        match &self.environment {
+            Some(environment) => environment.sample(direction),
+            None => self.background_color,
+        }
     }
 
     pub fn add_texture(&mut self, texture: Texture) -> usize {
@@ -148,20 +1037,393 @@ impl Scene {
         self.textures.len() - 1
     }
 
-    /// Encuentra la intersección más cercana en la escena
-    pub fn find_closest_intersection(&self, ray: &Ray) -> Option<(f32, &Box<dyn Intersectable>)> {
-        let mut closest_t = f32::INFINITY;
-        let mut closest_object: Option<&Box<dyn Intersectable>> = None;
+    /// Agrega una textura de datos horneada (ver `maps`) y retorna su ID
+    pub fn add_map(&mut self, map: Texture) -> usize {
+        self.maps.push(map);
+        self.maps.len() - 1
+    }
+
+    /// Da un nombre a un objeto, luz o textura para buscarlo después
+    /// Si el nombre ya estaba en uso pasa a referirse al nuevo elemento.
+    /// Retorna false (sin cambiar nada) si el ID no existe
+    pub fn set_name(&mut self, name: &str, item: SceneItem) -> bool {
+        let exists = match item {
+            SceneItem::Object(id) => self.object_index(id).is_some(),
+            SceneItem::Light(id) => id < self.lights.len(),
+            SceneItem::Texture(id) => id < self.textures.len(),
+        };
+        if exists {
+            self.names.insert(name.to_string(), item);
+        }
+        exists
+    }
 
+    /// Elemento con el nombre dado
+    pub fn find(&self, name: &str) -> Option<SceneItem> {
+        self.names.get(name).copied()
+    }
+
+    /// Nombre de un elemento, si tiene
+    pub fn name_of(&self, item: SceneItem) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(_, named)| **named == item)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Acceso mutable al elemento con el nombre dado, p. ej. para moverlo
+    /// entre fotogramas de una animación. Si se cambia la geometría de un
+    /// objeto hay que llamar a `refit_tlas`, y si se cambia la potencia de
+    /// una luz, a `rebuild_light_sampler`
+    pub fn get_mut(&mut self, name: &str) -> Option<SceneItemMut<'_>> {
+        match self.find(name)? {
+            SceneItem::Object(id) => {
+                let index = self.object_index(id)?;
+                Some(SceneItemMut::Object(&mut self.objects[index]))
+            }
+            SceneItem::Light(id) => self.lights.get_mut(id).map(SceneItemMut::Light),
+            SceneItem::Texture(id) => self.textures.get_mut(id).map(SceneItemMut::Texture),
+        }
+    }
+
+    /// Luz con el nombre dado (None si no existe o el nombre es de otro tipo)
+    pub fn light_mut(&mut self, name: &str) -> Option<&mut Light> {
+        match self.get_mut(name)? {
+            SceneItemMut::Light(light) => Some(light),
+            _ => None,
+        }
+    }
+
+    /// Recarga las texturas cuyos archivos cambiaron en disco
+    /// Retorna los IDs de las texturas recargadas. Si un archivo no se puede
+    /// leer (por ejemplo, mientras se está guardando) se conserva la versión
+    /// anterior y se vuelve a intentar en la siguiente llamada.
+    #[cfg(feature = "fs")]
+    pub fn reload_textures(&mut self) -> Vec<usize> {
+        let mut reloaded = Vec::new();
+
+        for (id, texture) in self.textures.iter_mut().enumerate() {
+            if let Ok(true) = texture.reload_if_changed() {
+                reloaded.push(id);
+            }
+        }
+
+        reloaded
+    }
+
+    /// Busca problemas que harían que el render saliera negro o roto sin
+    /// ningún error: luces sin potencia, cubos con min > max, materiales que
+    /// usan texturas inexistentes, posiciones con NaN... Conviene llamarla
+    /// antes de renderizar una escena armada a mano o leída de un archivo.
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut report = |severity, item: Option<SceneItem>, message: String| {
+            let name = item.and_then(|item| self.name_of(item)).map(str::to_string);
+            diagnostics.push(Diagnostic { severity, item, name, message });
+        };
+
+        let camera = &self.camera;
+        if !camera.position.is_finite() || !camera.look_at.is_finite() || !camera.up.is_finite() {
+            report(Severity::Error, None, "cámara con posición, punto de mira o vector up no finitos (NaN o infinito)".to_string());
+        } else if (camera.look_at - camera.position).length_squared() == 0.0 {
+            report(Severity::Error, None, "la cámara mira a su propia posición".to_string());
+        }
+
+        for (id, light) in self.lights.iter().enumerate() {
+            let item = Some(SceneItem::Light(id));
+            if !light.position.is_finite() || !light.color.is_finite() || !light.intensity.is_finite() {
+                report(Severity::Error, item, "posición, color o intensidad no finitos (NaN o infinito)".to_string());
+            } else if light.intensity < 0.0 {
+                report(Severity::Error, item, format!("intensidad negativa: {}", light.intensity));
+            } else if light.intensity == 0.0 || light.color.length_squared() == 0.0 {
+                report(Severity::Warning, item, "la luz tiene intensidad o color nulos y no ilumina nada".to_string());
+            }
+
+            let degenerate = |vector: Vec3| !vector.is_finite() || vector.length_squared() == 0.0;
+            match light.kind {
+                LightKind::Spot { direction, .. } | LightKind::Directional { direction } if degenerate(direction) => {
+                    report(Severity::Error, item, "dirección nula o no finita".to_string());
+                }
+                LightKind::Area { edge_u, edge_v, .. } if degenerate(edge_u.cross(&edge_v)) => {
+                    report(Severity::Error, item, "luz de área sin superficie (lados nulos, paralelos o no finitos)".to_string());
+                }
+                _ => {}
+            }
+        }
+
+        for (object, &id) in self.objects.iter().zip(&self.object_ids) {
+            let item = Some(SceneItem::Object(id));
+            for problem in object.validate() {
+                report(Severity::Error, item, problem);
+            }
+            if let Some(texture) = object.texture_id().filter(|&texture| texture >= self.textures.len()) {
+                report(
+                    Severity::Error,
+                    item,
+                    format!(
+                        "el material usa la textura {}, pero la escena solo tiene {} textura(s) (se verá con el color liso del material)",
+                        texture,
+                        self.textures.len()
+                    ),
+                );
+            }
+            let material = object.get_material();
+            for map in [material.occlusion_map, material.lightmap].into_iter().flatten() {
+                if map >= self.maps.len() {
+                    report(
+                        Severity::Warning,
+                        item,
+                        format!("el material usa el mapa horneado {}, pero la escena solo tiene {} (se ignora)", map, self.maps.len()),
+                    );
+                }
+            }
+        }
+
+        let emissive = self.objects.iter().any(|object| object.get_material().is_emissive());
+        let ambient = self.ambient_light.radiance().length_squared() > 0.0;
+        if self.lights.is_empty() && !ambient && self.environment.is_none() && !emissive {
+            report(
+                Severity::Warning,
+                None,
+                "la escena no tiene luces, luz ambiental, entorno ni objetos emisivos: los objetos se verán negros".to_string(),
+            );
+        }
+
+        diagnostics
+    }
+
+    /// Calcula un hash de todo lo que influye en la imagen renderizada
+    /// (cámara, fondo, luces, objetos y texturas). Si dos escenas tienen el
+    /// mismo hash producen la misma imagen.
+    pub fn fingerprint(&self) -> u64 {
+        let mut state = StableHasher::new();
+
+        let camera = &self.camera;
+        hash_vec3(&mut state, &camera.position);
+        hash_vec3(&mut state, &camera.look_at);
+        hash_vec3(&mut state, &camera.up);
+        hash_f32(&mut state, camera.roll);
+        hash_f32(&mut state, camera.fov);
+        hash_f32(&mut state, camera.aspect_ratio);
+        camera.projection.hash_state(&mut state);
+        hash_f32(&mut state, camera.shift_x);
+        hash_f32(&mut state, camera.shift_y);
+        state.write_u32(camera.width);
+        state.write_u32(camera.height);
+        hash_f32(&mut state, camera.aperture);
+        hash_f32(&mut state, camera.focus_distance);
+        hash_f32(&mut state, camera.exposure_compensation);
+        hash_f32(&mut state, camera.shutter_speed);
+        hash_f32(&mut state, camera.iso);
+        hash_f32(&mut state, camera.shutter_open);
+        hash_f32(&mut state, camera.shutter_close);
+        hash_vec3(&mut state, &camera.velocity);
+
+        hash_vec3(&mut state, &self.background_color);
+        self.settings.hash_state(&mut state);
+        state.write_u8(self.sampler as u8);
+        self.pixel_filter.hash_state(&mut state);
+        state.write_u8(self.integrator as u8);
+        state.write_u64(self.seed);
+        self.firefly.hash_state(&mut state);
+        match &self.far_clip {
+            Some(far_clip) => far_clip.hash_state(&mut state),
+            None => state.write_u8(u8::MAX),
+        }
+        hash_vec3(&mut state, &self.ambient_light.color);
+        hash_f32(&mut state, self.ambient_light.intensity);
+
+        match &self.ambient_occlusion {
+            Some(ao) => {
+                state.write_u32(ao.samples);
+                hash_f32(&mut state, ao.radius);
+            }
+            None => state.write_u32(0),
+        }
+
+        state.write_usize(self.lights.len());
+        for light in &self.lights {
+            light.hash_state(&mut state);
+        }
+
+        state.write_u32(self.light_sampler.as_ref().map_or(0, |sampler| sampler.samples_per_hit));
+
+        let mut linked_lights: Vec<&usize> = self.light_links.keys().collect();
+        linked_lights.sort();
+        for light_id in linked_lights {
+            let link = &self.light_links[light_id];
+            state.write_usize(*light_id);
+
+            let mut include: Vec<usize> = link.include.iter().flatten().copied().collect();
+            include.sort();
+            state.write_u8(link.include.is_some() as u8);
+            for id in include {
+                state.write_usize(id);
+            }
+
+            let mut exclude: Vec<usize> = link.exclude.iter().copied().collect();
+            exclude.sort();
+            for id in exclude {
+                state.write_usize(id);
+            }
+        }
+
+        state.write_usize(self.objects.len());
         for object in &self.objects {
+            object.hash_state(&mut state);
+        }
+
+        state.write_usize(self.textures.len());
+        for texture in &self.textures {
+            texture.hash_state(&mut state);
+        }
+        state.write_usize(self.maps.len());
+        for map in &self.maps {
+            map.hash_state(&mut state);
+        }
+
+        match &self.environment {
+            Some(environment) => environment.hash_state(&mut state),
+            None => state.write_u8(u8::MAX),
+        }
+
+        match &self.medium {
+            Some(medium) => medium.hash_state(&mut state),
+            None => state.write_u8(u8::MAX),
+        }
+
+        match &self.denoiser {
+            Some(denoiser) => denoiser.hash_state(&mut state),
+            None => state.write_u32(0),
+        }
+
+        state.finish()
+    }
+
+    /// Encuentra la intersección más cercana en la escena
+    pub fn find_closest_intersection(&self, ray: &Ray) -> Option<(f32, &dyn Intersectable)> {
+        self.find_closest_hit(ray)
+            .map(|(t, id)| (t, self.objects[id].as_ref()))
+    }
+
+    /// Encuentra la intersección más cercana y retorna la distancia y el ID del objeto
+    /// Las intersecciones más allá del plano lejano (si lo hay) no cuentan
+    pub fn find_closest_hit(&self, ray: &Ray) -> Option<(f32, usize)> {
+        let max_distance = self.max_ray_distance();
+
+        // Si se agregaron objetos directamente al vector después de
+        // construir la TLAS, está desactualizada y se prueban todos
+        let tlas = self.tlas();
+        if tlas.object_count != self.objects.len() {
+            let objects = self.objects.iter().enumerate().map(|(id, object)| (id, object.as_ref()));
+            return Self::closest_of(ray, max_distance, objects);
+        }
+
+        let bounded = tlas.bvh.intersect_within(ray, max_distance, |id, ray| self.objects[id].intersect(ray));
+        let unbounded = Self::closest_of(
+            ray,
+            max_distance,
+            tlas.unbounded.iter().map(|&id| (id, self.objects[id].as_ref())),
+        );
+
+        match (bounded, unbounded) {
+            (Some(a), Some(b)) => Some(if b.0 < a.0 { b } else { a }),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Intersección más cercana de cada rayo de un paquete
+    pub fn find_closest_hits(&self, rays: &RayPacket) -> [Option<(f32, usize)>; LANES] {
+        let tlas = self.tlas();
+        if tlas.object_count != self.objects.len() {
+            return std::array::from_fn(|lane| {
+                if rays.active[lane] { self.find_closest_hit(&rays.ray(lane)) } else { None }
+            });
+        }
+
+        let max_distance = self.max_ray_distance();
+        let mut closest = tlas
+            .bvh
+            .intersect_packet_within(rays, max_distance, |id, rays| self.objects[id].intersect_packet(rays));
+
+        for &id in &tlas.unbounded {
+            stats::count(Counter::IntersectionTests);
+            let hits = self.objects[id].intersect_packet(rays);
+            for lane in 0..LANES {
+                if let Some(t) = hits[lane] {
+                    if t < closest[lane].map_or(max_distance, |(best, _)| best) {
+                        closest[lane] = Some((t, id));
+                    }
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Intersección más cercana (antes de `max_distance`) recorriendo los
+    /// objetos uno por uno
+    fn closest_of<'a>(
+        ray: &Ray,
+        max_distance: f32,
+        objects: impl Iterator<Item = (usize, &'a dyn Intersectable)>,
+    ) -> Option<(f32, usize)> {
+        let mut closest_t = max_distance;
+        let mut closest_id: Option<usize> = None;
+
+        for (id, object) in objects {
+            stats::count(Counter::IntersectionTests);
             if let Some(t) = object.intersect(ray) {
                 if t < closest_t {
                     closest_t = t;
-                    closest_object = Some(object);
+                    closest_id = Some(id);
                 }
             }
         }
 
-        closest_object.map(|obj| (closest_t, obj))
+        closest_id.map(|id| (closest_t, id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_scene() -> Scene {
+        let camera = Camera::new(Point3::new(0.0, 0.0, 10.0), Point3::zero(), Vec3::new(0.0, 1.0, 0.0), 45.0, 1.0, 16, 16);
+        Scene::new(camera, Color::zero())
+    }
+
+    fn gray() -> Material {
+        Material::diffuse(Color::new(0.5, 0.5, 0.5))
+    }
+
+    #[test]
+    fn bulk_insertion_builds_the_tlas_once() {
+        let mut scene = empty_scene();
+        for i in 0..2000 {
+            let center = Point3::new((i % 50) as f32 * 3.0, (i / 50) as f32 * 3.0, 0.0);
+            scene.add_sphere(Sphere::new(center, 1.0, gray()));
+            // Agregar un objeto solo descarta la TLAS; no la reconstruye
+            assert!(!scene.tlas_is_current());
+        }
+
+        let ray = Ray::new(Point3::new(30.0, 30.0, 10.0), Vec3::new(0.0, 0.0, -1.0));
+        let (t, index) = scene.find_closest_hit(&ray).expect("el rayo debería chocar con una esfera");
+        assert!(scene.tlas_is_current());
+        assert!((t - 9.0).abs() < 1e-3);
+        assert_eq!(index, 10 * 50 + 10);
+    }
+
+    #[test]
+    fn tlas_is_rebuilt_after_adding_objects() {
+        let mut scene = empty_scene();
+        scene.add_sphere(Sphere::new(Point3::new(-5.0, 0.0, 0.0), 1.0, gray()));
+        let ray = Ray::new(Point3::new(5.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(scene.find_closest_hit(&ray).is_none());
+
+        let id = scene.add_sphere(Sphere::new(Point3::new(5.0, 0.0, 0.0), 1.0, gray()));
+        assert!(!scene.tlas_is_current());
+        assert_eq!(scene.find_closest_hit(&ray).map(|(_, index)| scene.object_id(index)), Some(id));
     }
 }
@@ -0,0 +1,252 @@
+use crate::vector::{Vec3, Color, Point3};
+use crate::camera::Camera;
+use crate::material::Material;
+use crate::light::Light;
+use crate::sphere::Sphere;
+use crate::plane::Plane;
+use crate::cube::Cube;
+use crate::pyramid::Pyramid;
+use crate::mesh::TriangleMesh;
+use crate::texture::Texture;
+use crate::scene::{Scene, SceneItem, Intersectable};
+use crate::settings::RenderSettings;
+
+/// Construcción encadenada de escenas con valores por defecto razonables
+///
+/// ```no_run
+/// use raytracer::light::Light;
+/// use raytracer::material::Material;
+/// use raytracer::scene_builder::SceneBuilder;
+/// use raytracer::vector::{Color, Point3, Vec3};
+///
+/// let scene = SceneBuilder::new()
+///     .resolution(640, 480)
+///     .camera(Point3::new(0.0, 1.0, 5.0), Point3::zero())
+///     .light(Light::white(Point3::new(5.0, 5.0, 5.0), 1.0))
+///     .sphere(Point3::zero(), 1.0, Material::shiny(Color::new(0.8, 0.3, 0.3)))
+///     .plane(Point3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Material::diffuse(Color::new(0.8, 0.8, 0.8)))
+///     .build()
+///     .unwrap();
+/// ```
+///
+/// Los valores inválidos (radios negativos, normales nulas, una cámara que
+/// se mira a sí misma...) no se detectan al llamar al método sino en
+/// `build`, que los reporta todos juntos.
+pub struct SceneBuilder {
+    settings: RenderSettings,
+    position: Point3,
+    look_at: Point3,
+    up: Vec3,
+    fov: f32,
+    configure_camera: Option<Box<dyn FnOnce(Camera) -> Camera>>,
+    background: Color,
+    ambient: Option<(Color, f32)>,
+    textures: Vec<Texture>,
+    lights: Vec<Light>,
+    objects: Vec<Box<dyn Intersectable>>,
+    names: Vec<(String, SceneItem)>,
+    last: Option<SceneItem>,
+    errors: Vec<String>,
+}
+
+impl Default for SceneBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SceneBuilder {
+    /// Escena vacía de 800x600 con la cámara en (0, 1, 5) mirando al origen
+    pub fn new() -> Self {
+        SceneBuilder {
+            settings: RenderSettings::default(),
+            position: Point3::new(0.0, 1.0, 5.0),
+            look_at: Point3::zero(),
+            up: Vec3::new(0.0, 1.0, 0.0),
+            fov: 45.0,
+            configure_camera: None,
+            background: Color::new(0.2, 0.2, 0.25),
+            ambient: None,
+            textures: Vec::new(),
+            lights: Vec::new(),
+            objects: Vec::new(),
+            names: Vec::new(),
+            last: None,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn resolution(mut self, width: u32, height: u32) -> Self {
+        if width == 0 || height == 0 {
+            self.errors.push(format!("resolución inválida: {}x{}", width, height));
+        }
+        self.settings = RenderSettings { width: width.max(1), height: height.max(1), ..self.settings };
+        self
+    }
+
+    pub fn samples_per_pixel(mut self, samples: u32) -> Self {
+        self.settings = self.settings.with_samples_per_pixel(samples);
+        self
+    }
+
+    /// Reemplaza todos los ajustes de render (resolución incluida)
+    pub fn settings(mut self, settings: RenderSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Posición de la cámara y punto al que mira
+    pub fn camera(mut self, position: Point3, look_at: Point3) -> Self {
+        if (look_at - position).length_squared() == 0.0 {
+            self.errors.push("la cámara mira a su propia posición".to_string());
+        }
+        self.position = position;
+        self.look_at = look_at;
+        self
+    }
+
+    pub fn up(mut self, up: Vec3) -> Self {
+        if up.length_squared() == 0.0 {
+            self.errors.push("el vector up de la cámara es nulo".to_string());
+        }
+        self.up = up;
+        self
+    }
+
+    /// Campo de visión vertical en grados
+    pub fn fov(mut self, fov: f32) -> Self {
+        if !(fov > 0.0 && fov < 180.0) {
+            self.errors.push(format!("fov fuera de rango (0, 180): {}", fov));
+        }
+        self.fov = fov;
+        self
+    }
+
+    /// Ajustes adicionales de la cámara (profundidad de campo, proyección...)
+    /// una vez creada con la resolución final
+    pub fn configure_camera(mut self, configure: impl FnOnce(Camera) -> Camera + 'static) -> Self {
+        self.configure_camera = Some(Box::new(configure));
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = color;
+        self
+    }
+
+    pub fn ambient(mut self, color: Color, intensity: f32) -> Self {
+        self.ambient = Some((color, intensity));
+        self
+    }
+
+    /// Agrega una textura; su índice es el orden en que se agregó
+    pub fn texture(mut self, texture: Texture) -> Self {
+        self.textures.push(texture);
+        self.last = Some(SceneItem::Texture(self.textures.len() - 1));
+        self
+    }
+
+    pub fn light(mut self, light: Light) -> Self {
+        if light.intensity < 0.0 {
+            self.errors.push(format!("luz con intensidad negativa: {}", light.intensity));
+        }
+        self.lights.push(light);
+        self.last = Some(SceneItem::Light(self.lights.len() - 1));
+        self
+    }
+
+    pub fn sphere(mut self, center: Point3, radius: f32, material: Material) -> Self {
+        if radius <= 0.0 {
+            self.errors.push(format!("esfera con radio no positivo: {}", radius));
+        }
+        self.object(Box::new(Sphere::new(center, radius, material)))
+    }
+
+    pub fn plane(mut self, point: Point3, normal: Vec3, material: Material) -> Self {
+        if normal.length_squared() == 0.0 {
+            self.errors.push("plano con normal nula".to_string());
+        }
+        self.object(Box::new(Plane::new(point, normal, material)))
+    }
+
+    /// Cubo centrado en `center` con arista `size`
+    pub fn cube(mut self, center: Point3, size: f32, material: Material) -> Self {
+        if size <= 0.0 {
+            self.errors.push(format!("cubo con tamaño no positivo: {}", size));
+        }
+        self.object(Box::new(Cube::centered(center, size, material)))
+    }
+
+    pub fn pyramid(mut self, center: Point3, size: f32, material: Material) -> Self {
+        if size <= 0.0 {
+            self.errors.push(format!("pirámide con tamaño no positivo: {}", size));
+        }
+        self.object(Box::new(Pyramid::centered(center, size, material)))
+    }
+
+    pub fn mesh(self, mesh: TriangleMesh) -> Self {
+        self.object(Box::new(mesh))
+    }
+
+    /// Cualquier otro objeto intersectable
+    pub fn object(mut self, object: Box<dyn Intersectable>) -> Self {
+        self.objects.push(object);
+        self.last = Some(SceneItem::Object(self.objects.len() - 1));
+        self
+    }
+
+    /// Da un nombre al último objeto, luz o textura agregado
+    /// (`.sphere(...).named("pelota")`)
+    pub fn named(mut self, name: &str) -> Self {
+        match self.last {
+            Some(item) => self.names.push((name.to_string(), item)),
+            None => self.errors.push(format!("nombre sin elemento al que asignarlo: {}", name)),
+        }
+        self
+    }
+
+    /// Crea la escena, o retorna todos los problemas encontrados
+    pub fn build(self) -> Result<Scene, Box<dyn std::error::Error>> {
+        let mut errors = self.errors;
+        let emissive = self.objects.iter().any(|object| object.get_material().is_emissive());
+        if self.lights.is_empty() && self.ambient.is_none() && !emissive {
+            errors.push("la escena no tiene luces, luz ambiental ni objetos emisivos".to_string());
+        }
+        if !errors.is_empty() {
+            return Err(format!("escena inválida: {}", errors.join("; ")).into());
+        }
+
+        let mut camera = Camera::new(
+            self.position,
+            self.look_at,
+            self.up,
+            self.fov,
+            self.settings.aspect_ratio(),
+            self.settings.width,
+            self.settings.height,
+        );
+        if let Some(configure) = self.configure_camera {
+            camera = configure(camera);
+        }
+
+        let mut scene = Scene::new(camera, self.background);
+        scene.set_render_settings(self.settings);
+        if let Some((color, intensity)) = self.ambient {
+            scene.set_ambient_light(color, intensity);
+        }
+        for texture in self.textures {
+            scene.add_texture(texture);
+        }
+        for light in self.lights {
+            scene.add_light(light);
+        }
+        for object in self.objects {
+            scene.add_object(object);
+        }
+        // La escena empieza vacía, así que los IDs coinciden con los del constructor
+        for (name, item) in &self.names {
+            scene.set_name(name, *item);
+        }
+        Ok(scene)
+    }
+}
@@ -0,0 +1,828 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::vector::{Vec3, Color, Point3};
+use crate::camera::Camera;
+use crate::material::Material;
+use crate::light::Light;
+use crate::sphere::Sphere;
+use crate::plane::Plane;
+use crate::cube::Cube;
+use crate::pyramid::Pyramid;
+use crate::mesh::TriangleMesh;
+use crate::obj;
+use crate::lod::{LodMesh, LodMetric};
+use crate::texture::Texture;
+use crate::scene::{Scene, SceneItem};
+use crate::settings::RenderSettings;
+use crate::sampler::SamplerKind;
+use crate::far_clip::FarClip;
+use crate::filter::PixelFilter;
+use crate::tonemap::ToneMapping;
+use crate::exposure::Exposure;
+use crate::gamma::ColorEncoding;
+use crate::postprocess::PostEffect;
+use crate::camera_path::{CameraPath, Interpolation};
+use crate::animation::{Timeline, ObjectAnimation, LightAnimation};
+use crate::assets::AssetPaths;
+
+/// Descripción de una escena en JSON, para crear escenas nuevas sin
+/// recompilar. Ejemplo mínimo:
+///
+/// ```json
+/// {
+///   "camera": { "position": [3, 2.5, 4], "look_at": [0, 0.5, 0], "fov": 45 },
+///   "settings": { "width": 800, "height": 600, "samples_per_pixel": 4, "sampler": "halton", "tone_mapping": "aces" },
+///   "materials": { "piedra": { "type": "diffuse", "color": [0.85, 0.85, 0.85] } },
+///   "objects": [
+///     { "type": "plane", "point": [0, -1, 0], "normal": [0, 1, 0], "material": "piedra" },
+///     { "type": "sphere", "name": "bola", "center": [0, 0.5, 0], "radius": 1, "material": { "type": "reflective" } }
+///   ],
+///   "lights": [
+///     { "type": "point", "position": [5, 6, 4], "intensity": 1 },
+///     { "type": "point", "position": [-2, 1, 3], "intensity": 0.5, "include": ["bola"] }
+///   ]
+/// }
+/// ```
+///
+/// Los vectores y colores son arreglos de tres números. Los materiales se
+/// pueden definir en línea o por nombre en `materials`. Los objetos y las
+/// luces pueden tener un `name` (ver `Scene::set_name`), y las luces pueden
+/// limitar los objetos que iluminan con `include` y `exclude`, listas de
+/// nombres o de posiciones en `objects` (ver `Scene::link_light`). Las rutas de las
+/// texturas y de las mallas .obj (`{ "type": "obj", "file": "tetera.obj" }`)
+/// se resuelven con `AssetPaths`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    camera: CameraDesc,
+    #[serde(default)]
+    settings: SettingsDesc,
+    #[serde(default = "default_background")]
+    background: [f32; 3],
+    #[serde(default)]
+    ambient: Option<AmbientDesc>,
+    #[serde(default)]
+    textures: Vec<TextureDesc>,
+    /// Mapas horneados (ver `Scene::maps`), para `occlusion_map` y `lightmap`
+    #[serde(default)]
+    maps: Vec<String>,
+    #[serde(default)]
+    materials: HashMap<String, MaterialDesc>,
+    #[serde(default)]
+    objects: Vec<ObjectEntry>,
+    #[serde(default)]
+    lights: Vec<LightEntry>,
+    #[serde(default)]
+    animation: Option<AnimationDesc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraDesc {
+    position: [f32; 3],
+    look_at: [f32; 3],
+    #[serde(default = "default_up")]
+    up: [f32; 3],
+    #[serde(default = "default_fov")]
+    fov: f32,
+    #[serde(default)]
+    roll: f32,
+    #[serde(default)]
+    aperture: f32,
+    focus_distance: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+struct SettingsDesc {
+    width: u32,
+    height: u32,
+    samples_per_pixel: u32,
+    max_depth: u32,
+    bias: f32,
+    /// Sampler de las muestras de cada píxel (por defecto, estratificado)
+    sampler: Option<SamplerKind>,
+    /// Plano lejano: `{ "distance": 500, "fade_start": 400 }` (ver `FarClip`)
+    far_clip: Option<FarClipDesc>,
+    /// Filtro de reconstrucción: `{ "type": "gaussian", "radius": 1.5 }`
+    /// (ver `PixelFilter`)
+    pixel_filter: Option<PixelFilterDesc>,
+    /// Tone mapping de la imagen final: "clamp", "reinhard" o "aces"
+    tone_mapping: Option<ToneMapping>,
+    /// Exposición en EV antes del tone mapping, o "auto" para elegirla
+    /// según la imagen (ver `Exposure`)
+    exposure: Option<ExposureDesc>,
+    /// Codificación de la imagen final: "srgb", "linear" o un valor de gamma
+    encoding: Option<EncodingDesc>,
+    /// Efectos sobre la imagen final, en orden (ver `PostEffect`), p. ej.
+    /// `[{ "bloom": { "threshold": 1, "intensity": 0.3, "radius": 12 } },
+    /// { "vignette": { "strength": 0.4, "radius": 0.5 } }, { "grain": { "amount": 0.05 } }]`
+    post_effects: Vec<PostEffect>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum PixelFilterDesc {
+    Box {
+        #[serde(default = "default_box_radius")]
+        radius: f32,
+    },
+    Tent { radius: f32 },
+    Gaussian {
+        radius: f32,
+        #[serde(default = "default_gaussian_alpha")]
+        alpha: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+enum ExposureDesc {
+    Ev(f32),
+    Auto(AutoExposure),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AutoExposure {
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(untagged)]
+enum EncodingDesc {
+    Gamma(f32),
+    Named(ColorEncoding),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FarClipDesc {
+    distance: f32,
+    fade_start: Option<f32>,
+}
+
+impl Default for SettingsDesc {
+    fn default() -> Self {
+        let defaults = RenderSettings::default();
+        SettingsDesc {
+            width: defaults.width,
+            height: defaults.height,
+            samples_per_pixel: defaults.samples_per_pixel,
+            max_depth: defaults.max_depth,
+            bias: defaults.bias,
+            sampler: None,
+            far_clip: None,
+            pixel_filter: None,
+            tone_mapping: None,
+            exposure: None,
+            encoding: None,
+            post_effects: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AmbientDesc {
+    color: [f32; 3],
+    intensity: f32,
+}
+
+/// Textura: ruta de una imagen o un color sólido
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TextureDesc {
+    Image(String),
+    Solid { color: [f32; 3] },
+}
+
+/// Material a partir de uno de los constructores de `Material`, con
+/// cualquier propiedad sobrescrita
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MaterialDesc {
+    #[serde(rename = "type", default)]
+    preset: MaterialPreset,
+    #[serde(default = "default_color")]
+    color: [f32; 3],
+    albedo: Option<f32>,
+    specular: Option<f32>,
+    shininess: Option<f32>,
+    reflectivity: Option<f32>,
+    transparency: Option<f32>,
+    roughness: Option<f32>,
+    emission: Option<[f32; 3]>,
+    texture: Option<usize>,
+    max_depth: Option<u32>,
+    occlusion_map: Option<usize>,
+    lightmap: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum MaterialPreset {
+    #[default]
+    Default,
+    Diffuse,
+    Shiny,
+    Reflective,
+    Transparent,
+    Emissive,
+}
+
+/// Material por nombre (de `materials`) o definido en línea
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MaterialRef {
+    Named(String),
+    Inline(MaterialDesc),
+}
+
+/// Objeto de la escena con su nombre opcional
+#[derive(Debug, Deserialize)]
+struct ObjectEntry {
+    name: Option<String>,
+    #[serde(flatten)]
+    object: ObjectDesc,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum ObjectDesc {
+    Sphere { center: [f32; 3], radius: f32, material: MaterialRef },
+    Plane { point: [f32; 3], normal: [f32; 3], material: MaterialRef },
+    /// Cubo centrado en `center` con arista `size`
+    Cube { center: [f32; 3], size: f32, material: MaterialRef },
+    /// Caja alineada a los ejes entre `min` y `max`
+    Box { min: [f32; 3], max: [f32; 3], material: MaterialRef },
+    Pyramid { center: [f32; 3], size: f32, material: MaterialRef },
+    Mesh { vertices: Vec<[f32; 3]>, triangles: Vec<[usize; 3]>, material: MaterialRef },
+    /// Malla de un archivo Wavefront .obj
+    Obj { file: String, material: MaterialRef },
+    /// Malla con niveles de detalle en archivos .obj, del más detallado al
+    /// más simple (ver `LodMesh`); el umbral del primero no se usa
+    Lod {
+        levels: Vec<LodLevelDesc>,
+        #[serde(default)]
+        metric: LodMetric,
+        material: MaterialRef,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LodLevelDesc {
+    file: String,
+    #[serde(default)]
+    threshold: f32,
+}
+
+/// Luz de la escena con su nombre opcional y su enlace de luz
+#[derive(Debug, Deserialize)]
+struct LightEntry {
+    name: Option<String>,
+    /// Si está, la luz solo ilumina estos objetos
+    include: Option<Vec<ObjectRef>>,
+    /// Objetos que la luz no ilumina
+    #[serde(default)]
+    exclude: Vec<ObjectRef>,
+    #[serde(flatten)]
+    light: LightDesc,
+}
+
+/// Objeto por nombre o por su posición en `objects`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ObjectRef {
+    Index(usize),
+    Named(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum LightDesc {
+    Point {
+        position: [f32; 3],
+        #[serde(default = "default_color")]
+        color: [f32; 3],
+        intensity: f32,
+        #[serde(default = "default_true")]
+        shadows: bool,
+    },
+    Spot {
+        position: [f32; 3],
+        direction: [f32; 3],
+        #[serde(default = "default_color")]
+        color: [f32; 3],
+        intensity: f32,
+        inner_angle: f32,
+        outer_angle: f32,
+        #[serde(default = "default_true")]
+        shadows: bool,
+    },
+    Area {
+        corner: [f32; 3],
+        edge_u: [f32; 3],
+        edge_v: [f32; 3],
+        #[serde(default = "default_color")]
+        color: [f32; 3],
+        intensity: f32,
+        #[serde(default = "default_light_samples")]
+        samples: u32,
+        #[serde(default = "default_true")]
+        shadows: bool,
+    },
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+        #[serde(default = "default_color")]
+        color: [f32; 3],
+        intensity: f32,
+        #[serde(default = "default_light_samples")]
+        samples: u32,
+        #[serde(default = "default_true")]
+        shadows: bool,
+    },
+    Directional {
+        direction: [f32; 3],
+        #[serde(default = "default_color")]
+        color: [f32; 3],
+        intensity: f32,
+        #[serde(default = "default_true")]
+        shadows: bool,
+    },
+}
+
+/// Animación: los objetos y las luces se indican por su posición en
+/// `objects` y `lights`, y los tiempos van en segundos. Ejemplo:
+///
+/// ```json
+/// "animation": {
+///   "fps": 24,
+///   "camera": { "keyframes": [
+///     { "time": 0, "position": [3, 2.5, 4], "look_at": [0, 0.5, 0] },
+///     { "time": 2, "position": [-3, 2.5, 4], "look_at": [0, 0.5, 0] }
+///   ] },
+///   "objects": [{ "object": 1, "keyframes": [
+///     { "time": 0, "rotation": [0, 0, 0] },
+///     { "time": 2, "rotation": [0, 90, 0], "translation": [0, 1, 0] }
+///   ] }],
+///   "lights": [{ "light": 0, "keyframes": [{ "time": 0, "intensity": 1 }, { "time": 2, "intensity": 0.2 }] }]
+/// }
+/// ```
+///
+/// Si un objeto no indica `pivot`, rota y escala alrededor del centro de
+/// su caja envolvente.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AnimationDesc {
+    #[serde(default = "default_fps")]
+    fps: f32,
+    #[serde(default)]
+    camera: Option<CameraPathDesc>,
+    #[serde(default)]
+    objects: Vec<ObjectAnimationDesc>,
+    #[serde(default)]
+    lights: Vec<LightAnimationDesc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraPathDesc {
+    #[serde(default)]
+    interpolation: InterpolationDesc,
+    keyframes: Vec<CameraKeyframeDesc>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum InterpolationDesc {
+    Linear,
+    #[default]
+    CatmullRom,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraKeyframeDesc {
+    time: f32,
+    position: [f32; 3],
+    look_at: [f32; 3],
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectAnimationDesc {
+    object: usize,
+    pivot: Option<[f32; 3]>,
+    keyframes: Vec<ObjectKeyframeDesc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectKeyframeDesc {
+    time: f32,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 3]>,
+    scale: Option<[f32; 3]>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LightAnimationDesc {
+    light: usize,
+    keyframes: Vec<LightKeyframeDesc>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LightKeyframeDesc {
+    time: f32,
+    position: Option<[f32; 3]>,
+    color: Option<[f32; 3]>,
+    intensity: Option<f32>,
+}
+
+fn default_background() -> [f32; 3] {
+    [0.2, 0.2, 0.25]
+}
+
+fn default_box_radius() -> f32 {
+    0.5
+}
+
+fn default_gaussian_alpha() -> f32 {
+    2.0
+}
+
+fn default_up() -> [f32; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn default_fov() -> f32 {
+    45.0
+}
+
+fn default_color() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_light_samples() -> u32 {
+    4
+}
+
+fn default_fps() -> f32 {
+    24.0
+}
+
+fn vec3(v: [f32; 3]) -> Vec3 {
+    Vec3::from(v)
+}
+
+/// Lee y construye la escena descrita en el archivo JSON `path`
+/// Las rutas de las texturas y mallas son relativas al archivo de la escena
+/// (o, si no están ahí, al directorio de trabajo)
+pub fn load(path: &str) -> Result<Scene, Box<dyn std::error::Error>> {
+    load_with(path, &AssetPaths::default())
+}
+
+/// Como `load`, buscando además en los directorios de `assets`
+pub fn load_with(path: &str, assets: &AssetPaths) -> Result<Scene, Box<dyn std::error::Error>> {
+    let text = crate::assets::read_to_string(path)
+        .map_err(|e| format!("no se pudo leer {}: {}", path, e))?;
+    let base_dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let assets = assets.clone().with_base_dir(base_dir);
+    parse_with(&text, &assets).map_err(|e| format!("{}: {}", path, e).into())
+}
+
+/// Construye una escena a partir del texto JSON
+/// Las rutas de las texturas y mallas son relativas al directorio de trabajo
+pub fn parse(text: &str) -> Result<Scene, Box<dyn std::error::Error>> {
+    parse_with(text, &AssetPaths::default())
+}
+
+/// Como `parse`, buscando las texturas y mallas según `assets`
+pub fn parse_with(text: &str, assets: &AssetPaths) -> Result<Scene, Box<dyn std::error::Error>> {
+    let file: SceneFile = serde_json::from_str(text)?;
+
+    let settings = RenderSettings::new(file.settings.width, file.settings.height)
+        .with_samples_per_pixel(file.settings.samples_per_pixel)
+        .with_max_depth(file.settings.max_depth)
+        .with_bias(file.settings.bias);
+
+    let desc = &file.camera;
+    let mut camera = Camera::new(
+        vec3(desc.position),
+        vec3(desc.look_at),
+        vec3(desc.up),
+        desc.fov,
+        settings.aspect_ratio(),
+        settings.width,
+        settings.height,
+    )
+    .with_roll(desc.roll);
+    if desc.aperture > 0.0 || desc.focus_distance.is_some() {
+        let focus_distance = desc.focus_distance.unwrap_or(camera.focus_distance);
+        camera = camera.with_depth_of_field(desc.aperture, focus_distance);
+    }
+
+    let mut scene = Scene::new(camera, vec3(file.background));
+    scene.set_render_settings(settings);
+    if let Some(sampler) = file.settings.sampler {
+        scene.set_sampler(sampler);
+    }
+    if let Some(desc) = file.settings.far_clip {
+        if desc.distance.is_nan() || desc.distance <= 0.0 {
+            return Err(format!("distancia del plano lejano no positiva: {}", desc.distance).into());
+        }
+        let far_clip = FarClip::new(desc.distance);
+        scene.set_far_clip(match desc.fade_start {
+            Some(start) => far_clip.with_fade(start),
+            None => far_clip,
+        });
+    }
+    if let Some(filter) = file.settings.pixel_filter {
+        scene.set_pixel_filter(match filter {
+            PixelFilterDesc::Box { radius } => PixelFilter::Box { radius: radius.max(0.5) },
+            PixelFilterDesc::Tent { radius } => PixelFilter::tent(radius),
+            PixelFilterDesc::Gaussian { radius, alpha } => PixelFilter::Gaussian { radius: radius.max(0.5), alpha },
+        });
+    }
+    if let Some(tone_mapping) = file.settings.tone_mapping {
+        scene.development.tone_mapping = tone_mapping;
+    }
+    if let Some(exposure) = file.settings.exposure {
+        scene.development.exposure = match exposure {
+            ExposureDesc::Ev(ev) => Exposure::Fixed(ev),
+            ExposureDesc::Auto(AutoExposure::Auto) => Exposure::auto(),
+        };
+    }
+    if let Some(encoding) = file.settings.encoding {
+        scene.development.encoding = match encoding {
+            EncodingDesc::Gamma(gamma) if gamma > 0.0 => ColorEncoding::Gamma(gamma),
+            EncodingDesc::Gamma(gamma) => return Err(format!("gamma de salida no positiva: {}", gamma).into()),
+            EncodingDesc::Named(encoding) => encoding,
+        };
+    }
+    scene.development.effects = file.settings.post_effects.clone();
+    if let Some(ambient) = &file.ambient {
+        scene.set_ambient_light(vec3(ambient.color), ambient.intensity);
+    }
+
+    for texture in &file.textures {
+        let texture = match texture {
+            TextureDesc::Image(path) => Texture::from_image(&assets.resolve_texture(path))
+                .map_err(|e| format!("no se pudo cargar la textura {}: {}", path, e))?,
+            TextureDesc::Solid { color } => Texture::solid(vec3(*color)),
+        };
+        scene.add_texture(texture);
+    }
+    for path in &file.maps {
+        let map = Texture::from_image(&assets.resolve_texture(path))
+            .map_err(|e| format!("no se pudo cargar el mapa {}: {}", path, e))?;
+        scene.add_map(map);
+    }
+
+    let resolve = |material: &MaterialRef| -> Result<Material, String> {
+        match material {
+            MaterialRef::Inline(desc) => Ok(build_material(desc)),
+            MaterialRef::Named(name) => file
+                .materials
+                .get(name)
+                .map(build_material)
+                .ok_or_else(|| format!("material desconocido: {}", name)),
+        }
+    };
+
+    for entry in &file.objects {
+        let id = match &entry.object {
+            ObjectDesc::Sphere { center, radius, material } => {
+                scene.add_sphere(Sphere::new(vec3(*center), *radius, resolve(material)?))
+            }
+            ObjectDesc::Plane { point, normal, material } => {
+                scene.add_plane(Plane::new(vec3(*point), vec3(*normal), resolve(material)?))
+            }
+            ObjectDesc::Cube { center, size, material } => {
+                scene.add_cube(Cube::centered(vec3(*center), *size, resolve(material)?))
+            }
+            ObjectDesc::Box { min, max, material } => {
+                scene.add_cube(Cube::new(vec3(*min), vec3(*max), resolve(material)?))
+            }
+            ObjectDesc::Pyramid { center, size, material } => {
+                scene.add_pyramid(Pyramid::centered(vec3(*center), *size, resolve(material)?))
+            }
+            ObjectDesc::Mesh { vertices, triangles, material } => {
+                if let Some(index) = triangles.iter().flatten().find(|&&index| index >= vertices.len()) {
+                    return Err(format!("índice de vértice fuera de rango en una malla: {}", index).into());
+                }
+                let vertices: Vec<Point3> = vertices.iter().map(|v| vec3(*v)).collect();
+                scene.add_mesh(TriangleMesh::new(vertices, triangles.clone(), resolve(material)?))
+            }
+            ObjectDesc::Obj { file, material } => {
+                scene.add_mesh(obj::load(&assets.resolve_mesh(file), resolve(material)?)?)
+            }
+            ObjectDesc::Lod { levels, metric, material } => {
+                let material = resolve(material)?;
+                let (first, rest) = levels.split_first().ok_or("malla con niveles de detalle sin niveles")?;
+                let mut mesh = LodMesh::new(obj::load(&assets.resolve_mesh(&first.file), material)?, *metric);
+                for level in rest {
+                    mesh = mesh.with_level(obj::load(&assets.resolve_mesh(&level.file), material)?, level.threshold);
+                }
+                scene.add_lod_mesh(mesh)
+            }
+        };
+        if let Some(name) = &entry.name {
+            name_item(&mut scene, name, SceneItem::Object(id))?;
+        }
+    }
+
+    for entry in &file.lights {
+        let id = scene.add_light(build_light(&entry.light));
+        if let Some(name) = &entry.name {
+            name_item(&mut scene, name, SceneItem::Light(id))?;
+        }
+        for object in entry.include.iter().flatten() {
+            let object = find_object(&scene, object)?;
+            scene.link_light(id, object);
+        }
+        for object in &entry.exclude {
+            let object = find_object(&scene, object)?;
+            scene.exclude_from_light(id, object);
+        }
+    }
+
+    if let Some(animation) = &file.animation {
+        let timeline = build_timeline(animation, &scene)?;
+        scene.set_animation(timeline);
+    }
+
+    Ok(scene)
+}
+
+/// Registra el nombre de un objeto o una luz; los nombres no se pueden repetir
+fn name_item(scene: &mut Scene, name: &str, item: SceneItem) -> Result<(), String> {
+    if scene.find(name).is_some() {
+        return Err(format!("nombre repetido: {}", name));
+    }
+    scene.set_name(name, item);
+    Ok(())
+}
+
+/// ID del objeto al que se refiere un enlace de luz
+fn find_object(scene: &Scene, object: &ObjectRef) -> Result<usize, String> {
+    match object {
+        // La escena se creó vacía, así que el ID es la posición en `objects`
+        ObjectRef::Index(id) => scene
+            .object(*id)
+            .map(|_| *id)
+            .ok_or_else(|| format!("enlace de luz con un objeto inexistente: {}", id)),
+        ObjectRef::Named(name) => match scene.find(name) {
+            Some(SceneItem::Object(id)) => Ok(id),
+            _ => Err(format!("enlace de luz con un objeto desconocido: {}", name)),
+        },
+    }
+}
+
+fn build_timeline(desc: &AnimationDesc, scene: &Scene) -> Result<Timeline, String> {
+    if desc.fps <= 0.0 {
+        return Err(format!("fps de la animación no positivo: {}", desc.fps));
+    }
+    let mut timeline = Timeline::new(desc.fps);
+
+    if let Some(camera) = &desc.camera {
+        let interpolation = match camera.interpolation {
+            InterpolationDesc::Linear => Interpolation::Linear,
+            InterpolationDesc::CatmullRom => Interpolation::CatmullRom,
+        };
+        let path = camera.keyframes.iter().fold(CameraPath::new(interpolation), |path, keyframe| {
+            path.with_keyframe(keyframe.time, vec3(keyframe.position), vec3(keyframe.look_at))
+        });
+        timeline = timeline.with_camera(path);
+    }
+
+    for object in &desc.objects {
+        // La escena se creó vacía, así que el ID es la posición en `objects`
+        let bounds = scene
+            .object(object.object)
+            .ok_or_else(|| format!("animación de un objeto inexistente: {}", object.object))?
+            .bounds();
+        let pivot = match (object.pivot, bounds) {
+            (Some(pivot), _) => vec3(pivot),
+            (None, Some(bounds)) => (bounds.min + bounds.max) * 0.5,
+            (None, None) => Point3::zero(),
+        };
+
+        let mut animation = ObjectAnimation::new(object.object).with_pivot(pivot);
+        for keyframe in &object.keyframes {
+            if let Some(translation) = keyframe.translation {
+                animation.translation.add_keyframe(keyframe.time, vec3(translation));
+            }
+            if let Some(rotation) = keyframe.rotation {
+                animation.rotation.add_keyframe(keyframe.time, vec3(rotation));
+            }
+            if let Some(scale) = keyframe.scale {
+                animation.scale.add_keyframe(keyframe.time, vec3(scale));
+            }
+        }
+        timeline = timeline.with_object(animation);
+    }
+
+    for light in &desc.lights {
+        if light.light >= scene.lights.len() {
+            return Err(format!("animación de una luz inexistente: {}", light.light));
+        }
+        let mut animation = LightAnimation::new(light.light);
+        for keyframe in &light.keyframes {
+            if let Some(position) = keyframe.position {
+                animation.position.add_keyframe(keyframe.time, vec3(position));
+            }
+            if let Some(color) = keyframe.color {
+                animation.color.add_keyframe(keyframe.time, vec3(color));
+            }
+            if let Some(intensity) = keyframe.intensity {
+                animation.intensity.add_keyframe(keyframe.time, intensity);
+            }
+        }
+        timeline = timeline.with_light(animation);
+    }
+
+    Ok(timeline)
+}
+
+fn build_material(desc: &MaterialDesc) -> Material {
+    let color: Color = vec3(desc.color);
+    let mut material = match desc.preset {
+        MaterialPreset::Default => Material::new(color),
+        MaterialPreset::Diffuse => Material::diffuse(color),
+        MaterialPreset::Shiny => Material::shiny(color),
+        MaterialPreset::Reflective => Material::reflective(color),
+        MaterialPreset::Transparent => Material::transparent(color, desc.transparency.unwrap_or(0.9)),
+        MaterialPreset::Emissive => Material::emissive(color, 1.0),
+    };
+
+    if let Some(albedo) = desc.albedo {
+        material.albedo = albedo;
+    }
+    if let Some(specular) = desc.specular {
+        material.specular = specular;
+    }
+    if let Some(shininess) = desc.shininess {
+        material.shininess = shininess;
+    }
+    if let Some(reflectivity) = desc.reflectivity {
+        material.reflectivity = reflectivity;
+    }
+    if let Some(transparency) = desc.transparency {
+        material = material.with_transparency(transparency);
+    }
+    if let Some(roughness) = desc.roughness {
+        material = material.with_roughness(roughness);
+    }
+    if let Some(emission) = desc.emission {
+        material = material.with_emission(vec3(emission));
+    }
+    if let Some(texture) = desc.texture {
+        material = material.with_texture(texture);
+    }
+    if let Some(max_depth) = desc.max_depth {
+        material = material.with_max_depth(max_depth);
+    }
+    if let Some(occlusion_map) = desc.occlusion_map {
+        material = material.with_occlusion_map(occlusion_map);
+    }
+    if let Some(lightmap) = desc.lightmap {
+        material = material.with_lightmap(lightmap);
+    }
+    material
+}
+
+fn build_light(desc: &LightDesc) -> Light {
+    let (light, shadows) = match *desc {
+        LightDesc::Point { position, color, intensity, shadows } => {
+            (Light::new(vec3(position), vec3(color), intensity), shadows)
+        }
+        LightDesc::Spot { position, direction, color, intensity, inner_angle, outer_angle, shadows } => (
+            Light::spot(vec3(position), vec3(direction), vec3(color), intensity, inner_angle, outer_angle),
+            shadows,
+        ),
+        LightDesc::Area { corner, edge_u, edge_v, color, intensity, samples, shadows } => (
+            Light::area(vec3(corner), vec3(edge_u), vec3(edge_v), vec3(color), intensity, samples),
+            shadows,
+        ),
+        LightDesc::Sphere { center, radius, color, intensity, samples, shadows } => {
+            (Light::sphere(vec3(center), radius, vec3(color), intensity, samples), shadows)
+        }
+        LightDesc::Directional { direction, color, intensity, shadows } => {
+            (Light::directional(vec3(direction), vec3(color), intensity), shadows)
+        }
+    };
+    light.with_shadows(shadows)
+}
@@ -0,0 +1,280 @@
+// Escenas de ejemplo construidas por código, listas para renderizar. Las
+// de la galería (`GALLERY`) se pueden elegir por nombre con `--scene`.
+
+use std::sync::Arc;
+
+use crate::vector::{Vec3, Color, Point3};
+use crate::camera::Camera;
+use crate::material::Material;
+use crate::light::Light;
+use crate::sphere::Sphere;
+use crate::plane::Plane;
+use crate::cube::Cube;
+use crate::pyramid::Pyramid;
+use crate::texture::Texture;
+use crate::transform::Transform;
+use crate::prefab::TextureOffset;
+use crate::sky::SkyModel;
+use crate::settings::RenderSettings;
+use crate::scene::Scene;
+use crate::sampling::Rng;
+
+/// Escenas incluidas: nombre y descripción
+pub const GALLERY: &[(&str, &str)] = &[
+    ("cornell", "caja de Cornell con dos bloques y una luz de área en el techo"),
+    ("diorama", "diorama de bloques con texturas de Minecraft"),
+    ("bodegon", "bodegón de vidrio y espejos"),
+    ("esferas", "cientos de esferas al azar (ver `random_spheres`)"),
+];
+
+/// Escena de la galería con el nombre dado (ver `GALLERY`)
+pub fn by_name(name: &str) -> Option<Scene> {
+    match name {
+        "cornell" => Some(cornell_box()),
+        "diorama" => Some(block_diorama()),
+        "bodegon" => Some(still_life()),
+        "esferas" => Some(random_spheres(0, 400)),
+        _ => None,
+    }
+}
+
+/// Radio de las esferas chicas de `random_spheres`
+const SMALL_RADIUS: f32 = 0.2;
+/// Mitad del lado de la cuadrícula de la portada de "Ray Tracing in One
+/// Weekend"; si hay más esferas la cuadrícula crece y la cámara se aleja
+const BASE_EXTENT: f32 = 11.0;
+
+/// Escena al estilo de la portada de "Ray Tracing in One Weekend": `count`
+/// esferas chicas sobre un suelo, cada una en su celda de una cuadrícula
+/// con una posición, color y material (difuso, metálico o vidrio) al azar,
+/// alrededor de tres esferas grandes. La misma `seed` produce siempre la
+/// misma escena.
+///
+/// Sirve de muestra y de prueba de carga para la BVH: con miles de esferas
+/// el tiempo de render debería crecer de forma logarítmica, no lineal.
+pub fn random_spheres(seed: u64, count: usize) -> Scene {
+    let mut rng = Rng::new(seed);
+    let big = [
+        (Point3::new(0.0, 1.0, 0.0), Material::transparent(Color::new(1.0, 1.0, 1.0), 0.95)),
+        (Point3::new(-4.0, 1.0, 0.0), Material::diffuse(Color::new(0.4, 0.2, 0.1))),
+        (Point3::new(4.0, 1.0, 0.0), Material::reflective(Color::new(0.7, 0.6, 0.5))),
+    ];
+
+    // Celdas en anillos cada vez más grandes alrededor del centro, saltando
+    // las que tocan a las esferas grandes, hasta ubicar todas las esferas
+    let mut spheres = Vec::with_capacity(count);
+    let mut ring = 0i32;
+    while spheres.len() < count {
+        for (a, b) in ring_cells(ring) {
+            if spheres.len() == count {
+                break;
+            }
+            let center = Point3::new(
+                a as f32 + 0.9 * rng.next_f32(),
+                SMALL_RADIUS,
+                b as f32 + 0.9 * rng.next_f32(),
+            );
+            if big.iter().any(|(big_center, _)| (center - *big_center).length() < 1.0 + SMALL_RADIUS) {
+                continue;
+            }
+            spheres.push(Sphere::new(center, SMALL_RADIUS, random_material(&mut rng)));
+        }
+        ring += 1;
+    }
+
+    let scale = (ring as f32 / BASE_EXTENT).max(1.0);
+    let camera = Camera::new(
+        Point3::new(13.0, 2.0, 3.0) * scale,
+        Point3::zero(),
+        Vec3::new(0.0, 1.0, 0.0),
+        20.0,
+        16.0 / 9.0,
+        1200,
+        675,
+    )
+    .with_depth_of_field(0.1, 10.0 * scale);
+
+    let mut scene = Scene::new(camera, Color::new(0.7, 0.8, 1.0));
+    scene.set_sky(SkyModel::new(40.0, 30.0, 3.0));
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), Material::diffuse(Color::new(0.5, 0.5, 0.5))));
+    for (center, material) in big {
+        scene.add_sphere(Sphere::new(center, 1.0, material));
+    }
+    for sphere in spheres {
+        scene.add_sphere(sphere);
+    }
+    scene
+}
+
+/// Celdas (a, b) del borde del cuadrado de lado 2·`ring` centrado en el origen
+fn ring_cells(ring: i32) -> Vec<(i32, i32)> {
+    if ring == 0 {
+        return vec![(0, 0)];
+    }
+    let mut cells = Vec::with_capacity(8 * ring as usize);
+    for i in -ring..ring {
+        cells.push((i, -ring));
+        cells.push((ring, i));
+        cells.push((-i, ring));
+        cells.push((-ring, -i));
+    }
+    cells
+}
+
+/// 80% difuso, 15% metálico con algo de rugosidad y 5% vidrio
+fn random_material(rng: &mut Rng) -> Material {
+    let choice = rng.next_f32();
+    if choice < 0.8 {
+        let color = |rng: &mut Rng| rng.next_f32() * rng.next_f32();
+        Material::diffuse(Color::new(color(rng), color(rng), color(rng)))
+    } else if choice < 0.95 {
+        let color = |rng: &mut Rng| 0.5 + 0.5 * rng.next_f32();
+        Material::reflective(Color::new(color(rng), color(rng), color(rng))).with_roughness(0.5 * rng.next_f32())
+    } else {
+        Material::transparent(Color::new(1.0, 1.0, 1.0), 0.95)
+    }
+}
+
+/// Caja de Cornell: paredes blancas con la izquierda roja y la derecha
+/// verde, un bloque alto y uno bajo girados, y una luz de área en el techo
+pub fn cornell_box() -> Scene {
+    let settings = RenderSettings::new(600, 600).with_samples_per_pixel(16);
+    let camera = Camera::new(
+        Point3::new(0.0, 1.0, 3.9),
+        Point3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        settings.aspect_ratio(),
+        settings.width,
+        settings.height,
+    );
+    let mut scene = Scene::new(camera, Color::zero());
+    scene.set_render_settings(settings);
+    scene.set_ambient_light(Color::new(1.0, 1.0, 1.0), 0.05);
+
+    let white = Material::diffuse(Color::new(0.73, 0.73, 0.73));
+    let red = Material::diffuse(Color::new(0.65, 0.05, 0.05));
+    let green = Material::diffuse(Color::new(0.12, 0.45, 0.15));
+    // Paredes de la caja de 2×2×2, abierta hacia la cámara
+    let walls = [
+        (Point3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), white),
+        (Point3::new(0.0, 2.0, 0.0), Vec3::new(0.0, -1.0, 0.0), white),
+        (Point3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 0.0, 1.0), white),
+        (Point3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), red),
+        (Point3::new(1.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0), green),
+    ];
+    for (point, normal, material) in walls {
+        scene.add_plane(Plane::new(point, normal, material));
+    }
+
+    // Panel emisivo para que la luz se vea, justo debajo del techo
+    let (corner, size) = (Point3::new(-0.25, 1.99, -0.25), 0.5);
+    scene.add_cube(Cube::new(corner, corner + Vec3::new(size, 0.005, size), Material::emissive(Color::new(1.0, 0.9, 0.75), 1.0)));
+    scene.add_light(Light::area(
+        corner - Vec3::new(0.0, 0.01, 0.0),
+        Vec3::new(size, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, size),
+        Color::new(1.0, 0.9, 0.75),
+        1.2,
+        4,
+    ));
+
+    let tall = Arc::new(Cube::new(Point3::new(-0.3, 0.0, -0.3), Point3::new(0.3, 1.2, 0.3), white));
+    scene.add_instance(tall, Transform::rotation_y(18.0).then(&Transform::translation(Vec3::new(-0.35, 0.0, -0.35))));
+    let short = Arc::new(Cube::new(Point3::new(-0.3, 0.0, -0.3), Point3::new(0.3, 0.6, 0.3), white));
+    scene.add_instance(short, Transform::rotation_y(-18.0).then(&Transform::translation(Vec3::new(0.35, 0.0, 0.3))));
+    scene
+}
+
+/// Diorama de bloques con las texturas de `textures/` (redstone y piedra):
+/// una escalera de piedra que sube hacia una torre de redstone. Si las
+/// imágenes no están (p. ej. al ejecutar desde otra carpeta) se usan
+/// colores lisos parecidos.
+pub fn block_diorama() -> Scene {
+    let settings = RenderSettings::new(800, 600).with_samples_per_pixel(4);
+    let camera = Camera::new(
+        Point3::new(5.0, 4.0, 6.0),
+        Point3::new(-0.5, 1.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        45.0,
+        settings.aspect_ratio(),
+        settings.width,
+        settings.height,
+    );
+    let mut scene = Scene::new(camera, Color::new(0.45, 0.6, 0.8));
+    scene.set_render_settings(settings);
+    scene.add_light(Light::directional(Vec3::new(0.6, 1.0, 0.4), Color::new(1.0, 0.95, 0.85), 0.9));
+
+    // Los cubos usan la textura 0 y los planos la 1 (ver `get_uv`); los
+    // bloques de piedra son cubos con la textura desplazada a la 1
+    scene.add_texture(texture_or("textures/redstoneblock.png", Color::new(0.8, 0.2, 0.2)));
+    scene.add_texture(texture_or("textures/stoneblock.png", Color::new(0.6, 0.6, 0.6)));
+
+    let material = Material::diffuse(Color::new(1.0, 1.0, 1.0));
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), material));
+
+    let block = |x: i32, y: i32, z: i32| Cube::new(
+        Point3::new(x as f32, y as f32, z as f32),
+        Point3::new(x as f32 + 1.0, y as f32 + 1.0, z as f32 + 1.0),
+        material,
+    );
+    // Escalera de piedra: cada escalón una fila más alta
+    for step in 0..3 {
+        for height in 0..=step {
+            for z in -1..1 {
+                scene.add_object(Box::new(TextureOffset::new(Box::new(block(step - 3, height, z)), 1)));
+            }
+        }
+    }
+    // Torre de redstone al final de la escalera, con un bloque suelto al lado
+    for height in 0..4 {
+        scene.add_cube(block(0, height, -1));
+    }
+    scene.add_cube(block(2, 0, 1));
+    scene.add_object(Box::new(TextureOffset::new(Box::new(block(1, 0, 2)), 1)));
+    scene
+}
+
+/// Bodegón sobre una mesa oscura: una esfera de vidrio, una de espejo, un
+/// cubo de vidrio ahumado y una pirámide brillante, con un foco cálido y
+/// una luz de relleno fría
+pub fn still_life() -> Scene {
+    let settings = RenderSettings::new(800, 600).with_samples_per_pixel(16).with_max_depth(8);
+    let camera = Camera::new(
+        Point3::new(0.0, 1.6, 5.0),
+        Point3::new(0.0, 0.6, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        35.0,
+        settings.aspect_ratio(),
+        settings.width,
+        settings.height,
+    )
+    .with_depth_of_field(0.05, 5.0);
+    let mut scene = Scene::new(camera, Color::new(0.05, 0.05, 0.07));
+    scene.set_render_settings(settings);
+    scene.set_ambient_light(Color::new(0.6, 0.7, 1.0), 0.1);
+
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), Material::shiny(Color::new(0.25, 0.18, 0.12)).with_roughness(0.3)));
+    scene.add_plane(Plane::new(Point3::new(0.0, 0.0, -3.0), Vec3::new(0.0, 0.0, 1.0), Material::diffuse(Color::new(0.35, 0.35, 0.4))));
+
+    scene.add_sphere(Sphere::new(Point3::new(-0.9, 0.6, 0.3), 0.6, Material::transparent(Color::new(0.8, 0.95, 0.9), 0.85)));
+    scene.add_sphere(Sphere::new(Point3::new(0.6, 0.7, -0.6), 0.7, Material::reflective(Color::new(0.9, 0.9, 0.9))));
+    scene.add_cube(Cube::centered(Point3::new(1.3, 0.3, 0.8), 0.6, Material::transparent(Color::new(0.5, 0.6, 0.7), 0.8)));
+    scene.add_pyramid(Pyramid::centered(Point3::new(-0.2, 0.35, 1.2), 0.7, Material::shiny(Color::new(0.8, 0.6, 0.2))));
+
+    scene.add_light(Light::spot(
+        Point3::new(-3.0, 4.0, 3.0),
+        Vec3::new(3.0, -3.5, -3.0),
+        Color::new(1.0, 0.85, 0.7),
+        1.5,
+        20.0,
+        35.0,
+    ));
+    scene.add_light(Light::sphere(Point3::new(4.0, 2.5, 2.0), 0.5, Color::new(0.6, 0.7, 1.0), 0.4, 3));
+    scene
+}
+
+/// Carga una textura o, si la imagen no se puede leer, usa un color liso
+fn texture_or(path: &str, fallback: Color) -> Texture {
+    Texture::from_image(path).unwrap_or_else(|_| Texture::solid(fallback))
+}
@@ -0,0 +1,133 @@
+// Máscaras de segmentación: cada píxel guarda la etiqueta del objeto (o del
+// material) que se ve en su centro, sin antialiasing, para que los bordes
+// no mezclen etiquetas. La etiqueta 0 es el fondo:
+//
+//   objetos     ID del objeto en la escena + 1 (no cambia al quitar otros)
+//   materiales  materiales distintos numerados desde 1 en el orden en que
+//               aparecen en `Scene::objects`
+//
+// La máscara se guarda como Framebuffer con la etiqueta en los tres
+// canales (exacta en EXR hasta 2^24) y se puede colorear para verla.
+
+use std::hash::Hasher;
+
+use image::{Rgb, RgbImage};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+
+use crate::vector::Color;
+use crate::scene::{Scene, SceneItem};
+use crate::framebuffer::Framebuffer;
+use crate::render_cache::{hash_material, StableHasher};
+use crate::sampling::hash_u64;
+
+/// Qué identifica cada etiqueta
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaskKind {
+    Object,
+    Material,
+}
+
+/// Etiquetas de los objetos de una escena
+pub struct Segmentation {
+    kind: MaskKind,
+    /// Etiqueta de cada posición de `Scene::objects`
+    labels: Vec<u32>,
+}
+
+impl Segmentation {
+    pub fn new(scene: &Scene, kind: MaskKind) -> Self {
+        let labels = match kind {
+            MaskKind::Object => (0..scene.objects.len()).map(|index| scene.object_id(index) as u32 + 1).collect(),
+            MaskKind::Material => {
+                let mut materials: Vec<u64> = Vec::new();
+                scene
+                    .objects
+                    .iter()
+                    .map(|object| {
+                        let mut hasher = StableHasher::new();
+                        hash_material(&mut hasher, object.get_material());
+                        let hash = hasher.finish();
+                        let position = materials.iter().position(|&known| known == hash).unwrap_or_else(|| {
+                            materials.push(hash);
+                            materials.len() - 1
+                        });
+                        position as u32 + 1
+                    })
+                    .collect()
+            }
+        };
+        Segmentation { kind, labels }
+    }
+
+    pub fn kind(&self) -> MaskKind {
+        self.kind
+    }
+
+    /// Etiqueta del objeto en la posición `index` de `Scene::objects`
+    pub fn label(&self, index: usize) -> u32 {
+        self.labels[index]
+    }
+
+    /// Máscara con un rayo por el centro de cada píxel
+    pub fn render(&self, scene: &Scene) -> Framebuffer {
+        let (width, height) = (scene.camera.width, scene.camera.height);
+        let pixels: Vec<Color> = (0..height)
+            .into_par_iter()
+            .flat_map_iter(|y| {
+                (0..width).map(move |x| {
+                    let u = (x as f32 + 0.5) / width as f32;
+                    let v = 1.0 - ((y as f32 + 0.5) / height as f32);
+                    let label = scene.find_closest_hit(&scene.camera.get_ray(u, v)).map_or(0, |(_, index)| self.label(index));
+                    Color::new(label as f32, label as f32, label as f32)
+                })
+            })
+            .collect();
+        Framebuffer::from_pixels(width, height, pixels)
+    }
+
+    /// Qué es cada etiqueta, con su color en `colorize`: el objeto y su
+    /// nombre, o los objetos que usan cada material
+    pub fn legend(&self, scene: &Scene) -> Value {
+        let mut labels: Vec<u32> = self.labels.clone();
+        labels.sort_unstable();
+        labels.dedup();
+
+        let entries: Vec<Value> = labels
+            .into_iter()
+            .map(|label| {
+                let objects: Vec<usize> = (0..self.labels.len())
+                    .filter(|&index| self.labels[index] == label)
+                    .map(|index| scene.object_id(index))
+                    .collect();
+                let Rgb(color) = label_color(label);
+                match self.kind {
+                    MaskKind::Object => json!({
+                        "label": label,
+                        "object": objects[0],
+                        "name": scene.name_of(SceneItem::Object(objects[0])),
+                        "color": color,
+                    }),
+                    MaskKind::Material => json!({ "label": label, "objects": objects, "color": color }),
+                }
+            })
+            .collect();
+        json!({ "background": 0, "labels": entries })
+    }
+}
+
+/// Color con el que se muestra una etiqueta: negro para el fondo y un
+/// color fijo (derivado de la etiqueta) para las demás
+pub fn label_color(label: u32) -> Rgb<u8> {
+    if label == 0 {
+        return Rgb([0, 0, 0]);
+    }
+    let hash = hash_u64(label as u64).to_le_bytes();
+    // Sin canales muy oscuros para que ninguna etiqueta se confunda con el fondo
+    Rgb([64 + hash[0] % 192, 64 + hash[1] % 192, 64 + hash[2] % 192])
+}
+
+/// Imagen con cada etiqueta de `mask` (ver `Segmentation::render`) en su color
+pub fn colorize(mask: &Framebuffer) -> RgbImage {
+    RgbImage::from_fn(mask.width(), mask.height(), |x, y| label_color(mask.get(x, y).x as u32))
+}
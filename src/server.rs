@@ -0,0 +1,293 @@
+// Servicio de render por HTTP (feature `server`): recibe escenas en el
+// formato JSON de `scene_file`, las renderiza en segundo plano de a una y
+// sirve el PNG terminado. Rutas:
+//
+//   POST   /render            cuerpo: la escena en JSON; responde 202 con el ID
+//   GET    /jobs              estado de todos los trabajos
+//   GET    /jobs/{id}         estado y avance de un trabajo
+//   GET    /jobs/{id}/image   PNG del render terminado
+//   DELETE /jobs/{id}         cancela el trabajo
+//
+// Las rutas de las texturas de la escena se leen del disco del servidor, así
+// que no conviene exponerlo fuera de una red de confianza.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::{Cursor, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+
+use image::ImageOutputFormat;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::scene::{Scene, Severity};
+use crate::scene_file;
+use crate::renderer::Renderer;
+use crate::cancel::CancelToken;
+use crate::framebuffer::Framebuffer;
+use crate::development::Development;
+
+/// Tamaño máximo aceptado para la descripción de una escena
+const MAX_SCENE_SIZE: u64 = 16 * 1024 * 1024;
+/// Trabajos terminados que se conservan; los más antiguos se descartan
+const MAX_FINISHED_JOBS: usize = 32;
+
+#[derive(Debug, Clone)]
+enum Status {
+    Queued,
+    Rendering,
+    Done(Arc<Vec<u8>>),
+    Failed(String),
+    Cancelled,
+}
+
+impl Status {
+    fn name(&self) -> &'static str {
+        match self {
+            Status::Queued => "queued",
+            Status::Rendering => "rendering",
+            Status::Done(_) => "done",
+            Status::Failed(_) => "failed",
+            Status::Cancelled => "cancelled",
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        !matches!(self, Status::Queued | Status::Rendering)
+    }
+}
+
+/// Trabajo de render compartido entre el hilo que atiende las peticiones
+/// y el que renderiza
+struct Job {
+    status: Mutex<Status>,
+    done: AtomicUsize,
+    total: AtomicUsize,
+    cancel: CancelToken,
+}
+
+impl Job {
+    fn status(&self) -> Status {
+        self.status.lock().unwrap().clone()
+    }
+
+    fn set_status(&self, status: Status) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    /// Fracción de bloques terminados (0.0 a 1.0)
+    fn progress(&self) -> f32 {
+        match self.status() {
+            Status::Done(_) => 1.0,
+            _ => self.done.load(Ordering::Relaxed) as f32 / self.total.load(Ordering::Relaxed).max(1) as f32,
+        }
+    }
+
+    fn to_json(&self, id: u64) -> Value {
+        let status = self.status();
+        let mut value = json!({ "id": id, "status": status.name(), "progress": self.progress() });
+        if let Status::Failed(error) = &status {
+            value["error"] = json!(error);
+        }
+        value
+    }
+}
+
+/// Servidor HTTP que renderiza escenas en segundo plano
+pub struct RenderServer {
+    server: tiny_http::Server,
+    jobs: BTreeMap<u64, Arc<Job>>,
+    queue: Sender<(Arc<Job>, Scene)>,
+    next_id: u64,
+    adjust_development: Box<dyn Fn(&mut Development)>,
+}
+
+impl RenderServer {
+    /// Escucha en `address` (p. ej. "127.0.0.1:8080") y lanza el hilo que
+    /// renderiza los trabajos en orden de llegada. Cada render usa todos los
+    /// núcleos, así que no se ejecutan dos a la vez
+    pub fn bind(address: &str) -> Result<Self, Box<dyn Error>> {
+        let server = tiny_http::Server::http(address)
+            .map_err(|e| format!("no se pudo escuchar en {}: {}", address, e))?;
+
+        let (queue, pending) = mpsc::channel::<(Arc<Job>, Scene)>();
+        std::thread::spawn(move || {
+            for (job, scene) in pending {
+                render_job(&job, &scene);
+            }
+        });
+
+        Ok(RenderServer {
+            server,
+            jobs: BTreeMap::new(),
+            queue,
+            next_id: 1,
+            adjust_development: Box::new(|_| {}),
+        })
+    }
+
+    /// Ajusta el revelado de cada escena recibida (ver `Scene::development`)
+    /// antes de renderizarla, p. ej. con las opciones de la línea de comandos
+    /// para que los PNG coincidan con los del CLI
+    pub fn with_development(mut self, adjust: impl Fn(&mut Development) + 'static) -> Self {
+        self.adjust_development = Box::new(adjust);
+        self
+    }
+
+    /// Dirección en la que escucha (útil si se pidió el puerto 0)
+    pub fn address(&self) -> String {
+        self.server.server_addr().to_string()
+    }
+
+    /// Atiende peticiones hasta que termine el proceso
+    pub fn run(mut self) {
+        while let Ok(mut request) = self.server.recv() {
+            let response = self.handle(&mut request);
+            let url = request.url().to_string();
+            if let Err(e) = request.respond(response) {
+                println!("⚠ No se pudo responder a {}: {}", url, e);
+            }
+        }
+    }
+
+    fn handle(&mut self, request: &mut Request) -> Response<Cursor<Vec<u8>>> {
+        // Se copian porque `submit` necesita la petición para leer el cuerpo
+        let method = request.method().clone();
+        let url = request.url().split('?').next().unwrap_or_default().to_string();
+        let path: Vec<&str> = url.trim_matches('/').split('/').collect();
+
+        match (&method, path.as_slice()) {
+            (Method::Post, ["render"]) => self.submit(request),
+            (Method::Get, ["jobs"]) => {
+                let jobs: Vec<Value> = self.jobs.iter().map(|(id, job)| job.to_json(*id)).collect();
+                json_response(200, json!(jobs))
+            }
+            (method, ["jobs", id, rest @ ..]) => {
+                let Some((id, job)) = id.parse().ok().and_then(|id| Some((id, self.jobs.get(&id)?))) else {
+                    return error_response(404, "trabajo desconocido");
+                };
+                match (method, rest) {
+                    (Method::Get, []) => json_response(200, job.to_json(id)),
+                    (Method::Get, ["image"]) => match job.status() {
+                        Status::Done(png) => Response::from_data(png.as_ref().clone())
+                            .with_header(header("Content-Type", "image/png")),
+                        status => error_response(409, &format!("el trabajo no terminó bien ({})", status.name())),
+                    },
+                    (Method::Delete, []) => {
+                        job.cancel.cancel();
+                        if let Status::Queued = job.status() {
+                            job.set_status(Status::Cancelled);
+                        }
+                        json_response(200, job.to_json(id))
+                    }
+                    _ => error_response(404, "ruta desconocida"),
+                }
+            }
+            _ => error_response(404, "ruta desconocida"),
+        }
+    }
+
+    /// Valida la escena del cuerpo de la petición y la pone en la cola
+    fn submit(&mut self, request: &mut Request) -> Response<Cursor<Vec<u8>>> {
+        let mut text = String::new();
+        if let Err(e) = request.as_reader().take(MAX_SCENE_SIZE + 1).read_to_string(&mut text) {
+            return error_response(400, &format!("no se pudo leer la escena: {}", e));
+        }
+        if text.len() as u64 > MAX_SCENE_SIZE {
+            return error_response(413, "escena demasiado grande");
+        }
+        let mut scene = match scene_file::parse(&text) {
+            Ok(scene) => scene,
+            Err(e) => return error_response(400, &format!("escena inválida: {}", e)),
+        };
+        let errors: Vec<String> = scene
+            .validate()
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| diagnostic.to_string())
+            .collect();
+        if !errors.is_empty() {
+            return error_response(400, &format!("escena inválida: {}", errors.join("; ")));
+        }
+
+        (self.adjust_development)(&mut scene.development);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let job = Arc::new(Job {
+            status: Mutex::new(Status::Queued),
+            done: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+            cancel: CancelToken::new(),
+        });
+        if self.queue.send((job.clone(), scene)).is_err() {
+            return error_response(500, "el hilo de render no está disponible");
+        }
+        self.jobs.insert(id, job.clone());
+        self.forget_old_jobs();
+
+        json_response(202, job.to_json(id)).with_header(header("Location", &format!("/jobs/{}", id)))
+    }
+
+    /// Descarta los trabajos terminados más antiguos por encima del límite
+    fn forget_old_jobs(&mut self) {
+        let finished: Vec<u64> = self
+            .jobs
+            .iter()
+            .filter(|(_, job)| job.status().is_finished())
+            .map(|(id, _)| *id)
+            .collect();
+        for id in finished.iter().take(finished.len().saturating_sub(MAX_FINISHED_JOBS)) {
+            self.jobs.remove(id);
+        }
+    }
+}
+
+fn render_job(job: &Job, scene: &Scene) {
+    // Cancelado mientras esperaba en la cola
+    if job.cancel.is_cancelled() {
+        return;
+    }
+    job.set_status(Status::Rendering);
+
+    let progress = |done: usize, total: usize| {
+        job.done.store(done, Ordering::Relaxed);
+        job.total.store(total, Ordering::Relaxed);
+    };
+    // Un fallo en una escena no debe tumbar el servicio
+    let result = panic::catch_unwind(AssertUnwindSafe(|| Renderer::render_cancellable(scene, &progress, &job.cancel)));
+
+    let status = match result {
+        _ if job.cancel.is_cancelled() => Status::Cancelled,
+        Ok(framebuffer) => match encode_png(&scene.development, &framebuffer) {
+            Ok(png) => Status::Done(Arc::new(png)),
+            Err(e) => Status::Failed(format!("no se pudo codificar la imagen: {}", e)),
+        },
+        Err(_) => Status::Failed("el render falló".to_string()),
+    };
+    job.set_status(status);
+}
+
+/// PNG de respuesta con el revelado de la escena
+fn encode_png(development: &Development, framebuffer: &Framebuffer) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut png = Cursor::new(Vec::new());
+    development.to_rgb8(framebuffer).write_to(&mut png, ImageOutputFormat::Png)?;
+    Ok(png.into_inner())
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("cabecera HTTP inválida")
+}
+
+fn json_response(status: u16, value: Value) -> Response<Cursor<Vec<u8>>> {
+    Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(header("Content-Type", "application/json"))
+}
+
+fn error_response(status: u16, message: &str) -> Response<Cursor<Vec<u8>>> {
+    json_response(status, json!({ "error": message }))
+}
@@ -0,0 +1,79 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::render_cache::hash_f32;
+
+/// Parámetros de calidad del render que se eligen al ejecutar
+/// (resolución, muestras, profundidad de rebotes y sesgo de los rayos)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RenderSettings {
+    pub width: u32,
+    pub height: u32,
+    pub samples_per_pixel: u32,
+    /// Reflexiones/transmisiones encadenadas (o rebotes del path tracer sin ruleta rusa)
+    pub max_depth: u32,
+    /// Desplazamiento del origen de los rayos secundarios sobre la superficie,
+    /// para que no choquen con ella misma por errores de redondeo
+    pub bias: f32,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        RenderSettings {
+            width: 800,
+            height: 600,
+            samples_per_pixel: 1,
+            max_depth: 5,
+            bias: 1e-4,
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Ajustes por defecto con la resolución dada
+    pub fn new(width: u32, height: u32) -> Self {
+        RenderSettings {
+            width: width.max(1),
+            height: height.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Número de rayos por píxel para el anti-aliasing (1 = desactivado)
+    pub fn with_samples_per_pixel(mut self, samples: u32) -> Self {
+        self.samples_per_pixel = samples.max(1);
+        self
+    }
+
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn with_bias(mut self, bias: f32) -> Self {
+        self.bias = bias.max(0.0);
+        self
+    }
+
+    /// Cambia la resolución por un factor (p. ej. 0.25 para una vista previa rápida)
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        let scale = scale.max(0.0);
+        self.width = ((self.width as f32 * scale).round() as u32).max(1);
+        self.height = ((self.height as f32 * scale).round() as u32).max(1);
+        self
+    }
+
+    pub fn aspect_ratio(&self) -> f32 {
+        self.width as f32 / self.height as f32
+    }
+
+    /// Agrega los ajustes al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        state.write_u32(self.width);
+        state.write_u32(self.height);
+        state.write_u32(self.samples_per_pixel);
+        state.write_u32(self.max_depth);
+        hash_f32(state, self.bias);
+    }
+}
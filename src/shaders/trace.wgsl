@@ -0,0 +1,381 @@
+// Trazado de rayos en GPU: un hilo por píxel.
+// Recorre la BVH de primitivas y sombrea con el mismo modelo que el
+// integrador de iluminación directa de CPU: luz ambiente, emisión, Phong con
+// sombras duras de luces puntuales y reflexiones especulares iterativas.
+// Las estructuras deben coincidir byte a byte con las de `gpu.rs`; wgpu no
+// admite buffers vacíos, así que el host sube al menos un elemento por buffer.
+
+const KIND_SPHERE: u32 = 0u;
+const KIND_BOX: u32 = 1u;
+const KIND_TRIANGLE: u32 = 2u;
+const KIND_PLANE: u32 = 3u;
+const NO_TEXTURE: u32 = 0xffffffffu;
+const NO_HIT: u32 = 0xffffffffu;
+const EPSILON: f32 = 1e-4;
+const INFINITY: f32 = 3.4e38;
+const PI: f32 = 3.14159265;
+const STACK_SIZE: u32 = 64u;
+
+struct Uniforms {
+    origin: vec4<f32>,
+    lower_left_corner: vec4<f32>,
+    horizontal: vec4<f32>,
+    vertical: vec4<f32>,
+    background: vec4<f32>,
+    ambient: vec4<f32>,
+    width: u32,
+    height: u32,
+    primitive_count: u32,
+    bounded_count: u32,
+    light_count: u32,
+    node_count: u32,
+    max_depth: u32,
+    samples: u32,
+}
+
+struct Primitive {
+    kind: u32,
+    object: u32,
+    texture: u32,
+    _pad: u32,
+    a: vec4<f32>,
+    b: vec4<f32>,
+    c: vec4<f32>,
+}
+
+struct BvhNode {
+    min: vec3<f32>,
+    first: u32,
+    max: vec3<f32>,
+    count: u32,
+}
+
+struct Material {
+    color: vec3<f32>,
+    albedo: f32,
+    emission: vec3<f32>,
+    specular: f32,
+    shininess: f32,
+    reflectivity: f32,
+    _pad0: u32,
+    _pad1: u32,
+}
+
+struct Light {
+    position: vec3<f32>,
+    intensity: f32,
+    color: vec3<f32>,
+    casts_shadows: u32,
+}
+
+struct Texture {
+    width: u32,
+    height: u32,
+    offset: u32,
+    _pad: u32,
+}
+
+struct Ray {
+    origin: vec3<f32>,
+    direction: vec3<f32>,
+}
+
+struct Hit {
+    t: f32,
+    primitive: u32,
+}
+
+@group(0) @binding(0) var<uniform> uniforms: Uniforms;
+@group(0) @binding(1) var<storage, read> primitives: array<Primitive>;
+@group(0) @binding(2) var<storage, read> nodes: array<BvhNode>;
+@group(0) @binding(3) var<storage, read> materials: array<Material>;
+@group(0) @binding(4) var<storage, read> lights: array<Light>;
+@group(0) @binding(5) var<storage, read> textures: array<Texture>;
+@group(0) @binding(6) var<storage, read> texels: array<vec4<f32>>;
+// Color lineal HDR por píxel; el tone mapping se hace al guardar, como en CPU
+@group(0) @binding(7) var<storage, read_write> output: array<vec4<f32>>;
+
+fn slab_range(ray: Ray, box_min: vec3<f32>, box_max: vec3<f32>) -> vec2<f32> {
+    let inv = 1.0 / ray.direction;
+    let t0 = (box_min - ray.origin) * inv;
+    let t1 = (box_max - ray.origin) * inv;
+    let near = min(t0, t1);
+    let far = max(t0, t1);
+    return vec2<f32>(max(max(near.x, near.y), near.z), min(min(far.x, far.y), far.z));
+}
+
+fn hit_bounds(ray: Ray, box_min: vec3<f32>, box_max: vec3<f32>, t_max: f32) -> bool {
+    let range = slab_range(ray, box_min, box_max);
+    return max(range.x, 0.0) <= min(range.y, t_max);
+}
+
+fn intersect_primitive(ray: Ray, primitive: Primitive) -> f32 {
+    var t = -1.0;
+    switch primitive.kind {
+        case KIND_SPHERE: {
+            let oc = ray.origin - primitive.a.xyz;
+            let a = dot(ray.direction, ray.direction);
+            let half_b = dot(oc, ray.direction);
+            let c = dot(oc, oc) - primitive.a.w * primitive.a.w;
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let root = sqrt(discriminant);
+                t = (-half_b - root) / a;
+                if t <= EPSILON {
+                    t = (-half_b + root) / a;
+                }
+            }
+        }
+        case KIND_BOX: {
+            let range = slab_range(ray, primitive.a.xyz, primitive.b.xyz);
+            if range.x <= range.y {
+                t = select(range.y, range.x, range.x > EPSILON);
+            }
+        }
+        case KIND_TRIANGLE: {
+            // Möller-Trumbore
+            let edge1 = primitive.b.xyz - primitive.a.xyz;
+            let edge2 = primitive.c.xyz - primitive.a.xyz;
+            let h = cross(ray.direction, edge2);
+            let det = dot(edge1, h);
+            if abs(det) > 1e-8 {
+                let inv_det = 1.0 / det;
+                let s = ray.origin - primitive.a.xyz;
+                let u = inv_det * dot(s, h);
+                let q = cross(s, edge1);
+                let v = inv_det * dot(ray.direction, q);
+                if u >= 0.0 && v >= 0.0 && u + v <= 1.0 {
+                    t = inv_det * dot(edge2, q);
+                }
+            }
+        }
+        default: {
+            let denom = dot(ray.direction, primitive.b.xyz);
+            if abs(denom) >= 1e-6 {
+                t = dot(primitive.a.xyz - ray.origin, primitive.b.xyz) / denom;
+            }
+        }
+    }
+    return select(-1.0, t, t > EPSILON);
+}
+
+// Intersección más cercana con t < t_max (misma lógica que `GpuScene::intersect`)
+fn closest_hit(ray: Ray, t_max: f32) -> Hit {
+    var closest = Hit(t_max, NO_HIT);
+    var stack: array<u32, STACK_SIZE>;
+    var top = 0u;
+    if uniforms.node_count > 0u {
+        stack[0] = 0u;
+        top = 1u;
+    }
+
+    while top > 0u {
+        top -= 1u;
+        let node = nodes[stack[top]];
+        if !hit_bounds(ray, node.min, node.max, closest.t) {
+            continue;
+        }
+
+        if node.count > 0u {
+            for (var i = node.first; i < node.first + node.count; i += 1u) {
+                let t = intersect_primitive(ray, primitives[i]);
+                if t > 0.0 && t < closest.t {
+                    closest = Hit(t, i);
+                }
+            }
+        } else if top + 2u <= STACK_SIZE {
+            stack[top] = node.first + 1u;
+            stack[top + 1u] = node.first;
+            top += 2u;
+        }
+    }
+
+    // Primitivas sin caja (planos)
+    for (var i = uniforms.bounded_count; i < uniforms.primitive_count; i += 1u) {
+        let t = intersect_primitive(ray, primitives[i]);
+        if t > 0.0 && t < closest.t {
+            closest = Hit(t, i);
+        }
+    }
+
+    return closest;
+}
+
+fn normal_at(primitive: Primitive, point: vec3<f32>) -> vec3<f32> {
+    switch primitive.kind {
+        case KIND_SPHERE: {
+            return normalize(point - primitive.a.xyz);
+        }
+        case KIND_BOX: {
+            // Cara más cercana al punto
+            let to_min = abs(point - primitive.a.xyz);
+            let to_max = abs(point - primitive.b.xyz);
+            let nearest = min(min(min(to_min.x, to_max.x), min(to_min.y, to_max.y)), min(to_min.z, to_max.z));
+            if nearest == to_min.x { return vec3<f32>(-1.0, 0.0, 0.0); }
+            if nearest == to_max.x { return vec3<f32>(1.0, 0.0, 0.0); }
+            if nearest == to_min.y { return vec3<f32>(0.0, -1.0, 0.0); }
+            if nearest == to_max.y { return vec3<f32>(0.0, 1.0, 0.0); }
+            if nearest == to_min.z { return vec3<f32>(0.0, 0.0, -1.0); }
+            return vec3<f32>(0.0, 0.0, 1.0);
+        }
+        case KIND_TRIANGLE: {
+            return normalize(cross(primitive.b.xyz - primitive.a.xyz, primitive.c.xyz - primitive.a.xyz));
+        }
+        default: {
+            return primitive.b.xyz;
+        }
+    }
+}
+
+// Coordenadas UV como en `get_uv` de cada forma; z = 0 si no hay UV
+fn uv_at(primitive: Primitive, point: vec3<f32>) -> vec3<f32> {
+    switch primitive.kind {
+        case KIND_SPHERE: {
+            let n = normalize(point - primitive.a.xyz);
+            return vec3<f32>(0.5 + atan2(n.z, n.x) / PI * 0.5, 0.5 - asin(n.y) / PI, 1.0);
+        }
+        case KIND_BOX: {
+            let box_min = primitive.a.xyz;
+            let box_max = primitive.b.xyz;
+            let size = box_max - box_min;
+            let local = (point - box_min) / size;
+            if abs(point.y - box_max.y) < EPSILON || abs(point.y - box_min.y) < EPSILON {
+                return vec3<f32>(local.x, local.z, 1.0);
+            }
+            if abs(point.x - box_min.x) < EPSILON || abs(point.x - box_max.x) < EPSILON {
+                return vec3<f32>(local.z, local.y, 1.0);
+            }
+            if abs(point.z - box_min.z) < EPSILON || abs(point.z - box_max.z) < EPSILON {
+                return vec3<f32>(local.x, local.y, 1.0);
+            }
+            return vec3<f32>(0.0);
+        }
+        case KIND_TRIANGLE: {
+            return vec3<f32>(0.0, 0.0, 1.0);
+        }
+        default: {
+            let normal = primitive.b.xyz;
+            var tangent: vec3<f32>;
+            if abs(normal.x) > 0.9 {
+                tangent = normalize(cross(vec3<f32>(0.0, 1.0, 0.0), normal));
+            } else {
+                tangent = normalize(cross(vec3<f32>(1.0, 0.0, 0.0), normal));
+            }
+            let bitangent = normalize(cross(normal, tangent));
+            let relative = point - primitive.a.xyz;
+            let u = (dot(relative, tangent) * 0.5) % 1.0;
+            let v = (dot(relative, bitangent) * 0.5) % 1.0;
+            return vec3<f32>(abs(u), abs(v), 1.0);
+        }
+    }
+}
+
+fn surface_color(primitive: Primitive, material: Material, point: vec3<f32>) -> vec3<f32> {
+    if primitive.texture == NO_TEXTURE || primitive.texture >= arrayLength(&textures) {
+        return material.color;
+    }
+    let uv = uv_at(primitive, point);
+    if uv.z == 0.0 {
+        return material.color;
+    }
+
+    let texture = textures[primitive.texture];
+    let x = min(u32(clamp(uv.x, 0.0, 1.0) * f32(texture.width)), texture.width - 1u);
+    let y = min(u32(clamp(uv.y, 0.0, 1.0) * f32(texture.height)), texture.height - 1u);
+    return texels[texture.offset + y * texture.width + x].xyz;
+}
+
+fn shade(ray: Ray, primitive: Primitive, point: vec3<f32>, normal: vec3<f32>) -> vec3<f32> {
+    let material = materials[primitive.object];
+    let base_color = surface_color(primitive, material, point);
+    let view_dir = normalize(uniforms.origin.xyz - point);
+    var color = base_color * uniforms.ambient.xyz + material.emission;
+
+    for (var i = 0u; i < uniforms.light_count; i += 1u) {
+        let light = lights[i];
+        let to_light = light.position - point;
+        let distance = length(to_light);
+        let light_dir = to_light / distance;
+
+        if light.casts_shadows != 0u {
+            let shadow = closest_hit(Ray(point + normal * EPSILON, light_dir), distance);
+            if shadow.primitive != NO_HIT {
+                continue;
+            }
+        }
+
+        let diffuse = base_color * max(dot(normal, light_dir), 0.0) * material.albedo * light.intensity;
+        let reflected = reflect(-light_dir, normal);
+        let specular = light.color * pow(max(dot(reflected, view_dir), 0.0), material.shininess)
+            * material.specular * light.intensity;
+        color += diffuse + specular;
+    }
+
+    return color;
+}
+
+// Reflexiones sin recursión: cada rebote aporta su color local ponderado por
+// la fracción de luz que aún no se reflejó
+fn trace(primary: Ray) -> vec3<f32> {
+    var ray = primary;
+    var color = vec3<f32>(0.0);
+    var throughput = vec3<f32>(1.0);
+
+    for (var depth = uniforms.max_depth; depth > 0u; depth -= 1u) {
+        let hit = closest_hit(ray, INFINITY);
+        if hit.primitive == NO_HIT {
+            color += throughput * uniforms.background.xyz;
+            break;
+        }
+
+        let primitive = primitives[hit.primitive];
+        let material = materials[primitive.object];
+        let point = ray.origin + ray.direction * hit.t;
+        let normal = normal_at(primitive, point);
+        let local = shade(ray, primitive, point, normal);
+
+        if material.reflectivity <= 0.0 || depth == 1u {
+            color += throughput * local;
+            break;
+        }
+
+        color += throughput * local * (1.0 - material.reflectivity);
+        throughput *= material.reflectivity;
+        ray = Ray(point + normal * EPSILON, reflect(ray.direction, normal));
+    }
+
+    return color;
+}
+
+// Número pseudoaleatorio en [0, 1) a partir de un estado PCG
+fn random(state: ptr<function, u32>) -> f32 {
+    *state = *state * 747796405u + 2891336453u;
+    var word = ((*state >> ((*state >> 28u) + 4u)) ^ *state) * 277803737u;
+    word = (word >> 22u) ^ word;
+    return f32(word) / 4294967296.0;
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if id.x >= uniforms.width || id.y >= uniforms.height {
+        return;
+    }
+
+    let width = f32(uniforms.width);
+    let height = f32(uniforms.height);
+    var state = id.y * uniforms.width + id.x;
+    var color = vec3<f32>(0.0);
+
+    for (var sample = 0u; sample < uniforms.samples; sample += 1u) {
+        var jitter = vec2<f32>(0.0);
+        if uniforms.samples > 1u {
+            jitter = vec2<f32>(random(&state), random(&state));
+        }
+        let u = (f32(id.x) + jitter.x) / width;
+        let v = 1.0 - (f32(id.y) + jitter.y) / height;
+        let through = uniforms.lower_left_corner.xyz + uniforms.horizontal.xyz * u + uniforms.vertical.xyz * v;
+        color += trace(Ray(uniforms.origin.xyz, normalize(through - uniforms.origin.xyz)));
+    }
+
+    output[id.y * uniforms.width + id.x] = vec4<f32>(color / f32(uniforms.samples), 1.0);
+}
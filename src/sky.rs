@@ -0,0 +1,160 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Vec3, Color};
+use crate::render_cache::hash_f32;
+
+/// Cielo analítico según el modelo de Preetham et al. (1999)
+/// El color del cielo depende de la posición del sol y de la turbidez
+/// (2 = cielo muy limpio, 10 = atmósfera brumosa)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SkyModel {
+    pub sun_elevation: f32, // Grados sobre el horizonte
+    pub sun_azimuth: f32,   // Grados alrededor del eje Y, desde +X hacia +Z
+    pub turbidity: f32,
+    pub intensity: f32,     // Escala de la luminancia del cielo
+    pub sun_intensity: f32, // Intensidad de la luz direccional del sol
+}
+
+impl SkyModel {
+    /// Crea un cielo con el sol en la posición dada
+    pub fn new(sun_elevation: f32, sun_azimuth: f32, turbidity: f32) -> Self {
+        SkyModel {
+            sun_elevation,
+            sun_azimuth,
+            turbidity: turbidity.clamp(1.7, 10.0),
+            intensity: 1.0,
+            sun_intensity: 1.0,
+        }
+    }
+
+    /// Dirección normalizada hacia el sol
+    pub fn sun_direction(&self) -> Vec3 {
+        let elevation = self.sun_elevation.to_radians();
+        let azimuth = self.sun_azimuth.to_radians();
+        Vec3::new(
+            elevation.cos() * azimuth.cos(),
+            elevation.sin(),
+            elevation.cos() * azimuth.sin(),
+        )
+        .normalize()
+    }
+
+    /// Color de la luz solar tras atravesar la atmósfera
+    /// Con el sol bajo la luz recorre más aire y se vuelve anaranjada
+    pub fn sun_color(&self) -> Color {
+        let zenith = (90.0 - self.sun_elevation.clamp(0.0, 90.0)).to_radians();
+        let zenith_deg = zenith.to_degrees();
+
+        // Masa óptica relativa (Kasten & Young)
+        let air_mass = 1.0 / (zenith.cos() + 0.15 * (93.885 - zenith_deg).powf(-1.253));
+
+        // Extinción aproximada por longitud de onda (rojo, verde, azul)
+        let rayleigh = [0.0247, 0.0442, 0.0940];
+        let mie = 0.008 * self.turbidity;
+
+        Color::new(
+            (-air_mass * (rayleigh[0] + mie)).exp(),
+            (-air_mass * (rayleigh[1] + mie)).exp(),
+            (-air_mass * (rayleigh[2] + mie)).exp(),
+        )
+    }
+
+    /// Radiancia del cielo en la dirección dada
+    pub fn radiance(&self, direction: &Vec3) -> Color {
+        let dir = direction.normalize();
+        let sun = self.sun_direction();
+        let t = self.turbidity;
+
+        // Bajo el horizonte se usa el color del horizonte, atenuado (suelo)
+        let below_horizon = dir.y < 0.0;
+        let dir = Vec3::new(dir.x, dir.y.max(0.001), dir.z).normalize();
+
+        let theta = dir.y.clamp(-1.0, 1.0).acos();
+        let theta_sun = sun.y.clamp(0.0, 1.0).acos();
+        let gamma = dir.dot(&sun).clamp(-1.0, 1.0).acos();
+
+        // Coeficientes de la función de distribución de Perez
+        let coeffs_y = [
+            0.1787 * t - 1.4630,
+            -0.3554 * t + 0.4275,
+            -0.0227 * t + 5.3251,
+            0.1206 * t - 2.5771,
+            -0.0670 * t + 0.3703,
+        ];
+        let coeffs_x = [
+            -0.0193 * t - 0.2592,
+            -0.0665 * t + 0.0008,
+            -0.0004 * t + 0.2125,
+            -0.0641 * t - 0.8989,
+            -0.0033 * t + 0.0452,
+        ];
+        let coeffs_yc = [
+            -0.0167 * t - 0.2608,
+            -0.0950 * t + 0.0092,
+            -0.0079 * t + 0.2102,
+            -0.0441 * t - 1.6537,
+            -0.0109 * t + 0.0529,
+        ];
+
+        // Valores en el cenit
+        let th = theta_sun;
+        let th2 = th * th;
+        let th3 = th2 * th;
+        let t2 = t * t;
+        let zenith_x = t2 * (0.00166 * th3 - 0.00375 * th2 + 0.00209 * th)
+            + t * (-0.02903 * th3 + 0.06377 * th2 - 0.03202 * th + 0.00394)
+            + (0.11693 * th3 - 0.21196 * th2 + 0.06052 * th + 0.25886);
+        let zenith_y = t2 * (0.00275 * th3 - 0.00610 * th2 + 0.00317 * th)
+            + t * (-0.04214 * th3 + 0.08970 * th2 - 0.04153 * th + 0.00516)
+            + (0.15346 * th3 - 0.26756 * th2 + 0.06670 * th + 0.26688);
+
+        // La luminancia se expresa relativa al cenit (el cenit vale 1.0)
+        let luminance = perez(theta, gamma, &coeffs_y) / perez(0.0, theta_sun, &coeffs_y);
+        let x = zenith_x * perez(theta, gamma, &coeffs_x) / perez(0.0, theta_sun, &coeffs_x);
+        let y = zenith_y * perez(theta, gamma, &coeffs_yc) / perez(0.0, theta_sun, &coeffs_yc);
+
+        // El cielo se oscurece cuando el sol se acerca al horizonte
+        let daylight = (sun.y * 4.0).clamp(0.05, 1.0);
+        let mut color = xyy_to_rgb(x, y, luminance * 0.5 * daylight) * self.intensity;
+
+        if below_horizon {
+            color *= 0.3;
+        }
+
+        color.max(&Color::zero())
+    }
+
+    /// Agrega el estado del cielo al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_f32(state, self.sun_elevation);
+        hash_f32(state, self.sun_azimuth);
+        hash_f32(state, self.turbidity);
+        hash_f32(state, self.intensity);
+        hash_f32(state, self.sun_intensity);
+    }
+}
+
+/// Función de distribución de luminancia de Perez
+fn perez(theta: f32, gamma: f32, c: &[f32; 5]) -> f32 {
+    let cos_theta = theta.cos().max(0.001);
+    let cos_gamma = gamma.cos();
+    (1.0 + c[0] * (c[1] / cos_theta).exp()) * (1.0 + c[2] * (c[3] * gamma).exp() + c[4] * cos_gamma * cos_gamma)
+}
+
+/// Convierte del espacio de color CIE xyY a RGB lineal (sRGB/Rec.709)
+fn xyy_to_rgb(x: f32, y: f32, luminance: f32) -> Color {
+    if y <= 0.0 {
+        return Color::zero();
+    }
+
+    let big_x = x / y * luminance;
+    let big_z = (1.0 - x - y) / y * luminance;
+
+    Color::new(
+        3.2406 * big_x - 1.5372 * luminance - 0.4986 * big_z,
+        -0.9689 * big_x + 1.8758 * luminance + 0.0415 * big_z,
+        0.0557 * big_x - 0.2040 * luminance + 1.0570 * big_z,
+    )
+}
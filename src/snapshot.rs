@@ -0,0 +1,177 @@
+// Instantáneas binarias de escenas: la escena completa (objetos, mallas con
+// su BVH, la TLAS, luces, texturas ya decodificadas, animación y ajustes) se
+// guarda tal como está en memoria, así que al cargarla no hay que leer
+// texturas ni mallas ni reconstruir ninguna BVH. Sirve para escenas
+// generadas que tardan en construirse y se renderizan varias veces.
+//
+// No es un formato de intercambio: cambia con los tipos de la escena, y una
+// instantánea de otra versión (`FORMAT_VERSION`) se rechaza y hay que
+// volver a generarla.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::vector::Vec3;
+use crate::material::Material;
+use crate::sphere::Sphere;
+use crate::plane::Plane;
+use crate::cube::Cube;
+use crate::pyramid::Pyramid;
+use crate::mesh::TriangleMesh;
+use crate::lod::LodMesh;
+use crate::instance::Instance;
+use crate::moving::MovingObject;
+use crate::prefab::TextureOffset;
+use crate::transform::Transform;
+use crate::scene::{Intersectable, Scene};
+
+/// Primeros bytes de toda instantánea
+const MAGIC: &[u8; 8] = b"RTSNAP\r\n";
+
+/// Versión del formato; se incrementa al cambiar los tipos que se guardan
+pub const FORMAT_VERSION: u32 = 6;
+
+/// Copia serializable de un objeto de la escena (ver `Intersectable::snapshot`)
+#[derive(Serialize, Deserialize)]
+pub enum ObjectSnapshot {
+    Sphere(Sphere),
+    Plane(Plane),
+    Cube(Cube),
+    Pyramid(Pyramid),
+    Mesh(TriangleMesh),
+    Lod(LodMesh),
+    /// `object` es la posición del objeto en la tabla de compartidos
+    Instance { object: usize, transform: Transform },
+    Moving { object: Box<ObjectSnapshot>, velocity: Vec3 },
+    TextureOffset { object: Box<ObjectSnapshot>, offset: usize, material: Option<Material> },
+}
+
+impl ObjectSnapshot {
+    /// Reconstruye el objeto; `shared` son los objetos compartidos ya
+    /// reconstruidos a los que apuntan las instancias
+    fn into_object(self, shared: &[Arc<dyn Intersectable>]) -> Result<Box<dyn Intersectable>, String> {
+        Ok(match self {
+            ObjectSnapshot::Sphere(sphere) => Box::new(sphere),
+            ObjectSnapshot::Plane(plane) => Box::new(plane),
+            ObjectSnapshot::Cube(cube) => Box::new(cube),
+            ObjectSnapshot::Pyramid(pyramid) => Box::new(pyramid),
+            ObjectSnapshot::Mesh(mesh) => Box::new(mesh),
+            ObjectSnapshot::Lod(lod) => Box::new(lod),
+            ObjectSnapshot::Instance { object, transform } => {
+                let object = shared.get(object).ok_or_else(|| format!("instancia de un objeto inexistente: {}", object))?;
+                Box::new(Instance::new(object.clone(), transform))
+            }
+            ObjectSnapshot::Moving { object, velocity } => Box::new(MovingObject::new(object.into_object(shared)?, velocity)),
+            ObjectSnapshot::TextureOffset { object, offset, material } => {
+                let mut object = TextureOffset::new(object.into_object(shared)?, offset);
+                object.material = material;
+                Box::new(object)
+            }
+        })
+    }
+}
+
+/// Tabla de los objetos que comparten las instancias: cada uno se guarda
+/// una sola vez aunque lo usen muchas instancias
+#[derive(Default)]
+pub struct SharedObjects {
+    indices: HashMap<usize, usize>,
+    objects: Vec<ObjectSnapshot>,
+}
+
+impl SharedObjects {
+    /// Posición de `object` en la tabla, agregándolo si es la primera vez
+    /// que aparece. Los objetos de los que depende quedan antes que él
+    pub fn share(&mut self, object: &Arc<dyn Intersectable>) -> Option<usize> {
+        let key = Arc::as_ptr(object) as *const () as usize;
+        if let Some(&index) = self.indices.get(&key) {
+            return Some(index);
+        }
+        let snapshot = object.snapshot(self)?;
+        self.objects.push(snapshot);
+        self.indices.insert(key, self.objects.len() - 1);
+        Some(self.objects.len() - 1)
+    }
+}
+
+/// (De)serialización de `Scene::objects`: la tabla de objetos compartidos
+/// seguida de los objetos de la escena
+pub(crate) mod objects {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(objects: &[Box<dyn Intersectable>], serializer: S) -> Result<S::Ok, S::Error> {
+        let mut shared = SharedObjects::default();
+        let snapshots = objects
+            .iter()
+            .enumerate()
+            .map(|(index, object)| {
+                object
+                    .snapshot(&mut shared)
+                    .ok_or_else(|| S::Error::custom(format!("el objeto {} no se puede guardar en una instantánea", index)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        (&shared.objects, &snapshots).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Box<dyn Intersectable>>, D::Error> {
+        let (shared, snapshots): (Vec<ObjectSnapshot>, Vec<ObjectSnapshot>) = Deserialize::deserialize(deserializer)?;
+        // Cada compartido solo depende de los anteriores
+        let mut objects: Vec<Arc<dyn Intersectable>> = Vec::with_capacity(shared.len());
+        for snapshot in shared {
+            let object = snapshot.into_object(&objects).map_err(D::Error::custom)?;
+            objects.push(Arc::from(object));
+        }
+        snapshots
+            .into_iter()
+            .map(|snapshot| snapshot.into_object(&objects))
+            .collect::<Result<_, _>>()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Instantánea de la escena en memoria
+/// Falla si algún objeto no se puede guardar (ver `Intersectable::snapshot`)
+pub fn to_bytes(scene: &Scene) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    scene.prepare_tlas();
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(FORMAT_VERSION.to_le_bytes());
+    bincode::serialize_into(&mut bytes, scene)?;
+    Ok(bytes)
+}
+
+/// Escena guardada con `to_bytes`
+pub fn from_bytes(bytes: &[u8]) -> Result<Scene, Box<dyn std::error::Error>> {
+    let header = MAGIC.len() + 4;
+    if bytes.len() < header || &bytes[..MAGIC.len()] != MAGIC {
+        return Err("no es una instantánea de escena".into());
+    }
+    let version = u32::from_le_bytes(bytes[MAGIC.len()..header].try_into()?);
+    if version != FORMAT_VERSION {
+        return Err(format!(
+            "la instantánea es de la versión {} del formato y se esperaba la {}; hay que volver a generarla",
+            version, FORMAT_VERSION
+        )
+        .into());
+    }
+    Ok(bincode::deserialize(&bytes[header..])?)
+}
+
+/// Guarda la instantánea de la escena en `path`
+#[cfg(feature = "fs")]
+pub fn save(scene: &Scene, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = to_bytes(scene)?;
+    crate::framebuffer::create_parent_dir(path)?;
+    std::fs::write(path, bytes).map_err(|e| format!("no se pudo escribir {}: {}", path, e))?;
+    Ok(())
+}
+
+/// Lee la instantánea `path` (ver `save`)
+#[cfg(feature = "fs")]
+pub fn load(path: &str) -> Result<Scene, Box<dyn std::error::Error>> {
+    let bytes = std::fs::read(path).map_err(|e| format!("no se pudo leer {}: {}", path, e))?;
+    from_bytes(&bytes).map_err(|e| format!("{}: {}", path, e).into())
+}
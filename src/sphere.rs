@@ -1,9 +1,17 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
 use crate::vector::{Point3, Vec3};
 use crate::ray::Ray;
 use crate::material::Material;
+use crate::aabb::Aabb;
+use crate::packet::{self, RayPacket, PacketHits};
+use crate::gpu::GpuPrimitive;
+use crate::render_cache::{hash_f32, hash_vec3, hash_material};
 
 /// Estructura que representa una esfera en el espacio 3D
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Serialize, Deserialize)]
 pub struct Sphere {
     pub center: Point3,
     pub radius: f32,
@@ -65,4 +73,79 @@ impl Sphere {
 
         Some((u, v, 0))
     }
+
+    /// Intersección con un paquete de rayos (ver `packet`)
+    pub fn intersect_packet(&self, rays: &RayPacket) -> PacketHits {
+        packet::intersect_sphere(rays, &self.center, self.radius)
+    }
+
+    /// Problemas que impiden renderizar la esfera (ver `Scene::validate`)
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if !self.center.is_finite() {
+            problems.push("esfera con centro no finito (NaN o infinito)".to_string());
+        }
+        if !(self.radius > 0.0 && self.radius.is_finite()) {
+            problems.push(format!("esfera con radio inválido: {} (debe ser positivo)", self.radius));
+        }
+        problems
+    }
+
+    /// Triángulos que aproximan la esfera (para exportarla): `segments`
+    /// meridianos y la mitad de paralelos
+    pub fn tessellate(&self, segments: usize) -> (Vec<Point3>, Vec<[usize; 3]>) {
+        let segments = segments.max(3);
+        let rings = (segments / 2).max(2);
+        let mut vertices = Vec::with_capacity((rings + 1) * (segments + 1));
+        for ring in 0..=rings {
+            let (sin_theta, cos_theta) = (std::f32::consts::PI * ring as f32 / rings as f32).sin_cos();
+            for segment in 0..=segments {
+                let (sin_phi, cos_phi) = (std::f32::consts::TAU * segment as f32 / segments as f32).sin_cos();
+                let direction = Vec3::new(sin_theta * cos_phi, cos_theta, sin_theta * sin_phi);
+                vertices.push(self.center + direction * self.radius);
+            }
+        }
+
+        let mut triangles = Vec::with_capacity(rings * segments * 2);
+        for ring in 0..rings {
+            for segment in 0..segments {
+                let a = ring * (segments + 1) + segment;
+                let b = a + segments + 1;
+                triangles.push([a, a + 1, b]);
+                triangles.push([a + 1, b + 1, b]);
+            }
+        }
+        (vertices, triangles)
+    }
+
+    /// Caja envolvente de la esfera
+    pub fn bounds(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::new(self.center - r, self.center + r)
+    }
+
+    /// Área de la superficie de la esfera
+    pub fn surface_area(&self) -> f32 {
+        4.0 * std::f32::consts::PI * self.radius * self.radius
+    }
+
+    /// Punto de la superficie a partir de (u, v) en [0, 1)², distribuido uniformemente
+    pub fn sample_surface(&self, u: f32, v: f32) -> Option<Point3> {
+        let z = 1.0 - 2.0 * u;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = 2.0 * std::f32::consts::PI * v;
+        Some(self.center + Vec3::new(r * phi.cos(), r * phi.sin(), z) * self.radius)
+    }
+
+    /// Primitiva para el backend de GPU (UV esféricas sobre la textura 0)
+    pub fn gpu_primitive(&self) -> GpuPrimitive {
+        GpuPrimitive::sphere(self.center, self.radius).with_texture(0)
+    }
+
+    /// Agrega el estado de la esfera al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_vec3(state, &self.center);
+        hash_f32(state, self.radius);
+        hash_material(state, &self.material);
+    }
 }
@@ -0,0 +1,120 @@
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Contadores que se registran durante un render
+#[derive(Debug, Clone, Copy)]
+pub enum Counter {
+    /// Rayos desde la cámara
+    PrimaryRays,
+    /// Rayos de visibilidad hacia luces, emisores, entorno y oclusión ambiental
+    ShadowRays,
+    /// Reflexiones, transmisiones y rebotes de los caminos
+    SecondaryRays,
+    /// Pruebas de intersección rayo-primitiva
+    IntersectionTests,
+    /// Nodos de BVH recorridos (TLAS y BLAS)
+    BvhNodeVisits,
+}
+
+const COUNTERS: usize = 5;
+
+// Cada hilo cuenta en su propia copia y la vuelca a los totales con `flush`
+// (al terminar cada bloque o fila), para no pelear por un atómico en cada rayo
+static TOTALS: [AtomicU64; COUNTERS] = [const { AtomicU64::new(0) }; COUNTERS];
+
+thread_local! {
+    static LOCAL: Cell<[u64; COUNTERS]> = const { Cell::new([0; COUNTERS]) };
+}
+
+/// Suma uno al contador en el hilo actual
+#[inline]
+pub fn count(counter: Counter) {
+    add(counter, 1);
+}
+
+/// Suma `amount` al contador en el hilo actual
+#[inline]
+pub fn add(counter: Counter, amount: u64) {
+    LOCAL.with(|local| {
+        let mut values = local.get();
+        values[counter as usize] += amount;
+        local.set(values);
+    });
+}
+
+/// Vuelca los contadores del hilo actual a los totales
+pub fn flush() {
+    let values = LOCAL.with(|local| local.replace([0; COUNTERS]));
+    for (total, value) in TOTALS.iter().zip(values) {
+        if value > 0 {
+            total.fetch_add(value, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Pone los totales a cero (antes de empezar un render)
+pub fn reset() {
+    LOCAL.with(|local| local.set([0; COUNTERS]));
+    for total in &TOTALS {
+        total.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Totales acumulados desde el último `reset`
+pub fn snapshot() -> RenderStats {
+    flush();
+    let value = |counter: Counter| TOTALS[counter as usize].load(Ordering::Relaxed);
+    RenderStats {
+        primary_rays: value(Counter::PrimaryRays),
+        shadow_rays: value(Counter::ShadowRays),
+        secondary_rays: value(Counter::SecondaryRays),
+        intersection_tests: value(Counter::IntersectionTests),
+        bvh_node_visits: value(Counter::BvhNodeVisits),
+    }
+}
+
+/// Estadísticas de rayos e intersecciones de un render
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderStats {
+    pub primary_rays: u64,
+    pub shadow_rays: u64,
+    pub secondary_rays: u64,
+    pub intersection_tests: u64,
+    pub bvh_node_visits: u64,
+}
+
+impl RenderStats {
+    pub fn total_rays(&self) -> u64 {
+        self.primary_rays + self.shadow_rays + self.secondary_rays
+    }
+
+    /// Millones de rayos por segundo
+    pub fn mrays_per_second(&self, elapsed: Duration) -> f64 {
+        self.total_rays() as f64 / elapsed.as_secs_f64().max(1e-9) / 1e6
+    }
+}
+
+impl fmt::Display for RenderStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let per_ray = |value: u64| value as f64 / self.total_rays().max(1) as f64;
+        writeln!(f, "  {:<25}{:>14}", "Rayos primarios:", self.primary_rays)?;
+        writeln!(f, "  {:<25}{:>14}", "Rayos de sombra:", self.shadow_rays)?;
+        writeln!(f, "  {:<25}{:>14}", "Rayos secundarios:", self.secondary_rays)?;
+        writeln!(
+            f,
+            "  {:<25}{:>14} ({:.1} por rayo)",
+            "Pruebas de intersección:",
+            self.intersection_tests,
+            per_ray(self.intersection_tests)
+        )?;
+        write!(
+            f,
+            "  {:<25}{:>14} ({:.1} por rayo)",
+            "Nodos de BVH visitados:",
+            self.bvh_node_visits,
+            per_ray(self.bvh_node_visits)
+        )
+    }
+}
@@ -0,0 +1,68 @@
+use std::hash::Hasher;
+
+use crate::vector::Color;
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+use crate::render_cache::hash_f32;
+use crate::tonemap::luminance;
+
+/// Cómo se combinan las imágenes de los dos ojos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StereoLayout {
+    /// Izquierda y derecha una al lado de la otra (visores VR, televisores 3D)
+    SideBySide,
+    /// Anaglifo rojo/cian: el ojo izquierdo en el canal rojo y el derecho en
+    /// verde y azul, para ver con gafas de papel
+    Anaglyph,
+}
+
+/// Render estereoscópico: la escena se renderiza desde dos cámaras separadas
+/// `interocular` unidades a lo largo del eje horizontal de la cámara
+/// Los ejes de las dos cámaras son paralelos, así que los objetos muy
+/// lejanos coinciden en ambas imágenes y los cercanos "salen" de la pantalla.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stereo {
+    pub interocular: f32,
+    pub layout: StereoLayout,
+}
+
+impl Stereo {
+    /// Separación típica entre los ojos de una persona (6.5 cm si la escena está en metros)
+    pub fn new(layout: StereoLayout) -> Self {
+        Stereo { interocular: 0.065, layout }
+    }
+
+    pub fn with_interocular(mut self, interocular: f32) -> Self {
+        self.interocular = interocular.max(0.0);
+        self
+    }
+
+    /// Cámaras del ojo izquierdo y del derecho
+    pub fn eyes(&self, camera: &Camera) -> (Camera, Camera) {
+        let half = self.interocular / 2.0;
+        (camera.with_eye_offset(-half), camera.with_eye_offset(half))
+    }
+
+    /// Combina las imágenes de los dos ojos según `layout`
+    pub fn compose(&self, left: &Framebuffer, right: &Framebuffer) -> Framebuffer {
+        let (width, height) = (left.width(), left.height());
+        match self.layout {
+            StereoLayout::SideBySide => Framebuffer::from_fn(width * 2, height, |x, y| {
+                if x < width { left.get(x, y) } else { right.get(x - width, y) }
+            }),
+            StereoLayout::Anaglyph => Framebuffer::from_fn(width, height, |x, y| {
+                // Cada ojo en gris: con colores saturados el anaglifo "puro"
+                // deja objetos visibles solo en un ojo
+                let l = luminance(left.get(x, y));
+                let r = luminance(right.get(x, y));
+                Color::new(l, r, r)
+            }),
+        }
+    }
+
+    /// Agrega el modo estéreo al hash del render (cambia la imagen guardada)
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        hash_f32(state, self.interocular);
+        state.write_u8(self.layout as u8);
+    }
+}
@@ -0,0 +1,227 @@
+// Vista del render en curso por HTTP (feature `server`), para seguir desde
+// un navegador renders en máquinas remotas o sin pantalla. Es un receptor de
+// avance como la ventana de `preview`: los bloques terminados se pintan en
+// una imagen compartida y cada cliente recibe una secuencia MJPEG
+// (multipart/x-mixed-replace) que cualquier navegador muestra en un <img>.
+// Rutas:
+//
+//   GET /             página con la imagen y el porcentaje
+//   GET /stream       secuencia MJPEG; se envía un cuadro nuevo cuando cambia la imagen
+//   GET /frame.jpg    la imagen actual
+//   GET /progress     {"done": bloques terminados, "total": bloques}
+//
+// Igual que el servicio de render, no conviene exponerlo fuera de una red de
+// confianza.
+
+use std::error::Error;
+use std::io::{self, Cursor, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use image::{ImageOutputFormat, ImageResult, RgbImage};
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response};
+
+use crate::vector::Color;
+use crate::renderer::Tile;
+use crate::progress::ProgressSink;
+use crate::framebuffer::color_to_rgb;
+use crate::tonemap::ToneMapping;
+use crate::gamma::{self, ColorEncoding};
+
+/// Tiempo mínimo entre dos cuadros enviados a un cliente
+const FRAME_INTERVAL: Duration = Duration::from_millis(250);
+/// Calidad JPEG de los cuadros (de 1 a 100)
+const JPEG_QUALITY: u8 = 80;
+/// Separador entre los cuadros de la secuencia
+const BOUNDARY: &str = "raytracer-frame";
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="es">
+<head>
+  <meta charset="utf-8">
+  <title>Raytracer - render en curso</title>
+  <style>
+    body { font-family: sans-serif; background: #1e1e24; color: #ddd; margin: 2em; }
+    img { display: block; margin-top: 1em; max-width: 100%; image-rendering: pixelated; }
+  </style>
+</head>
+<body>
+  <span id="status"></span>
+  <img src="stream" alt="render en curso">
+  <script>
+    const status = document.getElementById("status");
+    setInterval(async () => {
+      try {
+        const { done, total } = await (await fetch("progress")).json();
+        status.textContent = total ? `${Math.round((done / total) * 100)}% (${done}/${total} bloques)` : "esperando el render...";
+      } catch {
+        status.textContent = "render terminado";
+      }
+    }, 1000);
+  </script>
+</body>
+</html>
+"#;
+
+/// Imagen que se va formando; `version` aumenta con cada bloque pintado
+struct Frame {
+    image: RgbImage,
+    version: u64,
+}
+
+struct Shared {
+    frame: Mutex<Frame>,
+    changed: Condvar,
+    done: AtomicUsize,
+    total: AtomicUsize,
+}
+
+impl Shared {
+    /// Espera a que la imagen cambie respecto de `version` y la codifica en
+    /// JPEG; retorna la nueva versión
+    fn next_jpeg(&self, version: u64) -> ImageResult<(u64, Vec<u8>)> {
+        let mut frame = self.frame.lock().unwrap();
+        while frame.version == version {
+            frame = self.changed.wait(frame).unwrap();
+        }
+        let version = frame.version;
+        let image = frame.image.clone();
+        drop(frame);
+        Ok((version, encode_jpeg(&image)?))
+    }
+}
+
+/// Servidor HTTP que transmite el render en curso; se pasa al renderer como
+/// receptor de avance (ver `ProgressSink`)
+pub struct RenderStream {
+    shared: Arc<Shared>,
+    address: String,
+    tone_mapping: ToneMapping,
+    encoding: ColorEncoding,
+}
+
+impl RenderStream {
+    /// Escucha en `address` (p. ej. "0.0.0.0:8081") y atiende a cada
+    /// cliente en su propio hilo. La imagen empieza en negro, de
+    /// `width`×`height` píxeles; los bloques que no entran se ignoran
+    pub fn bind(address: &str, width: u32, height: u32) -> Result<Self, Box<dyn Error>> {
+        let server = tiny_http::Server::http(address)
+            .map_err(|e| format!("no se pudo escuchar en {}: {}", address, e))?;
+        let address = server.server_addr().to_string();
+
+        let shared = Arc::new(Shared {
+            frame: Mutex::new(Frame { image: RgbImage::new(width, height), version: 1 }),
+            changed: Condvar::new(),
+            done: AtomicUsize::new(0),
+            total: AtomicUsize::new(0),
+        });
+        let clients = shared.clone();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let shared = clients.clone();
+                // Un cliente que se desconecta no afecta al render
+                std::thread::spawn(move || {
+                    let _ = handle(request, &shared);
+                });
+            }
+        });
+
+        Ok(RenderStream {
+            shared,
+            address,
+            tone_mapping: ToneMapping::default(),
+            encoding: ColorEncoding::Gamma(gamma::DEFAULT_GAMMA),
+        })
+    }
+
+    /// Conversión a 8 bits de los cuadros (la misma que la del PNG final
+    /// para que la transmisión se le parezca)
+    pub fn with_tone_mapping(mut self, tone_mapping: ToneMapping, encoding: ColorEncoding) -> Self {
+        self.tone_mapping = tone_mapping;
+        self.encoding = encoding;
+        self
+    }
+
+    /// Dirección en la que escucha (útil si se pidió el puerto 0)
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+}
+
+impl ProgressSink for RenderStream {
+    fn on_progress(&self, done: usize, total: usize) {
+        self.shared.done.store(done, Ordering::Relaxed);
+        self.shared.total.store(total, Ordering::Relaxed);
+    }
+
+    fn on_tile(&self, tile: &Tile, pixels: &[Color]) {
+        let mut frame = self.shared.frame.lock().unwrap();
+        if tile.x1 > frame.image.width() || tile.y1 > frame.image.height() {
+            return;
+        }
+        let rows = pixels.chunks_exact(tile.width().max(1) as usize);
+        for (y, row) in (tile.y0..tile.y1).zip(rows) {
+            for (x, color) in (tile.x0..tile.x1).zip(row) {
+                frame.image.put_pixel(x, y, color_to_rgb(*color, self.tone_mapping, self.encoding));
+            }
+        }
+        frame.version += 1;
+        self.shared.changed.notify_all();
+    }
+}
+
+fn handle(request: Request, shared: &Shared) -> io::Result<()> {
+    let url = request.url().split('?').next().unwrap_or_default().to_string();
+    match (request.method(), url.as_str()) {
+        (Method::Get, "/") => request.respond(Response::from_string(PAGE).with_header(header("Content-Type", "text/html; charset=utf-8"))),
+        (Method::Get, "/stream") => stream(request, shared),
+        (Method::Get, "/frame.jpg") => match shared.next_jpeg(0) {
+            Ok((_, jpeg)) => request.respond(Response::from_data(jpeg).with_header(header("Content-Type", "image/jpeg"))),
+            Err(e) => request.respond(Response::from_string(e.to_string()).with_status_code(500)),
+        },
+        (Method::Get, "/progress") => {
+            let progress = json!({
+                "done": shared.done.load(Ordering::Relaxed),
+                "total": shared.total.load(Ordering::Relaxed),
+            });
+            request.respond(Response::from_string(progress.to_string()).with_header(header("Content-Type", "application/json")))
+        }
+        _ => request.respond(Response::from_string("ruta desconocida").with_status_code(404)),
+    }
+}
+
+/// Envía un cuadro cada vez que cambia la imagen (como mucho uno cada
+/// `FRAME_INTERVAL`) hasta que el cliente se desconecta
+fn stream(request: Request, shared: &Shared) -> io::Result<()> {
+    // La respuesta se escribe a mano: tiny_http acumula el cuerpo en bloques
+    // y un cuadro podría quedar a medias hasta que llegue el siguiente
+    let mut writer = request.into_writer();
+    write!(
+        writer,
+        "HTTP/1.1 200 OK\r\nContent-Type: multipart/x-mixed-replace; boundary={}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+        BOUNDARY
+    )?;
+
+    let mut version = 0;
+    loop {
+        let (next, jpeg) = shared.next_jpeg(version).map_err(io::Error::other)?;
+        version = next;
+        write!(writer, "--{}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n", BOUNDARY, jpeg.len())?;
+        writer.write_all(&jpeg)?;
+        writer.write_all(b"\r\n")?;
+        writer.flush()?;
+        std::thread::sleep(FRAME_INTERVAL);
+    }
+}
+
+fn encode_jpeg(image: &RgbImage) -> ImageResult<Vec<u8>> {
+    let mut jpeg = Cursor::new(Vec::new());
+    image.write_to(&mut jpeg, ImageOutputFormat::Jpeg(JPEG_QUALITY))?;
+    Ok(jpeg.into_inner())
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("cabecera HTTP inválida")
+}
@@ -1,17 +1,50 @@
+use serde::{Deserialize, Serialize};
+
 use crate::vector::Color;
-use image::RgbImage;
+use crate::render_cache::hash_vec3;
+use std::hash::Hasher;
+use std::time::SystemTime;
+
+use image::DynamicImage;
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Texture {
     pub width: u32,
     pub height: u32,
     pub data: Vec<Vec<Color>>,
+
+    // Archivo de origen y su fecha de modificación (para recarga en caliente)
+    pub path: Option<String>,
+    pub modified: Option<SystemTime>,
 }
 
 impl Texture {
+    #[cfg(feature = "fs")]
     pub fn from_image(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
         let img = image::open(path)?;
-        let rgb_img = img.to_rgb8();
+        Ok(Texture {
+            path: Some(path.to_string()),
+            modified,
+            ..Self::from_decoded(&img)
+        })
+    }
+
+    /// Sin la feature `fs` no se leen archivos (ver `from_memory`)
+    #[cfg(not(feature = "fs"))]
+    pub fn from_image(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Err(crate::assets::without_fs(std::path::Path::new(path)).into())
+    }
+
+    /// Textura a partir del contenido de un archivo de imagen (PNG, JPEG,
+    /// HDR...) ya leído, p. ej. descargado por el navegador
+    pub fn from_memory(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::from_decoded(&image::load_from_memory(bytes)?))
+    }
+
+    fn from_decoded(img: &DynamicImage) -> Self {
+        // Se carga en punto flotante para conservar valores > 1.0 de imágenes HDR
+        let rgb_img = img.to_rgb32f();
         let (width, height) = rgb_img.dimensions();
 
         let mut data = vec![vec![Color::zero(); width as usize]; height as usize];
@@ -19,19 +52,46 @@ impl Texture {
         for y in 0..height {
             for x in 0..width {
                 let pixel = rgb_img.get_pixel(x, y);
-                data[y as usize][x as usize] = Color::new(
-                    pixel[0] as f32 / 255.0,
-                    pixel[1] as f32 / 255.0,
-                    pixel[2] as f32 / 255.0,
-                );
+                data[y as usize][x as usize] = Color::new(pixel[0], pixel[1], pixel[2]);
             }
         }
 
-        Ok(Texture {
+        Texture {
             width,
             height,
             data,
-        })
+            path: None,
+            modified: None,
+        }
+    }
+
+    /// Textura de 1x1 con un color sólido (útil como respaldo)
+    pub fn solid(color: Color) -> Self {
+        Texture {
+            width: 1,
+            height: 1,
+            data: vec![vec![color]],
+            path: None,
+            modified: None,
+        }
+    }
+
+    /// Vuelve a cargar la textura si su archivo cambió en disco
+    /// Retorna Ok(true) si se recargó, Ok(false) si no hubo cambios
+    #[cfg(feature = "fs")]
+    pub fn reload_if_changed(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(false),
+        };
+
+        let modified = std::fs::metadata(&path)?.modified()?;
+        if self.modified == Some(modified) {
+            return Ok(false);
+        }
+
+        *self = Texture::from_image(&path)?;
+        Ok(true)
     }
 
     pub fn sample(&self, u: f32, v: f32) -> Color {
@@ -43,4 +103,36 @@ impl Texture {
 
         self.data[y as usize][x as usize]
     }
+
+    /// Muestrea la textura con interpolación bilineal
+    /// Los bordes se extienden (clamp-to-edge) en lugar de repetirse, de modo
+    /// que el filtrado nunca mezcla texeles del lado opuesto de la imagen
+    pub fn sample_bilinear(&self, u: f32, v: f32) -> Color {
+        let x = (u.clamp(0.0, 1.0) * self.width as f32 - 0.5).max(0.0);
+        let y = (v.clamp(0.0, 1.0) * self.height as f32 - 0.5).max(0.0);
+
+        let x0 = (x as u32).min(self.width - 1);
+        let y0 = (y as u32).min(self.height - 1);
+        let x1 = (x0 + 1).min(self.width - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+
+        let fx = (x - x0 as f32).clamp(0.0, 1.0);
+        let fy = (y - y0 as f32).clamp(0.0, 1.0);
+
+        let top = self.data[y0 as usize][x0 as usize] * (1.0 - fx) + self.data[y0 as usize][x1 as usize] * fx;
+        let bottom = self.data[y1 as usize][x0 as usize] * (1.0 - fx) + self.data[y1 as usize][x1 as usize] * fx;
+
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    /// Agrega el contenido de la textura al hash de la escena
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        state.write_u32(self.width);
+        state.write_u32(self.height);
+        for row in &self.data {
+            for texel in row {
+                hash_vec3(state, texel);
+            }
+        }
+    }
 }
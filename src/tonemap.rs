@@ -0,0 +1,53 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::Color;
+
+/// Operador que comprime los colores HDR del framebuffer al rango [0, 1]
+/// antes de cuantizarlos. El renderer ya no recorta la luz en 1.0, así que
+/// los brillos intensos conservan su información hasta este paso.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToneMapping {
+    /// Recorta cada canal a [0, 1] (comportamiento anterior)
+    #[default]
+    Clamp,
+    /// Reinhard sobre la luminancia: L / (1 + L), conserva el tono del color
+    Reinhard,
+    /// Aproximación de la curva fílmica ACES (Narkowicz 2015)
+    Aces,
+}
+
+/// Luminancia relativa de un color lineal (Rec. 709)
+pub fn luminance(color: Color) -> f32 {
+    0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z
+}
+
+impl ToneMapping {
+    /// Aplica el operador a un color lineal HDR
+    pub fn apply(&self, color: Color) -> Color {
+        let color = color.max(&Color::zero());
+        match self {
+            ToneMapping::Clamp => color.clamp(),
+            ToneMapping::Reinhard => {
+                let l = luminance(color);
+                if l <= 0.0 {
+                    return Color::zero();
+                }
+                (color * (1.0 / (1.0 + l))).clamp()
+            }
+            ToneMapping::Aces => Color::new(aces(color.x), aces(color.y), aces(color.z)),
+        }
+    }
+
+    /// Agrega el operador al hash del render (cambia la imagen guardada)
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        state.write_u8(*self as u8);
+    }
+}
+
+fn aces(x: f32) -> f32 {
+    let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+    ((x * (a * x + b)) / (x * (c * x + d) + e)).clamp(0.0, 1.0)
+}
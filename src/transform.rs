@@ -0,0 +1,188 @@
+use std::hash::Hasher;
+
+use serde::{Deserialize, Serialize};
+
+use crate::vector::{Point3, Vec3};
+use crate::render_cache::hash_f32;
+
+/// Transformación afín: p' = M·p + t
+/// Guarda también la inversa para llevar rayos al espacio local del objeto
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Transform {
+    pub matrix: [[f32; 3]; 3],
+    pub translation: Vec3,
+    inverse: [[f32; 3]; 3],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl Transform {
+    /// Crea una transformación a partir de la matriz lineal y la traslación
+    /// Retorna None si la matriz no es invertible
+    pub fn new(matrix: [[f32; 3]; 3], translation: Vec3) -> Option<Self> {
+        invert(&matrix).map(|inverse| Transform {
+            matrix,
+            translation,
+            inverse,
+        })
+    }
+
+    pub fn identity() -> Self {
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        Transform {
+            matrix: identity,
+            translation: Vec3::zero(),
+            inverse: identity,
+        }
+    }
+
+    pub fn translation(offset: Vec3) -> Self {
+        Transform {
+            translation: offset,
+            ..Transform::identity()
+        }
+    }
+
+    /// Escala por eje (los factores nulos se reemplazan por un valor mínimo)
+    pub fn scaling(scale: Vec3) -> Self {
+        let (x, y, z) = (nonzero(scale.x), nonzero(scale.y), nonzero(scale.z));
+        Transform {
+            matrix: [[x, 0.0, 0.0], [0.0, y, 0.0], [0.0, 0.0, z]],
+            translation: Vec3::zero(),
+            inverse: [[1.0 / x, 0.0, 0.0], [0.0, 1.0 / y, 0.0], [0.0, 0.0, 1.0 / z]],
+        }
+    }
+
+    /// Rotación alrededor del eje X (en grados)
+    pub fn rotation_x(degrees: f32) -> Self {
+        let (s, c) = degrees.to_radians().sin_cos();
+        Self::rotation([[1.0, 0.0, 0.0], [0.0, c, -s], [0.0, s, c]])
+    }
+
+    /// Rotación alrededor del eje Y (en grados)
+    pub fn rotation_y(degrees: f32) -> Self {
+        let (s, c) = degrees.to_radians().sin_cos();
+        Self::rotation([[c, 0.0, s], [0.0, 1.0, 0.0], [-s, 0.0, c]])
+    }
+
+    /// Rotación alrededor del eje Z (en grados)
+    pub fn rotation_z(degrees: f32) -> Self {
+        let (s, c) = degrees.to_radians().sin_cos();
+        Self::rotation([[c, -s, 0.0], [s, c, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Las rotaciones son ortogonales: la inversa es la traspuesta
+    fn rotation(matrix: [[f32; 3]; 3]) -> Self {
+        Transform {
+            matrix,
+            translation: Vec3::zero(),
+            inverse: transpose(&matrix),
+        }
+    }
+
+    /// Transformación que deshace `self`
+    pub fn inverse(&self) -> Transform {
+        Transform {
+            matrix: self.inverse,
+            translation: -apply(&self.inverse, &self.translation),
+            inverse: self.matrix,
+        }
+    }
+
+    /// Composición: primero se aplica `self` y después `next`
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            matrix: multiply(&next.matrix, &self.matrix),
+            translation: apply(&next.matrix, &self.translation) + next.translation,
+            inverse: multiply(&self.inverse, &next.inverse),
+        }
+    }
+
+    pub fn transform_point(&self, point: &Point3) -> Point3 {
+        apply(&self.matrix, point) + self.translation
+    }
+
+    pub fn transform_vector(&self, vector: &Vec3) -> Vec3 {
+        apply(&self.matrix, vector)
+    }
+
+    /// Las normales se transforman con la inversa traspuesta
+    pub fn transform_normal(&self, normal: &Vec3) -> Vec3 {
+        apply(&transpose(&self.inverse), normal).normalize()
+    }
+
+    pub fn inverse_transform_point(&self, point: &Point3) -> Point3 {
+        apply(&self.inverse, &(*point - self.translation))
+    }
+
+    pub fn inverse_transform_vector(&self, vector: &Vec3) -> Vec3 {
+        apply(&self.inverse, vector)
+    }
+
+    /// Si la matriz, su inversa y la traslación no tienen NaN ni infinitos
+    pub fn is_finite(&self) -> bool {
+        let finite = |matrix: &[[f32; 3]; 3]| matrix.iter().flatten().all(|value| value.is_finite());
+        finite(&self.matrix) && finite(&self.inverse) && self.translation.is_finite()
+    }
+
+    pub fn hash_state(&self, state: &mut dyn Hasher) {
+        for row in &self.matrix {
+            for value in row {
+                hash_f32(state, *value);
+            }
+        }
+        hash_f32(state, self.translation.x);
+        hash_f32(state, self.translation.y);
+        hash_f32(state, self.translation.z);
+    }
+}
+
+fn nonzero(value: f32) -> f32 {
+    if value.abs() < 1e-6 { 1e-6_f32.copysign(value) } else { value }
+}
+
+fn apply(m: &[[f32; 3]; 3], v: &Vec3) -> Vec3 {
+    Vec3::new(
+        m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+        m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+        m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+    )
+}
+
+fn multiply(a: &[[f32; 3]; 3], b: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut result = [[0.0; 3]; 3];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
+
+fn transpose(m: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    [
+        [m[0][0], m[1][0], m[2][0]],
+        [m[0][1], m[1][1], m[2][1]],
+        [m[0][2], m[1][2], m[2][2]],
+    ]
+}
+
+fn invert(m: &[[f32; 3]; 3]) -> Option<[[f32; 3]; 3]> {
+    let cofactor = |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+
+    let det = m[0][0] * cofactor(1, 2, 1, 2) - m[0][1] * cofactor(1, 2, 0, 2) + m[0][2] * cofactor(1, 2, 0, 1);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+
+    Some([
+        [cofactor(1, 2, 1, 2) * inv_det, -cofactor(0, 2, 1, 2) * inv_det, cofactor(0, 1, 1, 2) * inv_det],
+        [-cofactor(1, 2, 0, 2) * inv_det, cofactor(0, 2, 0, 2) * inv_det, -cofactor(0, 1, 0, 2) * inv_det],
+        [cofactor(1, 2, 0, 1) * inv_det, -cofactor(0, 2, 0, 1) * inv_det, cofactor(0, 1, 0, 1) * inv_det],
+    ])
+}
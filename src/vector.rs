@@ -1,5 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sampler::Sampler;
+
 /// Estructura de vector 3D utilizada para posiciones, direcciones y colores
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Vec3 {
     pub x: f32,
     pub y: f32,
@@ -12,7 +16,7 @@ pub type Color = Vec3;
 
 impl Vec3 {
     /// Crea un nuevo vector
-    pub fn new(x: f32, y: f32, z: f32) -> Self {
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
         Vec3 { x, y, z }
     }
 
@@ -67,6 +71,11 @@ impl Vec3 {
         *self - (*normal * (2.0 * self.dot(normal)))
     }
 
+    /// Si ningún componente es NaN ni infinito
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
     /// Limita los componentes del vector entre 0 y 1 (útil para colores)
     pub fn clamp(&self) -> Self {
         Vec3 {
@@ -75,6 +84,103 @@ impl Vec3 {
             z: self.z.clamp(0.0, 1.0),
         }
     }
+
+    /// Interpolación lineal: `self` con t = 0 y `other` con t = 1
+    pub fn lerp(&self, other: &Vec3, t: f32) -> Vec3 {
+        *self + (*other - *self) * t
+    }
+
+    /// Mínimo componente a componente
+    pub fn min(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(self.x.min(other.x), self.y.min(other.y), self.z.min(other.z))
+    }
+
+    /// Máximo componente a componente
+    pub fn max(&self, other: &Vec3) -> Vec3 {
+        Vec3::new(self.x.max(other.x), self.y.max(other.y), self.z.max(other.z))
+    }
+
+    /// Valor absoluto de cada componente
+    pub fn abs(&self) -> Vec3 {
+        Vec3::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    /// Si todos los componentes son casi cero (p. ej. una dirección
+    /// degenerada que no conviene normalizar)
+    pub fn near_zero(&self) -> bool {
+        const EPSILON: f32 = 1e-8;
+        self.x.abs() < EPSILON && self.y.abs() < EPSILON && self.z.abs() < EPSILON
+    }
+
+    /// Distancia entre dos puntos
+    pub fn distance(&self, other: &Vec3) -> f32 {
+        (*self - *other).length()
+    }
+
+    /// Dirección al azar distribuida uniformemente sobre la esfera unitaria
+    pub fn random_unit_vector(sampler: &mut dyn Sampler) -> Vec3 {
+        let (u, v) = sampler.next_2d();
+        let z = 1.0 - 2.0 * u;
+        let r = (1.0 - z * z).max(0.0).sqrt();
+        let phi = std::f32::consts::TAU * v;
+        Vec3::new(r * phi.cos(), r * phi.sin(), z)
+    }
+
+    /// Punto al azar distribuido uniformemente dentro de la esfera unitaria
+    pub fn random_in_unit_sphere(sampler: &mut dyn Sampler) -> Vec3 {
+        let direction = Vec3::random_unit_vector(sampler);
+        direction * sampler.next_1d().cbrt()
+    }
+
+    /// Dirección al azar distribuida uniformemente en el hemisferio
+    /// alrededor de `normal` (para densidad proporcional al coseno, ver
+    /// `sampling::cosine_hemisphere`)
+    pub fn random_in_hemisphere(normal: &Vec3, sampler: &mut dyn Sampler) -> Vec3 {
+        let direction = Vec3::random_unit_vector(sampler);
+        if direction.dot(normal) < 0.0 {
+            -direction
+        } else {
+            direction
+        }
+    }
+}
+
+// Acceso por índice de eje (0 = x, 1 = y, 2 = z) y conversión a arreglos
+
+impl std::ops::Index<usize> for Vec3 {
+    type Output = f32;
+
+    fn index(&self, axis: usize) -> &f32 {
+        match axis {
+            0 => &self.x,
+            1 => &self.y,
+            2 => &self.z,
+            _ => panic!("eje fuera de rango: {} (Vec3 tiene 3 componentes)", axis),
+        }
+    }
+}
+
+impl std::ops::IndexMut<usize> for Vec3 {
+    fn index_mut(&mut self, axis: usize) -> &mut f32 {
+        match axis {
+            0 => &mut self.x,
+            1 => &mut self.y,
+            2 => &mut self.z,
+            _ => panic!("eje fuera de rango: {} (Vec3 tiene 3 componentes)", axis),
+        }
+    }
+}
+
+impl From<[f32; 3]> for Vec3 {
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Vec3 { x, y, z }
+    }
+}
+
+impl From<Vec3> for [f32; 3] {
+    fn from(v: Vec3) -> Self {
+        [v.x, v.y, v.z]
+    }
 }
 
 // Implementar operadores aritméticos
@@ -123,6 +229,20 @@ impl std::ops::Mul<Vec3> for f32 {
     }
 }
 
+/// Producto componente a componente (de Hadamard), p. ej. para teñir un
+/// color con el de una luz
+impl std::ops::Mul<Vec3> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.x * rhs.x,
+            y: self.y * rhs.y,
+            z: self.z * rhs.z,
+        }
+    }
+}
+
 impl std::ops::Div<f32> for Vec3 {
     type Output = Vec3;
 
@@ -135,6 +255,19 @@ impl std::ops::Div<f32> for Vec3 {
     }
 }
 
+/// División componente a componente
+impl std::ops::Div<Vec3> for Vec3 {
+    type Output = Vec3;
+
+    fn div(self, rhs: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.x / rhs.x,
+            y: self.y / rhs.y,
+            z: self.z / rhs.z,
+        }
+    }
+}
+
 impl std::ops::Neg for Vec3 {
     type Output = Vec3;
 
@@ -173,6 +306,14 @@ impl std::ops::MulAssign<f32> for Vec3 {
     }
 }
 
+impl std::ops::MulAssign<Vec3> for Vec3 {
+    fn mul_assign(&mut self, rhs: Vec3) {
+        self.x *= rhs.x;
+        self.y *= rhs.y;
+        self.z *= rhs.z;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,6 +370,20 @@ mod tests {
         assert!(approx_equal(result.z, 3.0));
     }
 
+    #[test]
+    fn test_component_wise_operations() {
+        let v1 = Vec3::new(1.0, 2.0, 3.0);
+        let v2 = Vec3::new(4.0, 5.0, 6.0);
+        let product = v1 * v2;
+        assert!(approx_equal(product.x, 4.0));
+        assert!(approx_equal(product.y, 10.0));
+        assert!(approx_equal(product.z, 18.0));
+        let quotient = product / v2;
+        assert!(approx_equal(quotient.x, 1.0));
+        assert!(approx_equal(quotient.y, 2.0));
+        assert!(approx_equal(quotient.z, 3.0));
+    }
+
     #[test]
     fn test_dot_product() {
         let v1 = Vec3::new(1.0, 2.0, 3.0);
@@ -279,6 +434,58 @@ mod tests {
         assert!(approx_equal(reflected.z, expected.z));
     }
 
+    #[test]
+    fn test_indexing() {
+        let mut v = Vec3::new(1.0, 2.0, 3.0);
+        v[1] = 5.0;
+        assert!(approx_equal(v[0], 1.0));
+        assert!(approx_equal(v[1], 5.0));
+        assert!(approx_equal(v[2], 3.0));
+    }
+
+    #[test]
+    fn test_array_conversion() {
+        let v = Vec3::from([1.0, 2.0, 3.0]);
+        assert!(approx_equal(v.y, 2.0));
+        let array: [f32; 3] = v.into();
+        assert_eq!(array, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_lerp_and_distance() {
+        let v1 = Vec3::new(0.0, 0.0, 0.0);
+        let v2 = Vec3::new(2.0, 4.0, 4.0);
+        let middle = v1.lerp(&v2, 0.5);
+        assert!(approx_equal(middle.x, 1.0));
+        assert!(approx_equal(middle.y, 2.0));
+        assert!(approx_equal(middle.z, 2.0));
+        assert!(approx_equal(v1.distance(&v2), 6.0));
+    }
+
+    #[test]
+    fn test_min_max_abs() {
+        let v1 = Vec3::new(1.0, -5.0, 3.0);
+        let v2 = Vec3::new(2.0, 0.0, -3.0);
+        let min = v1.min(&v2);
+        let max = v1.max(&v2);
+        assert!(approx_equal(min.x, 1.0) && approx_equal(min.y, -5.0) && approx_equal(min.z, -3.0));
+        assert!(approx_equal(max.x, 2.0) && approx_equal(max.y, 0.0) && approx_equal(max.z, 3.0));
+        assert!(approx_equal(v1.abs().y, 5.0));
+        assert!(Vec3::new(1e-9, -1e-9, 0.0).near_zero());
+        assert!(!v1.near_zero());
+    }
+
+    #[test]
+    fn test_random_vectors() {
+        let mut rng = crate::sampling::Rng::new(7);
+        let normal = Vec3::new(0.0, 1.0, 0.0);
+        for _ in 0..100 {
+            assert!((Vec3::random_unit_vector(&mut rng).length() - 1.0).abs() < 1e-5);
+            assert!(Vec3::random_in_unit_sphere(&mut rng).length() <= 1.0 + 1e-5);
+            assert!(Vec3::random_in_hemisphere(&normal, &mut rng).dot(&normal) >= 0.0);
+        }
+    }
+
     #[test]
     fn test_clamp() {
         let v = Vec3::new(1.5, -0.5, 0.5);
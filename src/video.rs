@@ -0,0 +1,76 @@
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use image::RgbImage;
+
+use crate::framebuffer::create_parent_dir;
+
+/// Codifica una secuencia de imágenes como video enviándolas a un proceso
+/// `ffmpeg`, que tiene que estar instalado y en el PATH. El contenedor y el
+/// códec se deducen de la extensión de la ruta (.mp4, .webm, .mkv...)
+pub struct VideoEncoder {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    width: u32,
+    height: u32,
+}
+
+impl VideoEncoder {
+    /// Lanza ffmpeg para escribir `path` con cuadros de `width`×`height`
+    pub fn start(path: &str, width: u32, height: u32, fps: f32) -> Result<Self, Box<dyn std::error::Error>> {
+        create_parent_dir(path)?;
+        let mut child = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error"])
+            // Cuadros RGB de 8 bits sin cabecera por la entrada estándar
+            .args(["-f", "rawvideo", "-pix_fmt", "rgb24"])
+            .args(["-s", &format!("{}x{}", width, height), "-r", &fps.to_string(), "-i", "-"])
+            // yuv420p es lo que entienden todos los reproductores, pero
+            // exige dimensiones pares
+            .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2", "-pix_fmt", "yuv420p"])
+            .arg(path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("no se pudo ejecutar ffmpeg: {}", e))?;
+        let stdin = child.stdin.take();
+
+        Ok(VideoEncoder { child, stdin, width, height })
+    }
+
+    /// Agrega un cuadro al final del video
+    pub fn write_frame(&mut self, image: &RgbImage) -> Result<(), Box<dyn std::error::Error>> {
+        if image.dimensions() != (self.width, self.height) {
+            return Err(format!(
+                "cuadro de {}x{} en un video de {}x{}",
+                image.width(),
+                image.height(),
+                self.width,
+                self.height
+            )
+            .into());
+        }
+        let stdin = self.stdin.as_mut().ok_or("el video ya está cerrado")?;
+        stdin
+            .write_all(image.as_raw())
+            .map_err(|e| format!("ffmpeg dejó de aceptar cuadros: {}", e))?;
+        Ok(())
+    }
+
+    /// Cierra la entrada de ffmpeg y espera a que termine de escribir el archivo
+    pub fn finish(mut self) -> Result<(), Box<dyn std::error::Error>> {
+        drop(self.stdin.take());
+        let status = self.child.wait()?;
+        if !status.success() {
+            return Err(format!("ffmpeg terminó con error ({})", status).into());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for VideoEncoder {
+    /// Si no se llamó a `finish` (por un error a mitad del render) se cierra
+    /// igual la entrada para que ffmpeg guarde lo recibido y no quede colgado
+    fn drop(&mut self) {
+        drop(self.stdin.take());
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,136 @@
+// API para JavaScript (feature `wasm`): la escena se pasa como texto (JSON
+// o PBRT) o por nombre de la galería y se renderiza en el buffer RGBA de un
+// ImageData, listo para `putImageData`. Se compila sin la feature `fs`:
+//
+//     wasm-pack build --target web --no-default-features --features wasm
+//
+// y la demo de `web/` carga el paquete generado en `pkg/`. En el navegador
+// no hay hilos, así que el render ocupa el hilo de la página; para no
+// congelarla se puede renderizar por franjas de filas con `render_rows`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::framebuffer::{color_to_rgb, Framebuffer};
+use crate::development::Development;
+use crate::pbrt;
+use crate::renderer::{Renderer, Tile};
+use crate::progress::NoProgress;
+use crate::cancel::CancelToken;
+use crate::scene::Scene;
+use crate::scene_file;
+use crate::scenes;
+use crate::settings::RenderSettings;
+use crate::texture::Texture;
+
+/// Escena lista para renderizar desde JavaScript
+#[wasm_bindgen]
+pub struct WebRenderer {
+    scene: Scene,
+}
+
+#[wasm_bindgen]
+impl WebRenderer {
+    /// Escena en el formato JSON de `scene_file`; las texturas y mallas de
+    /// archivo no se pueden cargar (para las texturas, ver `add_texture`)
+    #[wasm_bindgen(constructor)]
+    pub fn new(scene_json: &str) -> Result<WebRenderer, JsError> {
+        let scene = scene_file::parse(scene_json).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(WebRenderer { scene })
+    }
+
+    /// Escena en el formato de PBRT (ver `pbrt`), sin Include
+    pub fn from_pbrt(text: &str) -> Result<WebRenderer, JsError> {
+        let scene = pbrt::parse(text).map_err(|e| JsError::new(&e.to_string()))?.scene;
+        Ok(WebRenderer { scene })
+    }
+
+    /// Escena incluida (cornell, diorama, bodegon o esferas); las texturas
+    /// de archivo se reemplazan por colores lisos
+    pub fn gallery(name: &str) -> Result<WebRenderer, JsError> {
+        scenes::by_name(name)
+            .map(|scene| WebRenderer { scene })
+            .ok_or_else(|| JsError::new(&format!("no existe una escena incluida llamada '{}'", name)))
+    }
+
+    /// Nombres de las escenas incluidas
+    pub fn gallery_names() -> Vec<String> {
+        scenes::GALLERY.iter().map(|(name, _)| name.to_string()).collect()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.scene.settings.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.scene.settings.height
+    }
+
+    /// Cambia la resolución; la cámara adopta la nueva proporción
+    pub fn set_resolution(&mut self, width: u32, height: u32) {
+        let settings = self.scene.settings;
+        self.scene.set_render_settings(RenderSettings { width: width.max(1), height: height.max(1), ..settings });
+        self.scene.camera.set_aspect_ratio(self.scene.settings.aspect_ratio());
+    }
+
+    pub fn set_samples_per_pixel(&mut self, samples: u32) {
+        let settings = self.scene.settings.with_samples_per_pixel(samples);
+        self.scene.set_render_settings(settings);
+    }
+
+    /// Agrega una textura a partir del contenido de un archivo de imagen
+    /// (p. ej. descargado con `fetch`) y retorna su ID, el siguiente a las
+    /// texturas de la escena
+    pub fn add_texture(&mut self, bytes: &[u8]) -> Result<usize, JsError> {
+        let texture = Texture::from_memory(bytes).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(self.scene.add_texture(texture))
+    }
+
+    /// Renderiza la imagen completa en `data`, el buffer RGBA de un
+    /// ImageData de `width`×`height` (`imageData.data`)
+    pub fn render(&self, data: &mut [u8]) -> Result<(), JsError> {
+        self.check_buffer(data)?;
+        let framebuffer = Renderer::render(&self.scene);
+        write_rows(&framebuffer, 0, &self.scene.development, data);
+        Ok(())
+    }
+
+    /// Renderiza solo las filas `y0..y1` en su lugar dentro de `data`, para
+    /// repartir el render en varios cuadros de la página. A diferencia de
+    /// `render`, no se aplica el denoiser de la escena
+    pub fn render_rows(&self, data: &mut [u8], y0: u32, y1: u32) -> Result<(), JsError> {
+        self.check_buffer(data)?;
+        let (width, height) = (self.width(), self.height());
+        let region = Tile { x0: 0, y0: y0.min(height), x1: width, y1: y1.min(height) };
+        let framebuffer = Renderer::render_region(&self.scene, &region, &NoProgress, &CancelToken::new());
+        write_rows(&framebuffer, region.y0, &self.scene.development, data);
+        Ok(())
+    }
+
+    fn check_buffer(&self, data: &[u8]) -> Result<(), JsError> {
+        let expected = self.width() as usize * self.height() as usize * 4;
+        if data.len() != expected {
+            return Err(JsError::new(&format!(
+                "el buffer tiene {} bytes y la imagen de {}x{} necesita {}",
+                data.len(),
+                self.width(),
+                self.height(),
+                expected
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Copia las filas de `framebuffer` en `data` (RGBA, opaco) a partir de la
+/// fila `y0`, con el tone mapping y la codificación de la escena (la
+/// exposición y los efectos necesitan la imagen completa y no se aplican)
+fn write_rows(framebuffer: &Framebuffer, y0: u32, development: &Development, data: &mut [u8]) {
+    let offset = (y0 * framebuffer.width()) as usize * 4;
+    let pixels = data[offset..].chunks_exact_mut(4);
+    for (pixel, color) in pixels.zip(framebuffer.pixels()) {
+        let rgb = color_to_rgb(*color, development.tone_mapping, development.encoding);
+        pixel.copy_from_slice(&[rgb[0], rgb[1], rgb[2], 255]);
+    }
+}
@@ -0,0 +1,26 @@
+// Pruebas de las pistas de animación y los recorridos de cámara con
+// instantes fuera de lo común.
+
+use raytracer::animation::Track;
+use raytracer::camera_path::{CameraPath, Interpolation};
+use raytracer::vector::Point3;
+
+#[test]
+fn track_with_nan_time_uses_the_first_keyframe() {
+    let track = Track::new().with_keyframe(0.0, 1.0f32).with_keyframe(1.0, 3.0).with_keyframe(f32::NAN, 10.0);
+    assert_eq!(track.evaluate(f32::NAN), Some(1.0));
+    assert_eq!(track.evaluate(0.5), Some(2.0));
+    assert_eq!(track.evaluate(f32::INFINITY), Some(3.0));
+}
+
+#[test]
+fn camera_path_with_nan_time_uses_the_first_keyframe() {
+    let path = CameraPath::new(Interpolation::CatmullRom)
+        .with_keyframe(0.0, Point3::new(0.0, 0.0, 5.0), Point3::zero())
+        .with_keyframe(f32::NAN, Point3::new(9.0, 9.0, 9.0), Point3::zero())
+        .with_keyframe(2.0, Point3::new(5.0, 0.0, 0.0), Point3::zero());
+    let (position, _) = path.evaluate(f32::NAN).unwrap();
+    assert_eq!(position.x, 0.0);
+    assert_eq!(position.z, 5.0);
+    assert!(path.evaluate(1.0).is_some());
+}
@@ -0,0 +1,174 @@
+// Pruebas de regresión con imágenes de referencia ("golden"): se renderizan
+// escenas pequeñas y se comparan con los PNG de `tests/golden/`. Un cambio
+// en el sombreado o en las intersecciones que altere la imagen hace fallar
+// la prueba y deja el render nuevo en `target/golden/` para compararlo.
+//
+// Si el cambio es intencional, se regeneran las referencias con
+//
+//     UPDATE_GOLDEN=1 cargo test --test golden
+//
+// y se revisan los PNG nuevos antes de confirmarlos.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use image::RgbImage;
+
+use raytracer::camera::Camera;
+use raytracer::cube::Cube;
+use raytracer::gamma::{self, ColorEncoding};
+use raytracer::light::Light;
+use raytracer::material::Material;
+use raytracer::mesh::TriangleMesh;
+use raytracer::plane::Plane;
+use raytracer::pyramid::Pyramid;
+use raytracer::renderer::Renderer;
+use raytracer::scene::Scene;
+use raytracer::scenes;
+use raytracer::settings::RenderSettings;
+use raytracer::sphere::Sphere;
+use raytracer::texture::Texture;
+use raytracer::tonemap::ToneMapping;
+use raytracer::transform::Transform;
+use raytracer::vector::{Color, Point3, Vec3};
+
+/// Diferencia (en niveles de 0 a 255) a partir de la cual un canal cuenta
+/// como distinto; por debajo se tolera el redondeo entre plataformas
+const CHANNEL_TOLERANCE: u8 = 3;
+/// Fracción de canales distintos que se tolera
+const MAX_DIFFERENT_FRACTION: f64 = 0.002;
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
+
+/// Renderiza `scene` y la compara con `tests/golden/{name}.png`
+fn check_golden(name: &str, scene: &Scene) {
+    let image = Renderer::render(scene).to_rgb8(ToneMapping::default(), ColorEncoding::Gamma(gamma::DEFAULT_GAMMA));
+    let path = golden_dir().join(format!("{}.png", name));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(golden_dir()).unwrap();
+        image.save(&path).unwrap();
+        return;
+    }
+
+    let golden = match image::open(&path) {
+        Ok(golden) => golden.to_rgb8(),
+        Err(e) => panic!("no se pudo leer {} ({}); generarla con UPDATE_GOLDEN=1", path.display(), e),
+    };
+    if let Err(message) = compare(&image, &golden) {
+        let actual = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target/golden").join(format!("{}.png", name));
+        std::fs::create_dir_all(actual.parent().unwrap()).unwrap();
+        image.save(&actual).unwrap();
+        panic!("{}: {} (render actual en {})", name, message, actual.display());
+    }
+}
+
+fn compare(image: &RgbImage, golden: &RgbImage) -> Result<(), String> {
+    if image.dimensions() != golden.dimensions() {
+        return Err(format!("tamaño {:?}, se esperaba {:?}", image.dimensions(), golden.dimensions()));
+    }
+    let differences: Vec<u8> = image
+        .as_raw()
+        .iter()
+        .zip(golden.as_raw())
+        .map(|(a, b)| a.abs_diff(*b))
+        .collect();
+    let different = differences.iter().filter(|&&difference| difference > CHANNEL_TOLERANCE).count();
+    let fraction = different as f64 / differences.len() as f64;
+    if fraction > MAX_DIFFERENT_FRACTION {
+        let max = differences.iter().max().copied().unwrap_or(0);
+        return Err(format!("{:.2}% de los canales difieren (diferencia máxima {})", fraction * 100.0, max));
+    }
+    Ok(())
+}
+
+fn small_scene(width: u32, height: u32, samples: u32, position: Point3, look_at: Point3) -> Scene {
+    let settings = RenderSettings::new(width, height).with_samples_per_pixel(samples);
+    let camera = Camera::new(position, look_at, Vec3::new(0.0, 1.0, 0.0), 45.0, settings.aspect_ratio(), width, height);
+    let mut scene = Scene::new(camera, Color::new(0.2, 0.25, 0.35));
+    scene.set_render_settings(settings);
+    scene
+}
+
+#[test]
+fn basic_shapes() {
+    let mut scene = small_scene(64, 48, 1, Point3::new(3.0, 2.5, 4.0), Point3::new(0.0, 0.5, 0.0));
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), Material::diffuse(Color::new(0.7, 0.7, 0.7))));
+    scene.add_sphere(Sphere::new(Point3::new(-1.0, 0.6, 0.0), 0.6, Material::diffuse(Color::new(0.8, 0.2, 0.2))));
+    scene.add_cube(Cube::new(Point3::new(0.2, 0.0, -0.5), Point3::new(1.2, 1.0, 0.5), Material::shiny(Color::new(0.2, 0.4, 0.8))));
+    scene.add_pyramid(Pyramid::centered(Point3::new(0.0, 0.0, 1.5), 0.8, Material::shiny(Color::new(0.9, 0.8, 0.2))));
+    scene.add_light(Light::white(Point3::new(5.0, 6.0, 4.0), 1.0));
+    check_golden("basic_shapes", &scene);
+}
+
+#[test]
+fn reflection_and_refraction() {
+    let mut scene = small_scene(64, 48, 1, Point3::new(0.0, 1.5, 5.0), Point3::new(0.0, 0.7, 0.0));
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), Material::diffuse(Color::new(0.6, 0.6, 0.6))));
+    scene.add_sphere(Sphere::new(Point3::new(-1.1, 0.8, 0.0), 0.8, Material::reflective(Color::new(0.9, 0.9, 0.9))));
+    scene.add_sphere(Sphere::new(Point3::new(1.1, 0.8, 0.5), 0.8, Material::transparent(Color::new(0.8, 1.0, 0.9), 0.9)));
+    scene.add_cube(Cube::centered(Point3::new(0.0, 0.5, -2.0), 1.0, Material::diffuse(Color::new(0.2, 0.7, 0.3))));
+    scene.add_light(Light::white(Point3::new(-3.0, 5.0, 4.0), 1.0));
+    check_golden("reflection_and_refraction", &scene);
+}
+
+#[test]
+fn light_types() {
+    let mut scene = small_scene(64, 48, 4, Point3::new(0.0, 3.0, 5.0), Point3::new(0.0, 0.3, 0.0));
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), Material::diffuse(Color::new(0.8, 0.8, 0.8))));
+    scene.add_sphere(Sphere::new(Point3::new(-1.2, 0.5, 0.0), 0.5, Material::diffuse(Color::new(0.9, 0.9, 0.9))));
+    scene.add_cube(Cube::centered(Point3::new(1.2, 0.5, 0.0), 1.0, Material::diffuse(Color::new(0.9, 0.9, 0.9))));
+    scene.add_light(Light::area(
+        Point3::new(-2.0, 3.0, -0.5),
+        Vec3::new(1.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 1.0),
+        Color::new(1.0, 0.9, 0.8),
+        0.6,
+        3,
+    ));
+    scene.add_light(Light::spot(
+        Point3::new(1.2, 3.0, 1.0),
+        Vec3::new(0.0, -1.0, -0.3),
+        Color::new(0.6, 0.7, 1.0),
+        0.8,
+        15.0,
+        25.0,
+    ));
+    scene.add_light(Light::directional(Vec3::new(1.0, 2.0, 1.0), Color::new(1.0, 1.0, 1.0), 0.2));
+    check_golden("light_types", &scene);
+}
+
+#[test]
+fn meshes_instances_and_textures() {
+    let mut scene = small_scene(64, 48, 1, Point3::new(2.5, 2.5, 4.0), Point3::new(0.0, 0.5, 0.0));
+    let stone = format!("{}/textures/stoneblock.png", env!("CARGO_MANIFEST_DIR"));
+    let texture = scene.add_texture(Texture::from_image(&stone).unwrap());
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), Material::diffuse(Color::new(0.7, 0.7, 0.7))));
+    scene.add_cube(Cube::centered(Point3::new(-1.2, 0.5, 0.0), 1.0, Material::diffuse(Color::new(1.0, 1.0, 1.0)).with_texture(texture)));
+
+    let vertices = vec![
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(0.5, 0.0, 0.9),
+        Point3::new(0.5, 1.2, 0.3),
+    ];
+    let triangles = vec![[0, 2, 1], [0, 1, 3], [1, 2, 3], [2, 0, 3]];
+    scene.add_mesh(TriangleMesh::new(vertices, triangles, Material::shiny(Color::new(0.9, 0.5, 0.1))));
+
+    let block = Arc::new(Cube::centered(Point3::zero(), 0.6, Material::diffuse(Color::new(0.3, 0.3, 0.9))));
+    let transform = Transform::rotation_y(30.0)
+        .then(&Transform::rotation_x(20.0))
+        .then(&Transform::translation(Vec3::new(0.3, 0.5, -1.2)));
+    scene.add_instance(block, transform);
+    scene.add_light(Light::white(Point3::new(4.0, 6.0, 3.0), 1.0));
+    check_golden("meshes_instances_and_textures", &scene);
+}
+
+#[test]
+fn path_traced_cornell_box() {
+    let mut scene = scenes::cornell_box();
+    scene.set_render_settings(RenderSettings::new(48, 48).with_samples_per_pixel(8));
+    check_golden("path_traced_cornell_box", &scene);
+}
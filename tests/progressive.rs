@@ -0,0 +1,78 @@
+// Pruebas del renderizado progresivo: cada pasada debe agregar muestras
+// nuevas, de modo que el ruido baje a medida que se acumulan.
+
+use std::time::Duration;
+
+use raytracer::camera::Camera;
+use raytracer::cancel::CancelToken;
+use raytracer::framebuffer::Framebuffer;
+use raytracer::integrator::IntegratorKind;
+use raytracer::material::Material;
+use raytracer::plane::Plane;
+use raytracer::renderer::Renderer;
+use raytracer::sampler::SamplerKind;
+use raytracer::scene::Scene;
+use raytracer::settings::RenderSettings;
+use raytracer::sphere::Sphere;
+use raytracer::vector::{Color, Point3, Vec3};
+
+/// Piso con una esfera encima, vistos desde arriba con oclusión ambiental:
+/// la imagen es suave y casi todo su ruido viene del muestreo
+fn occlusion_scene(sampler: SamplerKind) -> Scene {
+    let settings = RenderSettings::new(32, 32).with_samples_per_pixel(16);
+    let camera = Camera::new(Point3::new(0.0, 6.0, 0.01), Point3::zero(), Vec3::new(0.0, 1.0, 0.0), 45.0, 1.0, 32, 32);
+    let mut scene = Scene::new(camera, Color::zero());
+    scene.set_render_settings(settings);
+    scene.set_sampler(sampler);
+    scene.set_integrator(IntegratorKind::AmbientOcclusion);
+    scene.set_ambient_occlusion(4, 3.0);
+    let gray = Material::diffuse(Color::new(0.5, 0.5, 0.5));
+    scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), gray));
+    scene.add_sphere(Sphere::new(Point3::new(0.0, 2.0, 0.0), 1.0, gray));
+    scene
+}
+
+/// Diferencia cuadrática media entre píxeles vecinos en horizontal
+fn noise(image: &Framebuffer) -> f32 {
+    let pixels = image.pixels();
+    let width = image.width() as usize;
+    let differences: Vec<f32> = pixels
+        .chunks(width)
+        .flat_map(|row| row.windows(2).map(|pair| (pair[0] - pair[1]).length_squared()))
+        .collect();
+    differences.iter().sum::<f32>() / differences.len() as f32
+}
+
+#[test]
+fn noise_decreases_with_passes() {
+    for sampler in [SamplerKind::Random, SamplerKind::Stratified, SamplerKind::Halton, SamplerKind::BlueNoise] {
+        let scene = occlusion_scene(sampler);
+        let mut noise_per_pass = Vec::new();
+        Renderer::render_progressive(&scene, Duration::ZERO, &CancelToken::new(), |image, _| {
+            noise_per_pass.push(noise(image));
+        });
+
+        assert_eq!(noise_per_pass.len(), 16);
+        let first = noise_per_pass[0];
+        let last = noise_per_pass[15];
+        assert!(last < first * 0.5, "{:?}: ruido {} tras 1 pasada y {} tras 16", sampler, first, last);
+    }
+}
+
+#[test]
+fn cancel_returns_the_completed_passes() {
+    let scene = occlusion_scene(SamplerKind::Halton);
+    let cancel = CancelToken::new();
+    let mut snapshot = None;
+    let image = Renderer::render_progressive(&scene, Duration::ZERO, &cancel, |image, done| {
+        if done == 3 {
+            snapshot = Some(image.pixels().to_vec());
+            cancel.cancel();
+        }
+    });
+
+    let snapshot = snapshot.expect("no se completaron 3 pasadas");
+    for (pixel, expected) in image.pixels().iter().zip(&snapshot) {
+        assert!((*pixel - *expected).length_squared() == 0.0, "{:?} != {:?}", pixel, expected);
+    }
+}
@@ -0,0 +1,189 @@
+// Pruebas de la estructura de la escena: búsqueda de intersecciones y
+// elección de niveles de detalle (las de la TLAS están en `scene.rs`).
+
+use std::sync::Arc;
+
+use raytracer::camera::Camera;
+use raytracer::development::Development;
+use raytracer::exposure::Exposure;
+use raytracer::gamma::ColorEncoding;
+use raytracer::light::Light;
+use raytracer::lod::{LodMesh, LodMetric};
+use raytracer::material::Material;
+use raytracer::mesh::TriangleMesh;
+use raytracer::packet::RayPacket;
+use raytracer::plane::Plane;
+use raytracer::postprocess::PostEffect;
+use raytracer::ray::Ray;
+use raytracer::renderer::Renderer;
+use raytracer::scene::{Scene, SceneItem};
+use raytracer::sphere::Sphere;
+use raytracer::texture::Texture;
+use raytracer::tonemap::ToneMapping;
+use raytracer::transform::Transform;
+use raytracer::vector::{Color, Point3, Vec3};
+
+fn empty_scene() -> Scene {
+    let camera = Camera::new(Point3::new(0.0, 0.0, 10.0), Point3::zero(), Vec3::new(0.0, 1.0, 0.0), 45.0, 1.0, 16, 16);
+    Scene::new(camera, Color::zero())
+}
+
+fn gray() -> Material {
+    Material::diffuse(Color::new(0.5, 0.5, 0.5))
+}
+
+#[test]
+fn lod_level_is_chosen_from_the_camera() {
+    let sphere = Sphere::new(Point3::zero(), 1.0, gray());
+    let mesh = |segments| {
+        let (vertices, triangles) = sphere.tessellate(segments);
+        TriangleMesh::new(vertices, triangles, gray())
+    };
+    let lit_scene = |add: &dyn Fn(&mut Scene)| {
+        let mut scene = empty_scene();
+        add(&mut scene);
+        scene.add_light(Light::white(Point3::new(3.0, 3.0, 10.0), 1.0));
+        Renderer::render(&scene)
+    };
+
+    // Desde la cámara (a distancia 10) corresponde el nivel simple; los rayos
+    // de sombra que salen de la malla deben ver ese mismo nivel y no el
+    // detallado, que lo taparía
+    let lod = lit_scene(&|scene| {
+        scene.add_lod_mesh(LodMesh::new(mesh(64), LodMetric::Distance).with_level(mesh(6), 5.0));
+    });
+    let coarse = lit_scene(&|scene| {
+        scene.add_mesh(mesh(6));
+    });
+    for (a, b) in lod.pixels().iter().zip(coarse.pixels()) {
+        assert!(a.distance(b) < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn mesh_packet_hits_match_single_rays() {
+    let (vertices, triangles) = Sphere::new(Point3::zero(), 1.0, gray()).tessellate(16);
+    let mesh = TriangleMesh::new(vertices, triangles, gray());
+    let rays: Vec<Ray> = [-1.2, -0.5, 0.3, 0.9]
+        .iter()
+        .map(|&x| Ray::new(Point3::new(x, 0.1, 5.0), Vec3::new(0.0, 0.0, -1.0)))
+        .collect();
+
+    let hits = mesh.intersect_packet(&RayPacket::new(&rays));
+    for (ray, hit) in rays.iter().zip(hits) {
+        assert_eq!(hit, mesh.intersect(ray));
+    }
+}
+
+#[test]
+fn shadow_rays_past_the_layer_limit_are_occluded() {
+    // Piso iluminado a través de una pila de planos totalmente transparentes;
+    // la cámara queda bajo la pila y solo ve el piso
+    let lit_floor = |layers: usize| {
+        let camera = Camera::new(Point3::new(0.0, 1.0, 0.01), Point3::zero(), Vec3::new(0.0, 1.0, 0.0), 45.0, 1.0, 4, 4);
+        let mut scene = Scene::new(camera, Color::zero());
+        scene.add_plane(Plane::new(Point3::zero(), Vec3::new(0.0, 1.0, 0.0), gray()));
+        let clear = Material::transparent(Color::new(1.0, 1.0, 1.0), 1.0);
+        for i in 0..layers {
+            scene.add_plane(Plane::new(Point3::new(0.0, 2.0 + i as f32 * 0.1, 0.0), Vec3::new(0.0, 1.0, 0.0), clear));
+        }
+        scene.add_light(Light::white(Point3::new(0.0, 10.0, 0.0), 1.0));
+        Renderer::render(&scene).get(2, 2)
+    };
+
+    // El límite es de 16 capas: con exactamente 16 no queda nada detrás
+    let open = lit_floor(0);
+    for layers in [4, 16] {
+        let lit = lit_floor(layers);
+        assert!((lit - open).length() < 1e-3, "{} capas: {:?} != {:?}", layers, lit, open);
+    }
+    for layers in [17, 40] {
+        let shadowed = lit_floor(layers);
+        assert!(shadowed.length() < open.length() * 0.5, "{} capas: {:?} no está en sombra ({:?} sin capas)", layers, shadowed, open);
+    }
+}
+
+#[test]
+fn merge_renumbers_the_baked_maps_of_instances() {
+    // Prefab con una esfera horneada que comparten dos instancias, así que
+    // su material no se puede modificar en el lugar
+    let mut prefab = empty_scene();
+    let map = prefab.add_map(Texture::solid(Color::new(0.5, 0.5, 0.5)));
+    let baked = Arc::new(Sphere::new(Point3::zero(), 1.0, gray().with_occlusion_map(map)));
+    prefab.add_instance(baked.clone(), Transform::translation(Vec3::new(-2.0, 0.0, 0.0)));
+    prefab.add_instance(baked, Transform::translation(Vec3::new(2.0, 0.0, 0.0)));
+
+    let mut scene = empty_scene();
+    scene.add_map(Texture::solid(Color::zero()));
+    let ids = scene.merge(prefab, None);
+    assert_eq!(scene.maps.len(), 2);
+    for id in ids {
+        let material = scene.object(id).expect("objeto agregado").get_material();
+        assert_eq!(material.occlusion_map, Some(1));
+    }
+}
+
+#[test]
+fn scene_file_links_lights_by_name() {
+    let text = r#"{
+        "camera": { "position": [0, 0, 10], "look_at": [0, 0, 0] },
+        "objects": [
+            { "type": "sphere", "name": "izquierda", "center": [-2, 0, 0], "radius": 1, "material": { "type": "diffuse" } },
+            { "type": "sphere", "name": "derecha", "center": [2, 0, 0], "radius": 1, "material": { "type": "diffuse" } },
+            { "type": "plane", "point": [0, -1, 0], "normal": [0, 1, 0], "material": { "type": "diffuse" } }
+        ],
+        "lights": [
+            { "type": "point", "name": "clave", "position": [0, 5, 5], "intensity": 1, "include": ["izquierda", 2] },
+            { "type": "point", "position": [0, 5, -5], "intensity": 1, "exclude": ["derecha"] }
+        ]
+    }"#;
+    let scene = raytracer::scene_file::parse(text).expect("escena válida");
+
+    let left = scene.find("izquierda").expect("objeto con nombre");
+    let right = scene.find("derecha").expect("objeto con nombre");
+    assert_eq!(scene.find("clave"), Some(SceneItem::Light(0)));
+    let (SceneItem::Object(left), SceneItem::Object(right)) = (left, right) else {
+        panic!("los nombres deberían referirse a objetos");
+    };
+    assert!(scene.light_affects(0, left));
+    assert!(!scene.light_affects(0, right));
+    assert!(scene.light_affects(0, 2));
+    assert!(scene.light_affects(1, left));
+    assert!(!scene.light_affects(1, right));
+
+    let unknown = text.replace(r#""exclude": ["derecha"]"#, r#""exclude": ["centro"]"#);
+    assert!(raytracer::scene_file::parse(&unknown).is_err());
+    let misspelled = text.replace(r#""radius": 1, "material""#, r#""radio": 1, "material""#);
+    assert!(raytracer::scene_file::parse(&misspelled).is_err());
+}
+
+#[test]
+fn scene_file_sets_the_development() {
+    let scene = |settings: &str| {
+        let text = format!(r#"{{ "camera": {{ "position": [0, 0, 10], "look_at": [0, 0, 0] }}, "settings": {{ {} }} }}"#, settings);
+        raytracer::scene_file::parse(&text)
+    };
+
+    let default = scene("").expect("escena válida").development;
+    assert_eq!(default, Development::default());
+
+    let development = scene(r#""tone_mapping": "reinhard", "exposure": "auto", "encoding": "srgb""#).expect("escena válida").development;
+    assert_eq!(development.tone_mapping, ToneMapping::Reinhard);
+    assert_eq!(development.exposure, Exposure::auto());
+    assert_eq!(development.encoding, ColorEncoding::Srgb);
+
+    let development = scene(r#""exposure": -1.5, "encoding": 2.4"#).expect("escena válida").development;
+    assert_eq!(development.exposure, Exposure::Fixed(-1.5));
+    assert_eq!(development.encoding, ColorEncoding::Gamma(2.4));
+
+    let effects = r#""post_effects": [{ "bloom": { "threshold": 1, "intensity": 0.3, "radius": 12 } }, { "grain": { "amount": 0.05 } }]"#;
+    let development = scene(effects).expect("escena válida").development;
+    assert_eq!(
+        development.effects,
+        vec![PostEffect::Bloom { threshold: 1.0, intensity: 0.3, radius: 12 }, PostEffect::Grain { amount: 0.05, seed: 0 }]
+    );
+    assert!(scene(r#""post_effects": [{ "vignette": { "strength": 0.4 } }]"#).is_err());
+
+    assert!(scene(r#""encoding": 0"#).is_err());
+    assert!(scene(r#""tone_mapping": "filmic""#).is_err());
+}